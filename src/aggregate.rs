@@ -0,0 +1,317 @@
+use std::{
+    collections::HashMap,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use rust_roveri_api::{ClientEvent, ServerEvent};
+use wg_2024::{controller::DroneEvent, network::NodeId};
+
+use crate::events::{NodeEvent, ObservedEvent};
+
+/// The default window within which identical events of a node are coalesced.
+pub const DEFAULT_WINDOW: Duration = Duration::from_millis(100);
+
+/// The kind of a packet event, which identical events share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// A drone forwarded a packet.
+    DroneSent,
+    /// A drone dropped a packet.
+    DroneDropped,
+    /// A drone sent a packet to the simulation controller, to be delivered directly.
+    DroneShortcut,
+    /// A client sent a packet.
+    ClientSent,
+    /// A server sent a packet.
+    ServerSent,
+}
+
+impl EventKind {
+    /// Returns the kind of an event, or `None` if the event is never coalesced, e.g. a
+    /// diagnostic such as [`NodeEvent::GuiStalled`].
+    pub fn of(event: &NodeEvent) -> Option<Self> {
+        match event {
+            NodeEvent::Drone(DroneEvent::PacketSent(_)) => Some(EventKind::DroneSent),
+            NodeEvent::Drone(DroneEvent::PacketDropped(_)) => Some(EventKind::DroneDropped),
+            NodeEvent::Drone(DroneEvent::ControllerShortcut(_)) => Some(EventKind::DroneShortcut),
+            NodeEvent::Client(ClientEvent::PacketSent(_)) => Some(EventKind::ClientSent),
+            NodeEvent::Server(ServerEvent::PacketSent(_)) => Some(EventKind::ServerSent),
+            _ => None,
+        }
+    }
+}
+
+/// An update for the GUI, summarizing the events emitted by the nodes.
+#[derive(Debug, Clone)]
+pub enum GuiUpdate {
+    /// An event, delivered as it is: the first of its kind from its node within a window, or
+    /// an event which is never coalesced.
+    Event(ObservedEvent),
+    /// The events of the same kind from the same node which followed the delivered one within
+    /// a window.
+    Burst {
+        /// The ID of the node which emitted the events.
+        node: NodeId,
+        /// The kind of the events.
+        kind: EventKind,
+        /// The number of coalesced events.
+        count: usize,
+        /// When the first coalesced event was observed.
+        first: Instant,
+        /// When the last coalesced event was observed.
+        last: Instant,
+        /// The last coalesced event, e.g. to show the latest packet.
+        latest: NodeEvent,
+    },
+}
+
+/// The events of a kind coalesced since the start of the current window.
+#[derive(Debug)]
+struct Pending {
+    /// When the window closes.
+    until: Instant,
+    count: usize,
+    first: Instant,
+    last: Instant,
+    latest: Option<NodeEvent>,
+}
+
+/// Coalesces bursts of identical events into [`GuiUpdate`]s.
+///
+/// The first event of a kind from a node is delivered at once, and opens a window; the
+/// identical events observed within the window are counted, and summarized by a single
+/// [`GuiUpdate::Burst`] when it closes. A burst opens a new window, so that a sustained storm
+/// is summarized once per window, while a kind going quiet for a whole window is delivered at
+/// once again.
+#[derive(Debug)]
+pub struct Aggregator {
+    window: Duration,
+    pending: HashMap<(NodeId, EventKind), Pending>,
+}
+
+impl Aggregator {
+    /// Returns an aggregator coalescing the identical events within the given window.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Adds an event, timed by when it was observed.
+    ///
+    /// # Parameters
+    /// - `observed`: The event.
+    ///
+    /// Returns the updates to deliver at once, closing the expired windows first.
+    pub fn push(&mut self, observed: ObservedEvent) -> Vec<GuiUpdate> {
+        let mut updates = self.flush(observed.at);
+        let Some(kind) = EventKind::of(&observed.event) else {
+            updates.push(GuiUpdate::Event(observed));
+            return updates;
+        };
+        match self.pending.get_mut(&(observed.node, kind)) {
+            Some(pending) => {
+                if pending.count == 0 {
+                    pending.first = observed.at;
+                }
+                pending.count += 1;
+                pending.last = observed.at;
+                pending.latest = Some(observed.event);
+            }
+            None => {
+                let pending = Pending {
+                    until: observed.at + self.window,
+                    count: 0,
+                    first: observed.at,
+                    last: observed.at,
+                    latest: None,
+                };
+                self.pending.insert((observed.node, kind), pending);
+                updates.push(GuiUpdate::Event(observed));
+            }
+        }
+        updates
+    }
+
+    /// Closes the windows expired by the given time.
+    ///
+    /// Returns a [`GuiUpdate::Burst`] for every closed window with coalesced events, ordered
+    /// by the time of the first one.
+    pub fn flush(&mut self, now: Instant) -> Vec<GuiUpdate> {
+        let mut bursts = Vec::new();
+        let window = self.window;
+        self.pending.retain(|(node, kind), pending| {
+            if pending.until > now {
+                return true;
+            }
+            let Some(latest) = pending.latest.take() else {
+                return false;
+            };
+            bursts.push(GuiUpdate::Burst {
+                node: *node,
+                kind: *kind,
+                count: pending.count,
+                first: pending.first,
+                last: pending.last,
+                latest,
+            });
+            pending.count = 0;
+            pending.until += window;
+            if pending.until <= now {
+                pending.until = now + window;
+            }
+            true
+        });
+        bursts.sort_by_key(|burst| match burst {
+            GuiUpdate::Burst { first, .. } => *first,
+            GuiUpdate::Event(observed) => observed.at,
+        });
+        bursts
+    }
+
+    /// Returns when the next window closes, if any is open.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|pending| pending.until).min()
+    }
+}
+
+/// Spawns a thread coalescing the bursts of identical events of an event stream, so that a GUI
+/// fed from it stays responsive during stress tests, see [`Aggregator`].
+///
+/// Recorders should keep subscribing to the raw stream, which carries every event in full.
+/// The thread terminates when the stream is disconnected, after flushing the open windows, or
+/// when the returned receiver is dropped.
+///
+/// # Parameters
+/// - `events`: The raw event stream, e.g. from [`crate::handle::NetworkHandle::subscribe`].
+/// - `window`: The window within which identical events are coalesced, e.g.
+///   [`DEFAULT_WINDOW`].
+///
+/// Returns the stream of updates for the GUI.
+pub fn aggregate_stream(events: Receiver<ObservedEvent>, window: Duration) -> Receiver<GuiUpdate> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    thread::spawn(move || {
+        let mut aggregator = Aggregator::new(window);
+        loop {
+            let updates = match aggregator.deadline() {
+                Some(deadline) => match events.recv_deadline(deadline) {
+                    Ok(observed) => aggregator.push(observed),
+                    Err(RecvTimeoutError::Timeout) => aggregator.flush(Instant::now()),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                },
+                None => match events.recv() {
+                    Ok(observed) => aggregator.push(observed),
+                    Err(_) => break,
+                },
+            };
+            for update in updates {
+                if sender.send(update).is_err() {
+                    return;
+                }
+            }
+        }
+        // Every open window closes by then.
+        for update in aggregator.flush(Instant::now() + window) {
+            let _ = sender.send(update);
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::aggregate::{aggregate_stream, Aggregator, EventKind, GuiUpdate};
+    use crate::events::{NodeEvent, ObservedEvent};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    fn dropped(node: u8, at: Instant) -> ObservedEvent {
+        let packet = Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        };
+        ObservedEvent {
+            node,
+            at,
+            event: NodeEvent::Drone(DroneEvent::PacketDropped(packet)),
+        }
+    }
+
+    #[test]
+    fn test_aggregator() {
+        let window = Duration::from_millis(100);
+        let mut aggregator = Aggregator::new(window);
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+
+        // The first drop of each drone is delivered at once, the others are coalesced.
+        assert!(matches!(
+            aggregator.push(dropped(1, ms(0)))[..],
+            [GuiUpdate::Event(_)]
+        ));
+        for at in 1..=1000 {
+            assert!(aggregator.push(dropped(1, ms(at / 20))).is_empty());
+        }
+        assert_eq!(aggregator.push(dropped(2, ms(10))).len(), 1);
+        let stalled = ObservedEvent {
+            node: 1,
+            at: ms(20),
+            event: NodeEvent::GuiStalled { id: 1 },
+        };
+        assert_eq!(aggregator.push(stalled).len(), 1);
+        assert_eq!(aggregator.deadline(), Some(ms(100)));
+
+        let bursts = aggregator.flush(ms(100));
+        let [GuiUpdate::Burst {
+            node: 1,
+            kind: EventKind::DroneDropped,
+            count: 1000,
+            first,
+            last,
+            ..
+        }] = bursts[..]
+        else {
+            panic!("expected a single burst, got {:?}", bursts);
+        };
+        assert_eq!((first, last), (ms(0), ms(50)));
+
+        // A quiet window closes without an update, and the next drop is delivered at once.
+        assert!(aggregator.flush(ms(200)).is_empty());
+        assert_eq!(aggregator.deadline(), None);
+        assert!(matches!(
+            aggregator.push(dropped(1, ms(250)))[..],
+            [GuiUpdate::Event(_)]
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_stream() {
+        let (sender, events) = crossbeam_channel::unbounded();
+        let updates = aggregate_stream(events, Duration::from_millis(20));
+        for _ in 0..100 {
+            sender.send(dropped(3, Instant::now())).unwrap();
+        }
+        drop(sender);
+
+        let updates: Vec<GuiUpdate> = updates.iter().collect();
+        assert!(matches!(updates[0], GuiUpdate::Event(_)));
+        let coalesced: usize = updates[1..]
+            .iter()
+            .map(|update| match update {
+                GuiUpdate::Burst { count, .. } => *count,
+                GuiUpdate::Event(_) => 1,
+            })
+            .sum();
+        assert_eq!(coalesced, 99);
+    }
+}
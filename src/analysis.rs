@@ -0,0 +1,1458 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+};
+
+use fixedbitset::FixedBitSet;
+use wg_2024::{config::Config, network::NodeId, packet::NodeType};
+
+use crate::index::DenseIndex;
+use crate::model::{self, Topology};
+use crate::validate::ValidatedConfig;
+
+/// Tolerance used by [`is_isomorphic`] when comparing drone PDRs.
+pub const DEFAULT_PDR_TOLERANCE: f32 = 1e-6;
+
+/// Role of a node, as seen by the analysis routines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Role {
+    Drone,
+    Client,
+    Server,
+}
+
+/// Dense representation of a configuration, where nodes are identified by their index.
+struct DenseGraph {
+    roles: Vec<Role>,
+    /// The PDR of every node; always `0` for clients and servers.
+    pdrs: Vec<f32>,
+    neighbors: Vec<Vec<usize>>,
+    adjacency: Vec<FixedBitSet>,
+}
+
+impl DenseGraph {
+    /// Builds the dense graph of a topology.
+    ///
+    /// Neighbors which are not defined in the topology are ignored.
+    fn new(topology: &Topology) -> Self {
+        let mut roles = Vec::new();
+        let mut pdrs = Vec::new();
+        for node in &topology.nodes {
+            let (role, pdr) = match node.role {
+                model::Role::Drone { pdr } => (Role::Drone, pdr),
+                model::Role::Client => (Role::Client, 0.0),
+                model::Role::Server => (Role::Server, 0.0),
+            };
+            roles.push(role);
+            pdrs.push(pdr);
+        }
+
+        let index = DenseIndex::new(topology.nodes.iter().map(|node| node.id));
+
+        let n_nodes = roles.len();
+        let mut adjacency = vec![FixedBitSet::with_capacity(n_nodes); n_nodes];
+        let mut lists = topology.neighbors();
+        for (position, node) in topology.nodes.iter().enumerate() {
+            for id in lists.remove(&node.id).unwrap_or_default() {
+                if let Some(neighbor) = index.position(id) {
+                    adjacency[position].insert(neighbor);
+                }
+            }
+        }
+        let neighbors = adjacency.iter().map(|set| set.ones().collect()).collect();
+
+        Self {
+            roles,
+            pdrs,
+            neighbors,
+            adjacency,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.roles.len()
+    }
+}
+
+/// Checks whether two configurations describe the same topology, up to a renaming of node IDs.
+///
+/// Two configurations are isomorphic if there is a one-to-one mapping between their nodes that
+/// preserves node types, edges and drone PDRs (compared with [`DEFAULT_PDR_TOLERANCE`]).
+///
+/// # Parameters
+/// - `first`: The first configuration.
+/// - `second`: The second configuration.
+///
+/// Returns `true` if the configurations are isomorphic.
+pub fn is_isomorphic(first: &Config, second: &Config) -> bool {
+    is_isomorphic_with_tolerance(first, second, DEFAULT_PDR_TOLERANCE)
+}
+
+/// Checks whether two configurations describe the same topology, up to a renaming of node IDs,
+/// considering two PDRs equal if they differ by at most `pdr_tolerance`.
+///
+/// Nodes are first partitioned by color refinement (node type, degree and the colors of their
+/// neighbors, iterated until stable); the mapping is then searched by backtracking only among
+/// nodes of the same color.
+///
+/// # Parameters
+/// - `first`: The first configuration.
+/// - `second`: The second configuration.
+/// - `pdr_tolerance`: The maximum allowed difference between the PDRs of two mapped drones.
+///
+/// Returns `true` if the configurations are isomorphic.
+///
+/// # Performance
+/// `O(n * m log n)` for the refinement; the backtracking is exponential in the worst case, but
+/// close to linear on the irregular topologies used in practice.
+pub fn is_isomorphic_with_tolerance(first: &Config, second: &Config, pdr_tolerance: f32) -> bool {
+    if first.drone.len() != second.drone.len()
+        || first.client.len() != second.client.len()
+        || first.server.len() != second.server.len()
+    {
+        return false;
+    }
+    let first = DenseGraph::new(&Topology::from(first));
+    let second = DenseGraph::new(&Topology::from(second));
+    let Some((first_colors, second_colors)) = refine_colors(&first, &second) else {
+        return false;
+    };
+
+    // Map the nodes with the rarest colors first to prune the search early.
+    let mut class_sizes: HashMap<usize, usize> = HashMap::new();
+    for color in &first_colors {
+        *class_sizes.entry(*color).or_default() += 1;
+    }
+    let mut order: Vec<usize> = (0..first.len()).collect();
+    order.sort_by_key(|node| (class_sizes[&first_colors[*node]], first_colors[*node]));
+
+    let mut matcher = Matcher {
+        first: &first,
+        second: &second,
+        first_colors: &first_colors,
+        second_colors: &second_colors,
+        pdr_tolerance,
+        mapping: vec![None; first.len()],
+        used: FixedBitSet::with_capacity(second.len()),
+    };
+    matcher.extend(&order)
+}
+
+/// Computes a shared coloring of two graphs by color refinement.
+///
+/// Returns `None` if the color histograms of the two graphs differ, which proves that they
+/// are not isomorphic.
+fn refine_colors(first: &DenseGraph, second: &DenseGraph) -> Option<(Vec<usize>, Vec<usize>)> {
+    let initial = |graph: &DenseGraph| -> Vec<(Role, usize)> {
+        (0..graph.len())
+            .map(|node| (graph.roles[node], graph.neighbors[node].len()))
+            .collect()
+    };
+    let (mut first_colors, mut second_colors) = compress(&initial(first), &initial(second));
+    let mut n_colors = count_colors(&first_colors, &second_colors);
+
+    loop {
+        if histogram(&first_colors) != histogram(&second_colors) {
+            return None;
+        }
+        let signature = |graph: &DenseGraph, colors: &[usize]| -> Vec<(usize, Vec<usize>)> {
+            (0..graph.len())
+                .map(|node| {
+                    let mut around: Vec<usize> = graph.neighbors[node]
+                        .iter()
+                        .map(|neighbor| colors[*neighbor])
+                        .collect();
+                    around.sort_unstable();
+                    (colors[node], around)
+                })
+                .collect()
+        };
+        let (first_next, second_next) = compress(
+            &signature(first, &first_colors),
+            &signature(second, &second_colors),
+        );
+        let n_next = count_colors(&first_next, &second_next);
+        first_colors = first_next;
+        second_colors = second_next;
+        if n_next == n_colors {
+            break;
+        }
+        n_colors = n_next;
+    }
+
+    (histogram(&first_colors) == histogram(&second_colors)).then_some((first_colors, second_colors))
+}
+
+/// Replaces the signatures of both graphs with small integers, consistently across the graphs.
+fn compress<T: Ord + Clone>(first: &[T], second: &[T]) -> (Vec<usize>, Vec<usize>) {
+    let mut labels: BTreeMap<T, usize> = BTreeMap::new();
+    for signature in first.iter().chain(second) {
+        labels.entry(signature.clone()).or_insert(0);
+    }
+    for (label, value) in labels.values_mut().enumerate() {
+        *value = label;
+    }
+    let relabel = |signatures: &[T]| signatures.iter().map(|s| labels[s]).collect();
+    (relabel(first), relabel(second))
+}
+
+fn count_colors(first: &[usize], second: &[usize]) -> usize {
+    first.iter().chain(second).max().map_or(0, |max| max + 1)
+}
+
+fn histogram(colors: &[usize]) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for color in colors {
+        *histogram.entry(*color).or_default() += 1;
+    }
+    histogram
+}
+
+/// Backtracking search of a color-, PDR- and edge-preserving mapping between two graphs.
+struct Matcher<'a> {
+    first: &'a DenseGraph,
+    second: &'a DenseGraph,
+    first_colors: &'a [usize],
+    second_colors: &'a [usize],
+    pdr_tolerance: f32,
+    mapping: Vec<Option<usize>>,
+    used: FixedBitSet,
+}
+
+impl Matcher<'_> {
+    /// Maps the nodes in `order`, one at a time, undoing choices which lead to a dead end.
+    fn extend(&mut self, order: &[usize]) -> bool {
+        let Some((node, rest)) = order.split_first() else {
+            return true;
+        };
+        for candidate in 0..self.second.len() {
+            if self.used.contains(candidate) || !self.is_compatible(*node, candidate) {
+                continue;
+            }
+            self.mapping[*node] = Some(candidate);
+            self.used.insert(candidate);
+            if self.extend(rest) {
+                return true;
+            }
+            self.mapping[*node] = None;
+            self.used.set(candidate, false);
+        }
+        false
+    }
+
+    /// Checks whether `node` can be mapped onto `candidate` given the current partial mapping.
+    fn is_compatible(&self, node: usize, candidate: usize) -> bool {
+        if self.first_colors[node] != self.second_colors[candidate]
+            || (self.first.pdrs[node] - self.second.pdrs[candidate]).abs() > self.pdr_tolerance
+        {
+            return false;
+        }
+        self.mapping
+            .iter()
+            .enumerate()
+            .filter_map(|(other, mapped)| mapped.map(|mapped| (other, mapped)))
+            .all(|(other, mapped)| {
+                self.first.adjacency[node].contains(other)
+                    == self.second.adjacency[candidate].contains(mapped)
+                    && self.first.adjacency[other].contains(node)
+                        == self.second.adjacency[mapped].contains(candidate)
+            })
+    }
+}
+
+/// Returns the drones whose crash would disconnect some client from some server.
+///
+/// Paths are only allowed to traverse drones, as clients and servers do not forward packets.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+///
+/// Returns the IDs of the critical drones, ordered as in the configuration.
+///
+/// # Performance
+/// `O(d * c * (n + m))`, where `d` is the number of drones, `c` the number of clients, `n`
+/// the number of nodes and `m` the number of edges.
+pub fn critical_drones(config: &Config) -> Vec<NodeId> {
+    let topology = Topology::from(config);
+    let neighbors = topology.neighbors();
+    let neighbors_of = |id: NodeId| neighbors.get(&id).map_or(&[][..], Vec::as_slice);
+    let drones: HashMap<NodeId, &[NodeId]> = topology
+        .nodes_of_type(NodeType::Drone)
+        .map(|drone| (drone.id, neighbors_of(drone.id)))
+        .collect();
+
+    let reachable_pairs = |crashed: Option<NodeId>| -> usize {
+        topology
+            .nodes_of_type(NodeType::Client)
+            .map(|client| {
+                let reached = reachable_through_drones(&drones, neighbors_of(client.id), crashed);
+                topology
+                    .nodes_of_type(NodeType::Server)
+                    .filter(|server| {
+                        neighbors_of(server.id)
+                            .iter()
+                            .any(|drone| reached.contains(drone))
+                    })
+                    .count()
+            })
+            .sum()
+    };
+
+    let baseline = reachable_pairs(None);
+    topology
+        .nodes_of_type(NodeType::Drone)
+        .map(|drone| drone.id)
+        .filter(|id| reachable_pairs(Some(*id)) < baseline)
+        .collect()
+}
+
+/// Returns the drones reachable from the given ones, moving only through drones other than
+/// `crashed`.
+///
+/// `neighbors` maps every drone to its neighbors.
+fn reachable_through_drones(
+    neighbors: &HashMap<NodeId, &[NodeId]>,
+    start: &[NodeId],
+    crashed: Option<NodeId>,
+) -> HashSet<NodeId> {
+    let mut reached = HashSet::new();
+    let mut queue: VecDeque<NodeId> = start
+        .iter()
+        .copied()
+        .filter(|id| neighbors.contains_key(id))
+        .collect();
+    while let Some(id) = queue.pop_front() {
+        if Some(id) == crashed || !reached.insert(id) {
+            continue;
+        }
+        queue.extend(
+            neighbors[&id]
+                .iter()
+                .filter(|neighbor| neighbors.contains_key(neighbor)),
+        );
+    }
+    reached
+}
+
+/// The single points of failure of a topology, as found by [`analyze_topology`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologyAnalysis {
+    /// The cut vertices of the drone subgraph, i.e. the drones whose crash would split the
+    /// other drones into more pieces, in ascending order.
+    pub cut_drones: Vec<NodeId>,
+    /// The bridges of the drone subgraph, i.e. the links between drones whose removal would
+    /// split the drones into more pieces, in ascending order.
+    pub bridges: Vec<Link>,
+    /// The drones whose crash would disconnect some client from some server, see
+    /// [`critical_drones`], ordered as in the configuration.
+    pub critical_drones: Vec<NodeId>,
+}
+
+impl TopologyAnalysis {
+    /// Returns `true` if no single drone, or link between drones, is a point of failure.
+    pub fn is_redundant(&self) -> bool {
+        self.cut_drones.is_empty() && self.bridges.is_empty() && self.critical_drones.is_empty()
+    }
+}
+
+/// Finds the single points of failure of a topology: the drones and links between drones
+/// whose failure would split the drones, and the drones whose crash would partition clients
+/// from servers, e.g. to catch topologies which die when one drone crashes before deploying
+/// them.
+///
+/// A cut drone is not necessarily critical, e.g. if it only separates drones which no client
+/// or server uses, and a critical drone is not necessarily a cut drone, e.g. if it is the only
+/// neighbor of a client.
+///
+/// # Parameters
+/// - `config`: The network configuration; one-way links between drones are taken as two-way.
+///
+/// Returns the analysis of the topology.
+///
+/// # Performance
+/// `O(d * c * (n + m))`, as [`critical_drones`]; the cut drones and the bridges alone take
+/// `O(d + m)`, where `d` is the number of drones and `m` the number of edges.
+pub fn analyze_topology(config: &Config) -> TopologyAnalysis {
+    let (cut_drones, bridges) = cut_drones_and_bridges(&undirected(drone_graph(config)));
+    TopologyAnalysis {
+        cut_drones: cut_drones.into_iter().collect(),
+        bridges: bridges.into_iter().collect(),
+        critical_drones: critical_drones(config),
+    }
+}
+
+/// Returns the cut vertices and the bridges of an undirected graph, with Tarjan's lowlink
+/// algorithm.
+///
+/// `graph` maps every node to its neighbors, listing every edge on both endpoints, once.
+fn cut_drones_and_bridges(
+    graph: &BTreeMap<NodeId, Vec<NodeId>>,
+) -> (BTreeSet<NodeId>, BTreeSet<Link>) {
+    let mut cut = BTreeSet::new();
+    let mut bridges = BTreeSet::new();
+    // The discovery time and the lowlink of every visited node.
+    let mut discovery: HashMap<NodeId, usize> = HashMap::new();
+    let mut low: HashMap<NodeId, usize> = HashMap::new();
+    for &root in graph.keys() {
+        if discovery.contains_key(&root) {
+            continue;
+        }
+        discovery.insert(root, discovery.len());
+        low.insert(root, discovery[&root]);
+        let mut root_children = 0;
+        // The DFS path, as `(node, parent, index of the next neighbor to visit)`.
+        let mut stack: Vec<(NodeId, Option<NodeId>, usize)> = vec![(root, None, 0)];
+        while let Some((node, parent, next)) = stack.last_mut() {
+            let (node, parent) = (*node, *parent);
+            if let Some(&neighbor) = graph[&node].get(*next) {
+                *next += 1;
+                if Some(neighbor) == parent {
+                    continue;
+                }
+                match discovery.get(&neighbor) {
+                    Some(&time) => {
+                        let node_low = low.get_mut(&node).expect("visited node");
+                        *node_low = (*node_low).min(time);
+                    }
+                    None => {
+                        let time = discovery.len();
+                        discovery.insert(neighbor, time);
+                        low.insert(neighbor, time);
+                        stack.push((neighbor, Some(node), 0));
+                    }
+                }
+                continue;
+            }
+            stack.pop();
+            let Some(parent) = parent else {
+                continue;
+            };
+            let node_low = low[&node];
+            let parent_low = low.get_mut(&parent).expect("visited node");
+            *parent_low = (*parent_low).min(node_low);
+            if node_low > discovery[&parent] {
+                bridges.insert((parent.min(node), parent.max(node)));
+            }
+            if parent == root {
+                root_children += 1;
+            } else if node_low >= discovery[&parent] {
+                cut.insert(parent);
+            }
+        }
+        if root_children > 1 {
+            cut.insert(root);
+        }
+    }
+    (cut, bridges)
+}
+
+/// Quantitative metrics of a topology, as computed by [`topology_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyReport {
+    /// The largest number of hops between two nodes, moving only through drones.
+    pub diameter: usize,
+    /// The mean number of neighbors of a node.
+    pub avg_degree: f64,
+    /// The number of nodes with each number of neighbors.
+    pub degree_histogram: BTreeMap<usize, usize>,
+    /// The fewest neighbors of a drone, clients and servers included.
+    pub min_drone_degree: usize,
+    /// The vertex connectivity of the drone subgraph, i.e. the fewest drones whose crash
+    /// splits the other drones, or leaves a single one; `0` if the drones are already split.
+    pub k_connectivity_estimate: usize,
+    /// The number of paths between every client and every server which share no drone, i.e.
+    /// the fewest drones whose crash would disconnect them, by `(client, server)`.
+    pub client_server_path_counts: BTreeMap<(NodeId, NodeId), usize>,
+}
+
+/// Measures a topology, e.g. to compare candidate topologies quantitatively before running
+/// simulations on them.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+///
+/// Returns the metrics of the topology; one-way links between drones are taken as two-way.
+///
+/// # Performance
+/// `O(n * (n + m) + (k + c * s) * k * m)`, where `n` is the number of nodes, `m` the number of
+/// edges, `k` the connectivity, `c` the number of clients and `s` the number of servers.
+pub fn topology_report(config: &ValidatedConfig) -> TopologyReport {
+    let topology = Topology::from(config.config());
+    let neighbors = topology.neighbors();
+    let degree = |id: NodeId| neighbors.get(&id).map_or(0, Vec::len);
+    let drones: HashSet<NodeId> = topology
+        .nodes_of_type(NodeType::Drone)
+        .map(|drone| drone.id)
+        .collect();
+
+    let diameter = topology
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let paths = ShortestPaths::from(&neighbors, &drones, node.id);
+            paths.distance.into_values().max()
+        })
+        .max()
+        .unwrap_or(0);
+    let mut degree_histogram = BTreeMap::new();
+    for node in &topology.nodes {
+        *degree_histogram.entry(degree(node.id)).or_default() += 1;
+    }
+    let total_degree: usize = topology.nodes.iter().map(|node| degree(node.id)).sum();
+    let avg_degree = total_degree as f64 / topology.nodes.len().max(1) as f64;
+
+    let graph: BTreeMap<NodeId, Vec<NodeId>> = topology
+        .nodes
+        .iter()
+        .map(|node| {
+            (
+                node.id,
+                neighbors.get(&node.id).cloned().unwrap_or_default(),
+            )
+        })
+        .collect();
+    let flow = PathFlow::new(&undirected(graph), |id| drones.contains(&id));
+    let mut client_server_path_counts = BTreeMap::new();
+    for client in topology.nodes_of_type(NodeType::Client) {
+        for server in topology.nodes_of_type(NodeType::Server) {
+            let paths = flow.disjoint_paths(client.id, server.id, usize::MAX);
+            client_server_path_counts.insert((client.id, server.id), paths);
+        }
+    }
+
+    TopologyReport {
+        diameter,
+        avg_degree,
+        degree_histogram,
+        min_drone_degree: drones.iter().map(|id| degree(*id)).min().unwrap_or(0),
+        k_connectivity_estimate: vertex_connectivity(&undirected(drone_graph(config.config()))),
+        client_server_path_counts,
+    }
+}
+
+/// Returns the vertex connectivity of an undirected graph, with Even's algorithm.
+///
+/// `graph` maps every node to its neighbors, listing every edge on both endpoints, once.
+fn vertex_connectivity(graph: &BTreeMap<NodeId, Vec<NodeId>>) -> usize {
+    let nodes: Vec<NodeId> = graph.keys().copied().collect();
+    let flow = PathFlow::new(graph, |_| true);
+    // The connectivity is at most the smallest degree, which is reached by complete graphs.
+    let mut connectivity = graph.values().map(Vec::len).min().unwrap_or(0);
+    // Some separator of the smallest size misses one of the first `connectivity + 1` nodes, so
+    // only the pairs starting from them need to be checked.
+    let mut index = 0;
+    while index <= connectivity && index < nodes.len() {
+        let node = nodes[index];
+        for &other in &nodes[index + 1..] {
+            if !graph[&node].contains(&other) {
+                connectivity = flow.disjoint_paths(node, other, connectivity);
+            }
+        }
+        index += 1;
+    }
+    connectivity
+}
+
+/// Returns a graph with every edge listed on both of its endpoints, once.
+///
+/// `graph` maps every node to its neighbors.
+fn undirected(mut graph: BTreeMap<NodeId, Vec<NodeId>>) -> BTreeMap<NodeId, Vec<NodeId>> {
+    let edges: Vec<Link> = graph
+        .iter()
+        .flat_map(|(&id, neighbors)| neighbors.iter().map(move |&neighbor| (id, neighbor)))
+        .collect();
+    for (id, neighbor) in edges {
+        let reverse = graph.entry(neighbor).or_default();
+        if !reverse.contains(&id) {
+            reverse.push(id);
+        }
+    }
+    graph
+}
+
+/// A flow network with unit capacities, whose maximum flow between two nodes counts the paths
+/// between them which share no other node.
+///
+/// Every node which can be crossed is split into an entry and an exit vertex, linked by a
+/// single arc, so that at most one path crosses it.
+#[derive(Debug, Clone)]
+struct PathFlow {
+    /// The dense index of every node.
+    index: HashMap<NodeId, usize>,
+    /// The head and the residual capacity of every arc; arc `i ^ 1` is the reverse of arc `i`.
+    arcs: Vec<(usize, u32)>,
+    /// The arcs leaving every vertex.
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl PathFlow {
+    /// Builds the network of an undirected graph.
+    ///
+    /// # Parameters
+    /// - `graph`: Maps every node to its neighbors, listing every edge on both endpoints.
+    /// - `crossable`: Whether paths can cross a node, e.g. only drones forward packets.
+    fn new(graph: &BTreeMap<NodeId, Vec<NodeId>>, crossable: impl Fn(NodeId) -> bool) -> Self {
+        let index: HashMap<NodeId, usize> = (graph.keys().enumerate())
+            .map(|(index, id)| (*id, index))
+            .collect();
+        let mut flow = Self {
+            arcs: Vec::new(),
+            outgoing: vec![Vec::new(); 2 * index.len()],
+            index,
+        };
+        for (&id, neighbors) in graph {
+            let node = flow.index[&id];
+            if crossable(id) {
+                flow.add_arc(2 * node, 2 * node + 1);
+            }
+            for neighbor in neighbors {
+                let neighbor = flow.index[neighbor];
+                flow.add_arc(2 * node + 1, 2 * neighbor);
+            }
+        }
+        flow
+    }
+
+    /// Adds an arc of capacity one, and its reverse.
+    fn add_arc(&mut self, from: usize, to: usize) {
+        self.outgoing[from].push(self.arcs.len());
+        self.arcs.push((to, 1));
+        self.outgoing[to].push(self.arcs.len());
+        self.arcs.push((from, 0));
+    }
+
+    /// Returns the number of paths from `source` to `sink` which share no other node, up to
+    /// `limit`, with the Edmonds-Karp algorithm.
+    fn disjoint_paths(&self, source: NodeId, sink: NodeId, limit: usize) -> usize {
+        let mut arcs = self.arcs.clone();
+        let (from, to) = (2 * self.index[&source] + 1, 2 * self.index[&sink]);
+        let mut paths = 0;
+        while paths < limit {
+            // The arc through which every vertex was reached by the search.
+            let mut via: Vec<Option<usize>> = vec![None; self.outgoing.len()];
+            let mut visited = vec![false; self.outgoing.len()];
+            visited[from] = true;
+            let mut queue = VecDeque::from([from]);
+            while let Some(vertex) = queue.pop_front() {
+                if vertex == to {
+                    break;
+                }
+                for &arc in &self.outgoing[vertex] {
+                    let (head, capacity) = arcs[arc];
+                    if capacity > 0 && !visited[head] {
+                        visited[head] = true;
+                        via[head] = Some(arc);
+                        queue.push_back(head);
+                    }
+                }
+            }
+            if !visited[to] {
+                break;
+            }
+            let mut vertex = to;
+            while let Some(arc) = via[vertex] {
+                arcs[arc].1 -= 1;
+                arcs[arc ^ 1].1 += 1;
+                vertex = arcs[arc ^ 1].0;
+            }
+            paths += 1;
+        }
+        paths
+    }
+}
+
+/// Returns a proper coloring of the drone subgraph, where linked drones never share a color,
+/// e.g. to model the channels of drones which must not interfere with each other.
+///
+/// The coloring is greedy, coloring first the drone whose neighbors have the most distinct
+/// colors (DSatur), so it uses at most one color more than the largest number of drone
+/// neighbors of a drone, but not necessarily the fewest colors; see [`color_drones_with`].
+///
+/// # Parameters
+/// - `config`: The network configuration.
+///
+/// Returns the color of every drone, numbered from `0`.
+///
+/// # Performance
+/// `O(d * (d + m))`, where `d` is the number of drones and `m` the number of edges.
+pub fn color_drones(config: &Config) -> HashMap<NodeId, u8> {
+    let graph = drone_graph(config);
+    let mut colors: HashMap<NodeId, u8> = HashMap::with_capacity(graph.len());
+    while colors.len() < graph.len() {
+        let saturation = |id: NodeId| -> usize {
+            let neighbor_colors: HashSet<u8> = graph[&id]
+                .iter()
+                .filter_map(|n| colors.get(n))
+                .copied()
+                .collect();
+            neighbor_colors.len()
+        };
+        let next = graph
+            .iter()
+            .filter(|(id, _)| !colors.contains_key(id))
+            .max_by_key(|(id, neighbors)| (saturation(**id), neighbors.len(), Reverse(**id)))
+            .map(|(id, _)| *id)
+            .unwrap();
+        let color = (0..=u8::MAX)
+            .find(|color| graph[&next].iter().all(|n| colors.get(n) != Some(color)))
+            .unwrap();
+        colors.insert(next, color);
+    }
+    colors
+}
+
+/// Checks that the given number of colors suffices to color the drone subgraph, see
+/// [`color_drones`].
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `n_colors`: The number of colors available, e.g. of non-interfering channels.
+///
+/// Returns a coloring using at most `n_colors` colors, or an error if there is none.
+///
+/// # Performance
+/// Exponential in the worst case: when the greedy coloring uses too many colors, every
+/// coloring is searched.
+pub fn color_drones_with(config: &Config, n_colors: usize) -> Result<HashMap<NodeId, u8>, String> {
+    let greedy = color_drones(config);
+    let used = greedy.values().max().map_or(0, |color| *color as usize + 1);
+    if used <= n_colors {
+        return Ok(greedy);
+    }
+
+    let graph = drone_graph(config);
+    let mut order: Vec<NodeId> = graph.keys().copied().collect();
+    order.sort_by_key(|id| Reverse(graph[id].len()));
+    let mut colors = HashMap::with_capacity(graph.len());
+    if color_exhaustively(&graph, &order, n_colors, &mut colors) {
+        Ok(colors)
+    } else {
+        Err(format!(
+            "The drones cannot be colored with {} colors",
+            n_colors
+        ))
+    }
+}
+
+/// Returns the drone neighbors of every drone, in ascending order.
+fn drone_graph(config: &Config) -> BTreeMap<NodeId, Vec<NodeId>> {
+    let drones: HashSet<NodeId> = config.drone.iter().map(|drone| drone.id).collect();
+    config
+        .drone
+        .iter()
+        .map(|drone| {
+            let mut neighbors: Vec<NodeId> = drone
+                .connected_node_ids
+                .iter()
+                .copied()
+                .filter(|id| drones.contains(id))
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            (drone.id, neighbors)
+        })
+        .collect()
+}
+
+/// Colors the drones of `order` by backtracking, on top of the given partial coloring.
+///
+/// A drone is only given a new color once, as the first color not used so far, since the
+/// colorings differing by a renaming of the colors are equivalent.
+///
+/// Returns `true` if every drone was colored with at most `n_colors` colors.
+fn color_exhaustively(
+    graph: &BTreeMap<NodeId, Vec<NodeId>>,
+    order: &[NodeId],
+    n_colors: usize,
+    colors: &mut HashMap<NodeId, u8>,
+) -> bool {
+    let Some((&id, rest)) = order.split_first() else {
+        return true;
+    };
+    let used = colors.values().max().map_or(0, |color| *color as usize + 1);
+    for color in 0..n_colors.min(used + 1) {
+        let color = color as u8;
+        if graph[&id].iter().all(|n| colors.get(n) != Some(&color)) {
+            colors.insert(id, color);
+            if color_exhaustively(graph, rest, n_colors, colors) {
+                return true;
+            }
+            colors.remove(&id);
+        }
+    }
+    false
+}
+
+/// A measure of the importance of a drone in the network, see [`rank_drones`].
+///
+/// Paths only go through drones, since clients and servers do not forward packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Centrality {
+    /// The number of neighbors of the drone.
+    Degree,
+    /// The number of pairs of other nodes whose shortest paths go through the drone, every
+    /// pair counting for the fraction of its shortest paths which do.
+    Betweenness,
+    /// The reciprocal of the mean distance from the drone to the nodes it reaches.
+    Closeness,
+}
+
+/// Ranks the drones by their centrality, e.g. so that a scenario can target the most
+/// important drone without hard-coding its ID.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `metric`: The measure of centrality.
+///
+/// Returns every drone with its centrality, from the most central; ties are broken in favor
+/// of the drone with the smallest ID.
+///
+/// # Performance
+/// `O(n * (n + m))` for the betweenness and closeness, where `n` is the number of nodes and
+/// `m` the number of edges.
+pub fn rank_drones(config: &Config, metric: Centrality) -> Vec<(NodeId, f64)> {
+    let topology = Topology::from(config);
+    let neighbors = topology.neighbors();
+    let drones: HashSet<NodeId> = topology
+        .nodes_of_type(NodeType::Drone)
+        .map(|drone| drone.id)
+        .collect();
+    let degree = |id: &NodeId| neighbors.get(id).map_or(0, Vec::len);
+
+    let mut ranking: Vec<(NodeId, f64)> = match metric {
+        Centrality::Degree => drones.iter().map(|id| (*id, degree(id) as f64)).collect(),
+        Centrality::Closeness => drones
+            .iter()
+            .map(|id| {
+                let paths = ShortestPaths::from(&neighbors, &drones, *id);
+                let total: usize = paths.distance.values().sum();
+                let reached = paths.distance.len() - 1;
+                let closeness = if total == 0 {
+                    0.0
+                } else {
+                    reached as f64 / total as f64
+                };
+                (*id, closeness)
+            })
+            .collect(),
+        Centrality::Betweenness => {
+            let mut betweenness: HashMap<NodeId, f64> =
+                drones.iter().map(|id| (*id, 0.0)).collect();
+            for source in &topology.nodes {
+                let paths = ShortestPaths::from(&neighbors, &drones, source.id);
+                let mut dependency: HashMap<NodeId, f64> = HashMap::new();
+                for node in paths.order.iter().rev() {
+                    let through = dependency.get(node).copied().unwrap_or(0.0);
+                    for previous in &paths.previous[node] {
+                        let share = paths.count[previous] / paths.count[node];
+                        *dependency.entry(*previous).or_default() += share * (1.0 + through);
+                    }
+                    if *node != source.id {
+                        if let Some(score) = betweenness.get_mut(node) {
+                            *score += through;
+                        }
+                    }
+                }
+            }
+            // Every pair was counted from both of its ends.
+            betweenness
+                .into_iter()
+                .map(|(id, score)| (id, score / 2.0))
+                .collect()
+        }
+    };
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranking
+}
+
+/// The shortest paths from a node to every node it reaches through drones.
+pub(crate) struct ShortestPaths {
+    /// The reached nodes, by increasing distance.
+    order: Vec<NodeId>,
+    /// The distance of every reached node.
+    pub(crate) distance: HashMap<NodeId, usize>,
+    /// The number of shortest paths to every reached node.
+    count: HashMap<NodeId, f64>,
+    /// The nodes preceding every reached node on its shortest paths.
+    previous: HashMap<NodeId, Vec<NodeId>>,
+}
+
+impl ShortestPaths {
+    /// Searches the graph breadth-first from `source`, only moving on from drones.
+    pub(crate) fn from(
+        neighbors: &HashMap<NodeId, Vec<NodeId>>,
+        drones: &HashSet<NodeId>,
+        source: NodeId,
+    ) -> Self {
+        let mut paths = Self {
+            order: Vec::new(),
+            distance: HashMap::from([(source, 0)]),
+            count: HashMap::from([(source, 1.0)]),
+            previous: HashMap::from([(source, Vec::new())]),
+        };
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            paths.order.push(node);
+            if node != source && !drones.contains(&node) {
+                continue;
+            }
+            let distance = paths.distance[&node] + 1;
+            for &neighbor in neighbors.get(&node).map_or(&[][..], Vec::as_slice) {
+                if let Entry::Vacant(entry) = paths.distance.entry(neighbor) {
+                    entry.insert(distance);
+                    paths.previous.insert(neighbor, Vec::new());
+                    queue.push_back(neighbor);
+                }
+                if paths.distance[&neighbor] == distance {
+                    let count = paths.count[&node];
+                    *paths.count.entry(neighbor).or_default() += count;
+                    paths.previous.get_mut(&neighbor).unwrap().push(node);
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// A path between two nodes, with the probability that a packet sent along it is delivered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredPath {
+    /// The nodes of the path, from the source to the destination.
+    pub hops: Vec<NodeId>,
+    /// The probability that no drone of the path drops the packet.
+    pub delivery: f64,
+}
+
+/// Returns the `k` paths between two nodes with the highest delivery probability, e.g. as an
+/// oracle against which the source routes chosen by the clients are checked in tests.
+///
+/// Paths only go through drones and never visit a node twice. Every drone reached by a
+/// packet, after the source, drops it with its packet drop rate, so a drone costs
+/// `-ln(1 - pdr)` and the cheapest paths are the most reliable ones; paths through drones
+/// dropping every packet are never returned.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `from`: The ID of the source node.
+/// - `to`: The ID of the destination node.
+/// - `k`: The maximum number of paths.
+///
+/// Returns the paths from the most reliable; ties are broken in favor of the
+/// lexicographically smallest path.
+///
+/// # Performance
+/// `O(k * n^3)`, where `n` is the number of nodes, by Yen's algorithm.
+pub fn k_best_paths(config: &Config, from: NodeId, to: NodeId, k: usize) -> Vec<ScoredPath> {
+    k_best_paths_in(&Topology::from(config), from, to, k)
+}
+
+/// Returns the `k` paths between two nodes of a topology with the highest delivery
+/// probability, see [`k_best_paths`].
+///
+/// Edges are directed, so a path only follows the edges from a node to its neighbors, e.g.
+/// the live links of a running network.
+///
+/// # Parameters
+/// - `topology`: The topology.
+/// - `from`: The ID of the source node.
+/// - `to`: The ID of the destination node.
+/// - `k`: The maximum number of paths.
+///
+/// Returns the paths from the most reliable; ties are broken in favor of the
+/// lexicographically smallest path.
+///
+/// # Performance
+/// `O(k * n^3)`, where `n` is the number of nodes, by Yen's algorithm.
+pub fn k_best_paths_in(topology: &Topology, from: NodeId, to: NodeId, k: usize) -> Vec<ScoredPath> {
+    let graph = CostGraph {
+        neighbors: topology.neighbors(),
+        drones: topology
+            .nodes_of_type(NodeType::Drone)
+            .map(|drone| drone.id)
+            .collect(),
+        costs: topology
+            .nodes
+            .iter()
+            .map(|node| {
+                let cost = match node.role {
+                    model::Role::Drone { pdr } => -(1.0 - pdr as f64).ln(),
+                    model::Role::Client | model::Role::Server => 0.0,
+                };
+                (node.id, cost)
+            })
+            .collect(),
+    };
+    if k == 0 || !graph.costs.contains_key(&from) || !graph.costs.contains_key(&to) {
+        return Vec::new();
+    }
+
+    let Some(best) = graph.cheapest_path(from, to, &HashSet::new(), &HashSet::new()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<Vec<NodeId>> = vec![best];
+    let mut candidates: Vec<(f64, Vec<NodeId>)> = Vec::new();
+    while paths.len() < k {
+        let previous = &paths[paths.len() - 1];
+        for spur in 0..previous.len() - 1 {
+            let root = &previous[..=spur];
+            let removed_edges: HashSet<(NodeId, NodeId)> = paths
+                .iter()
+                .filter(|path| path.len() > spur + 1 && path[..=spur] == *root)
+                .map(|path| (path[spur], path[spur + 1]))
+                .collect();
+            let removed_nodes: HashSet<NodeId> = root[..spur].iter().copied().collect();
+            let Some(tail) =
+                graph.cheapest_path(previous[spur], to, &removed_nodes, &removed_edges)
+            else {
+                continue;
+            };
+            let path: Vec<NodeId> = root[..spur].iter().copied().chain(tail).collect();
+            if !paths.contains(&path) && candidates.iter().all(|(_, other)| *other != path) {
+                candidates.push((graph.cost(&path), path));
+            }
+        }
+        let Some(next) = (0..candidates.len()).min_by(|&a, &b| {
+            let ((a_cost, a_path), (b_cost, b_path)) = (&candidates[a], &candidates[b]);
+            a_cost.total_cmp(b_cost).then(a_path.cmp(b_path))
+        }) else {
+            break;
+        };
+        paths.push(candidates.swap_remove(next).1);
+    }
+
+    paths
+        .into_iter()
+        .map(|hops| ScoredPath {
+            delivery: (-graph.cost(&hops)).exp(),
+            hops,
+        })
+        .collect()
+}
+
+/// A graph whose nodes cost `-ln(1 - pdr)` to reach, see [`k_best_paths`].
+struct CostGraph {
+    neighbors: HashMap<NodeId, Vec<NodeId>>,
+    drones: HashSet<NodeId>,
+    costs: HashMap<NodeId, f64>,
+}
+
+impl CostGraph {
+    /// Returns the cost of a path, the sum of the costs of its nodes after the source.
+    fn cost(&self, path: &[NodeId]) -> f64 {
+        path.iter().skip(1).map(|id| self.costs[id]).sum()
+    }
+
+    /// Returns the cheapest path between two nodes by Dijkstra's algorithm, only moving on from
+    /// the source and from drones, avoiding the given nodes and directed edges.
+    ///
+    /// Ties are broken in favor of the smallest node IDs.
+    fn cheapest_path(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        removed_nodes: &HashSet<NodeId>,
+        removed_edges: &HashSet<(NodeId, NodeId)>,
+    ) -> Option<Vec<NodeId>> {
+        let mut distance: BTreeMap<NodeId, f64> = BTreeMap::from([(from, 0.0)]);
+        let mut previous: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut visited: HashSet<NodeId> = HashSet::new();
+        loop {
+            let (node, cost) = distance
+                .iter()
+                .filter(|(id, _)| !visited.contains(id))
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(id, cost)| (*id, *cost))?;
+            if node == to {
+                break;
+            }
+            visited.insert(node);
+            if node != from && !self.drones.contains(&node) {
+                continue;
+            }
+            for &neighbor in self.neighbors.get(&node).map_or(&[][..], Vec::as_slice) {
+                let step = self.costs.get(&neighbor).copied().unwrap_or(f64::INFINITY);
+                if removed_nodes.contains(&neighbor)
+                    || removed_edges.contains(&(node, neighbor))
+                    || visited.contains(&neighbor)
+                    || step.is_infinite()
+                {
+                    continue;
+                }
+                let total = cost + step;
+                if distance.get(&neighbor).is_none_or(|known| total < *known) {
+                    distance.insert(neighbor, total);
+                    previous.insert(neighbor, node);
+                }
+            }
+        }
+
+        let mut path = vec![to];
+        while let Some(node) = previous.get(path.last().unwrap()) {
+            path.push(*node);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// An undirected link, with the smaller ID first.
+pub type Link = (NodeId, NodeId);
+
+/// Differences between an expected topology and an observed one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologyDiff {
+    /// Links which are expected, but were not observed.
+    pub missing_links: BTreeSet<Link>,
+    /// Links which were observed, but are not expected.
+    pub unexpected_links: BTreeSet<Link>,
+    /// Nodes which are expected, but were not observed.
+    pub missing_nodes: BTreeSet<NodeId>,
+    /// Nodes which were observed, but are not expected.
+    pub unexpected_nodes: BTreeSet<NodeId>,
+}
+
+impl TopologyDiff {
+    /// Returns `true` if the topologies match.
+    pub fn is_empty(&self) -> bool {
+        self.missing_links.is_empty()
+            && self.unexpected_links.is_empty()
+            && self.missing_nodes.is_empty()
+            && self.unexpected_nodes.is_empty()
+    }
+}
+
+/// Returns the undirected links of a configuration.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+pub fn config_links(config: &Config) -> BTreeSet<Link> {
+    Topology::from(config).links()
+}
+
+/// Compares two sets of links.
+///
+/// # Parameters
+/// - `expected`: The expected links.
+/// - `observed`: The observed links.
+pub fn diff_links(expected: &BTreeSet<Link>, observed: &BTreeSet<Link>) -> TopologyDiff {
+    TopologyDiff {
+        missing_links: expected.difference(observed).copied().collect(),
+        unexpected_links: observed.difference(expected).copied().collect(),
+        ..TopologyDiff::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::{
+        analyze_topology, color_drones, color_drones_with, critical_drones, is_isomorphic,
+        is_isomorphic_with_tolerance, k_best_paths, rank_drones, topology_report, Centrality,
+    };
+    use crate::generator::generate_small_world;
+    use crate::validate::{network_validate_str, ValidatedConfig};
+    use wg_2024::config::Config;
+    use wg_2024::config::{Client, Drone, Server};
+    use wg_2024::network::NodeId;
+
+    /// Renames every node of the configuration with the given function.
+    fn remap(config: &Config, rename: impl Fn(NodeId) -> NodeId) -> Config {
+        let mut config = config.clone();
+        for drone in &mut config.drone {
+            drone.id = rename(drone.id);
+            drone
+                .connected_node_ids
+                .iter_mut()
+                .for_each(|id| *id = rename(*id));
+        }
+        for client in &mut config.client {
+            client.id = rename(client.id);
+            client
+                .connected_drone_ids
+                .iter_mut()
+                .for_each(|id| *id = rename(*id));
+        }
+        for server in &mut config.server {
+            server.id = rename(server.id);
+            server
+                .connected_drone_ids
+                .iter_mut()
+                .for_each(|id| *id = rename(*id));
+        }
+        config
+    }
+
+    #[test]
+    fn test_isomorphic_after_remapping() {
+        let config = generate_small_world(16, 4, 0.3, 4, 3, 11).unwrap();
+        let mut remapped = remap(&config, |id| 100 - id);
+        remapped.drone.reverse();
+
+        assert!(is_isomorphic(&config, &remapped));
+    }
+
+    #[test]
+    fn test_not_isomorphic_different_structure() {
+        let first = generate_small_world(16, 4, 0.3, 4, 3, 11).unwrap();
+        let second = generate_small_world(16, 4, 0.3, 4, 3, 12).unwrap();
+
+        assert!(!is_isomorphic(&first, &second));
+    }
+
+    #[test]
+    fn test_isomorphic_pdr_tolerance() {
+        let config = generate_small_world(10, 4, 0.2, 2, 2, 3).unwrap();
+        let mut changed = remap(&config, |id| id + 50);
+        changed.drone[0].pdr += 0.01;
+
+        assert!(!is_isomorphic(&config, &changed));
+        assert!(is_isomorphic_with_tolerance(&config, &changed, 0.02));
+    }
+
+    #[test]
+    fn test_critical_drones() {
+        // Client 10 reaches server 20 either through drone 1 or drone 2, but only through 3.
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![3, 10],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![3, 10],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 3,
+                connected_node_ids: vec![1, 2, 20],
+                pdr: 0.0,
+            },
+        ];
+        let config = Config {
+            drone,
+            client: vec![Client {
+                id: 10,
+                connected_drone_ids: vec![1, 2],
+            }],
+            server: vec![Server {
+                id: 20,
+                connected_drone_ids: vec![3],
+            }],
+        };
+
+        assert_eq!(critical_drones(&config), vec![3]);
+    }
+
+    #[test]
+    fn test_topology_report() {
+        // Drone 1 hangs off the triangle of drones 2, 3 and 4, and is the only neighbor of
+        // client 10; server 20 is connected to drones 3 and 4.
+        let config = network_validate_str(
+            r#"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 10]
+            pdr = 0.0
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [1, 3, 4]
+            pdr = 0.0
+
+            [[drone]]
+            id = 3
+            connected_node_ids = [2, 4, 20]
+            pdr = 0.0
+
+            [[drone]]
+            id = 4
+            connected_node_ids = [2, 3, 20]
+            pdr = 0.0
+
+            [[client]]
+            id = 10
+            connected_drone_ids = [1]
+
+            [[server]]
+            id = 20
+            connected_drone_ids = [3, 4]
+            "#,
+        )
+        .unwrap();
+        let report = topology_report(&ValidatedConfig::new(config.clone()).unwrap());
+        assert_eq!(report.diameter, 4);
+        assert_eq!(report.avg_degree, 14.0 / 6.0);
+        assert_eq!(report.degree_histogram, [(1, 1), (2, 2), (3, 3)].into());
+        assert_eq!(report.min_drone_degree, 2);
+        assert_eq!(report.k_connectivity_estimate, 1);
+        assert_eq!(report.client_server_path_counts, [((10, 20), 1)].into());
+
+        // Linking the client and drone 1 to drone 3 leaves no single point of failure.
+        let mut redundant = config;
+        redundant.drone[0].connected_node_ids.push(3);
+        redundant.drone[2].connected_node_ids.extend([1, 10]);
+        redundant.client[0].connected_drone_ids.push(3);
+        let report = topology_report(&ValidatedConfig::new(redundant).unwrap());
+        assert_eq!(report.diameter, 2);
+        assert_eq!(report.k_connectivity_estimate, 2);
+        assert_eq!(report.client_server_path_counts, [((10, 20), 2)].into());
+    }
+
+    #[test]
+    fn test_analyze_topology() {
+        // Drone 1 hangs off the triangle of drones 2, 3 and 4, and is the only neighbor of
+        // client 10.
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2, 10],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1, 3, 4],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 3,
+                connected_node_ids: vec![2, 4, 20],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 4,
+                connected_node_ids: vec![2, 3, 20],
+                pdr: 0.0,
+            },
+        ];
+        let mut config = Config {
+            drone,
+            client: vec![Client {
+                id: 10,
+                connected_drone_ids: vec![1],
+            }],
+            server: vec![Server {
+                id: 20,
+                connected_drone_ids: vec![3, 4],
+            }],
+        };
+
+        let analysis = analyze_topology(&config);
+        assert_eq!(analysis.cut_drones, [2]);
+        assert_eq!(analysis.bridges, [(1, 2)]);
+        assert_eq!(analysis.critical_drones, [1, 2]);
+        assert!(!analysis.is_redundant());
+
+        // Linking drone 1 to drone 3 leaves the client as the only weak spot.
+        config.drone[0].connected_node_ids.push(3);
+        config.drone[2].connected_node_ids.push(1);
+        let analysis = analyze_topology(&config);
+        assert!(analysis.cut_drones.is_empty());
+        assert!(analysis.bridges.is_empty());
+        assert_eq!(analysis.critical_drones, [1]);
+    }
+
+    #[test]
+    fn test_color_drones() {
+        let config = generate_small_world(12, 4, 0.3, 2, 2, 5).unwrap();
+        let colors = color_drones(&config);
+        assert_eq!(colors.len(), 12);
+        for drone in &config.drone {
+            for neighbor in &drone.connected_node_ids {
+                assert_ne!(colors.get(&drone.id), colors.get(neighbor));
+            }
+        }
+
+        // A ring of six drones is bipartite, but a triangle is not.
+        let ring = generate_small_world(6, 2, 0.0, 1, 1, 1).unwrap();
+        let colors = color_drones_with(&ring, 2).unwrap();
+        assert!(colors.values().all(|color| *color < 2));
+        let triangle = generate_small_world(3, 2, 0.0, 1, 1, 1).unwrap();
+        assert!(color_drones_with(&triangle, 2).is_err());
+        assert_eq!(color_drones_with(&triangle, 3).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_rank_drones() {
+        // A chain of drones 1-2-3, with client 10 on drone 1 and server 20 on drone 3.
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2, 10],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1, 3],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 3,
+                connected_node_ids: vec![2, 20],
+                pdr: 0.0,
+            },
+        ];
+        let config = Config {
+            drone,
+            client: vec![Client {
+                id: 10,
+                connected_drone_ids: vec![1],
+            }],
+            server: vec![Server {
+                id: 20,
+                connected_drone_ids: vec![3],
+            }],
+        };
+
+        let degree = rank_drones(&config, Centrality::Degree);
+        assert_eq!(degree, vec![(1, 2.0), (2, 2.0), (3, 2.0)]);
+        // Drone 2 is on the paths of 4 pairs, drone 1 on the paths of 3.
+        let betweenness = rank_drones(&config, Centrality::Betweenness);
+        assert_eq!(betweenness, vec![(2, 4.0), (1, 3.0), (3, 3.0)]);
+        let closeness = rank_drones(&config, Centrality::Closeness);
+        assert_eq!(closeness[0], (2, 4.0 / 6.0));
+    }
+
+    #[test]
+    fn test_k_best_paths() {
+        // Client 10 reaches server 20 through drone 1, drone 2, or both.
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2, 10, 20],
+                pdr: 0.5,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1, 10, 20],
+                pdr: 0.1,
+            },
+        ];
+        let config = Config {
+            drone,
+            client: vec![Client {
+                id: 10,
+                connected_drone_ids: vec![1, 2],
+            }],
+            server: vec![Server {
+                id: 20,
+                connected_drone_ids: vec![1, 2],
+            }],
+        };
+
+        let paths = k_best_paths(&config, 10, 20, 5);
+        let hops: Vec<&[u8]> = paths.iter().map(|path| path.hops.as_slice()).collect();
+        assert_eq!(
+            hops,
+            vec![
+                &[10, 2, 20][..],
+                &[10, 1, 20],
+                &[10, 1, 2, 20],
+                &[10, 2, 1, 20]
+            ]
+        );
+        assert!((paths[0].delivery - 0.9).abs() < 1e-6);
+        assert!((paths[2].delivery - 0.45).abs() < 1e-6);
+        assert_eq!(k_best_paths(&config, 10, 20, 1).len(), 1);
+        assert!(k_best_paths(&config, 10, 99, 3).is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::index::NODE_CAPACITY;
+use crate::model::{Attributes, Topology};
+
+/// The correspondence between the original IDs of a topology and the anonymized ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdMapping {
+    anonymized: BTreeMap<NodeId, NodeId>,
+}
+
+impl IdMapping {
+    /// Returns a mapping from the given IDs to random distinct IDs below [`NODE_CAPACITY`].
+    fn random(ids: impl IntoIterator<Item = NodeId>, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut ids: Vec<NodeId> = ids.into_iter().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let max_id = if ids.len() <= NODE_CAPACITY {
+            NODE_CAPACITY
+        } else {
+            NodeId::MAX as usize + 1
+        };
+        let mut free: Vec<NodeId> = (0..max_id).map(|id| id as NodeId).collect();
+        free.shuffle(&mut rng);
+        Self {
+            anonymized: ids.into_iter().zip(free).collect(),
+        }
+    }
+
+    /// Returns the anonymized ID of a node, given its original ID.
+    pub fn anonymized(&self, original: NodeId) -> Option<NodeId> {
+        self.anonymized.get(&original).copied()
+    }
+
+    /// Returns the original ID of a node, given its anonymized ID.
+    pub fn original(&self, anonymized: NodeId) -> Option<NodeId> {
+        self.anonymized
+            .iter()
+            .find(|(_, id)| **id == anonymized)
+            .map(|(original, _)| *original)
+    }
+
+    /// Returns every pair of original and anonymized IDs, by original ID.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.anonymized
+            .iter()
+            .map(|(original, anonymized)| (*original, *anonymized))
+    }
+
+    /// Returns the anonymized ID of a node known to the mapping.
+    fn map(&self, original: NodeId) -> NodeId {
+        self.anonymized[&original]
+    }
+}
+
+/// Anonymizes a configuration, so that it can be shared publicly without revealing which
+/// nodes of which group map where.
+///
+/// Every ID, including the ones of the neighbors which are not defined, is replaced by a
+/// random distinct ID, the nodes of every type are shuffled and the neighbors are sorted;
+/// the structure and the packet drop rates are preserved, as are any inconsistencies.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns the anonymized configuration, and the mapping needed to reverse the
+/// anonymization, which must be kept private.
+pub fn anonymize(config: &Config, seed: u64) -> (Config, IdMapping) {
+    let ids = (config.drone.iter())
+        .flat_map(|drone| std::iter::once(drone.id).chain(drone.connected_node_ids.clone()))
+        .chain(config.client.iter().flat_map(|client| {
+            std::iter::once(client.id).chain(client.connected_drone_ids.clone())
+        }))
+        .chain(config.server.iter().flat_map(|server| {
+            std::iter::once(server.id).chain(server.connected_drone_ids.clone())
+        }));
+    let mapping = IdMapping::random(ids, seed);
+    let remap = |ids: &[NodeId]| {
+        let mut ids: Vec<NodeId> = ids.iter().map(|id| mapping.map(*id)).collect();
+        ids.sort_unstable();
+        ids
+    };
+
+    let mut anonymized = config.clone();
+    for drone in &mut anonymized.drone {
+        drone.id = mapping.map(drone.id);
+        drone.connected_node_ids = remap(&drone.connected_node_ids);
+    }
+    for client in &mut anonymized.client {
+        client.id = mapping.map(client.id);
+        client.connected_drone_ids = remap(&client.connected_drone_ids);
+    }
+    for server in &mut anonymized.server {
+        server.id = mapping.map(server.id);
+        server.connected_drone_ids = remap(&server.connected_drone_ids);
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    anonymized.drone.shuffle(&mut rng);
+    anonymized.client.shuffle(&mut rng);
+    anonymized.server.shuffle(&mut rng);
+    (anonymized, mapping)
+}
+
+/// Anonymizes a topology as [`anonymize`] does, also stripping the attributes of the nodes,
+/// e.g. their names, zones and source files.
+///
+/// # Parameters
+/// - `topology`: The topology.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns the anonymized topology, and the mapping needed to reverse the anonymization.
+pub fn anonymize_topology(topology: &Topology, seed: u64) -> (Topology, IdMapping) {
+    let ids = (topology.nodes.iter().map(|node| node.id))
+        .chain(topology.edges.iter().flat_map(|(from, to)| [*from, *to]));
+    let mapping = IdMapping::random(ids, seed);
+
+    let mut anonymized = topology.clone();
+    for node in &mut anonymized.nodes {
+        node.id = mapping.map(node.id);
+        node.attributes = Attributes::new();
+    }
+    for (from, to) in &mut anonymized.edges {
+        *from = mapping.map(*from);
+        *to = mapping.map(*to);
+    }
+    anonymized.nodes.shuffle(&mut StdRng::seed_from_u64(seed));
+    anonymized.edges.sort_unstable();
+    (anonymized, mapping)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::is_isomorphic;
+    use crate::anonymize::{anonymize, anonymize_topology};
+    use crate::generator::generate_small_world;
+    use crate::model::Topology;
+    use crate::validate::validate_config;
+
+    #[test]
+    fn test_anonymize() {
+        let config = generate_small_world(12, 4, 0.2, 3, 2, 7).unwrap();
+        let (anonymized, mapping) = anonymize(&config, 42);
+        assert!(validate_config(&anonymized).is_ok());
+        assert!(is_isomorphic(&config, &anonymized));
+        assert_eq!(mapping.iter().count(), 17);
+        for drone in &config.drone {
+            let id = mapping.anonymized(drone.id).unwrap();
+            assert_eq!(mapping.original(id), Some(drone.id));
+            let twin = anonymized
+                .drone
+                .iter()
+                .find(|other| other.id == id)
+                .unwrap();
+            assert_eq!(twin.pdr, drone.pdr);
+        }
+        assert_eq!(anonymize(&config, 42).1, mapping);
+        assert_ne!(anonymize(&config, 43).1, mapping);
+
+        let mut topology = Topology::from(&config);
+        topology.nodes[0]
+            .attributes
+            .insert("name".to_string(), "group 7".to_string());
+        let (anonymized, _) = anonymize_topology(&topology, 42);
+        assert!(anonymized
+            .nodes
+            .iter()
+            .all(|node| node.attributes.is_empty()));
+        assert_eq!(anonymized.links().len(), topology.links().len());
+    }
+}
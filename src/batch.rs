@@ -0,0 +1,173 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use wg_2024::config::Config;
+
+use crate::handle::{RunSummary, ShutdownPolicy};
+use crate::init::{try_network_init_validated, InitOptions};
+use crate::scenario::Scenario;
+use crate::validate::ValidatedConfig;
+
+/// Options of [`run_batch`].
+#[derive(Clone, Copy, Debug)]
+pub struct BatchOptions {
+    /// The options used to initialize every network.
+    pub init: InitOptions,
+    /// The maximum number of networks running at the same time; `1` runs them sequentially.
+    pub parallelism: usize,
+    /// How every network is torn down once its scenario is over.
+    pub shutdown: ShutdownPolicy,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            init: InitOptions::default(),
+            parallelism: 1,
+            shutdown: ShutdownPolicy::default(),
+        }
+    }
+}
+
+/// Validates, initializes, runs a scenario on, and tears down the network of every
+/// configuration file, e.g. to sweep over many generated topologies in one command.
+///
+/// # Parameters
+/// - `configs`: The paths of the configuration files.
+/// - `scenario`: Builds the scenario to run on the network of a configuration.
+/// - `options`: The options of the batch.
+///
+/// Returns the summary of every run, taken after the network was torn down, in the order of
+/// `configs`, or an error if the configuration is invalid or its network could not be
+/// initialized.
+pub fn run_batch<S>(
+    configs: &[PathBuf],
+    scenario: S,
+    options: &BatchOptions,
+) -> Vec<Result<RunSummary, String>>
+where
+    S: Fn(&Config) -> Scenario + Sync,
+{
+    let results = Mutex::new(vec![None; configs.len()]);
+    let next = AtomicUsize::new(0);
+    let workers = options.parallelism.clamp(1, configs.len().max(1));
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(path) = configs.get(index) else {
+                    break;
+                };
+                let result = run_one(path, &scenario, options);
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.expect("Every configuration is run by some worker"))
+        .collect()
+}
+
+/// Runs the network of a single configuration file, as described by [`run_batch`].
+fn run_one<S>(path: &Path, scenario: &S, options: &BatchOptions) -> Result<RunSummary, String>
+where
+    S: Fn(&Config) -> Scenario,
+{
+    let file_path = path
+        .to_str()
+        .ok_or_else(|| format!("Invalid configuration path: {}", path.display()))?;
+    let config =
+        ValidatedConfig::from_file(file_path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    run_config(&config, scenario, &options.init, options.shutdown)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Initializes the network of a validated configuration, runs a scenario on it, and tears it
+/// down.
+///
+/// Returns the summary of the run, taken after the network was torn down, or an error if the
+/// network could not be initialized.
+pub(crate) fn run_config<S>(
+    config: &ValidatedConfig,
+    scenario: &S,
+    init: &InitOptions,
+    shutdown: ShutdownPolicy,
+) -> Result<RunSummary, String>
+where
+    S: Fn(&Config) -> Scenario,
+{
+    let init = try_network_init_validated(config, init).map_err(|e| e.to_string())?;
+
+    scenario(config.config()).run(&init.handle);
+    init.shutdown(shutdown);
+    Ok(init.handle.summary())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::batch::{run_batch, BatchOptions};
+    use crate::handle::ThreadState;
+    use crate::init::InitOptions;
+    use crate::scenario::Scenario;
+    use crate::stub::{echo_server_factory, null_drone_factory, ping_client_factory};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.1
+
+        [[drone]]
+        id = 4
+        connected_node_ids = [1, 3]
+        pdr = 0.1
+
+        [[client]]
+        id = 2
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 3
+        connected_drone_ids = [1, 4]
+    "#;
+
+    #[test]
+    fn test_run_batch() {
+        let valid = env::temp_dir().join(format!("batch_{}.toml", std::process::id()));
+        fs::write(&valid, CONFIG).unwrap();
+        let missing = PathBuf::from("missing.toml");
+        let options = BatchOptions {
+            init: InitOptions {
+                drone_factory: null_drone_factory,
+                client_factory: ping_client_factory,
+                server_factory: echo_server_factory,
+                ..InitOptions::default()
+            },
+            parallelism: 2,
+            ..BatchOptions::default()
+        };
+
+        let configs = [valid.clone(), missing, valid.clone()];
+        let summaries = run_batch(&configs, Scenario::crash_critical_path, &options);
+        fs::remove_file(valid).unwrap();
+
+        assert_eq!(summaries.len(), 3);
+        assert!(summaries[1].is_err());
+        for summary in [&summaries[0], &summaries[2]] {
+            let termination = &summary.as_ref().unwrap().termination;
+            assert_eq!(termination.nodes.len(), 4);
+            assert!(termination.with_state(ThreadState::Running).is_empty());
+        }
+    }
+}
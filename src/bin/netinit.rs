@@ -0,0 +1,347 @@
+//! Command line front-end of the network initializer.
+//!
+//! ```text
+//! netinit validate <FILE> [--dot <OUT>]
+//! netinit validate <DIR>
+//! netinit validate --example <NAME> [--dot <OUT>]
+//! ```
+//!
+//! Validates a configuration file, or one of the configurations embedded in the crate, and
+//! exits with a non-zero status if it is invalid. With `--dot`, the topology is also rendered
+//! as a Graphviz graph, even if it is invalid, to inspect the violations by eye.
+//!
+//! Given a directory, validates every `*.toml` file in it, printing one result per file, and
+//! exits with a non-zero status if any of them is invalid.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+};
+
+use network_initializer::prelude::{
+    example, example_names, network_validate_many, network_validate_str, topology_to_dot,
+};
+use wg_2024::config::Config;
+
+/// The usage, printed when the arguments cannot be parsed.
+const USAGE: &str = "Usage: netinit validate <FILE> [--dot <OUT>]\n       \
+                     netinit validate <DIR>\n       \
+                     netinit validate --example <NAME> [--dot <OUT>]";
+
+/// Where the configuration to validate comes from.
+#[derive(Debug, PartialEq, Eq)]
+enum Source {
+    /// A configuration file, or a directory of configuration files, by path.
+    File(String),
+    /// A configuration embedded in the crate, by name, see [`example`].
+    Example(String),
+}
+
+/// The arguments of the `validate` command.
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    /// The configuration to validate.
+    source: Source,
+    /// The path of the Graphviz file to write, if any.
+    dot: Option<String>,
+}
+
+/// Parses the arguments of the `validate` command.
+///
+/// # Parameters
+/// - `args`: The arguments, without the name of the program.
+///
+/// Returns the parsed arguments, or the usage if some argument is missing or unexpected.
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let Some((command, mut rest)) = args.split_first() else {
+        return Err(USAGE.to_string());
+    };
+    if command != "validate" {
+        return Err(USAGE.to_string());
+    }
+    let mut source = None;
+    let mut dot = None;
+    while let Some((arg, tail)) = rest.split_first() {
+        rest = tail;
+        // Every option may only be given once, and takes a value.
+        let repeated = match arg.as_str() {
+            "--dot" | "--example" => {
+                let (value, tail) = rest.split_first().ok_or_else(|| USAGE.to_string())?;
+                rest = tail;
+                if arg == "--dot" {
+                    dot.replace(value.clone()).is_some()
+                } else {
+                    source.replace(Source::Example(value.clone())).is_some()
+                }
+            }
+            path if !path.starts_with("--") => {
+                source.replace(Source::File(path.to_string())).is_some()
+            }
+            _ => true,
+        };
+        if repeated {
+            return Err(USAGE.to_string());
+        }
+    }
+    match source {
+        Some(source) => Ok(Args { source, dot }),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+/// Reads the configuration to validate.
+///
+/// Returns the TOML of the configuration, or an error if the file cannot be read or there is
+/// no example with the given name.
+fn read_source(source: &Source) -> Result<String, String> {
+    match source {
+        Source::File(path) => {
+            fs::read_to_string(path).map_err(|_| "Unable to read configuration file".to_string())
+        }
+        Source::Example(name) => example(name).map(str::to_string).ok_or_else(|| {
+            let names: Vec<&str> = example_names().collect();
+            format!(
+                "Unknown example {}, expected one of {}",
+                name,
+                names.join(", ")
+            )
+        }),
+    }
+}
+
+/// Renders the topology of a configuration, valid or not, to a Graphviz file.
+///
+/// # Parameters
+/// - `config_data`: The TOML of the configuration.
+/// - `path`: The path of the Graphviz file.
+///
+/// Returns an error if the configuration cannot be deserialized or the file cannot be written.
+fn write_dot(config_data: &str, path: &str) -> Result<(), String> {
+    let config: Config =
+        toml::from_str(config_data).map_err(|e| format!("Failed to deserialize TOML: {}", e))?;
+    fs::write(path, topology_to_dot(&config)).map_err(|_| "Unable to write DOT file".to_string())
+}
+
+/// Validates the configuration, writing its topology first if requested.
+fn run(args: &Args) -> Result<Config, String> {
+    let config_data = read_source(&args.source)?;
+    if let Some(path) = &args.dot {
+        write_dot(&config_data, path)?;
+    }
+    network_validate_str(&config_data)
+}
+
+/// The outcome of the validation of a file of a directory: its path, and its configuration or
+/// its errors.
+type FileResult = (PathBuf, Result<Config, String>);
+
+/// Validates every `*.toml` file of a directory, in the order of their paths.
+///
+/// # Parameters
+/// - `dir`: The path of the directory.
+///
+/// Returns the outcome of every file, as [`network_validate_many`] with the errors joined into
+/// a single message, or an error if the directory cannot be read or has no configuration file.
+fn run_dir(dir: &Path) -> Result<Vec<FileResult>, String> {
+    let entries = fs::read_dir(dir).map_err(|_| "Unable to read configuration directory")?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|_| "Unable to read configuration directory")?
+            .path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension == "toml")
+        {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        return Err(format!("No configuration file in {}", dir.display()));
+    }
+    paths.sort();
+    let parallelism = thread::available_parallelism().map_or(1, usize::from);
+    Ok(network_validate_many(&paths, parallelism)
+        .into_iter()
+        .map(|(path, result)| {
+            let result = result
+                .map(|validated| validated.into_config())
+                .map_err(|report| {
+                    let messages: Vec<String> =
+                        report.errors.iter().map(ToString::to_string).collect();
+                    messages.join("; ")
+                });
+            (path, result)
+        })
+        .collect())
+}
+
+/// Describes a valid configuration.
+fn summary(config: &Config) -> String {
+    format!(
+        "Valid configuration: {} drones, {} clients, {} servers",
+        config.drone.len(),
+        config.client.len(),
+        config.server.len()
+    )
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let dir = match &args.source {
+        Source::File(path) if Path::new(path).is_dir() => Some(Path::new(path)),
+        _ => None,
+    };
+    let Some(dir) = dir else {
+        return match run(&args) {
+            Ok(config) => {
+                println!("{}", summary(&config));
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("{}", error);
+                ExitCode::FAILURE
+            }
+        };
+    };
+    if args.dot.is_some() {
+        eprintln!("--dot requires a single configuration\n{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+    match run_dir(dir) {
+        Ok(results) => {
+            let mut valid = true;
+            for (path, result) in results {
+                match result {
+                    Ok(config) => println!("{}: {}", path.display(), summary(&config)),
+                    Err(error) => {
+                        valid = false;
+                        println!("{}: {}", path.display(), error);
+                    }
+                }
+            }
+            if valid {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use network_initializer::prelude::example;
+
+    use crate::{parse_args, read_source, run, run_dir, write_dot, Args, Source};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let file = |path: &str| Source::File(path.to_string());
+        let example = |name: &str| Source::Example(name.to_string());
+        assert_eq!(
+            parse_args(&args(&["validate", "config.toml"])),
+            Ok(Args {
+                source: file("config.toml"),
+                dot: None
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&["validate", "--example", "star10"])),
+            Ok(Args {
+                source: example("star10"),
+                dot: None
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&["validate", "--dot", "out.dot", "config.toml"])),
+            Ok(Args {
+                source: file("config.toml"),
+                dot: Some("out.dot".to_string())
+            })
+        );
+        assert!(parse_args(&args(&["validate"])).is_err());
+        assert!(parse_args(&args(&["validate", "--example"])).is_err());
+        assert!(parse_args(&args(&["validate", "config.toml", "--dot"])).is_err());
+        assert!(parse_args(&args(&["validate", "a.toml", "--example", "star10"])).is_err());
+        assert!(parse_args(&args(&["check", "config.toml"])).is_err());
+    }
+
+    #[test]
+    fn test_write_dot() {
+        let dir = env::temp_dir();
+        let config_path = dir.join(format!("netinit_{}.toml", std::process::id()));
+        let dot_path = dir.join(format!("netinit_{}.dot", std::process::id()));
+        // A one-way link fails the validation, but is still rendered.
+        let config_data = example("star10").unwrap().replacen("[1, 11]", "[1]", 1);
+        fs::write(&config_path, config_data).unwrap();
+        let result = run(&Args {
+            source: Source::File(config_path.to_str().unwrap().to_string()),
+            dot: Some(dot_path.to_str().unwrap().to_string()),
+        });
+        let dot = fs::read_to_string(&dot_path).unwrap();
+        fs::remove_file(&config_path).unwrap();
+        fs::remove_file(&dot_path).unwrap();
+        assert!(result.is_err());
+        assert!(dot.contains("one-way"));
+
+        let error = write_dot("[[drone]]", dot_path.to_str().unwrap()).unwrap_err();
+        assert!(error.starts_with("Failed to deserialize TOML"));
+    }
+
+    #[test]
+    fn test_read_example() {
+        assert!(read_source(&Source::Example("star10".to_string())).is_ok());
+        let error = read_source(&Source::Example("star11".to_string())).unwrap_err();
+        assert!(error.starts_with("Unknown example star11"));
+    }
+
+    #[test]
+    fn test_run_dir() {
+        let dir = env::temp_dir().join(format!("netinit_dir_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_data = example("star10").unwrap();
+        fs::write(dir.join("b_valid.toml"), config_data).unwrap();
+        fs::write(
+            dir.join("a_invalid.toml"),
+            config_data.replacen("[1, 11]", "[1]", 1),
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "not a configuration").unwrap();
+        let results = run_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let names: Vec<_> = results
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["a_invalid.toml", "b_valid.toml"]);
+        assert!(results[0].1.is_err());
+        assert!(results[1].1.is_ok());
+
+        let empty = env::temp_dir().join(format!("netinit_empty_{}", std::process::id()));
+        fs::create_dir_all(&empty).unwrap();
+        let error = run_dir(&empty).unwrap_err();
+        fs::remove_dir_all(&empty).unwrap();
+        assert!(error.starts_with("No configuration file"));
+    }
+}
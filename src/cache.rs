@@ -0,0 +1,280 @@
+use std::{collections::BTreeMap, fs};
+
+use rust_roveri_api::DroneImpl;
+use wg_2024::{
+    config::{Client, Config, Drone, Server},
+    network::NodeId,
+};
+
+use crate::distribution::impl_code;
+use crate::validate::{source_hash, ValidatedConfig};
+
+/// Magic bytes at the start of every cache file.
+const CACHE_MAGIC: &[u8; 4] = b"NICF";
+
+/// Version of the encoding, bumped whenever the layout of cache files changes.
+const CACHE_VERSION: u8 = 2;
+
+/// The implementation code of a drone which does not choose its implementation.
+const NO_IMPL: u8 = u8::MAX;
+
+impl ValidatedConfig {
+    /// Writes the configuration to a cache file, in a compact binary encoding, along with the
+    /// implementations chosen by its drones and the hash of the file it was read from.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the cache file.
+    ///
+    /// Returns an error if the configuration was not read with [`ValidatedConfig::from_file`],
+    /// or the cache file cannot be written.
+    pub fn save_cache(&self, path: &str) -> Result<(), String> {
+        let hash = self
+            .source_hash
+            .ok_or_else(|| "The configuration was not read from a file".to_string())?;
+        let config = self.config();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(CACHE_MAGIC);
+        data.push(CACHE_VERSION);
+        data.extend_from_slice(&hash.to_le_bytes());
+        encode_len(&mut data, config.drone.len())?;
+        for drone in &config.drone {
+            data.push(drone.id);
+            data.extend_from_slice(&drone.pdr.to_le_bytes());
+            encode_ids(&mut data, &drone.connected_node_ids)?;
+            let code = match self.drone_impls().get(&drone.id) {
+                Some(drone_impl) => impl_code(*drone_impl)
+                    .and_then(|code| u8::try_from(code).ok())
+                    .filter(|code| *code != NO_IMPL)
+                    .ok_or_else(|| "Unable to cache the implementation of a drone".to_string())?,
+                None => NO_IMPL,
+            };
+            data.push(code);
+        }
+        encode_len(&mut data, config.client.len())?;
+        for client in &config.client {
+            data.push(client.id);
+            encode_ids(&mut data, &client.connected_drone_ids)?;
+        }
+        encode_len(&mut data, config.server.len())?;
+        for server in &config.server {
+            data.push(server.id);
+            encode_ids(&mut data, &server.connected_drone_ids)?;
+        }
+
+        fs::write(path, data).map_err(|_| "Unable to write cache file".to_string())
+    }
+
+    /// Reads a configuration from a cache file, skipping parsing and validation.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the cache file.
+    /// - `source_path`: The path of the configuration file the cache was created from.
+    ///
+    /// Returns the cached configuration, or an error if the cache is unreadable, corrupted,
+    /// or stale because the configuration file changed.
+    pub fn load_cache(path: &str, source_path: &str) -> Result<Self, String> {
+        let source =
+            fs::read(source_path).map_err(|_| "Unable to read configuration file".to_string())?;
+        let data = fs::read(path).map_err(|_| "Unable to read cache file".to_string())?;
+        let hash = source_hash(&source);
+
+        let mut reader = Reader(&data);
+        if reader.take(CACHE_MAGIC.len())? != CACHE_MAGIC || reader.byte()? != CACHE_VERSION {
+            return Err("Unsupported cache file".to_string());
+        }
+        if reader.u64()? != hash {
+            return Err("The cache is stale".to_string());
+        }
+        let mut drone_impls = BTreeMap::new();
+        let drone = (0..reader.len()?)
+            .map(|_| {
+                let drone = Drone {
+                    id: reader.byte()?,
+                    pdr: reader.f32()?,
+                    connected_node_ids: reader.ids()?,
+                };
+                match reader.byte()? {
+                    NO_IMPL => {}
+                    code => {
+                        let drone_impl = DroneImpl::from_code(usize::from(code))
+                            .ok_or_else(|| "Corrupted cache file".to_string())?;
+                        drone_impls.insert(drone.id, drone_impl);
+                    }
+                }
+                Ok(drone)
+            })
+            .collect::<Result<_, String>>()?;
+        let client = (0..reader.len()?)
+            .map(|_| {
+                Ok(Client {
+                    id: reader.byte()?,
+                    connected_drone_ids: reader.ids()?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        let server = (0..reader.len()?)
+            .map(|_| {
+                Ok(Server {
+                    id: reader.byte()?,
+                    connected_drone_ids: reader.ids()?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        if !reader.0.is_empty() {
+            return Err("Corrupted cache file".to_string());
+        }
+
+        Ok(Self::new_unchecked(
+            Config {
+                drone,
+                client,
+                server,
+            },
+            drone_impls,
+            Some(hash),
+        ))
+    }
+}
+
+/// Reads and validates a configuration file through a cache.
+///
+/// The cache is used if it was created from the current contents of the file; otherwise the
+/// file is parsed and validated, and the cache is rewritten.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+/// - `cache_path`: The path of the cache file.
+///
+/// Returns the validated configuration, or an error if the file is unreadable or invalid.
+pub fn network_validate_cached(
+    file_path: &str,
+    cache_path: &str,
+) -> Result<ValidatedConfig, String> {
+    if let Ok(config) = ValidatedConfig::load_cache(cache_path, file_path) {
+        return Ok(config);
+    }
+    let config = ValidatedConfig::from_file(file_path)?;
+    // A cache which cannot be written only costs a parse on the next run.
+    let _ = config.save_cache(cache_path);
+    Ok(config)
+}
+
+/// Appends a length, as a 16-bit little-endian integer.
+fn encode_len(data: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    let len = u16::try_from(len).map_err(|_| "Too many nodes to cache".to_string())?;
+    data.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+/// Appends a list of node IDs, prefixed by its length.
+fn encode_ids(data: &mut Vec<u8>, ids: &[NodeId]) -> Result<(), String> {
+    encode_len(data, ids.len())?;
+    data.extend_from_slice(ids);
+    Ok(())
+}
+
+/// Cursor over the contents of a cache file.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < n {
+            return Err("Corrupted cache file".to_string());
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        let bytes = self.take(2)?;
+        Ok(usize::from(u16::from_le_bytes([bytes[0], bytes[1]])))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(f32::from_le_bytes(bytes))
+    }
+
+    fn ids(&mut self) -> Result<Vec<NodeId>, String> {
+        let len = self.len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use rust_roveri_api::DroneImpl;
+
+    use crate::cache::network_validate_cached;
+    use crate::validate::ValidatedConfig;
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.1
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 3, 4]
+        pdr = 0.25
+        impl = 1
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1, 2]
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [1, 2]
+    "#;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = env::temp_dir();
+        let source = dir.join(format!("cache_{}.toml", std::process::id()));
+        let cache = dir.join(format!("cache_{}.bin", std::process::id()));
+        let (source, cache) = (source.to_str().unwrap(), cache.to_str().unwrap());
+        fs::write(source, CONFIG).unwrap();
+
+        let config = network_validate_cached(source, cache).unwrap();
+        let cached = ValidatedConfig::load_cache(cache, source).unwrap();
+        assert_eq!(cached.config().drone[1].pdr, 0.25);
+        assert_eq!(cached.config().server[0].connected_drone_ids, vec![1, 2]);
+        assert_eq!(
+            format!("{:?}", cached.config()),
+            format!("{:?}", config.config())
+        );
+        assert_eq!(cached.drone_impls(), config.drone_impls());
+        assert_eq!(
+            cached.drone_impls().get(&2),
+            DroneImpl::from_code(1).as_ref()
+        );
+
+        fs::write(source, CONFIG.replace("0.25", "0.5")).unwrap();
+        assert_eq!(
+            ValidatedConfig::load_cache(cache, source).map(|_| ()),
+            Err("The cache is stale".to_string())
+        );
+        let config = network_validate_cached(source, cache).unwrap();
+        assert_eq!(config.config().drone[1].pdr, 0.5);
+
+        fs::remove_file(source).unwrap();
+        fs::remove_file(cache).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+/// A channel whose length can be read whatever the type of its items.
+pub(crate) trait Queue: Debug + Send + Sync {
+    fn len(&self) -> usize;
+    fn capacity(&self) -> Option<usize>;
+}
+
+impl<T: Send> Queue for Sender<T> {
+    fn len(&self) -> usize {
+        Sender::len(self)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Sender::capacity(self)
+    }
+}
+
+impl<T: Send> Queue for Receiver<T> {
+    fn len(&self) -> usize {
+        Receiver::len(self)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Receiver::capacity(self)
+    }
+}
+
+/// A channel registered under a name.
+type Named = (String, Box<dyn Queue>);
+
+/// A named channel of a network, as listed by [`crate::handle::NetworkHandle::channels`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelInfo {
+    /// The name of the channel, e.g. `pkt 11` or `cmd drone 7`.
+    pub name: String,
+    /// The number of messages waiting in the channel.
+    pub len: usize,
+    /// The capacity of the channel, or `None` if it is unbounded.
+    pub capacity: Option<usize>,
+}
+
+/// A debug registry of the channels of a network, tagged with human-readable names so that
+/// profiles and queue-depth dashboards can tell them apart.
+///
+/// The registry keeps a clone of one end of every channel, so it only registers ends which are
+/// kept alive by the handle anyway. Clones share the same channels.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChannelRegistry(Arc<Mutex<Vec<Named>>>);
+
+impl ChannelRegistry {
+    /// Registers a channel under a name.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the channel.
+    /// - `queue`: One end of the channel.
+    pub(crate) fn register(&self, name: String, queue: impl Queue + 'static) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, Box::new(queue)));
+    }
+
+    /// Creates a channel, bounded to the given capacity if any, and registers its sender under
+    /// a name.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the channel.
+    /// - `capacity`: The capacity of the channel, or `None` for an unbounded channel.
+    pub(crate) fn channel<T: Send + 'static>(
+        &self,
+        name: String,
+        capacity: Option<usize>,
+    ) -> (Sender<T>, Receiver<T>) {
+        let (sender, receiver) = match capacity {
+            Some(capacity) => crossbeam_channel::bounded(capacity),
+            None => crossbeam_channel::unbounded(),
+        };
+        self.register(name, sender.clone());
+        (sender, receiver)
+    }
+
+    /// Returns the current state of every channel, in registration order.
+    pub(crate) fn channels(&self) -> Vec<ChannelInfo> {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, queue)| ChannelInfo {
+                name: name.clone(),
+                len: queue.len(),
+                capacity: queue.capacity(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::channels::{ChannelInfo, ChannelRegistry};
+
+    #[test]
+    fn test_channel_registry() {
+        let registry = ChannelRegistry::default();
+        let (sender, _receiver) = registry.channel::<u8>("cmd drone 7".to_string(), Some(2));
+        let (other, receiver) = crossbeam_channel::unbounded::<u8>();
+        registry.register("evt drone 7".to_string(), receiver);
+
+        sender.send(1).unwrap();
+        other.send(1).unwrap();
+        other.send(2).unwrap();
+        assert_eq!(
+            registry.channels(),
+            vec![
+                ChannelInfo {
+                    name: "cmd drone 7".to_string(),
+                    len: 1,
+                    capacity: Some(2),
+                },
+                ChannelInfo {
+                    name: "evt drone 7".to_string(),
+                    len: 2,
+                    capacity: None,
+                },
+            ]
+        );
+    }
+}
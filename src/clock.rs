@@ -0,0 +1,106 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The slowest supported time scale.
+pub const MIN_TIME_SCALE: f64 = 0.1;
+
+/// The fastest supported time scale.
+pub const MAX_TIME_SCALE: f64 = 10.0;
+
+/// Longest wall-clock sleep between two checks of the time scale, so that a change of scale
+/// takes effect on the sleeping threads.
+const SCALE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The last change of scale of a clock.
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    /// When the scale was changed.
+    wall: Instant,
+    /// The simulated time when the scale was changed.
+    simulated: Duration,
+    /// How many simulated seconds elapse every wall-clock second.
+    scale: f64,
+}
+
+/// Virtual clock of a network, running at an adjustable multiple of the wall clock.
+///
+/// Clones share the same time and scale.
+#[derive(Debug, Clone)]
+pub(crate) struct SimClock(Arc<Mutex<Anchor>>);
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Anchor {
+            wall: Instant::now(),
+            simulated: Duration::ZERO,
+            scale: 1.0,
+        })))
+    }
+}
+
+impl SimClock {
+    /// Returns the simulated time elapsed since the clock was created.
+    pub(crate) fn now(&self) -> Duration {
+        let anchor = *self.0.lock().unwrap_or_else(|e| e.into_inner());
+        anchor.simulated + anchor.wall.elapsed().mul_f64(anchor.scale)
+    }
+
+    /// Returns the current time scale.
+    pub(crate) fn scale(&self) -> f64 {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).scale
+    }
+
+    /// Changes the time scale, without changing the simulated time elapsed so far.
+    ///
+    /// Returns `false`, leaving the scale unchanged, if the scale is out of
+    /// `MIN_TIME_SCALE..=MAX_TIME_SCALE`.
+    pub(crate) fn set_scale(&self, scale: f64) -> bool {
+        if !(MIN_TIME_SCALE..=MAX_TIME_SCALE).contains(&scale) {
+            return false;
+        }
+        let mut anchor = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let wall = Instant::now();
+        let elapsed = wall.duration_since(anchor.wall).mul_f64(anchor.scale);
+        *anchor = Anchor {
+            wall,
+            simulated: anchor.simulated + elapsed,
+            scale,
+        };
+        true
+    }
+
+    /// Blocks the current thread until the simulated time reaches `at`.
+    pub(crate) fn sleep_until(&self, at: Duration) {
+        loop {
+            let now = self.now();
+            if now >= at {
+                return;
+            }
+            let wall = (at - now).div_f64(self.scale());
+            thread::sleep(wall.min(SCALE_POLL_INTERVAL));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::clock::{SimClock, MAX_TIME_SCALE};
+
+    #[test]
+    fn test_time_scale() {
+        let clock = SimClock::default();
+        assert!(!clock.set_scale(MAX_TIME_SCALE * 2.0));
+        assert!(!clock.set_scale(f64::NAN));
+        assert!(clock.set_scale(MAX_TIME_SCALE));
+
+        let start = Instant::now();
+        clock.sleep_until(Duration::from_millis(500));
+        assert!(start.elapsed() < Duration::from_millis(250));
+        assert!(clock.now() >= Duration::from_millis(500));
+    }
+}
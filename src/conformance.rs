@@ -0,0 +1,515 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crossbeam_channel::select;
+use rust_roveri_api::DroneImpl;
+use wg_2024::{
+    network::NodeId,
+    packet::{NackType, Packet, PacketType},
+};
+
+use crate::distribution::DistributionPlan;
+use crate::events::{NodeEvent, ObservedEvent};
+use crate::handle::NetworkHandle;
+use crate::tap::TappedPacket;
+use crate::topology::TopologyUpdate;
+
+/// A protocol invariant checked by the [`ConformanceMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A routed packet must be sent with its hop index pointing at the receiver.
+    HopIndexIncrement,
+    /// A routed packet must only be sent to a node of its route.
+    OnRoute,
+    /// An initiator must not reuse a flood ID for a different flood.
+    UniqueFloodId,
+    /// An ACK or NACK must travel back along the route of the fragment it refers to.
+    ReverseRoute,
+}
+
+/// A violation of the protocol, attributed to the node which committed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolViolation {
+    /// The ID of the offending node.
+    pub node: NodeId,
+    /// The implementation of the offending node, if it is a drone.
+    pub drone_impl: Option<DroneImpl>,
+    /// The violated rule.
+    pub rule: Rule,
+}
+
+/// A protocol-relevant situation which a test plan is expected to exercise, tracked by the
+/// [`ConformanceMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Situation {
+    /// A drone dropped a fragment, as reported by a `Dropped` NACK.
+    Drop,
+    /// A NACK other than `Dropped` was sent, e.g. for an error in routing.
+    Nack,
+    /// A node crashed while a fragment routed through it was not acknowledged yet.
+    CrashDuringTransfer,
+    /// A link was added or removed, or a node crashed, while a flood was in progress, i.e.
+    /// packets of a flood started before the change were observed after it.
+    RouteChangeMidFlood,
+}
+
+impl Situation {
+    /// Every situation, in order.
+    pub const ALL: [Situation; 4] = [
+        Situation::Drop,
+        Situation::Nack,
+        Situation::CrashDuringTransfer,
+        Situation::RouteChangeMidFlood,
+    ];
+
+    /// Returns the name of the situation, as shown in a [`CoverageReport`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Situation::Drop => "drop",
+            Situation::Nack => "nack",
+            Situation::CrashDuringTransfer => "crash during transfer",
+            Situation::RouteChangeMidFlood => "route change mid-flood",
+        }
+    }
+}
+
+/// How many times every [`Situation`] was exercised, e.g. by a scripted scenario, see
+/// [`crate::scenario::Scenario::run_with_coverage`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// The number of occurrences of the situations which were exercised.
+    pub hits: BTreeMap<Situation, usize>,
+}
+
+impl CoverageReport {
+    /// Returns the situations which were exercised at least once, in order.
+    pub fn covered(&self) -> Vec<Situation> {
+        self.hits.keys().copied().collect()
+    }
+
+    /// Returns the situations which were never exercised, in order.
+    pub fn missing(&self) -> Vec<Situation> {
+        Situation::ALL
+            .into_iter()
+            .filter(|situation| !self.hits.contains_key(situation))
+            .collect()
+    }
+
+    /// Returns `true` if every situation was exercised.
+    pub fn is_complete(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    fn hit(&mut self, situation: Situation) {
+        *self.hits.entry(situation).or_default() += 1;
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for situation in Situation::ALL {
+            match self.hits.get(&situation) {
+                Some(hits) => writeln!(f, "{}: {}", situation.as_str(), hits)?,
+                None => writeln!(f, "{}: not covered", situation.as_str())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Online checker of protocol invariants, fed with the packets observed by the packet tap.
+///
+/// The monitor also tracks the coverage of the protocol-relevant situations, for which it
+/// must be fed with the topology updates as well.
+#[derive(Debug, Default)]
+pub struct ConformanceMonitor {
+    drone_impls: HashMap<NodeId, DroneImpl>,
+    /// The session of every flood, by initiator and flood ID.
+    floods: HashMap<(NodeId, u64), u64>,
+    /// The route of every fragment, by source, session and fragment index.
+    fragments: HashMap<(NodeId, u64, u64), Vec<NodeId>>,
+    /// The number of changes to the routes so far.
+    route_changes: usize,
+    /// The number of changes to the routes when the packets of every flood were last
+    /// observed, by initiator and flood ID.
+    flood_route_changes: HashMap<(NodeId, u64), usize>,
+    coverage: CoverageReport,
+}
+
+impl ConformanceMonitor {
+    /// Returns a monitor attributing the violations of drones to their implementations.
+    ///
+    /// # Parameters
+    /// - `plan`: The distribution of the monitored network.
+    pub fn new(plan: &DistributionPlan) -> Self {
+        Self {
+            drone_impls: plan.drones.iter().copied().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Checks a packet delivered to a node.
+    ///
+    /// # Parameters
+    /// - `tapped`: The packet, as observed by the tap.
+    ///
+    /// Returns the violations committed by sending the packet.
+    pub fn observe(&mut self, tapped: &TappedPacket) -> Vec<ProtocolViolation> {
+        let mut violations = Vec::new();
+        let packet = &tapped.packet;
+        let hops = &packet.routing_header.hops;
+        let hop_index = packet.routing_header.hop_index;
+        self.track_coverage(packet);
+
+        if let PacketType::FloodRequest(flood) = &packet.pack_type {
+            // A path trace with a single entry is the flood leaving its initiator.
+            if flood.path_trace.len() == 1 {
+                let session = self
+                    .floods
+                    .entry((flood.initiator_id, flood.flood_id))
+                    .or_insert(packet.session_id);
+                if *session != packet.session_id {
+                    violations.push(self.violation(flood.initiator_id, Rule::UniqueFloodId));
+                }
+            }
+            return violations;
+        }
+
+        if hops.get(hop_index) != Some(&tapped.to) {
+            match hops.iter().position(|hop| *hop == tapped.to) {
+                Some(position) if position > 0 => {
+                    violations.push(self.violation(hops[position - 1], Rule::HopIndexIncrement));
+                }
+                _ => {
+                    if let Some(sender) = hop_index.checked_sub(1).and_then(|i| hops.get(i)) {
+                        violations.push(self.violation(*sender, Rule::OnRoute));
+                    }
+                }
+            }
+            return violations;
+        }
+
+        // Routes are only checked on the first hop, when the packet leaves its source.
+        if hop_index == 1 {
+            if let Some(violation) = self.check_route(packet) {
+                violations.push(violation);
+            }
+        }
+        violations
+    }
+
+    /// Records the route of a fragment, or checks the route of an ACK or NACK against the
+    /// route of the fragment it refers to; the route is forgotten once the fragment is
+    /// acknowledged or rejected.
+    fn check_route(&mut self, packet: &Packet) -> Option<ProtocolViolation> {
+        let hops = &packet.routing_header.hops;
+        let fragment_index = match &packet.pack_type {
+            PacketType::MsgFragment(fragment) => {
+                let key = (*hops.first()?, packet.session_id, fragment.fragment_index);
+                self.fragments.insert(key, hops.clone());
+                return None;
+            }
+            PacketType::Ack(ack) => ack.fragment_index,
+            PacketType::Nack(nack) => nack.fragment_index,
+            _ => return None,
+        };
+        let key = (*hops.last()?, packet.session_id, fragment_index);
+        let fragment_route = self.fragments.get(&key)?;
+        let responder = *hops.first()?;
+        let position = fragment_route.iter().position(|hop| *hop == responder)?;
+        let expected: Vec<NodeId> = fragment_route[..=position].iter().rev().copied().collect();
+        self.fragments.remove(&key);
+        (*hops != expected).then(|| self.violation(responder, Rule::ReverseRoute))
+    }
+
+    /// Applies a topology update, tracking the situations it causes.
+    ///
+    /// A crashed node will neither forward nor acknowledge any packet, so the fragments routed
+    /// through it and the floods it initiated are forgotten.
+    ///
+    /// # Parameters
+    /// - `update`: The update, e.g. from
+    ///   [`crate::handle::NetworkHandle::topology_updates`].
+    pub fn observe_update(&mut self, update: &TopologyUpdate) {
+        match update {
+            TopologyUpdate::LinkAdded { .. } | TopologyUpdate::LinkRemoved { .. } => {
+                self.route_changes += 1;
+            }
+            TopologyUpdate::Crashed { node } => {
+                self.route_changes += 1;
+                if self.fragments.values().any(|route| route.contains(node)) {
+                    self.coverage.hit(Situation::CrashDuringTransfer);
+                }
+                self.fragments.retain(|_, route| !route.contains(node));
+                self.floods.retain(|(initiator, _), _| initiator != node);
+                self.flood_route_changes
+                    .retain(|(initiator, _), _| initiator != node);
+            }
+            TopologyUpdate::PdrChanged { .. } => {}
+        }
+    }
+
+    /// Returns the situations exercised so far.
+    pub fn coverage(&self) -> &CoverageReport {
+        &self.coverage
+    }
+
+    /// Tracks the situations shown by a packet.
+    fn track_coverage(&mut self, packet: &Packet) {
+        let flood = match &packet.pack_type {
+            PacketType::Nack(nack) => {
+                match nack.nack_type {
+                    NackType::Dropped => self.coverage.hit(Situation::Drop),
+                    _ => self.coverage.hit(Situation::Nack),
+                }
+                return;
+            }
+            PacketType::FloodRequest(flood) => (flood.initiator_id, flood.flood_id),
+            PacketType::FloodResponse(flood) => match flood.path_trace.first() {
+                Some((initiator, _)) => (*initiator, flood.flood_id),
+                None => return,
+            },
+            _ => return,
+        };
+        let seen = self
+            .flood_route_changes
+            .entry(flood)
+            .or_insert(self.route_changes);
+        if *seen != self.route_changes {
+            *seen = self.route_changes;
+            self.coverage.hit(Situation::RouteChangeMidFlood);
+        }
+    }
+
+    fn violation(&self, node: NodeId, rule: Rule) -> ProtocolViolation {
+        ProtocolViolation {
+            node,
+            drone_impl: self.drone_impls.get(&node).copied(),
+            rule,
+        }
+    }
+}
+
+/// Spawns a thread checking the packets delivered to the nodes of a network.
+///
+/// Every violation is published to the subscribers of [`NetworkHandle::subscribe`] as a
+/// [`NodeEvent::ProtocolViolation`]. The monitor is fed with the topology updates as well, so
+/// that it forgets the packets of the nodes which crashed. The thread is one of the relays of
+/// the network, and terminates when they are stopped.
+///
+/// # Parameters
+/// - `handle`: The handle of the network, initialized with
+///   [`InitOptions::packet_taps`](crate::init::InitOptions::packet_taps).
+/// - `monitor`: The monitor performing the checks.
+pub fn spawn_conformance_monitor(handle: &NetworkHandle, mut monitor: ConformanceMonitor) {
+    let packets = handle.tap();
+    let mut updates = handle.topology_updates();
+    let hub = handle.shared().events.clone();
+    handle.shared().relays.spawn(move |stopped| loop {
+        let tapped = select! {
+            recv(packets) -> tapped => tapped.ok(),
+            recv(stopped) -> _ => packets.try_recv().ok(),
+            recv(updates) -> update => {
+                match update {
+                    Ok(update) => monitor.observe_update(&update),
+                    Err(_) => updates = crossbeam_channel::never(),
+                }
+                continue;
+            }
+        };
+        let Some(tapped) = tapped else {
+            break;
+        };
+        for violation in monitor.observe(&tapped) {
+            hub.publish(ObservedEvent {
+                node: violation.node,
+                at: tapped.at,
+                event: NodeEvent::ProtocolViolation(violation),
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::conformance::{spawn_conformance_monitor, ConformanceMonitor, Rule, Situation};
+    use crate::events::NodeEvent;
+    use crate::handle::{NetworkHandle, Shared};
+    use crate::tap::TappedPacket;
+    use crate::topology::TopologyUpdate;
+    use wg_2024::network::{NodeId, SourceRoutingHeader};
+    use wg_2024::packet::{
+        Ack, FloodRequest, Fragment, Nack, NackType, NodeType, Packet, PacketType, FRAGMENT_DSIZE,
+    };
+
+    fn tapped(
+        to: NodeId,
+        pack_type: PacketType,
+        hops: Vec<NodeId>,
+        hop_index: usize,
+    ) -> TappedPacket {
+        TappedPacket {
+            to,
+            at: Instant::now(),
+            packet: Packet {
+                pack_type,
+                routing_header: SourceRoutingHeader { hop_index, hops },
+                session_id: 5,
+            },
+        }
+    }
+
+    fn fragment() -> PacketType {
+        PacketType::MsgFragment(Fragment {
+            fragment_index: 0,
+            total_n_fragments: 1,
+            length: 0,
+            data: [0; FRAGMENT_DSIZE],
+        })
+    }
+
+    #[test]
+    fn test_hop_index_increment() {
+        let mut monitor = ConformanceMonitor::default();
+
+        assert!(monitor
+            .observe(&tapped(2, fragment(), vec![10, 1, 2, 20], 2))
+            .is_empty());
+        let violations = monitor.observe(&tapped(2, fragment(), vec![10, 1, 2, 20], 1));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            (violations[0].node, violations[0].rule),
+            (1, Rule::HopIndexIncrement)
+        );
+    }
+
+    #[test]
+    fn test_reverse_route() {
+        let mut monitor = ConformanceMonitor::default();
+        let ack = PacketType::Ack(Ack { fragment_index: 0 });
+
+        assert!(monitor
+            .observe(&tapped(1, fragment(), vec![10, 1, 2, 20], 1))
+            .is_empty());
+        assert!(monitor
+            .observe(&tapped(2, ack.clone(), vec![20, 2, 1, 10], 1))
+            .is_empty());
+
+        assert!(monitor
+            .observe(&tapped(1, fragment(), vec![10, 1, 2, 20], 1))
+            .is_empty());
+        let violations = monitor.observe(&tapped(3, ack, vec![20, 3, 1, 10], 1));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            (violations[0].node, violations[0].rule),
+            (20, Rule::ReverseRoute)
+        );
+    }
+
+    #[test]
+    fn test_forget_routes() {
+        let mut monitor = ConformanceMonitor::default();
+        let nack = PacketType::Nack(Nack {
+            fragment_index: 0,
+            nack_type: NackType::Dropped,
+        });
+        let request = PacketType::FloodRequest(FloodRequest {
+            flood_id: 7,
+            initiator_id: 10,
+            path_trace: vec![(10, NodeType::Client)],
+        });
+
+        // A rejected fragment is forgotten.
+        monitor.observe(&tapped(1, fragment(), vec![10, 1, 2, 20], 1));
+        monitor.observe(&tapped(1, nack, vec![2, 1, 10], 1));
+        assert!(monitor.fragments.is_empty());
+
+        // So are the fragments routed through a crashed node, and the floods it initiated.
+        monitor.observe(&tapped(1, fragment(), vec![10, 1, 2, 20], 1));
+        monitor.observe(&tapped(1, request, vec![], 0));
+        monitor.observe_update(&TopologyUpdate::Crashed { node: 2 });
+        assert!(monitor.fragments.is_empty());
+        assert_eq!(monitor.floods.len(), 1);
+        monitor.observe_update(&TopologyUpdate::Crashed { node: 10 });
+        assert!(monitor.floods.is_empty());
+        assert!(monitor.flood_route_changes.is_empty());
+    }
+
+    #[test]
+    fn test_spawn_conformance_monitor() {
+        let shared = Shared::default();
+        let handle = NetworkHandle::new(vec![], shared.clone());
+        let events = handle.subscribe();
+        spawn_conformance_monitor(&handle, ConformanceMonitor::default());
+
+        shared
+            .packets
+            .publish(tapped(2, fragment(), vec![10, 1, 2, 20], 1));
+        let observed = events.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(observed.node, 1);
+        assert!(matches!(
+            observed.event,
+            NodeEvent::ProtocolViolation(violation) if violation.rule == Rule::HopIndexIncrement
+        ));
+
+        // The monitor is joined with the relays of the network.
+        shared.relays.stop();
+        assert_eq!(shared.relays.len(), 0);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let mut monitor = ConformanceMonitor::default();
+        assert_eq!(monitor.coverage().missing(), Situation::ALL);
+
+        let nack = |nack_type| {
+            PacketType::Nack(Nack {
+                fragment_index: 0,
+                nack_type,
+            })
+        };
+        monitor.observe(&tapped(2, nack(NackType::Dropped), vec![1, 2, 10], 1));
+        monitor.observe(&tapped(2, nack(NackType::Dropped), vec![1, 2, 10], 1));
+        monitor.observe(&tapped(
+            2,
+            nack(NackType::ErrorInRouting(3)),
+            vec![1, 2, 10],
+            1,
+        ));
+
+        // A crash on the route of a fragment which is not acknowledged yet.
+        monitor.observe(&tapped(1, fragment(), vec![10, 1, 2, 20], 1));
+        monitor.observe_update(&TopologyUpdate::Crashed { node: 3 });
+        assert_eq!(
+            monitor.coverage().covered(),
+            [Situation::Drop, Situation::Nack]
+        );
+        monitor.observe_update(&TopologyUpdate::Crashed { node: 2 });
+
+        // A link removed between two requests of the same flood.
+        let request = |path_trace: Vec<(NodeId, NodeType)>| {
+            PacketType::FloodRequest(FloodRequest {
+                flood_id: 7,
+                initiator_id: 10,
+                path_trace,
+            })
+        };
+        monitor.observe(&tapped(1, request(vec![(10, NodeType::Client)]), vec![], 0));
+        monitor.observe_update(&TopologyUpdate::LinkRemoved {
+            node: 1,
+            neighbor: 4,
+        });
+        let path_trace = vec![(10, NodeType::Client), (1, NodeType::Drone)];
+        monitor.observe(&tapped(4, request(path_trace), vec![], 0));
+
+        let coverage = monitor.coverage();
+        assert!(coverage.is_complete(), "{}", coverage);
+        assert_eq!(coverage.hits[&Situation::Drop], 2);
+        assert_eq!(coverage.hits[&Situation::RouteChangeMidFlood], 1);
+        assert!(coverage.to_string().starts_with("drop: 2\nnack: 1\n"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use wg_2024::{config::Config, network::NodeId, packet::NodeType};
+
+use crate::analysis::{critical_drones, ShortestPaths};
+use crate::model::Topology;
+
+/// The lowest delivery rate used by [`difficulty`], so that drones dropping every packet do
+/// not make the score infinite.
+const MIN_DELIVERY_RATE: f64 = 0.01;
+
+/// How hard a topology is to route through, computed by [`difficulty`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyScore {
+    /// The number of nodes.
+    pub nodes: usize,
+    /// The number of links beyond the ones needed to connect the nodes, per node.
+    pub redundancy: f64,
+    /// The mean packet drop rate of the drones, `0` if there are none.
+    pub average_pdr: f64,
+    /// The largest number of hops between a client and a server, through drones.
+    pub diameter: usize,
+    /// The number of drones whose crash would disconnect some client from some server.
+    pub bottlenecks: usize,
+    /// The overall score, higher for harder topologies.
+    pub score: f64,
+}
+
+/// Computes how hard a topology is to route through, so that the topologies used to evaluate
+/// different groups can be checked to be comparably hard.
+///
+/// The score adds up:
+/// - the size, as `log2(nodes)`;
+/// - the length of the paths, as the `diameter` divided by the mean delivery rate of the
+///   drones, `1 - average_pdr`;
+/// - the fragility, as the number of `bottlenecks` plus `1 / (1 + redundancy)`.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+///
+/// # Performance
+/// `O(d * c * (n + m))`, where `d` is the number of drones, `c` the number of clients, `n`
+/// the number of nodes and `m` the number of edges, dominated by the search of the
+/// bottlenecks.
+pub fn difficulty(config: &Config) -> DifficultyScore {
+    let topology = Topology::from(config);
+    let neighbors = topology.neighbors();
+    let drones: HashSet<NodeId> = topology
+        .nodes_of_type(NodeType::Drone)
+        .map(|drone| drone.id)
+        .collect();
+    let servers: Vec<NodeId> = topology
+        .nodes_of_type(NodeType::Server)
+        .map(|server| server.id)
+        .collect();
+
+    let nodes = topology.nodes.len();
+    let links = topology.links().len();
+    let redundancy = if nodes == 0 {
+        0.0
+    } else {
+        links.saturating_sub(nodes - 1) as f64 / nodes as f64
+    };
+    let average_pdr = if config.drone.is_empty() {
+        0.0
+    } else {
+        config
+            .drone
+            .iter()
+            .map(|drone| drone.pdr as f64)
+            .sum::<f64>()
+            / config.drone.len() as f64
+    };
+    let diameter = topology
+        .nodes_of_type(NodeType::Client)
+        .flat_map(|client| {
+            let paths = ShortestPaths::from(&neighbors, &drones, client.id);
+            servers
+                .iter()
+                .filter_map(|server| paths.distance.get(server).copied())
+                .collect::<Vec<_>>()
+        })
+        .max()
+        .unwrap_or(0);
+    let bottlenecks = critical_drones(config).len();
+
+    let size = (nodes.max(1) as f64).log2();
+    let delivery_rate = (1.0 - average_pdr).max(MIN_DELIVERY_RATE);
+    let score =
+        size + diameter as f64 / delivery_rate + bottlenecks as f64 + 1.0 / (1.0 + redundancy);
+    DifficultyScore {
+        nodes,
+        redundancy,
+        average_pdr,
+        diameter,
+        bottlenecks,
+        score,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wg_2024::config::{Client, Config, Drone, Server};
+
+    use crate::difficulty::difficulty;
+
+    #[test]
+    fn test_difficulty() {
+        // A chain of drones 1-2-3, with client 10 on drone 1 and server 20 on drone 3.
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2, 10],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1, 3],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 3,
+                connected_node_ids: vec![2, 20],
+                pdr: 0.0,
+            },
+        ];
+        let mut config = Config {
+            drone,
+            client: vec![Client {
+                id: 10,
+                connected_drone_ids: vec![1],
+            }],
+            server: vec![Server {
+                id: 20,
+                connected_drone_ids: vec![3],
+            }],
+        };
+        let chain = difficulty(&config);
+        assert_eq!((chain.nodes, chain.diameter, chain.bottlenecks), (5, 4, 3));
+        assert_eq!(chain.redundancy, 0.0);
+        assert_eq!(chain.score, 5f64.log2() + 4.0 + 3.0 + 1.0);
+
+        // A shortcut between drones 1 and 3 makes drone 2 redundant.
+        config.drone[0].connected_node_ids.push(3);
+        config.drone[2].connected_node_ids.push(1);
+        let shortcut = difficulty(&config);
+        assert_eq!((shortcut.diameter, shortcut.bottlenecks), (3, 2));
+        assert!(shortcut.score < chain.score);
+
+        // Lossy drones make the same topology harder.
+        config.drone.iter_mut().for_each(|drone| drone.pdr = 0.5);
+        let lossy = difficulty(&config);
+        assert_eq!(lossy.average_pdr, 0.5);
+        assert!(lossy.score > shortcut.score);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+
+use wg_2024::{
+    config::Config,
+    network::SourceRoutingHeader,
+    packet::{FloodRequest, NodeType, Packet, PacketType},
+};
+
+use crate::analysis::{config_links, diff_links, Link, TopologyDiff};
+use crate::handle::NetworkHandle;
+use crate::tap::TappedPacket;
+
+/// First flood ID (and session ID) used by the floods of the discovery harness, chosen far
+/// above the IDs used by the clients so that the recorded traffic is not mixed with theirs.
+pub const DISCOVERY_FLOOD_ID_BASE: u64 = 1 << 63;
+
+/// Time without flood traffic after which the discovery is considered complete.
+const DISCOVERY_QUIET_PERIOD: Duration = Duration::from_millis(200);
+
+/// The flood traffic recorded during a discovery, in order of observation.
+#[derive(Debug, Clone, Default)]
+pub struct FloodRecording {
+    /// The flood requests delivered to the nodes.
+    pub requests: Vec<TappedPacket>,
+    /// The flood responses delivered to the nodes.
+    pub responses: Vec<TappedPacket>,
+}
+
+impl FloodRecording {
+    /// Returns the links crossed by the recorded floods.
+    ///
+    /// A request crosses the link between the last node of its path trace and its receiver,
+    /// while a response proves every link of its path trace.
+    pub fn discovered_links(&self) -> BTreeSet<Link> {
+        let requests = self.requests.iter().filter_map(|tapped| {
+            let PacketType::FloodRequest(flood) = &tapped.packet.pack_type else {
+                return None;
+            };
+            let (last, _) = flood.path_trace.last()?;
+            Some((*last, tapped.to))
+        });
+        let responses = self.responses.iter().flat_map(|tapped| {
+            let PacketType::FloodResponse(flood) = &tapped.packet.pack_type else {
+                return Vec::new();
+            };
+            flood
+                .path_trace
+                .windows(2)
+                .map(|pair| (pair[0].0, pair[1].0))
+                .collect()
+        });
+        requests
+            .chain(responses)
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect()
+    }
+
+    /// Compares the links crossed by the recorded floods with the links of a configuration.
+    ///
+    /// # Parameters
+    /// - `config`: The configuration of the network.
+    ///
+    /// Returns the differences; a missing link usually means that a drone did not forward
+    /// the floods correctly.
+    pub fn diff(&self, config: &Config) -> TopologyDiff {
+        diff_links(&config_links(config), &self.discovered_links())
+    }
+}
+
+/// Floods a live network on behalf of every client and records the flood traffic.
+///
+/// A flood request is injected, as if sent by the client, into each of the neighbors of every
+/// client; the traffic is recorded until no flood packet is observed for a short while, or the
+/// timeout expires.
+///
+/// # Parameters
+/// - `handle`: The handle of the network, initialized from `config` with
+///   [`InitOptions::packet_taps`](crate::init::InitOptions::packet_taps).
+/// - `config`: The configuration of the network.
+/// - `timeout`: The maximum time to wait for the floods to complete.
+///
+/// Returns the recording, along with its differences from the configured topology.
+pub fn record_flood_discovery(
+    handle: &NetworkHandle,
+    config: &Config,
+    timeout: Duration,
+) -> (FloodRecording, TopologyDiff) {
+    let tap = handle.tap();
+    for (index, client) in config.client.iter().enumerate() {
+        let flood_id = DISCOVERY_FLOOD_ID_BASE + index as u64;
+        for neighbor in &client.connected_drone_ids {
+            let packet = Packet {
+                pack_type: PacketType::FloodRequest(FloodRequest {
+                    flood_id,
+                    initiator_id: client.id,
+                    path_trace: vec![(client.id, NodeType::Client)],
+                }),
+                routing_header: SourceRoutingHeader {
+                    hop_index: 0,
+                    hops: Vec::new(),
+                },
+                session_id: flood_id,
+            };
+            handle.send_packet(*neighbor, packet);
+        }
+    }
+
+    let mut recording = FloodRecording::default();
+    let deadline = Instant::now() + timeout;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        let wait = DISCOVERY_QUIET_PERIOD.min(deadline - now);
+        let Ok(tapped) = tap.recv_timeout(wait) else {
+            break;
+        };
+        match &tapped.packet.pack_type {
+            PacketType::FloodRequest(flood) if flood.flood_id >= DISCOVERY_FLOOD_ID_BASE => {
+                recording.requests.push(tapped);
+            }
+            PacketType::FloodResponse(flood) if flood.flood_id >= DISCOVERY_FLOOD_ID_BASE => {
+                recording.responses.push(tapped);
+            }
+            _ => {}
+        }
+    }
+
+    let diff = recording.diff(config);
+    (recording, diff)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use crate::discovery::{FloodRecording, DISCOVERY_FLOOD_ID_BASE};
+    use crate::generator::generate_small_world;
+    use crate::tap::TappedPacket;
+    use wg_2024::network::{NodeId, SourceRoutingHeader};
+    use wg_2024::packet::{FloodRequest, FloodResponse, NodeType, Packet, PacketType};
+
+    fn tapped(to: NodeId, pack_type: PacketType) -> TappedPacket {
+        TappedPacket {
+            to,
+            at: Instant::now(),
+            packet: Packet {
+                pack_type,
+                routing_header: SourceRoutingHeader {
+                    hop_index: 0,
+                    hops: Vec::new(),
+                },
+                session_id: DISCOVERY_FLOOD_ID_BASE,
+            },
+        }
+    }
+
+    #[test]
+    fn test_discovered_links() {
+        let request = PacketType::FloodRequest(FloodRequest {
+            flood_id: DISCOVERY_FLOOD_ID_BASE,
+            initiator_id: 10,
+            path_trace: vec![(10, NodeType::Client), (1, NodeType::Drone)],
+        });
+        let response = PacketType::FloodResponse(FloodResponse {
+            flood_id: DISCOVERY_FLOOD_ID_BASE,
+            path_trace: vec![
+                (10, NodeType::Client),
+                (1, NodeType::Drone),
+                (3, NodeType::Drone),
+            ],
+        });
+        let recording = FloodRecording {
+            requests: vec![tapped(2, request)],
+            responses: vec![tapped(10, response)],
+        };
+
+        let links: Vec<_> = recording.discovered_links().into_iter().collect();
+        assert_eq!(links, vec![(1, 2), (1, 3), (1, 10)]);
+    }
+
+    #[test]
+    fn test_empty_recording_misses_every_link() {
+        let config = generate_small_world(6, 2, 0.0, 2, 1, 1).unwrap();
+        let diff = FloodRecording::default().diff(&config);
+
+        assert!(!diff.is_empty());
+        assert!(diff.unexpected_links.is_empty());
+    }
+}
@@ -0,0 +1,631 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rust_roveri_api::{
+    ClientType, DroneImpl, ServerType, MAX_CLIENT_TYPES, MAX_IMPL, MAX_SERVER_TYPES,
+};
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::migrate::Migrations;
+use crate::validate::{ErrorCode, ValidatedConfig, ValidationError};
+
+/// Key of a `[[drone]]` entry choosing the implementation of the drone, either by name, e.g.
+/// `impl = "rust_roveri"` (see [`impl_name`]), or by code, e.g. `impl = 0`.
+pub const IMPL_KEY: &str = "impl";
+
+/// The implementation or type assigned to every node of a configuration, computed before any
+/// node is spawned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DistributionPlan {
+    /// The implementation of every drone, in configuration order.
+    pub drones: Vec<(NodeId, DroneImpl)>,
+    /// The type of every client, in configuration order.
+    pub clients: Vec<(NodeId, ClientType)>,
+    /// The type of every server, in configuration order.
+    pub servers: Vec<(NodeId, ServerType)>,
+}
+
+impl DistributionPlan {
+    /// Plans the distribution of a configuration.
+    ///
+    /// Implementations and types are assigned round-robin, in the order in which the nodes
+    /// appear in the configuration.
+    ///
+    /// # Parameters
+    /// - `config`: A reference to the network configuration.
+    ///
+    /// # Panics
+    /// Panics if some node cannot be assigned an implementation or type, see
+    /// [`DistributionPlan::try_new`].
+    pub fn new(config: &Config) -> Self {
+        Self::with_drone_impls(config, &BTreeMap::new())
+    }
+
+    /// Plans the distribution of a configuration, as [`DistributionPlan::new`], except for
+    /// the drones whose implementation is chosen.
+    ///
+    /// The other drones keep the implementation assigned to their position by the
+    /// round-robin.
+    ///
+    /// # Parameters
+    /// - `config`: A reference to the network configuration.
+    /// - `drone_impls`: The implementation chosen for some drones, e.g. by
+    ///   [`parse_drone_impls`].
+    ///
+    /// # Panics
+    /// Panics if some node cannot be assigned an implementation or type, see
+    /// [`DistributionPlan::try_with_drone_impls`].
+    pub fn with_drone_impls(config: &Config, drone_impls: &BTreeMap<NodeId, DroneImpl>) -> Self {
+        Self::try_with_drone_impls(config, drone_impls).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Plans the distribution of a configuration, as [`DistributionPlan::new`].
+    ///
+    /// # Parameters
+    /// - `config`: A reference to the network configuration.
+    ///
+    /// Returns the distribution, or an error if some node cannot be assigned an implementation
+    /// or type, e.g. because the configuration has clients but no client type is available.
+    pub fn try_new(config: &Config) -> Result<Self, ValidationError> {
+        Self::try_with_drone_impls(config, &BTreeMap::new())
+    }
+
+    /// Plans the distribution of a configuration, as [`DistributionPlan::with_drone_impls`].
+    ///
+    /// # Parameters
+    /// - `config`: A reference to the network configuration.
+    /// - `drone_impls`: The implementation chosen for some drones.
+    ///
+    /// Returns the distribution, or an error if some node cannot be assigned an implementation
+    /// or type, e.g. because the configuration has clients but no client type is available.
+    pub fn try_with_drone_impls(
+        config: &Config,
+        drone_impls: &BTreeMap<NodeId, DroneImpl>,
+    ) -> Result<Self, ValidationError> {
+        Self::assign(config, drone_impls, &CodeOrders::sequential())
+    }
+
+    /// Plans the distribution of a configuration, as [`DistributionPlan::try_new`], except
+    /// that the round-robin visits the codes of every kind of node in an order drawn from a
+    /// seed.
+    ///
+    /// The spread is as even as the one of [`DistributionPlan::new`], but which
+    /// implementations or types get the extra nodes, and which node gets which, depends on
+    /// the seed; the same seed always yields the same plan.
+    ///
+    /// # Parameters
+    /// - `config`: A reference to the network configuration.
+    /// - `seed`: The seed of the run.
+    ///
+    /// Returns the distribution, or an error if some node cannot be assigned an implementation
+    /// or type.
+    pub fn try_seeded(config: &Config, seed: u64) -> Result<Self, ValidationError> {
+        Self::assign(config, &BTreeMap::new(), &CodeOrders::shuffled(seed))
+    }
+
+    /// Assigns the implementations and types round-robin, visiting the codes in the given
+    /// orders, except for the drones whose implementation is chosen.
+    fn assign(
+        config: &Config,
+        drone_impls: &BTreeMap<NodeId, DroneImpl>,
+        orders: &CodeOrders,
+    ) -> Result<Self, ValidationError> {
+        let drones = config
+            .drone
+            .iter()
+            .enumerate()
+            .map(|(index, drone)| {
+                let drone_impl = match drone_impls.get(&drone.id) {
+                    Some(drone_impl) => *drone_impl,
+                    None => round_robin(
+                        drone.id,
+                        index,
+                        &orders.drones,
+                        DroneImpl::from_code,
+                        "drone implementation",
+                    )?,
+                };
+                Ok((drone.id, drone_impl))
+            })
+            .collect::<Result<_, ValidationError>>()?;
+        let clients = config
+            .client
+            .iter()
+            .enumerate()
+            .map(|(index, client)| {
+                let client_type = round_robin(
+                    client.id,
+                    index,
+                    &orders.clients,
+                    ClientType::from_code,
+                    "client type",
+                )?;
+                Ok((client.id, client_type))
+            })
+            .collect::<Result<_, ValidationError>>()?;
+        let servers = config
+            .server
+            .iter()
+            .enumerate()
+            .map(|(index, server)| {
+                let server_type = round_robin(
+                    server.id,
+                    index,
+                    &orders.servers,
+                    ServerType::from_code,
+                    "server type",
+                )?;
+                Ok((server.id, server_type))
+            })
+            .collect::<Result<_, ValidationError>>()?;
+        Ok(Self {
+            drones,
+            clients,
+            servers,
+        })
+    }
+
+    /// Returns the number of drones assigned to every implementation, indexed by code.
+    pub fn drones_distro(&self) -> [usize; MAX_IMPL] {
+        code_counts(self.drones.iter().map(|(_, i)| *i), DroneImpl::from_code)
+    }
+
+    /// Returns the number of clients assigned to every type, indexed by code.
+    pub fn clients_distro(&self) -> [usize; MAX_CLIENT_TYPES] {
+        code_counts(self.clients.iter().map(|(_, t)| *t), ClientType::from_code)
+    }
+
+    /// Returns the number of servers assigned to every type, indexed by code.
+    pub fn servers_distro(&self) -> [usize; MAX_SERVER_TYPES] {
+        code_counts(self.servers.iter().map(|(_, t)| *t), ServerType::from_code)
+    }
+
+    /// Returns the drone implementations which are not assigned to any drone, ordered by code.
+    pub fn unused_impls(&self) -> Vec<DroneImpl> {
+        let counts = self.drones_distro();
+        (0..MAX_IMPL)
+            .filter(|code| counts[*code] == 0)
+            .filter_map(DroneImpl::from_code)
+            .collect()
+    }
+}
+
+/// The order in which the round-robin visits the codes of every kind of node.
+struct CodeOrders {
+    drones: Vec<usize>,
+    clients: Vec<usize>,
+    servers: Vec<usize>,
+}
+
+impl CodeOrders {
+    /// Returns the codes in ascending order.
+    fn sequential() -> Self {
+        Self {
+            drones: (0..MAX_IMPL).collect(),
+            clients: (0..MAX_CLIENT_TYPES).collect(),
+            servers: (0..MAX_SERVER_TYPES).collect(),
+        }
+    }
+
+    /// Returns the codes in an order drawn from a seed.
+    fn shuffled(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut orders = Self::sequential();
+        orders.drones.shuffle(&mut rng);
+        orders.clients.shuffle(&mut rng);
+        orders.servers.shuffle(&mut rng);
+        orders
+    }
+}
+
+/// Assigns an implementation or type to a node by the round-robin.
+///
+/// # Parameters
+/// - `id`: The ID of the node.
+/// - `index`: The position of the node among the nodes of its type.
+/// - `codes`: The available codes, in the order in which they are visited.
+/// - `from_code`: Maps a code to the implementation or type.
+/// - `kind`: What is assigned, for the error message.
+///
+/// Returns the implementation or type, or an error if no code is available or the code of the
+/// node does not map to any.
+fn round_robin<T>(
+    id: NodeId,
+    index: usize,
+    codes: &[usize],
+    from_code: fn(usize) -> Option<T>,
+    kind: &str,
+) -> Result<T, ValidationError> {
+    let Some(code) = index.checked_rem(codes.len()).map(|index| codes[index]) else {
+        return Err(ValidationError::new(
+            ErrorCode::TypeUnavailable,
+            format!(
+                "Node [{}] cannot be assigned a {}, as none is available",
+                id, kind
+            ),
+        ));
+    };
+    from_code(code).ok_or_else(|| {
+        ValidationError::new(
+            ErrorCode::TypeUnavailable,
+            format!(
+                "Node [{}] cannot be assigned a {}, as code {} of {} maps to none",
+                id,
+                kind,
+                code,
+                codes.len()
+            ),
+        )
+    })
+}
+
+/// Counts the nodes assigned to each of `N` codes.
+///
+/// # Parameters
+/// - `assigned`: The implementation or type of every node.
+/// - `from_code`: Maps a code to the implementation or type.
+fn code_counts<T: PartialEq, const N: usize>(
+    assigned: impl Iterator<Item = T>,
+    from_code: fn(usize) -> Option<T>,
+) -> [usize; N] {
+    let mut counts = [0; N];
+    for item in assigned {
+        if let Some(code) = (0..N).find(|code| from_code(*code).as_ref() == Some(&item)) {
+            counts[code] += 1;
+        }
+    }
+    counts
+}
+
+/// Returns the name of an implementation, as accepted by the [`IMPL_KEY`] of a drone: the name
+/// of its variant in snake case, e.g. `rust_roveri`.
+pub fn impl_name(drone_impl: DroneImpl) -> String {
+    let mut name = String::new();
+    for (index, c) in format!("{:?}", drone_impl).chars().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            name.push('_');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+/// Parses the implementations chosen by the [`IMPL_KEY`] of the drones of a configuration.
+///
+/// The configuration is upgraded to the current version of the format first, with
+/// [`Migrations::default`], as it is when validated.
+///
+/// # Parameters
+/// - `config_data`: The TOML representation of the configuration.
+///
+/// Returns the chosen implementations, by drone ID, or an error if the configuration cannot be
+/// deserialized or some drone chooses an unknown implementation.
+pub fn parse_drone_impls(
+    config_data: &str,
+) -> Result<BTreeMap<NodeId, DroneImpl>, ValidationError> {
+    let malformed = |message: String| ValidationError::new(ErrorCode::Malformed, message);
+    let mut table: toml::Table = toml::from_str(config_data)
+        .map_err(|e| malformed(format!("Unable to deserialize TOML: {}", e)))?;
+    Migrations::default()
+        .migrate(&mut table)
+        .map_err(malformed)?;
+    drone_impls_from_table(&table)
+}
+
+/// Reads the implementations chosen by the [`IMPL_KEY`] of the drones of a configuration,
+/// already upgraded to the current version of the format.
+///
+/// Entries which are not drones with an ID are skipped, as they are rejected when the
+/// configuration is deserialized.
+///
+/// # Parameters
+/// - `table`: The TOML representation of the configuration.
+///
+/// Returns the chosen implementations, by drone ID, or an error if some drone chooses an
+/// unknown implementation.
+pub(crate) fn drone_impls_from_table(
+    table: &toml::Table,
+) -> Result<BTreeMap<NodeId, DroneImpl>, ValidationError> {
+    let drones = table
+        .get("drone")
+        .and_then(toml::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut drone_impls = BTreeMap::new();
+    for drone in drones {
+        let id = (drone.get("id").and_then(toml::Value::as_integer))
+            .and_then(|id| NodeId::try_from(id).ok());
+        let (Some(id), Some(value)) = (id, drone.get(IMPL_KEY)) else {
+            continue;
+        };
+        let drone_impl = match value {
+            toml::Value::Integer(code) => usize::try_from(*code)
+                .ok()
+                .filter(|code| *code < MAX_IMPL)
+                .and_then(DroneImpl::from_code),
+            toml::Value::String(name) => (0..MAX_IMPL)
+                .filter_map(DroneImpl::from_code)
+                .find(|drone_impl| impl_name(*drone_impl) == *name),
+            _ => None,
+        };
+        let Some(drone_impl) = drone_impl else {
+            let known: Vec<String> = (0..MAX_IMPL)
+                .filter_map(DroneImpl::from_code)
+                .map(impl_name)
+                .collect();
+            return Err(ValidationError::new(
+                ErrorCode::UnknownImpl,
+                format!(
+                    "Drone [{}] has unknown implementation {}, expected a code below {} or one of {}",
+                    id,
+                    value,
+                    MAX_IMPL,
+                    known.join(", ")
+                ),
+            ));
+        };
+        drone_impls.insert(id, drone_impl);
+    }
+    Ok(drone_impls)
+}
+
+/// Returns the code of a drone implementation, i.e. the code mapped to it by
+/// `DroneImpl::from_code`.
+pub(crate) fn impl_code(drone_impl: DroneImpl) -> Option<usize> {
+    (0..MAX_IMPL).find(|code| DroneImpl::from_code(*code) == Some(drone_impl))
+}
+
+/// Reads and validates a configuration file, planning its distribution with the
+/// implementations chosen by its drones.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+///
+/// Returns the configuration and its distribution, to initialize the network with
+/// [`crate::init::network_init_with_plan`], if the configuration file provided is valid, an
+/// error otherwise.
+pub fn network_validate_plan(file_path: &str) -> Result<(Config, DistributionPlan), String> {
+    let config = ValidatedConfig::from_file(file_path)?;
+    let plan = config.plan().map_err(String::from)?;
+    Ok((config.into_config(), plan))
+}
+
+/// Rules that the spread of a [`DistributionPlan`] must satisfy.
+///
+/// A ratio of `r` means that no implementation (or type) may be assigned to more than `r`
+/// times the nodes of any other one. The default requirements enforce no rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DistroRequirements {
+    /// The maximum ratio between the most and the least used drone implementations, if any.
+    pub max_drone_ratio: Option<f64>,
+    /// The maximum ratio between the most and the least used client types, if any.
+    pub max_client_ratio: Option<f64>,
+    /// The maximum ratio between the most and the least used server types, if any.
+    pub max_server_ratio: Option<f64>,
+}
+
+/// Verifies that a distribution plan satisfies the given requirements.
+///
+/// # Parameters
+/// - `plan`: The planned distribution.
+/// - `requirements`: The rules to enforce.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(1)`.
+pub fn verify_distribution(
+    plan: &DistributionPlan,
+    requirements: &DistroRequirements,
+) -> Result<(), ValidationError> {
+    verify_spread(
+        &plan.drones_distro(),
+        requirements.max_drone_ratio,
+        "drone implementations",
+    )?;
+    verify_spread(
+        &plan.clients_distro(),
+        requirements.max_client_ratio,
+        "client types",
+    )?;
+    verify_spread(
+        &plan.servers_distro(),
+        requirements.max_server_ratio,
+        "server types",
+    )
+}
+
+/// Verifies that the most used code is assigned at most `max_ratio` times the nodes of the
+/// least used one.
+fn verify_spread(
+    counts: &[usize],
+    max_ratio: Option<f64>,
+    kind: &str,
+) -> Result<(), ValidationError> {
+    let Some(max_ratio) = max_ratio else {
+        return Ok(());
+    };
+    let (Some(min), Some(max)) = (counts.iter().min(), counts.iter().max()) else {
+        return Ok(());
+    };
+    if *max as f64 > max_ratio * *min as f64 {
+        return Err(ValidationError::new(
+            ErrorCode::DistributionImbalance,
+            format!(
+                "The planned {} range from {} to {} nodes, exceeding the maximum ratio of {}",
+                kind, min, max, max_ratio
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::distribution::{
+        impl_name, network_validate_plan, parse_drone_impls, round_robin, verify_distribution,
+        DistributionPlan, DistroRequirements,
+    };
+    use crate::generator::generate_small_world;
+    use crate::validate::{network_validate_str, ErrorCode, ValidatedConfig};
+    use rust_roveri_api::{DroneImpl, MAX_IMPL};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4, 5]
+        pdr = 0.1
+        impl = 2
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 3, 4, 5]
+        pdr = 0.1
+
+        [[drone]]
+        id = 3
+        connected_node_ids = [1, 2, 5]
+        pdr = 0.1
+        impl = "rust_roveri"
+
+        [[client]]
+        id = 4
+        connected_drone_ids = [1, 2]
+
+        [[server]]
+        id = 5
+        connected_drone_ids = [1, 2, 3]
+    "#;
+
+    #[test]
+    fn test_plan_round_robin() {
+        let config = generate_small_world(MAX_IMPL + 1, 4, 0.1, 3, 3, 1).unwrap();
+        let plan = DistributionPlan::new(&config);
+
+        assert_eq!(plan.drones.len(), MAX_IMPL + 1);
+        assert_eq!(plan.drones[0].1, DroneImpl::from_code(0).unwrap());
+        assert_eq!(plan.drones[MAX_IMPL].1, DroneImpl::from_code(0).unwrap());
+        assert_eq!(plan.drones_distro()[0], 2);
+        assert_eq!(plan.drones_distro().iter().sum::<usize>(), MAX_IMPL + 1);
+        assert_eq!(plan.clients_distro().iter().sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_verify_distribution() {
+        let config = generate_small_world(MAX_IMPL + 1, 4, 0.1, 3, 3, 1).unwrap();
+        let plan = DistributionPlan::new(&config);
+
+        assert_eq!(
+            verify_distribution(&plan, &DistroRequirements::default()),
+            Ok(())
+        );
+        let balanced = DistroRequirements {
+            max_drone_ratio: Some(2.0),
+            ..DistroRequirements::default()
+        };
+        assert_eq!(verify_distribution(&plan, &balanced), Ok(()));
+        let strict = DistroRequirements {
+            max_drone_ratio: Some(1.5),
+            ..DistroRequirements::default()
+        };
+        assert_eq!(
+            verify_distribution(&plan, &strict).map_err(|err| err.code),
+            Err(ErrorCode::DistributionImbalance)
+        );
+    }
+
+    #[test]
+    fn test_drone_impls() {
+        let rust_roveri = DroneImpl::from_code(0).unwrap();
+        assert_eq!(impl_name(rust_roveri), "rust_roveri");
+
+        let config = network_validate_str(CONFIG).unwrap();
+        let drone_impls = parse_drone_impls(CONFIG).unwrap();
+        assert_eq!(drone_impls.len(), 2);
+        let plan = DistributionPlan::with_drone_impls(&config, &drone_impls);
+        let assigned: Vec<DroneImpl> = plan.drones.iter().map(|(_, i)| *i).collect();
+        let code = |code| DroneImpl::from_code(code).unwrap();
+        assert_eq!(assigned, [code(2), code(1), code(0)]);
+        assert_eq!(plan.drones_distro()[..3], [1, 1, 1]);
+
+        let unknown = CONFIG.replace("impl = 2", &format!("impl = {}", MAX_IMPL));
+        let error = parse_drone_impls(&unknown).unwrap_err();
+        assert_eq!(error.code, ErrorCode::UnknownImpl);
+        assert!(error
+            .message
+            .starts_with("Drone [1] has unknown implementation"));
+        let unknown = CONFIG.replace("\"rust_roveri\"", "\"roveri\"");
+        assert_eq!(
+            parse_drone_impls(&unknown).map_err(|error| error.code),
+            Err(ErrorCode::UnknownImpl)
+        );
+        // The validation rejects unknown implementations too.
+        assert!(network_validate_str(&unknown)
+            .unwrap_err()
+            .starts_with("Drone [3] has unknown implementation"));
+    }
+
+    #[test]
+    fn test_validated_drone_impls() {
+        let path = env::temp_dir().join(format!("drone_impls_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, CONFIG).unwrap();
+        let validated = ValidatedConfig::from_file(path);
+        let planned = network_validate_plan(path);
+        fs::remove_file(path).unwrap();
+
+        let validated = validated.unwrap();
+        assert_eq!(validated.drone_impls(), &parse_drone_impls(CONFIG).unwrap());
+        let plan = validated.plan().unwrap();
+        assert_eq!(
+            plan,
+            DistributionPlan::with_drone_impls(validated.config(), validated.drone_impls())
+        );
+        assert_eq!(planned.unwrap().1, plan);
+        let code = |code| DroneImpl::from_code(code).unwrap();
+        let unused: Vec<DroneImpl> = (3..MAX_IMPL).map(code).collect();
+        assert_eq!(plan.unused_impls(), unused);
+    }
+
+    #[test]
+    fn test_plan_seeded() {
+        let config = generate_small_world(MAX_IMPL + 1, 4, 0.1, 3, 3, 1).unwrap();
+        let plan = DistributionPlan::try_seeded(&config, 7).unwrap();
+        assert_eq!(plan, DistributionPlan::try_seeded(&config, 7).unwrap());
+
+        // The spread is as even as the round-robin one, with the extra drone on any code.
+        let sequential = DistributionPlan::new(&config);
+        let mut counts = plan.drones_distro();
+        let mut expected = sequential.drones_distro();
+        counts.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(counts, expected);
+        assert_eq!(plan.clients_distro().iter().sum::<usize>(), 3);
+        assert!(
+            (0..10).any(|seed| DistributionPlan::try_seeded(&config, seed).unwrap() != sequential)
+        );
+    }
+
+    #[test]
+    fn test_type_unavailable() {
+        let config = network_validate_str(CONFIG).unwrap();
+        assert_eq!(
+            DistributionPlan::try_new(&config),
+            Ok(DistributionPlan::new(&config))
+        );
+
+        let none = round_robin(4, 0, &[], |_| Some(()), "client type").unwrap_err();
+        assert_eq!(none.code, ErrorCode::TypeUnavailable);
+        assert_eq!(
+            none.message,
+            "Node [4] cannot be assigned a client type, as none is available"
+        );
+        let miscounted = |code| (code < 1).then_some(code);
+        assert_eq!(round_robin(5, 2, &[0, 1], miscounted, "server type"), Ok(0));
+        let error = round_robin(5, 3, &[0, 1], miscounted, "server type").unwrap_err();
+        assert_eq!(error.code, ErrorCode::TypeUnavailable);
+        assert!(error.message.contains("code 1 of 2"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::model::{Role, Topology};
+
+/// Renders the topology of a configuration as a Graphviz DOT graph, e.g. to inspect by eye
+/// why a configuration fails the validation.
+///
+/// Drones are drawn as blue ellipses labelled with their packet drop rate, clients as green
+/// boxes and servers as orange octagons. A link declared by both endpoints is drawn once,
+/// labelled with the drop rate of each direction (the PDR of the receiving drone, from the
+/// endpoint with the smaller ID first); a neighbor declared by one endpoint only is drawn as a
+/// red arrow labelled `one-way`, and a neighbor which is not a node of the configuration as a
+/// red dashed outline.
+///
+/// # Parameters
+/// - `config`: The network configuration, which does not need to be valid.
+///
+/// Returns the DOT source of an undirected graph; the nodes of the configuration, the unknown
+/// neighbors and the links are each ordered by ID.
+pub fn topology_to_dot(config: &Config) -> String {
+    let topology = Topology::from(config);
+    let roles: BTreeMap<NodeId, Role> = topology
+        .nodes
+        .iter()
+        .map(|node| (node.id, node.role))
+        .collect();
+    let edges: BTreeSet<(NodeId, NodeId)> = topology.edges.iter().copied().collect();
+    let pdr = |id: NodeId| match roles.get(&id) {
+        Some(Role::Drone { pdr }) => *pdr,
+        _ => 0.0,
+    };
+
+    let mut dot = String::from("graph network {\n    node [style=filled];\n");
+    for (id, role) in &roles {
+        let (label, attributes) = match role {
+            Role::Drone { pdr } => (
+                format!("drone {}\\npdr {}", id, pdr),
+                "shape=ellipse, fillcolor=lightblue",
+            ),
+            Role::Client => (format!("client {}", id), "shape=box, fillcolor=palegreen"),
+            Role::Server => (
+                format!("server {}", id),
+                "shape=octagon, fillcolor=lightsalmon",
+            ),
+        };
+        let _ = writeln!(dot, "    {} [label=\"{}\", {}];", id, label, attributes);
+    }
+    let unknown: BTreeSet<NodeId> = edges
+        .iter()
+        .map(|(_, to)| *to)
+        .filter(|id| !roles.contains_key(id))
+        .collect();
+    for id in unknown {
+        let _ = writeln!(
+            dot,
+            "    {} [label=\"unknown {}\", style=dashed, color=red];",
+            id, id
+        );
+    }
+
+    for &(a, b) in &edges {
+        if edges.contains(&(b, a)) {
+            if a <= b {
+                let _ = writeln!(
+                    dot,
+                    "    {} -- {} [label=\"{} / {}\"];",
+                    a,
+                    b,
+                    pdr(b),
+                    pdr(a)
+                );
+            }
+        } else {
+            let _ = writeln!(
+                dot,
+                "    {} -- {} [label=\"one-way\", dir=forward, color=red, fontcolor=red];",
+                a, b
+            );
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use wg_2024::config::Config;
+
+    use crate::dot::topology_to_dot;
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 9]
+        pdr = 0.1
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 3]
+        pdr = 0.5
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [2]
+    "#;
+
+    #[test]
+    fn test_topology_to_dot() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let dot = topology_to_dot(&config);
+        let expected = [
+            "graph network {",
+            "    node [style=filled];",
+            "    1 [label=\"drone 1\\npdr 0.1\", shape=ellipse, fillcolor=lightblue];",
+            "    2 [label=\"drone 2\\npdr 0.5\", shape=ellipse, fillcolor=lightblue];",
+            "    3 [label=\"client 3\", shape=box, fillcolor=palegreen];",
+            "    4 [label=\"server 4\", shape=octagon, fillcolor=lightsalmon];",
+            "    9 [label=\"unknown 9\", style=dashed, color=red];",
+            "    1 -- 2 [label=\"0.5 / 0.1\"];",
+            "    1 -- 3 [label=\"0 / 0.1\"];",
+            "    1 -- 9 [label=\"one-way\", dir=forward, color=red, fontcolor=red];",
+            "    2 -- 3 [label=\"one-way\", dir=forward, color=red, fontcolor=red];",
+            "    4 -- 2 [label=\"one-way\", dir=forward, color=red, fontcolor=red];",
+            "}",
+        ];
+        assert_eq!(dot.lines().collect::<Vec<_>>(), expected);
+    }
+}
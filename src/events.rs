@@ -0,0 +1,375 @@
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use rust_roveri_api::{ClientEvent, ServerEvent};
+use wg_2024::{
+    controller::DroneEvent,
+    network::NodeId,
+    packet::{NackType, Packet, PacketType},
+};
+
+use crate::channels::Queue;
+use crate::conformance::ProtocolViolation;
+use crate::handle::{relay_recv, Relays};
+use crate::topology::LinkDivergence;
+
+/// An event emitted by a node to the simulation controller.
+#[derive(Debug, Clone)]
+pub enum NodeEvent {
+    Drone(DroneEvent),
+    Client(ClientEvent),
+    Server(ServerEvent),
+    /// The GUI of a client has not received the messages of the client for a while, see
+    /// [`crate::handle::GuiStall`]. Published to the subscribers only.
+    GuiStalled {
+        id: NodeId,
+    },
+    /// A message of the GUI of a client was discarded instead of being forwarded, because the
+    /// network is quiesced, see [`crate::handle::NetworkHandle::quiesce`]. Published to the
+    /// subscribers only.
+    GuiMessageRejected {
+        id: NodeId,
+    },
+    /// The topology graph departs from the links set by the commands sent to the nodes, see
+    /// [`crate::handle::NetworkHandle::reconcile`]. Published to the subscribers only.
+    LinkDivergence(LinkDivergence),
+    /// An event referenced nodes which are not part of the network. Published to the
+    /// subscribers only.
+    Suspicious(SuspiciousEvent),
+    /// A node broke the protocol, as checked by the monitor of
+    /// [`crate::conformance::spawn_conformance_monitor`]. Published to the subscribers only.
+    ProtocolViolation(ProtocolViolation),
+}
+
+/// An event referencing nodes which are not part of the network, a symptom of a node
+/// corrupting the IDs in its packets.
+#[derive(Debug, Clone)]
+pub struct SuspiciousEvent {
+    /// The IDs which are not part of the network, in increasing order.
+    pub unknown: Vec<NodeId>,
+    /// Whether the event was dropped instead of being forwarded, see
+    /// [`SuspiciousEventPolicy`].
+    pub dropped: bool,
+    /// The event.
+    pub event: Box<NodeEvent>,
+}
+
+/// What happens to an event referencing nodes which are not part of the network.
+///
+/// In both cases, a [`NodeEvent::Suspicious`] is published to the subscribers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SuspiciousEventPolicy {
+    /// The event is forwarded as usual.
+    #[default]
+    Flag,
+    /// The event is neither forwarded to the simulation controller nor published.
+    Drop,
+}
+
+/// The screening of the events of a node against the nodes of the network.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EventScreen {
+    /// The IDs of the nodes of the network, or `None` if events are not screened.
+    known: Option<Arc<BTreeSet<NodeId>>>,
+    policy: SuspiciousEventPolicy,
+}
+
+impl EventScreen {
+    /// Returns a screen flagging the events which reference nodes not in `known`.
+    pub(crate) fn new(known: Arc<BTreeSet<NodeId>>, policy: SuspiciousEventPolicy) -> Self {
+        Self {
+            known: Some(known),
+            policy,
+        }
+    }
+
+    /// Returns the IDs referenced by an event which are not part of the network.
+    fn unknown(&self, event: &NodeEvent) -> Vec<NodeId> {
+        let Some(known) = &self.known else {
+            return Vec::new();
+        };
+        let packet = match event {
+            NodeEvent::Drone(
+                DroneEvent::PacketSent(packet)
+                | DroneEvent::PacketDropped(packet)
+                | DroneEvent::ControllerShortcut(packet),
+            ) => packet,
+            NodeEvent::Client(ClientEvent::PacketSent(packet)) => packet,
+            NodeEvent::Server(ServerEvent::PacketSent(packet)) => packet,
+            _ => return Vec::new(),
+        };
+        let unknown: BTreeSet<NodeId> = packet_ids(packet)
+            .filter(|id| !known.contains(id))
+            .collect();
+        unknown.into_iter().collect()
+    }
+}
+
+/// Returns the node IDs referenced by a packet, in its routing header and its contents.
+fn packet_ids(packet: &Packet) -> impl Iterator<Item = NodeId> + '_ {
+    let contents: Vec<NodeId> = match &packet.pack_type {
+        PacketType::Nack(nack) => match nack.nack_type {
+            NackType::ErrorInRouting(id) | NackType::UnexpectedRecipient(id) => vec![id],
+            NackType::DestinationIsDrone | NackType::Dropped => Vec::new(),
+        },
+        PacketType::FloodRequest(request) => std::iter::once(request.initiator_id)
+            .chain(request.path_trace.iter().map(|(id, _)| *id))
+            .collect(),
+        PacketType::FloodResponse(response) => {
+            response.path_trace.iter().map(|(id, _)| *id).collect()
+        }
+        PacketType::MsgFragment(_) | PacketType::Ack(_) => Vec::new(),
+    };
+    packet.routing_header.hops.iter().copied().chain(contents)
+}
+
+/// An event observed on its way to the simulation controller, attributed to its emitter.
+#[derive(Debug, Clone)]
+pub struct ObservedEvent {
+    /// The ID of the node which emitted the event.
+    pub node: NodeId,
+    /// When the event was observed.
+    pub at: Instant,
+    /// The event.
+    pub event: NodeEvent,
+}
+
+/// Fan-out of observed items to the subscribers of a network.
+///
+/// Clones share the same subscribers.
+#[derive(Debug)]
+pub(crate) struct Hub<T>(Arc<Mutex<Vec<Sender<T>>>>);
+
+/// Fan-out of the events emitted by the nodes.
+pub(crate) type EventHub = Hub<ObservedEvent>;
+
+impl<T> Clone for Hub<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Default for Hub<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Vec::new())))
+    }
+}
+
+impl<T: Clone> Hub<T> {
+    /// Returns a receiver of every item observed from now on.
+    pub(crate) fn subscribe(&self) -> Receiver<T> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(sender);
+        receiver
+    }
+
+    /// Delivers an item to every subscriber, forgetting those which dropped their receiver.
+    pub(crate) fn publish(&self, item: T) {
+        let mut subscribers = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain(|subscriber| subscriber.send(item.clone()).is_ok());
+    }
+}
+
+/// Accounting of the event channel between a node and its relay.
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone)]
+pub(crate) struct EventQueue {
+    channel: Arc<dyn Queue>,
+    relayed: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+impl EventQueue {
+    /// Returns the number of events emitted by the node and not yet relayed.
+    pub(crate) fn queued(&self) -> usize {
+        self.channel.len()
+    }
+
+    /// Returns the capacity of the channel, or `None` if it is unbounded.
+    pub(crate) fn capacity(&self) -> Option<usize> {
+        self.channel.capacity()
+    }
+
+    /// Returns the number of events relayed so far.
+    pub(crate) fn relayed(&self) -> usize {
+        self.relayed.load(Ordering::SeqCst)
+    }
+
+    /// Returns the largest number of events observed waiting in the channel.
+    pub(crate) fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+}
+
+/// Returns a new event channel, bounded to the given capacity, if any.
+pub(crate) fn event_channel<T>(capacity: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    match capacity {
+        Some(capacity) => crossbeam_channel::bounded(capacity),
+        None => crossbeam_channel::unbounded(),
+    }
+}
+
+/// Spawns a thread forwarding the events of a node to the simulation controller, publishing a
+/// copy of each to the subscribers of the hub.
+///
+/// With [`InitOptions::per_node_events`](crate::init::InitOptions::per_node_events), every node
+/// gets its own relay, so that events are attributed to the node which emitted them. The thread
+/// terminates when the node drops its event sender, or the relays of the network are stopped.
+///
+/// # Parameters
+/// - `node`: The ID of the node.
+/// - `from`: The channel on which the node sends its events.
+/// - `to`: The channel on which the simulation controller receives them.
+/// - `hub`: The hub publishing the events to the subscribers.
+/// - `wrap`: The function wrapping the events of the node into a [`NodeEvent`].
+/// - `screen`: The screening of the events against the nodes of the network.
+/// - `relays`: The relays of the network, stopping this one on shutdown.
+///
+/// Returns the accounting of the channel of the node, updated by the relay.
+pub(crate) fn spawn_event_relay<T: Clone + Send + 'static>(
+    node: NodeId,
+    from: Receiver<T>,
+    to: Sender<T>,
+    hub: EventHub,
+    wrap: fn(T) -> NodeEvent,
+    screen: EventScreen,
+    relays: &Relays,
+) -> EventQueue {
+    let queue = EventQueue {
+        channel: Arc::new(from.clone()),
+        relayed: Arc::default(),
+        peak: Arc::default(),
+    };
+    let counters = queue.clone();
+    relays.spawn(move |stopped| {
+        while let Some(event) = relay_recv(&from, &stopped) {
+            // The event was still waiting in the channel, together with the ones behind it.
+            counters.peak.fetch_max(from.len() + 1, Ordering::SeqCst);
+            let wrapped = wrap(event.clone());
+            let unknown = screen.unknown(&wrapped);
+            let dropped = !unknown.is_empty() && screen.policy == SuspiciousEventPolicy::Drop;
+            if !dropped {
+                hub.publish(ObservedEvent {
+                    node,
+                    at: Instant::now(),
+                    event: wrapped.clone(),
+                });
+                let _ = to.send(event);
+            }
+            if !unknown.is_empty() {
+                log::warn!(node = node; "Event referencing unknown nodes {:?}", unknown);
+                let suspicious = SuspiciousEvent {
+                    unknown,
+                    dropped,
+                    event: Box::new(wrapped),
+                };
+                hub.publish(ObservedEvent {
+                    node,
+                    at: Instant::now(),
+                    event: NodeEvent::Suspicious(suspicious),
+                });
+            }
+            counters.relayed.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    queue
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+    use crate::events::{
+        event_channel, spawn_event_relay, EventHub, EventScreen, NodeEvent, SuspiciousEventPolicy,
+    };
+    use crate::handle::Relays;
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    #[test]
+    fn test_event_relay() {
+        let hub = EventHub::default();
+        let subscriber = hub.subscribe();
+        let (node_tx, node_rx) = event_channel(Some(4));
+        let (sc_tx, sc_rx) = crossbeam_channel::unbounded();
+        let screen = EventScreen::default();
+        let relays = Relays::default();
+        let queue = spawn_event_relay(3, node_rx, sc_tx, hub, NodeEvent::Drone, screen, &relays);
+        assert_eq!(queue.capacity(), Some(4));
+
+        let packet = Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        };
+        node_tx.send(DroneEvent::PacketDropped(packet)).unwrap();
+
+        let timeout = Duration::from_secs(1);
+        assert!(matches!(
+            sc_rx.recv_timeout(timeout),
+            Ok(DroneEvent::PacketDropped(_))
+        ));
+        let observed = subscriber.recv_timeout(timeout).unwrap();
+        assert_eq!(observed.node, 3);
+        assert!(matches!(
+            observed.event,
+            NodeEvent::Drone(DroneEvent::PacketDropped(_))
+        ));
+        drop(node_tx);
+        while queue.relayed() < 1 {
+            std::thread::yield_now();
+        }
+        assert_eq!((queue.queued(), queue.peak()), (0, 1));
+    }
+
+    #[test]
+    fn test_suspicious_event() {
+        let hub = EventHub::default();
+        let subscriber = hub.subscribe();
+        let (node_tx, node_rx) = event_channel(None);
+        let (sc_tx, sc_rx) = crossbeam_channel::unbounded();
+        let known = Arc::new(BTreeSet::from([1, 2, 3]));
+        let screen = EventScreen::new(known, SuspiciousEventPolicy::Drop);
+        let relays = Relays::default();
+        spawn_event_relay(3, node_rx, sc_tx, hub, NodeEvent::Drone, screen, &relays);
+
+        let packet = |hops| Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader { hop_index: 0, hops },
+            session_id: 0,
+        };
+        node_tx
+            .send(DroneEvent::PacketSent(packet(vec![3, 99, 2])))
+            .unwrap();
+        node_tx
+            .send(DroneEvent::PacketSent(packet(vec![3, 2])))
+            .unwrap();
+
+        let timeout = Duration::from_secs(1);
+        let observed = subscriber.recv_timeout(timeout).unwrap();
+        let NodeEvent::Suspicious(suspicious) = observed.event else {
+            panic!("expected a suspicious event, got {:?}", observed.event);
+        };
+        assert_eq!((suspicious.unknown, suspicious.dropped), (vec![99], true));
+        let forwarded = sc_rx.recv_timeout(timeout).unwrap();
+        assert!(
+            matches!(forwarded, DroneEvent::PacketSent(packet) if packet.routing_header.hops == [3, 2])
+        );
+    }
+}
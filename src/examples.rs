@@ -0,0 +1,48 @@
+/// The canonical configurations embedded in the crate, by name.
+const EXAMPLES: [(&str, &str); 4] = [
+    ("default", include_str!("config.toml")),
+    ("grid9", include_str!("examples/grid9.toml")),
+    ("ring8", include_str!("examples/ring8.toml")),
+    ("star10", include_str!("examples/star10.toml")),
+];
+
+/// Returns the contents of an example configuration file, e.g. to pass to
+/// [`crate::validate::network_validate_str`] without depending on the working directory.
+///
+/// # Parameters
+/// - `name`: The name of the example, one of [`names`].
+///
+/// Returns the TOML of the example, or `None` if there is no example with the given name.
+pub fn get(name: &str) -> Option<&'static str> {
+    EXAMPLES
+        .iter()
+        .find(|(example, _)| *example == name)
+        .map(|(_, config)| *config)
+}
+
+/// Returns the names of the example configurations, in alphabetical order.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    EXAMPLES.iter().map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::examples::{get, names};
+    use crate::validate::network_validate_str;
+
+    #[test]
+    fn test_examples() {
+        for name in names() {
+            let config = network_validate_str(get(name).unwrap());
+            assert!(config.is_ok(), "{}: {:?}", name, config.err());
+        }
+        assert_eq!(
+            network_validate_str(get("star10").unwrap())
+                .unwrap()
+                .drone
+                .len(),
+            10
+        );
+        assert!(get("missing").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::Receiver;
+use wg_2024::{controller::DroneEvent, network::NodeId};
+
+use crate::{
+    events::{NodeEvent, ObservedEvent},
+    handle::{NetworkHandle, ThreadState},
+};
+
+/// Maximum time between two checks of the termination state of the nodes.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// An event that an expectation waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The given drone forwarded a packet.
+    PacketSent { node: NodeId },
+    /// The given drone dropped a packet.
+    PacketDropped { node: NodeId },
+    /// The given drone asked the controller to deliver a packet.
+    ControllerShortcut { node: NodeId },
+    /// The thread of the given node terminated.
+    CrashOf { node: NodeId },
+    /// The thread of any node terminated.
+    Crash,
+}
+
+/// Something that happened in the network, as seen by [`Expectations`].
+#[derive(Debug)]
+enum Observation {
+    Event(Box<ObservedEvent>),
+    Terminated(NodeId),
+}
+
+impl Event {
+    fn matches(&self, observation: &Observation) -> bool {
+        match (self, observation) {
+            (Event::Crash, Observation::Terminated(_)) => true,
+            (Event::CrashOf { node }, Observation::Terminated(id)) => node == id,
+            (Event::PacketSent { node }, Observation::Event(observed)) => {
+                observed.node == *node
+                    && matches!(observed.event, NodeEvent::Drone(DroneEvent::PacketSent(_)))
+            }
+            (Event::PacketDropped { node }, Observation::Event(observed)) => {
+                observed.node == *node
+                    && matches!(
+                        observed.event,
+                        NodeEvent::Drone(DroneEvent::PacketDropped(_))
+                    )
+            }
+            (Event::ControllerShortcut { node }, Observation::Event(observed)) => {
+                observed.node == *node
+                    && matches!(
+                        observed.event,
+                        NodeEvent::Drone(DroneEvent::ControllerShortcut(_))
+                    )
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Assertions on the events of a live network, for end-to-end tests.
+///
+/// Only what happens after the creation of the `Expectations` is observed. Events are consumed
+/// in order: once an expectation is satisfied, the following ones only consider later events.
+///
+/// ```ignore
+/// let mut network = Expectations::new(&init_data.handle);
+/// network.expect(Event::PacketDropped { node: 7 }).within(Duration::from_secs(2));
+/// network.expect_no(Event::Crash).within(Duration::from_secs(1));
+/// ```
+pub struct Expectations {
+    handle: NetworkHandle,
+    events: Receiver<ObservedEvent>,
+    pending: VecDeque<Observation>,
+    terminated: HashSet<NodeId>,
+}
+
+/// A single expectation, checked by [`Expectation::within`].
+pub struct Expectation<'a> {
+    expectations: &'a mut Expectations,
+    event: Event,
+    negated: bool,
+}
+
+impl Expectations {
+    /// Starts observing the network behind the given handle, which must have been initialized
+    /// with [`InitOptions::per_node_events`](crate::init::InitOptions::per_node_events) for the
+    /// events of the nodes to be observed.
+    pub fn new(handle: &NetworkHandle) -> Self {
+        let events = handle.subscribe();
+        let terminated = handle
+            .termination_report()
+            .nodes
+            .into_iter()
+            .filter(|(_, state)| *state != ThreadState::Running)
+            .map(|(id, _)| id)
+            .collect();
+        Self {
+            handle: handle.clone(),
+            events,
+            pending: VecDeque::new(),
+            terminated,
+        }
+    }
+
+    /// Expects the given event to happen.
+    pub fn expect(&mut self, event: Event) -> Expectation<'_> {
+        Expectation {
+            expectations: self,
+            event,
+            negated: false,
+        }
+    }
+
+    /// Expects the given event not to happen.
+    pub fn expect_no(&mut self, event: Event) -> Expectation<'_> {
+        Expectation {
+            expectations: self,
+            event,
+            negated: true,
+        }
+    }
+
+    /// Waits up to `timeout` for new observations, queueing them.
+    fn poll(&mut self, timeout: Duration) {
+        if let Ok(event) = self.events.recv_timeout(timeout.min(POLL_INTERVAL)) {
+            self.pending.push_back(Observation::Event(Box::new(event)));
+        }
+        self.pending.extend(
+            self.events
+                .try_iter()
+                .map(|event| Observation::Event(Box::new(event))),
+        );
+        for (id, state) in self.handle.termination_report().nodes {
+            if state != ThreadState::Running && self.terminated.insert(id) {
+                self.pending.push_back(Observation::Terminated(id));
+            }
+        }
+    }
+}
+
+impl Expectation<'_> {
+    /// Checks the expectation for at most `timeout`.
+    ///
+    /// # Panics
+    /// If an expected event does not happen within the timeout, or an unexpected one does.
+    #[track_caller]
+    pub fn within(self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        loop {
+            while let Some(observation) = self.expectations.pending.pop_front() {
+                if !self.event.matches(&observation) {
+                    continue;
+                }
+                if self.negated {
+                    panic!(
+                        "Expected no {:?} within {:?}, but observed {:?}",
+                        self.event, timeout, observation
+                    );
+                }
+                return;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                if !self.negated {
+                    panic!("Expected {:?} within {:?}", self.event, timeout);
+                }
+                return;
+            }
+            self.expectations.poll(deadline - now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::events::{spawn_event_relay, EventHub, EventScreen, NodeEvent};
+    use crate::expect::{Event, Expectations};
+    use crate::handle::{spawn_node, NetworkHandle, NodeEntry, Relays, Shared};
+    use rust_roveri_api::Command;
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    fn ack() -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_expectations() {
+        let hub = EventHub::default();
+        let (event_tx, event_rx) = crossbeam_channel::unbounded();
+        let (sc_tx, _sc_rx) = crossbeam_channel::unbounded();
+        let screen = EventScreen::default();
+        let relays = Relays::default();
+        spawn_event_relay(
+            7,
+            event_rx,
+            sc_tx,
+            hub.clone(),
+            NodeEvent::Drone,
+            screen,
+            &relays,
+        );
+        let (exit_tx, exit_rx) = crossbeam_channel::unbounded::<()>();
+        let liveness = spawn_node(move || {
+            let _ = exit_rx.recv();
+        });
+        let (packet_tx, _packet_rx) = crossbeam_channel::unbounded();
+        let shared = Shared {
+            events: hub,
+            ..Shared::default()
+        };
+        let handle = NetworkHandle::new(
+            vec![NodeEntry::new(7, Command::None, packet_tx, liveness)],
+            shared,
+        );
+        let mut network = Expectations::new(&handle);
+
+        event_tx.send(DroneEvent::PacketDropped(ack())).unwrap();
+        network
+            .expect(Event::PacketDropped { node: 7 })
+            .within(Duration::from_secs(2));
+        network
+            .expect_no(Event::Crash)
+            .within(Duration::from_millis(50));
+
+        exit_tx.send(()).unwrap();
+        network
+            .expect(Event::CrashOf { node: 7 })
+            .within(Duration::from_secs(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected PacketSent")]
+    fn test_expectation_timeout() {
+        let handle = NetworkHandle::new(vec![], Shared::default());
+        Expectations::new(&handle)
+            .expect(Event::PacketSent { node: 1 })
+            .within(Duration::from_millis(20));
+    }
+}
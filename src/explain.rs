@@ -0,0 +1,206 @@
+use crate::validate::ErrorCode;
+
+/// A longer description of a validation rule, suitable for a help panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    /// The rule, in one sentence.
+    pub rule: &'static str,
+    /// Why the rule exists.
+    pub rationale: &'static str,
+    /// How to fix a configuration which violates the rule.
+    pub fix: &'static str,
+}
+
+/// Explains the rule identified by an error code.
+///
+/// # Parameters
+/// - `code`: The code of the violated rule, e.g. from a [`crate::validate::ValidationError`].
+///
+/// Returns the explanation of the rule.
+pub fn explain(code: ErrorCode) -> Explanation {
+    let (rule, rationale, fix) = match code {
+        ErrorCode::InvalidPdr => (
+            "The packet drop rate of a drone must be between 0 and 1.",
+            "The packet drop rate is the probability that a drone drops a fragment instead of \
+             forwarding it, so values outside of [0, 1] are meaningless.",
+            "Set the `pdr` of the drone to a value between 0.0 and 1.0.",
+        ),
+        ErrorCode::SelfConnection => (
+            "A node must not list itself as a neighbor.",
+            "Links connect two distinct nodes: a drone linked to itself would forward packets \
+             back to itself, which no source route can express.",
+            "Remove the ID of the node from its own neighbor list.",
+        ),
+        ErrorCode::DuplicateNeighbor => (
+            "A node must not list the same neighbor more than once.",
+            "Every neighbor is given a single channel, so a repeated entry either adds the \
+             same channel twice or hides a typo in the intended neighbor.",
+            "Remove the repeated IDs from the neighbor list of the node.",
+        ),
+        ErrorCode::ClientNeighborCount => (
+            "A client must be connected to one or two drones.",
+            "The protocol places clients at the edge of the network: they need at least one \
+             drone to reach it, and may have at most two so that they do not become hubs of \
+             the topology.",
+            "Give the client one or two drones in `connected_drone_ids`.",
+        ),
+        ErrorCode::ServerNeighborCount => (
+            "A server must be connected to at least two drones.",
+            "Servers are shared by every client, so the protocol requires them to survive the \
+             crash of any single neighbor drone.",
+            "Connect the server to at least one more drone.",
+        ),
+        ErrorCode::DuplicateId => (
+            "Every node must have a different ID.",
+            "Source routing headers identify nodes by ID alone, so two nodes with the same ID \
+             could not be told apart by the routes.",
+            "Give one of the nodes an ID which is not used by any other node.",
+        ),
+        ErrorCode::NeighborNotDrone => (
+            "Clients and servers may only be connected to drones.",
+            "Clients and servers do not forward packets, so a link between them could never \
+             be used by a route of any other node.",
+            "Connect the client or server to a drone instead.",
+        ),
+        ErrorCode::UnknownNeighbor => (
+            "Every neighbor must be a node of the configuration.",
+            "A channel can only be created towards a node which is spawned, so a link to a \
+             missing node would leave the neighbor list inconsistent with the topology.",
+            "Add the missing node, or remove it from the neighbor list.",
+        ),
+        ErrorCode::NotBidirectional => (
+            "Every link must be declared by both of its endpoints.",
+            "Packets travel in both directions (fragments one way, ACKs and NACKs back), so \
+             both endpoints need a channel towards each other.",
+            "Add each node to the neighbor list of the other one, or remove the link from both.",
+        ),
+        ErrorCode::NotConnected => (
+            "The topology must be connected.",
+            "A client can only talk to the servers it can reach, so a disconnected part of the \
+             network would make some messages undeliverable from the start.",
+            "Add links between the disconnected parts of the network.",
+        ),
+        ErrorCode::NotAtEdge => (
+            "Clients and servers must be on the edge of the network.",
+            "Only drones forward packets, so the drones must stay connected to each other \
+             without relying on any client or server.",
+            "Add links between drones, so that they are connected without going through \
+             clients or servers.",
+        ),
+        ErrorCode::LinkTooLong => (
+            "Linked nodes must be placed within the allowed radius of each other.",
+            "Positions model a wireless network, where nodes can only reach the nodes in \
+             range; a longer link could not exist in the modeled space.",
+            "Move the nodes closer to each other, or remove the link.",
+        ),
+        ErrorCode::NodeCount => (
+            "The number of nodes of each type must be within the limits of the policy.",
+            "The validation policy sets the sizes of the networks accepted by a deployment, \
+             e.g. the ones used for grading.",
+            "Add or remove nodes of the reported type.",
+        ),
+        ErrorCode::UnusedImpl => (
+            "Every drone implementation should be assigned to some drone.",
+            "Implementations are assigned round-robin, so a network with fewer drones than \
+             implementations leaves some of them untested.",
+            "Add drones, so that there are at least as many drones as implementations.",
+        ),
+        ErrorCode::DistributionImbalance => (
+            "Implementations and types must be spread evenly across the nodes.",
+            "An uneven spread would make the behavior of the network depend mostly on a few \
+             implementations, skewing comparisons between them.",
+            "Change the number of nodes, so that the round-robin assignment is more even.",
+        ),
+        ErrorCode::UnknownNode => (
+            "A node can only be validated if it exists.",
+            "Targeted checks refer to a node by ID, so the ID must belong to some node of the \
+             configuration.",
+            "Check the ID of the node, or add the node to the configuration.",
+        ),
+        ErrorCode::IdOutOfRange => (
+            "Every node ID must be below the maximum number of nodes.",
+            "The simulation controller keeps per-node data in arrays indexed by node ID, so a \
+             larger ID would have no slot in them.",
+            "Renumber the node, and the neighbor lists referencing it, with a smaller ID.",
+        ),
+        ErrorCode::InconsistentLink => (
+            "Both endpoints of a link must agree on the attributes of the link.",
+            "A link is a single connection, so an attribute declared by both of its endpoints \
+             with different values leaves it ambiguous which one applies.",
+            "Declare the attribute on one endpoint only, or give it the same value on both.",
+        ),
+        ErrorCode::NoDrones => (
+            "The network must have at least one drone.",
+            "Clients and servers are only connected to drones, and only drones forward \
+             packets, so without drones no message can ever be delivered.",
+            "Add drones, and connect the clients and servers to them.",
+        ),
+        ErrorCode::Malformed => (
+            "The configuration file must be readable, and be a valid TOML configuration.",
+            "The nodes and their links are only known once the file is read and deserialized, \
+             so no other rule can be checked before.",
+            "Check the path of the file, and fix the syntax error or the mistyped field \
+             reported by the deserializer.",
+        ),
+        ErrorCode::UnknownImpl => (
+            "A drone can only choose one of the known drone implementations.",
+            "The implementation of a drone is built by the drone factory from its code, so a \
+             code or name outside of the known ones has nothing to build.",
+            "Use a code below the number of implementations, or one of the listed names, or \
+             remove the key to keep the round-robin assignment.",
+        ),
+        ErrorCode::SinglePointOfFailure => (
+            "No single drone crash should disconnect a client from a server.",
+            "Drones crash at runtime, and a topology depending on one of them for some \
+             client-server pair stops working as soon as that drone goes down.",
+            "Connect the affected clients or servers to a second drone, or add drone links \
+             bypassing the reported drones.",
+        ),
+        ErrorCode::TypeUnavailable => (
+            "Every node must be assignable to an available implementation or type.",
+            "Nodes are built from the implementation or type assigned to them round-robin, so \
+             a build with no client or server types, or with a miscounted number of them, \
+             would leave some node with nothing to build.",
+            "Remove the nodes of the reported kind, or rebuild against an API crate providing \
+             implementations or types for them.",
+        ),
+        ErrorCode::DropsEverything => (
+            "A drone should not have a packet drop rate of exactly 1.",
+            "Such a drone drops every fragment it receives, so it only ever answers with Nack \
+             messages: a valid setting, but rarely an intended one.",
+            "Lower the `pdr` of the drone, or remove the drone from the topology.",
+        ),
+        ErrorCode::NoDroneNeighbors => (
+            "A drone should be connected to at least another drone, if the network has any.",
+            "Clients and servers do not forward packets, so a drone only connected to them is \
+             a dead end, which usually means that a link to the rest of the drones is missing.",
+            "Connect the drone to another drone, or to the clients and servers it should serve.",
+        ),
+        ErrorCode::HighAveragePdr => (
+            "The average packet drop rate of the drones should not be very high.",
+            "Fragments are dropped at every hop, so with a high average rate most messages need \
+             many retransmissions, and the simulation is dominated by Nack messages.",
+            "Lower the `pdr` of the drones, unless the network is meant to be stress tested.",
+        ),
+    };
+    Explanation {
+        rule,
+        rationale,
+        fix,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::explain::explain;
+    use crate::validate::ErrorCode;
+
+    #[test]
+    fn test_explain() {
+        let explanation = explain(ErrorCode::ClientNeighborCount);
+
+        assert!(explanation.rule.contains("one or two drones"));
+        assert!(explanation.rationale.contains("protocol"));
+        assert!(!explanation.fix.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::index::NODE_CAPACITY;
+
+/// A node ID declared by more than one of the configurations checked by
+/// [`check_disjoint_ids`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdCollision {
+    /// The colliding ID.
+    pub id: NodeId,
+    /// The positions of the configurations declaring a node with the ID, in ascending order.
+    pub configs: Vec<usize>,
+}
+
+impl fmt::Display for IdCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let configs: Vec<String> = self.configs.iter().map(|index| index.to_string()).collect();
+        write!(
+            f,
+            "Node ID [{}] is declared by configurations {}",
+            self.id,
+            configs.join(", ")
+        )
+    }
+}
+
+/// The ID collisions between configurations, with the remappings which would resolve them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisjointReport {
+    /// The colliding IDs, in ascending order.
+    pub collisions: Vec<IdCollision>,
+    /// The suggested new ID of the colliding nodes, for every configuration in order, to apply
+    /// with [`remap_ids`]; the first configuration declaring an ID keeps it.
+    pub remappings: Vec<BTreeMap<NodeId, NodeId>>,
+    /// The colliding nodes left without a suggestion, as `(configuration, id)`, since no ID
+    /// below [`NODE_CAPACITY`] is free.
+    pub unresolved: Vec<(usize, NodeId)>,
+}
+
+/// Checks that configurations to be merged, or run side by side and bridged, declare disjoint
+/// node IDs, e.g. for simulations federating the networks of several teams.
+///
+/// # Parameters
+/// - `configs`: The configurations.
+///
+/// Returns an error with every collision, and the remappings suggested to resolve them, if
+/// some ID is declared by more than one configuration. A suggested ID is never used, either as
+/// a node or as a neighbor, by any of the configurations, nor suggested for another node.
+///
+/// # Performance
+/// `O(n log n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn check_disjoint_ids(configs: &[Config]) -> Result<(), DisjointReport> {
+    let mut owners: BTreeMap<NodeId, Vec<usize>> = BTreeMap::new();
+    let mut used: BTreeSet<NodeId> = BTreeSet::new();
+    for (index, config) in configs.iter().enumerate() {
+        let ids: BTreeSet<NodeId> = node_ids(config).collect();
+        for id in ids {
+            owners.entry(id).or_default().push(index);
+        }
+        used.extend(referenced_ids(config));
+    }
+
+    let mut report = DisjointReport {
+        remappings: vec![BTreeMap::new(); configs.len()],
+        ..DisjointReport::default()
+    };
+    let mut free = (0..NODE_CAPACITY)
+        .map(|id| id as NodeId)
+        .filter(|id| !used.contains(id));
+    for (id, configs) in owners {
+        if configs.len() < 2 {
+            continue;
+        }
+        for &index in &configs[1..] {
+            match free.next() {
+                Some(new_id) => {
+                    report.remappings[index].insert(id, new_id);
+                }
+                None => report.unresolved.push((index, id)),
+            }
+        }
+        report.collisions.push(IdCollision { id, configs });
+    }
+    if report.collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(report)
+    }
+}
+
+/// Renames nodes of a configuration, in their declarations and in the neighbor lists.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `remapping`: The new ID of the renamed nodes, e.g. suggested by [`check_disjoint_ids`].
+///
+/// Returns the renamed configuration; the IDs which are not remapped are kept.
+pub fn remap_ids(config: &Config, remapping: &BTreeMap<NodeId, NodeId>) -> Config {
+    let rename = |id: &NodeId| remapping.get(id).copied().unwrap_or(*id);
+    let mut config = config.clone();
+    for drone in &mut config.drone {
+        drone.id = rename(&drone.id);
+        drone.connected_node_ids = drone.connected_node_ids.iter().map(rename).collect();
+    }
+    for client in &mut config.client {
+        client.id = rename(&client.id);
+        client.connected_drone_ids = client.connected_drone_ids.iter().map(rename).collect();
+    }
+    for server in &mut config.server {
+        server.id = rename(&server.id);
+        server.connected_drone_ids = server.connected_drone_ids.iter().map(rename).collect();
+    }
+    config
+}
+
+/// Returns the IDs of the nodes declared by a configuration.
+fn node_ids(config: &Config) -> impl Iterator<Item = NodeId> + '_ {
+    (config.drone.iter().map(|drone| drone.id))
+        .chain(config.client.iter().map(|client| client.id))
+        .chain(config.server.iter().map(|server| server.id))
+}
+
+/// Returns the IDs of the nodes declared by a configuration, and of their neighbors.
+fn referenced_ids(config: &Config) -> impl Iterator<Item = NodeId> + '_ {
+    let neighbors = (config.drone.iter())
+        .flat_map(|drone| drone.connected_node_ids.iter().copied())
+        .chain(
+            config
+                .client
+                .iter()
+                .flat_map(|client| client.connected_drone_ids.iter().copied()),
+        )
+        .chain(
+            config
+                .server
+                .iter()
+                .flat_map(|server| server.connected_drone_ids.iter().copied()),
+        );
+    node_ids(config).chain(neighbors)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::federate::{check_disjoint_ids, remap_ids};
+    use crate::generator::generate_small_world;
+    use crate::validate::check_config;
+
+    #[test]
+    fn test_check_disjoint_ids() {
+        let first = generate_small_world(4, 2, 0.1, 1, 1, 1).unwrap();
+        let second = generate_small_world(3, 2, 0.1, 1, 1, 2).unwrap();
+        let report = check_disjoint_ids(&[first.clone(), second.clone()]).unwrap_err();
+        assert!(!report.collisions.is_empty());
+        assert!(report.unresolved.is_empty());
+        assert!(report.remappings[0].is_empty());
+        assert_eq!(report.collisions[0].configs, [0, 1]);
+        assert_eq!(report.remappings[1].len(), report.collisions.len());
+
+        let second = remap_ids(&second, &report.remappings[1]);
+        assert_eq!(check_config(&second), Ok(()));
+        assert_eq!(check_disjoint_ids(&[first, second]), Ok(()));
+    }
+}
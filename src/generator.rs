@@ -0,0 +1,694 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rust_roveri_api::MAX_NODES;
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::model::{Role, Topology};
+use crate::validate::{self_check, validate_config, ErrorCode};
+
+/// Number of independent attempts made by a generator before giving up.
+const MAX_ATTEMPTS: usize = 64;
+/// Number of edge swaps tried while repairing a single invalid edge.
+const MAX_REPAIR_SWAPS: usize = 1024;
+/// Range from which the PDR of generated drones is drawn.
+const DEFAULT_PDR_RANGE: (f32, f32) = (0.0, 0.1);
+
+/// Undirected edge between two drones, expressed as drone indices.
+type Edge = (usize, usize);
+
+/// The single rule violation injected by [`generate_invalid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A drone is declared twice.
+    DuplicateId,
+    /// An edge between two drones is declared by only one of them.
+    AsymmetricEdge,
+    /// A client is connected to no drone.
+    IsolatedClient,
+    /// A drone has a packet drop rate outside of `[0, 1]`.
+    BadPdr,
+    /// A drone lists a neighbor which is not defined in the configuration.
+    MissingNode,
+}
+
+/// Generates a random topology whose drone subgraph follows the given degree sequence.
+///
+/// The drone subgraph is built with a configuration-model construction: each drone gets as
+/// many stubs as its requested degree, the stubs are shuffled and paired into edges, and the
+/// resulting self-loops and parallel edges are repaired with degree-preserving edge swaps.
+/// Clients are then attached to one or two random drones, servers to two random drones, and
+/// the resulting configuration is validated. Unlucky constructions (e.g. a disconnected drone
+/// subgraph) are retried with fresh randomness.
+///
+/// Drones are assigned the IDs `1..=n`, followed by the clients and then by the servers.
+///
+/// # Parameters
+/// - `drone_degrees`: The number of drone neighbors of each drone.
+/// - `n_clients`: The number of clients to attach.
+/// - `n_servers`: The number of servers to attach.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns a valid configuration, or an error if the degree sequence could not be realized.
+///
+/// # Performance
+/// `O(a * (n + m))`, where `a` is the number of attempts, `n` is the number of nodes and `m` is
+/// the number of edges.
+pub fn generate_from_degrees(
+    drone_degrees: &[usize],
+    n_clients: usize,
+    n_servers: usize,
+    seed: u64,
+) -> Result<Config, String> {
+    let n_drones = drone_degrees.len();
+    check_node_count(n_drones, n_clients, n_servers)?;
+    if drone_degrees.iter().sum::<usize>() % 2 != 0 {
+        return Err("The sum of the drone degrees must be even".to_string());
+    }
+    if let Some(degree) = drone_degrees.iter().find(|degree| **degree >= n_drones) {
+        return Err(format!(
+            "Drone degree {} cannot be realized with {} drones",
+            degree, n_drones
+        ));
+    }
+
+    generate_valid(n_drones, n_clients, n_servers, seed, |rng| {
+        pair_stubs(drone_degrees, rng)
+            .ok_or_else(|| "Unable to repair self-loops and duplicate edges".to_string())
+    })
+}
+
+/// Generates a random small-world topology using the Watts–Strogatz model.
+///
+/// The drone subgraph starts as a ring lattice where each drone is connected to its `k / 2`
+/// nearest drones on each side, after which every lattice edge is rewired to a random drone
+/// with probability `beta`. Clients and servers are then attached as in
+/// [`generate_from_degrees`].
+///
+/// # Parameters
+/// - `n_drones`: The number of drones.
+/// - `k`: The mean drone degree of the initial lattice; it must be even and smaller than `n_drones`.
+/// - `beta`: The rewiring probability, between 0 and 1.
+/// - `n_clients`: The number of clients to attach.
+/// - `n_servers`: The number of servers to attach.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns a valid configuration, or an error if the parameters are invalid.
+///
+/// # Performance
+/// `O(a * (n + m) log m)`, where `a` is the number of attempts, `n` is the number of nodes and
+/// `m` is the number of edges.
+pub fn generate_small_world(
+    n_drones: usize,
+    k: usize,
+    beta: f64,
+    n_clients: usize,
+    n_servers: usize,
+    seed: u64,
+) -> Result<Config, String> {
+    check_node_count(n_drones, n_clients, n_servers)?;
+    if k == 0 || !k.is_multiple_of(2) || k >= n_drones {
+        return Err(format!(
+            "Invalid mean degree {} for {} drones: it must be even, positive and smaller than the number of drones",
+            k, n_drones
+        ));
+    }
+    if !(0.0..=1.0).contains(&beta) {
+        return Err(format!("Invalid rewiring probability: {}", beta));
+    }
+
+    generate_valid(n_drones, n_clients, n_servers, seed, |rng| {
+        Ok(watts_strogatz(n_drones, k, beta, rng))
+    })
+}
+
+/// Generates a random scale-free topology using the Barabási–Albert model.
+///
+/// The drone subgraph starts as a clique of `m + 1` drones; every further drone is then
+/// connected to `m` distinct existing drones, chosen with probability proportional to their
+/// degree (preferential attachment). Clients and servers are then attached as in
+/// [`generate_from_degrees`].
+///
+/// # Parameters
+/// - `n_drones`: The number of drones.
+/// - `m`: The number of edges added with every new drone; it must be positive and smaller than `n_drones`.
+/// - `n_clients`: The number of clients to attach.
+/// - `n_servers`: The number of servers to attach.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns a valid configuration, or an error if the parameters are invalid.
+///
+/// # Performance
+/// `O(a * (n + m) log m)`, where `a` is the number of attempts, `n` is the number of nodes and
+/// `m` is the number of edges.
+pub fn generate_scale_free(
+    n_drones: usize,
+    m: usize,
+    n_clients: usize,
+    n_servers: usize,
+    seed: u64,
+) -> Result<Config, String> {
+    check_node_count(n_drones, n_clients, n_servers)?;
+    if m == 0 || m >= n_drones {
+        return Err(format!(
+            "Invalid number of edges per drone {} for {} drones: it must be positive and smaller than the number of drones",
+            m, n_drones
+        ));
+    }
+
+    generate_valid(n_drones, n_clients, n_servers, seed, |rng| {
+        Ok(barabasi_albert(n_drones, m, rng))
+    })
+}
+
+/// Generates a random topology with the given average drone degree, e.g. to stress-test the
+/// simulation controller with hundreds of nodes.
+///
+/// The drone subgraph starts as a random spanning tree, so that it is connected, and random
+/// edges between distinct drones are then added until the requested average degree is
+/// reached. Clients and servers are then attached as in [`generate_from_degrees`].
+///
+/// # Parameters
+/// - `n_drones`: The number of drones.
+/// - `n_clients`: The number of clients to attach.
+/// - `n_servers`: The number of servers to attach.
+/// - `avg_degree`: The average number of drone neighbors of a drone, at most `n_drones - 1`;
+///   it is raised to the degree of a spanning tree, `2 (n_drones - 1) / n_drones`, if lower.
+/// - `pdr_range`: The range from which the PDR of every drone is drawn, within `[0, 1]`.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns a valid configuration, or an error if the parameters are invalid.
+///
+/// # Performance
+/// `O(a * (n + m) log m)`, where `a` is the number of attempts, `n` is the number of nodes and
+/// `m` is the number of edges.
+pub fn generate_random_topology(
+    n_drones: usize,
+    n_clients: usize,
+    n_servers: usize,
+    avg_degree: f64,
+    pdr_range: (f32, f32),
+    seed: u64,
+) -> Result<Config, String> {
+    check_node_count(n_drones, n_clients, n_servers)?;
+    if !(0.0..=n_drones.saturating_sub(1) as f64).contains(&avg_degree) {
+        return Err(format!(
+            "Invalid average degree {} for {} drones: it must be between 0 and the number of drones minus one",
+            avg_degree, n_drones
+        ));
+    }
+    let (low, high) = pdr_range;
+    if !(0.0 <= low && low <= high && high <= 1.0) {
+        return Err(format!("Invalid PDR range: [{}, {}]", low, high));
+    }
+
+    let n_edges =
+        ((n_drones as f64 * avg_degree / 2.0).round() as usize).max(n_drones.saturating_sub(1));
+    generate_valid_with_pdr(n_drones, n_clients, n_servers, pdr_range, seed, |rng| {
+        Ok(random_connected(n_drones, n_edges, rng))
+    })
+}
+
+/// Generates a configuration which violates exactly one validation rule.
+///
+/// A valid small-world topology is generated first, then a single violation of the requested
+/// kind is injected at a random location. The returned [`ErrorCode`] is the one reported by
+/// [`check_config`](crate::validate::check_config) for the generated configuration, which makes
+/// this function suitable for regression suites over the validator itself.
+///
+/// # Parameters
+/// - `kind`: The kind of violation to inject.
+/// - `seed`: The seed of the random number generator.
+///
+/// Returns the invalid configuration together with the expected error code.
+///
+/// # Performance
+/// `O(a * (n + m) log m)`, where `a` is the number of attempts, `n` is the number of nodes and
+/// `m` is the number of edges.
+pub fn generate_invalid(kind: ViolationKind, seed: u64) -> (Config, ErrorCode) {
+    let mut config = generate_small_world(12, 4, 0.1, 3, 3, seed)
+        .expect("the base topology parameters are valid");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let n_drones = config.drone.len();
+
+    let code = match kind {
+        ViolationKind::DuplicateId => {
+            let duplicate = config.drone[rng.gen_range(0..n_drones)].clone();
+            config.drone.push(duplicate);
+            ErrorCode::DuplicateId
+        }
+        ViolationKind::AsymmetricEdge => {
+            // Generated drones use the IDs `1..=n_drones`, and every drone of a small-world
+            // topology has at least one drone neighbor.
+            let index = rng.gen_range(0..n_drones);
+            let id = config.drone[index].id;
+            let neighbor = *config.drone[index]
+                .connected_node_ids
+                .iter()
+                .find(|neighbor| (**neighbor as usize) <= n_drones)
+                .expect("small-world drones have drone neighbors");
+            config.drone[neighbor as usize - 1]
+                .connected_node_ids
+                .retain(|other| *other != id);
+            ErrorCode::NotBidirectional
+        }
+        ViolationKind::IsolatedClient => {
+            let index = rng.gen_range(0..config.client.len());
+            let client = &mut config.client[index];
+            let id = client.id;
+            client.connected_drone_ids.clear();
+            for drone in &mut config.drone {
+                drone.connected_node_ids.retain(|neighbor| *neighbor != id);
+            }
+            ErrorCode::ClientNeighborCount
+        }
+        ViolationKind::BadPdr => {
+            let pdr = if rng.gen_bool(0.5) { -0.5 } else { 1.5 };
+            config.drone[rng.gen_range(0..n_drones)].pdr = pdr;
+            ErrorCode::InvalidPdr
+        }
+        ViolationKind::MissingNode => {
+            let n_nodes = n_drones + config.client.len() + config.server.len();
+            let missing = to_id(n_nodes);
+            config.drone[rng.gen_range(0..n_drones)]
+                .connected_node_ids
+                .push(missing);
+            ErrorCode::UnknownNeighbor
+        }
+    };
+
+    self_check("generate_invalid", &config, Some(code));
+    (config, code)
+}
+
+/// Repeatedly builds a drone subgraph and attaches clients and servers to it until the
+/// resulting configuration passes validation.
+///
+/// `drone_edges` is invoked once per attempt and returns the drone subgraph, or an error if
+/// the attempt could not produce one.
+fn generate_valid(
+    n_drones: usize,
+    n_clients: usize,
+    n_servers: usize,
+    seed: u64,
+    drone_edges: impl FnMut(&mut StdRng) -> Result<Vec<Edge>, String>,
+) -> Result<Config, String> {
+    let pdr_range = DEFAULT_PDR_RANGE;
+    generate_valid_with_pdr(n_drones, n_clients, n_servers, pdr_range, seed, drone_edges)
+}
+
+/// Same as [`generate_valid`], drawing the PDR of the drones from the given range.
+fn generate_valid_with_pdr(
+    n_drones: usize,
+    n_clients: usize,
+    n_servers: usize,
+    pdr_range: (f32, f32),
+    seed: u64,
+    mut drone_edges: impl FnMut(&mut StdRng) -> Result<Vec<Edge>, String>,
+) -> Result<Config, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut last_error = String::new();
+    for _ in 0..MAX_ATTEMPTS {
+        let edges = match drone_edges(&mut rng) {
+            Ok(edges) => edges,
+            Err(err) => {
+                last_error = err;
+                continue;
+            }
+        };
+        let config = build_config(n_drones, &edges, n_clients, n_servers, pdr_range, &mut rng);
+        match validate_config(&config) {
+            Ok(()) => {
+                let counts = (config.drone.len(), config.client.len(), config.server.len());
+                assert!(
+                    !cfg!(feature = "self-check") || counts == (n_drones, n_clients, n_servers),
+                    "generated {:?} drones, clients and servers instead of {:?}",
+                    counts,
+                    (n_drones, n_clients, n_servers)
+                );
+                return Ok(config);
+            }
+            Err(err) => last_error = err,
+        }
+    }
+    Err(format!(
+        "Unable to generate a valid topology after {} attempts: {}",
+        MAX_ATTEMPTS, last_error
+    ))
+}
+
+/// Checks that the requested number of nodes can be assigned distinct, in-range node IDs.
+fn check_node_count(n_drones: usize, n_clients: usize, n_servers: usize) -> Result<(), String> {
+    let n_nodes = n_drones + n_clients + n_servers;
+    let max_nodes = (MAX_NODES - 1).min(NodeId::MAX as usize);
+    if n_nodes > max_nodes {
+        return Err(format!(
+            "Cannot generate {} nodes: at most {} are supported",
+            n_nodes, max_nodes
+        ));
+    }
+    Ok(())
+}
+
+/// Converts a zero-based node index into the node ID used by the generators.
+fn to_id(index: usize) -> NodeId {
+    (index + 1) as NodeId
+}
+
+/// Returns the canonical (sorted) form of an undirected edge.
+fn normalize((a, b): Edge) -> Edge {
+    (a.min(b), a.max(b))
+}
+
+/// Shuffles the stubs of every drone and pairs them into edges, repairing invalid edges.
+///
+/// Returns `None` if the self-loops and duplicate edges could not be repaired.
+fn pair_stubs(degrees: &[usize], rng: &mut StdRng) -> Option<Vec<Edge>> {
+    let mut stubs: Vec<usize> = degrees
+        .iter()
+        .enumerate()
+        .flat_map(|(drone, degree)| std::iter::repeat_n(drone, *degree))
+        .collect();
+    stubs.shuffle(rng);
+    let mut edges: Vec<Edge> = stubs
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    repair_edges(&mut edges, rng).then_some(edges)
+}
+
+/// Builds a Watts–Strogatz drone subgraph: a ring lattice whose edges are randomly rewired.
+///
+/// Rewirings that would create a self-loop or a parallel edge are skipped, so the number of
+/// edges always equals `n * k / 2`.
+fn watts_strogatz(n: usize, k: usize, beta: f64, rng: &mut StdRng) -> Vec<Edge> {
+    let mut edges: BTreeSet<Edge> = (0..n)
+        .flat_map(|drone| (1..=k / 2).map(move |offset| normalize((drone, (drone + offset) % n))))
+        .collect();
+    for drone in 0..n {
+        for offset in 1..=k / 2 {
+            if !rng.gen_bool(beta) {
+                continue;
+            }
+            let target = rng.gen_range(0..n);
+            let rewired = normalize((drone, target));
+            if target == drone || edges.contains(&rewired) {
+                continue;
+            }
+            if edges.remove(&normalize((drone, (drone + offset) % n))) {
+                edges.insert(rewired);
+            }
+        }
+    }
+    edges.into_iter().collect()
+}
+
+/// Builds a connected random drone subgraph with the given number of edges, which must be at
+/// least `n - 1` and at most `n (n - 1) / 2`.
+///
+/// Every drone but the first one of a random order is linked to a random drone preceding it,
+/// which yields a random spanning tree; the remaining edges are drawn uniformly among the
+/// missing ones.
+fn random_connected(n: usize, n_edges: usize, rng: &mut StdRng) -> Vec<Edge> {
+    let mut order: Vec<usize> = (0..n).collect();
+    order.shuffle(rng);
+    let mut edges: BTreeSet<Edge> = (1..n)
+        .map(|position| normalize((order[rng.gen_range(0..position)], order[position])))
+        .collect();
+    while edges.len() < n_edges {
+        let edge = (rng.gen_range(0..n), rng.gen_range(0..n));
+        if edge.0 != edge.1 {
+            edges.insert(normalize(edge));
+        }
+    }
+    edges.into_iter().collect()
+}
+
+/// Builds a Barabási–Albert drone subgraph through preferential attachment.
+fn barabasi_albert(n: usize, m: usize, rng: &mut StdRng) -> Vec<Edge> {
+    let mut edges = Vec::with_capacity(m * n);
+    // Every drone appears once per incident edge, so uniform sampling from this list
+    // selects drones proportionally to their degree.
+    let mut endpoints = Vec::with_capacity(2 * m * n);
+    for a in 0..=m {
+        for b in (a + 1)..=m {
+            edges.push((a, b));
+            endpoints.extend([a, b]);
+        }
+    }
+    for drone in (m + 1)..n {
+        let mut targets = BTreeSet::new();
+        while targets.len() < m {
+            targets.insert(endpoints[rng.gen_range(0..endpoints.len())]);
+        }
+        for target in targets {
+            edges.push((target, drone));
+            endpoints.extend([target, drone]);
+        }
+    }
+    edges
+}
+
+/// Removes self-loops and parallel edges by swapping endpoints with random edges.
+///
+/// Every swap replaces two edges `(a, b)`, `(c, d)` with `(a, c)`, `(b, d)`, which preserves
+/// the degree of every drone. Returns `false` if an edge could not be repaired.
+fn repair_edges(edges: &mut [Edge], rng: &mut StdRng) -> bool {
+    let mut counts: HashMap<Edge, usize> = HashMap::with_capacity(edges.len());
+    for edge in edges.iter() {
+        *counts.entry(normalize(*edge)).or_default() += 1;
+    }
+    let is_invalid = |edge: Edge, counts: &HashMap<Edge, usize>| {
+        edge.0 == edge.1 || counts[&normalize(edge)] > 1
+    };
+
+    for index in 0..edges.len() {
+        let mut swaps = 0;
+        while is_invalid(edges[index], &counts) {
+            if swaps == MAX_REPAIR_SWAPS {
+                return false;
+            }
+            swaps += 1;
+            let other = rng.gen_range(0..edges.len());
+            if other == index {
+                continue;
+            }
+            let (a, b) = edges[index];
+            let (c, d) = if rng.gen_bool(0.5) {
+                edges[other]
+            } else {
+                (edges[other].1, edges[other].0)
+            };
+            remove_edge(&mut counts, edges[index]);
+            remove_edge(&mut counts, edges[other]);
+            let (first, second) = ((a, c), (b, d));
+            if a != c
+                && b != d
+                && normalize(first) != normalize(second)
+                && !counts.contains_key(&normalize(first))
+                && !counts.contains_key(&normalize(second))
+            {
+                edges[index] = first;
+                edges[other] = second;
+            }
+            *counts.entry(normalize(edges[index])).or_default() += 1;
+            *counts.entry(normalize(edges[other])).or_default() += 1;
+        }
+    }
+    true
+}
+
+/// Decrements the multiplicity of an edge, dropping it once it reaches zero.
+fn remove_edge(counts: &mut HashMap<Edge, usize>, edge: Edge) {
+    let edge = normalize(edge);
+    if let Some(count) = counts.get_mut(&edge) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&edge);
+        }
+    }
+}
+
+/// Builds a configuration from a drone subgraph, attaching the requested clients and servers.
+///
+/// Clients are connected to one or two random drones, servers to two random drones, and the
+/// back-edges are added to the drones' neighbor lists so that the topology is bidirectional.
+fn build_config(
+    n_drones: usize,
+    edges: &[Edge],
+    n_clients: usize,
+    n_servers: usize,
+    pdr_range: (f32, f32),
+    rng: &mut StdRng,
+) -> Config {
+    let mut topology = Topology::default();
+    for index in 0..n_drones {
+        let pdr = rng.gen_range(pdr_range.0..=pdr_range.1);
+        topology.add_node(to_id(index), Role::Drone { pdr });
+    }
+    for (a, b) in edges {
+        topology.add_link(to_id(*a), to_id(*b));
+    }
+
+    let drone_indices: Vec<usize> = (0..n_drones).collect();
+    for offset in 0..n_clients {
+        let id = to_id(n_drones + offset);
+        topology.add_node(id, Role::Client);
+        let n_neighbors = if rng.gen_bool(0.5) { 2 } else { 1 };
+        for index in drone_indices.choose_multiple(rng, n_neighbors) {
+            topology.add_link(to_id(*index), id);
+        }
+    }
+    for offset in 0..n_servers {
+        let id = to_id(n_drones + n_clients + offset);
+        topology.add_node(id, Role::Server);
+        for index in drone_indices.choose_multiple(rng, 2) {
+            topology.add_link(to_id(*index), id);
+        }
+    }
+
+    Config::try_from(topology).expect("generated node IDs are distinct")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generator::{
+        generate_from_degrees, generate_invalid, generate_random_topology, generate_scale_free,
+        generate_small_world, ViolationKind,
+    };
+    use crate::validate::{check_config, validate_config};
+
+    #[test]
+    fn test_generate_from_degrees() {
+        let degrees = [3, 3, 3, 3, 2, 2];
+        let config = generate_from_degrees(&degrees, 3, 2, 42).unwrap();
+
+        assert_eq!(validate_config(&config), Ok(()));
+        assert_eq!(config.client.len(), 3);
+        assert_eq!(config.server.len(), 2);
+        for (drone, degree) in config.drone.iter().zip(degrees) {
+            let n_drone_neighbors = drone
+                .connected_node_ids
+                .iter()
+                .filter(|id| (**id as usize) <= degrees.len())
+                .count();
+            assert_eq!(n_drone_neighbors, degree);
+        }
+    }
+
+    #[test]
+    fn test_generate_from_degrees_deterministic() {
+        let degrees = [4, 3, 3, 2, 2, 2, 2];
+        let first = generate_from_degrees(&degrees, 2, 2, 7).unwrap();
+        let second = generate_from_degrees(&degrees, 2, 2, 7).unwrap();
+
+        assert_eq!(format!("{:?}", first), format!("{:?}", second));
+    }
+
+    #[test]
+    fn test_generate_from_degrees_odd_sum() {
+        let result = generate_from_degrees(&[2, 2, 1], 0, 0, 0);
+
+        assert_eq!(
+            result.map(|_| ()),
+            Err("The sum of the drone degrees must be even".to_string())
+        );
+    }
+
+    #[test]
+    fn test_generate_small_world() {
+        const N_DRONES: usize = 20;
+        const K: usize = 4;
+        let config = generate_small_world(N_DRONES, K, 0.2, 4, 3, 1).unwrap();
+
+        assert_eq!(validate_config(&config), Ok(()));
+        let n_drone_edges: usize = config
+            .drone
+            .iter()
+            .map(|drone| {
+                drone
+                    .connected_node_ids
+                    .iter()
+                    .filter(|id| (**id as usize) <= N_DRONES)
+                    .count()
+            })
+            .sum();
+        assert_eq!(n_drone_edges / 2, N_DRONES * K / 2);
+    }
+
+    #[test]
+    fn test_generate_small_world_invalid_degree() {
+        let result = generate_small_world(10, 3, 0.1, 1, 1, 0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_generate_scale_free() {
+        const N_DRONES: usize = 30;
+        const M: usize = 2;
+        let config = generate_scale_free(N_DRONES, M, 5, 4, 3).unwrap();
+
+        assert_eq!(validate_config(&config), Ok(()));
+        let n_drone_edges: usize = config
+            .drone
+            .iter()
+            .map(|drone| {
+                drone
+                    .connected_node_ids
+                    .iter()
+                    .filter(|id| (**id as usize) <= N_DRONES)
+                    .count()
+            })
+            .sum();
+        assert_eq!(n_drone_edges / 2, M * (M + 1) / 2 + (N_DRONES - M - 1) * M);
+    }
+
+    #[test]
+    fn test_generate_random_topology() {
+        const N_DRONES: usize = 150;
+        let config = generate_random_topology(N_DRONES, 50, 40, 4.0, (0.05, 0.3), 9).unwrap();
+
+        assert_eq!(validate_config(&config), Ok(()));
+        assert!(config
+            .drone
+            .iter()
+            .all(|drone| (0.05..=0.3).contains(&drone.pdr)));
+        let n_drone_edges: usize = config
+            .drone
+            .iter()
+            .map(|drone| {
+                drone
+                    .connected_node_ids
+                    .iter()
+                    .filter(|id| (**id as usize) <= N_DRONES)
+                    .count()
+            })
+            .sum();
+        assert_eq!(n_drone_edges / 2, N_DRONES * 4 / 2);
+
+        // A degree too low for a connected subgraph is raised to the one of a tree.
+        let tree = generate_random_topology(10, 2, 2, 0.0, (0.0, 0.0), 3).unwrap();
+        assert_eq!(validate_config(&tree), Ok(()));
+        assert!(generate_random_topology(10, 2, 2, 10.0, (0.0, 0.1), 3).is_err());
+        assert!(generate_random_topology(10, 2, 2, 3.0, (0.5, 0.1), 3).is_err());
+    }
+
+    #[test]
+    fn test_generate_invalid() {
+        let kinds = [
+            ViolationKind::DuplicateId,
+            ViolationKind::AsymmetricEdge,
+            ViolationKind::IsolatedClient,
+            ViolationKind::BadPdr,
+            ViolationKind::MissingNode,
+        ];
+        for kind in kinds {
+            for seed in 0..8 {
+                let (config, code) = generate_invalid(kind, seed);
+
+                assert_eq!(check_config(&config).map_err(|err| err.code), Err(code));
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::{collections::BTreeMap, collections::HashMap, fs, time::Duration};
+
+use serde::Deserialize;
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::validate::{network_validate_str, ErrorCode, ValidationError};
+
+/// Planar position of a node, declared through the optional `x` and `y` keys of its entry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Position {
+    /// Returns the euclidean distance between two positions.
+    pub fn distance(&self, other: &Position) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Positions of the nodes which declare one, indexed by node ID.
+pub type Positions = HashMap<NodeId, Position>;
+
+/// Model used to derive the latency of a link from its length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyModel {
+    /// Latency of a zero-length link.
+    pub base: Duration,
+    /// Latency added for every unit of distance.
+    pub per_unit: Duration,
+}
+
+/// The geographic view of a single node entry; every other key is ignored.
+#[derive(Deserialize)]
+struct GeoNode {
+    id: NodeId,
+    x: Option<f64>,
+    y: Option<f64>,
+}
+
+/// The geographic view of a configuration file.
+#[derive(Deserialize)]
+struct GeoConfig {
+    #[serde(default)]
+    drone: Vec<GeoNode>,
+    #[serde(default)]
+    client: Vec<GeoNode>,
+    #[serde(default)]
+    server: Vec<GeoNode>,
+}
+
+/// Reads and validates a configuration file, together with the positions of its nodes.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+/// - `max_distance`: If set, the maximum allowed length of a link between two placed nodes.
+///
+/// Returns the configuration and the node positions if the configuration file provided is
+/// valid, an error otherwise.
+pub fn network_validate_geo(
+    file_path: &str,
+    max_distance: Option<f64>,
+) -> Result<(Config, Positions), String> {
+    let config_data = fs::read_to_string(file_path)
+        .map_err(|_| "Unable to read configuration file".to_string())?;
+    let config = network_validate_str(&config_data)?;
+    let positions = parse_positions(&config_data)?;
+    if let Some(max_distance) = max_distance {
+        validate_link_distances(&config, &positions, max_distance)?;
+    }
+    Ok((config, positions))
+}
+
+/// Extracts the node positions from the TOML representation of a configuration.
+///
+/// Positions are optional: nodes which declare neither `x` nor `y` are simply left out of the
+/// returned map, while nodes declaring only one of them are rejected.
+///
+/// # Parameters
+/// - `config_data`: The contents of a configuration file.
+///
+/// Returns the positions of the placed nodes, or an error if a position is incomplete.
+pub fn parse_positions(config_data: &str) -> Result<Positions, String> {
+    let geo: GeoConfig =
+        toml::from_str(config_data).map_err(|e| format!("Failed to deserialize TOML: {}", e))?;
+
+    let mut positions = Positions::new();
+    for node in geo.drone.iter().chain(&geo.client).chain(&geo.server) {
+        match (node.x, node.y) {
+            (Some(x), Some(y)) => {
+                positions.insert(node.id, Position { x, y });
+            }
+            (None, None) => {}
+            _ => {
+                return Err(format!(
+                    "Node [{}] declares only one of its coordinates",
+                    node.id
+                ))
+            }
+        }
+    }
+    Ok(positions)
+}
+
+/// Validates that every link between two placed nodes is at most `max_distance` long.
+///
+/// Links with at least one endpoint without a position are not checked.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `positions`: The node positions.
+/// - `max_distance`: The maximum allowed length of a link.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m log m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn validate_link_distances(
+    config: &Config,
+    positions: &Positions,
+    max_distance: f64,
+) -> Result<(), ValidationError> {
+    for ((a, b), distance) in link_lengths(config, positions) {
+        if distance > max_distance {
+            return Err(ValidationError::new(
+                ErrorCode::LinkTooLong,
+                format!(
+                    "The link between [{}] and [{}] is {:.2} long, exceeding the maximum of {}",
+                    a, b, distance, max_distance
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Derives the latency of every link between two placed nodes from its length.
+///
+/// Links are keyed by their endpoints, with the smaller ID first.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `positions`: The node positions.
+/// - `model`: The latency model.
+///
+/// Returns the latency of every link whose endpoints are both placed.
+pub fn link_latencies(
+    config: &Config,
+    positions: &Positions,
+    model: LatencyModel,
+) -> BTreeMap<(NodeId, NodeId), Duration> {
+    link_lengths(config, positions)
+        .into_iter()
+        .map(|(link, distance)| (link, model.base + model.per_unit.mul_f64(distance)))
+        .collect()
+}
+
+/// Computes the length of every link whose endpoints are both placed.
+fn link_lengths(config: &Config, positions: &Positions) -> BTreeMap<(NodeId, NodeId), f64> {
+    let edges =
+        config
+            .drone
+            .iter()
+            .flat_map(|drone| drone.connected_node_ids.iter().map(|id| (drone.id, *id)))
+            .chain(
+                config.client.iter().flat_map(|client| {
+                    client.connected_drone_ids.iter().map(|id| (client.id, *id))
+                }),
+            )
+            .chain(
+                config.server.iter().flat_map(|server| {
+                    server.connected_drone_ids.iter().map(|id| (server.id, *id))
+                }),
+            );
+
+    let mut lengths = BTreeMap::new();
+    for (a, b) in edges {
+        if let (Some(from), Some(to)) = (positions.get(&a), positions.get(&b)) {
+            lengths.insert((a.min(b), a.max(b)), from.distance(to));
+        }
+    }
+    lengths
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::geo::{
+        link_latencies, parse_positions, validate_link_distances, LatencyModel, Position,
+    };
+    use crate::validate::{network_validate_str, ErrorCode};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.1
+        x = 0.0
+        y = 0.0
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 3, 4]
+        pdr = 0.1
+        x = 3.0
+        y = 4.0
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1, 2]
+        x = 0.0
+        y = 1.0
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [1, 2]
+    "#;
+
+    #[test]
+    fn test_parse_positions() {
+        let positions = parse_positions(CONFIG).unwrap();
+
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[&2], Position { x: 3.0, y: 4.0 });
+    }
+
+    #[test]
+    fn test_parse_positions_incomplete() {
+        let result =
+            parse_positions("[[drone]]\nid = 1\nconnected_node_ids = []\npdr = 0.0\nx = 1.0");
+
+        assert_eq!(
+            result,
+            Err("Node [1] declares only one of its coordinates".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_link_distances() {
+        let config = network_validate_str(CONFIG).unwrap();
+        let positions = parse_positions(CONFIG).unwrap();
+
+        assert_eq!(validate_link_distances(&config, &positions, 5.0), Ok(()));
+        assert_eq!(
+            validate_link_distances(&config, &positions, 4.5).map_err(|err| err.code),
+            Err(ErrorCode::LinkTooLong)
+        );
+    }
+
+    #[test]
+    fn test_link_latencies() {
+        let config = network_validate_str(CONFIG).unwrap();
+        let positions = parse_positions(CONFIG).unwrap();
+        let model = LatencyModel {
+            base: Duration::from_millis(1),
+            per_unit: Duration::from_millis(2),
+        };
+
+        let latencies = link_latencies(&config, &positions, model);
+
+        assert_eq!(latencies.len(), 3);
+        assert_eq!(latencies[&(1, 2)], Duration::from_millis(11));
+        assert_eq!(latencies[&(1, 3)], Duration::from_millis(3));
+    }
+}
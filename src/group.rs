@@ -0,0 +1,43 @@
+use std::collections::BTreeSet;
+
+use rust_roveri_api::DroneImpl;
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// Attribute of a [`crate::model::Topology`] node holding its zone, e.g. `north`.
+pub const ZONE_ATTRIBUTE: &str = "zone";
+
+/// A selection of nodes of a running network, resolved by
+/// [`crate::handle::NetworkHandle::select`] and acted upon by the bulk operations of the
+/// handle, e.g. [`crate::handle::NetworkHandle::crash_group`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeGroup {
+    /// The nodes of the given type.
+    Type(NodeType),
+    /// The nodes whose [`ZONE_ATTRIBUTE`] has the given value, see
+    /// [`crate::handle::NetworkHandle::set_zones`].
+    Zone(String),
+    /// The drones running the given implementation.
+    Impl(DroneImpl),
+    /// The nodes with the given IDs.
+    Ids(BTreeSet<NodeId>),
+}
+
+/// Returns the group of the nodes of the given type.
+pub fn by_type(node_type: NodeType) -> NodeGroup {
+    NodeGroup::Type(node_type)
+}
+
+/// Returns the group of the nodes in the given zone.
+pub fn by_zone(zone: &str) -> NodeGroup {
+    NodeGroup::Zone(zone.to_string())
+}
+
+/// Returns the group of the drones running the given implementation.
+pub fn by_impl(drone_impl: DroneImpl) -> NodeGroup {
+    NodeGroup::Impl(drone_impl)
+}
+
+/// Returns the group of the nodes with the given IDs.
+pub fn by_ids(ids: impl IntoIterator<Item = NodeId>) -> NodeGroup {
+    NodeGroup::Ids(ids.into_iter().collect())
+}
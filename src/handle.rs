@@ -0,0 +1,2863 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fmt, fs,
+    io::{self, Write},
+    mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{select, Receiver, Sender, TrySendError};
+use fixedbitset::FixedBitSet;
+use rust_roveri_api::{
+    ClientCommand, ClientGuiMessage, Command, GuiClientMessage, NodeType, ServerCommand,
+};
+use wg_2024::{config::Config, controller::DroneCommand, network::NodeId, packet::Packet};
+
+use crate::analysis::{config_links, diff_links, k_best_paths_in, Link, ScoredPath, TopologyDiff};
+use crate::channels::{ChannelInfo, ChannelRegistry};
+use crate::clock::SimClock;
+use crate::distribution::DistributionPlan;
+use crate::events::{EventHub, EventQueue, Hub, NodeEvent, ObservedEvent};
+use crate::group::{NodeGroup, ZONE_ATTRIBUTE};
+use crate::journal::{
+    CommandJournal, CommandRecord, ISSUER_DRAIN, ISSUER_HANDLE, ISSUER_SHUTDOWN, ISSUER_TEARDOWN,
+};
+use crate::model::{Role, Topology};
+use crate::spawn::SpawnPlan;
+use crate::tap::{TapCounters, TapStats, TappedPacket};
+use crate::topology::{
+    client_update, drone_update, server_update, LinkDivergence, Timeline, TopologyTracker,
+    TopologyUpdate,
+};
+use crate::transcript::Transcript;
+use crate::validate::ValidatedConfig;
+
+/// Interval between two samples of the packet queues while draining.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Number of consecutive samples in which every packet queue must be empty for the network to be
+/// considered drained, so that packets being processed by a node are given time to show up.
+const DRAIN_QUIET_SAMPLES: usize = 3;
+
+/// Interval between two samples of the command queues of a node, while waiting for it to
+/// acknowledge a command.
+const ACK_POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// Time after which a node whose queues are not shrinking is considered unresponsive.
+const UNRESPONSIVE_AFTER: Duration = Duration::from_secs(2);
+
+/// Observed state of a node, as reported by [`NetworkHandle::probe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    /// The node is running and has processed every command and packet sent to it.
+    Ready,
+    /// The node is running and is processing the given number of queued commands and packets.
+    Busy { queue: usize },
+    /// The node is running, but its queues have not shrunk for a while.
+    Unresponsive,
+    /// The thread of the node has terminated.
+    Crashed,
+}
+
+impl NodeStatus {
+    /// Returns the name of the status, as written in CSV exports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeStatus::Ready => "ready",
+            NodeStatus::Busy { .. } => "busy",
+            NodeStatus::Unresponsive => "unresponsive",
+            NodeStatus::Crashed => "crashed",
+        }
+    }
+}
+
+/// Termination state of the thread of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThreadState {
+    /// The thread is still running.
+    Running,
+    /// The thread returned normally.
+    Returned,
+    /// The thread panicked.
+    Panicked,
+}
+
+impl ThreadState {
+    /// Returns the name of the state, as written in CSV exports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThreadState::Running => "running",
+            ThreadState::Returned => "returned",
+            ThreadState::Panicked => "panicked",
+        }
+    }
+}
+
+/// Backlog of the events emitted by a node, as reported by [`NetworkHandle::event_backlog`].
+///
+/// Every node sends its events on its own channel, drained by a relay towards the simulation
+/// controller, so a node emitting events faster than they are relayed only fills its own
+/// channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventBacklog {
+    /// The ID of the node.
+    pub node: NodeId,
+    /// The number of events emitted and not yet relayed.
+    pub queued: usize,
+    /// The largest number of events observed waiting at once.
+    pub peak: usize,
+    /// The number of events relayed so far.
+    pub relayed: usize,
+    /// The capacity of the event channel of the node, or `None` if it is unbounded. A node
+    /// with a full channel blocks until the relay catches up.
+    pub capacity: Option<usize>,
+}
+
+/// The timeouts of the stages of [`NetworkHandle::shutdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownPolicy {
+    /// The maximum time to wait for the packet queues to empty, before crashing the nodes.
+    pub graceful: Duration,
+    /// The maximum time to wait for the nodes to terminate after the crash command, before
+    /// abandoning their threads.
+    pub crash: Duration,
+}
+
+impl Default for ShutdownPolicy {
+    fn default() -> Self {
+        Self {
+            graceful: Duration::from_secs(1),
+            crash: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The stage of [`NetworkHandle::shutdown`] at which a node terminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShutdownStage {
+    /// The node terminated before being sent a crash command.
+    Graceful,
+    /// The node terminated after being sent a crash command.
+    Crash,
+    /// The node ignored the crash command, and its thread was left running.
+    Abandoned,
+}
+
+/// The outcome of [`NetworkHandle::shutdown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Whether the packet queues emptied before the nodes were crashed.
+    pub drained: bool,
+    /// The stage at which every node terminated, ordered as in the configuration.
+    pub nodes: Vec<(NodeId, ShutdownStage)>,
+}
+
+impl ShutdownReport {
+    /// Returns the IDs of the nodes which terminated at the given stage.
+    pub fn with_stage(&self, stage: ShutdownStage) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .filter(|(_, node_stage)| *node_stage == stage)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns the IDs of the nodes which required a crash command or were abandoned.
+    pub fn escalated(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .filter(|(_, stage)| *stage != ShutdownStage::Graceful)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// The order in which [`NetworkHandle::teardown`] crashes the nodes, one type at a time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeardownOrder {
+    /// The type of the nodes crashed by every stage, in order. The types which are not listed
+    /// are crashed by additional stages, clients first and drones last.
+    pub stages: Vec<wg_2024::packet::NodeType>,
+    /// The maximum time to wait for the nodes of a stage to terminate, before moving on to the
+    /// next stage.
+    pub stage_timeout: Duration,
+}
+
+impl Default for TeardownOrder {
+    /// Clients, then servers, then drones, so that no packet is sent through a drone which
+    /// already crashed.
+    fn default() -> Self {
+        Self {
+            stages: vec![
+                wg_2024::packet::NodeType::Client,
+                wg_2024::packet::NodeType::Server,
+                wg_2024::packet::NodeType::Drone,
+            ],
+            stage_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A stage of [`NetworkHandle::teardown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeardownStage {
+    /// The type of the nodes crashed by the stage.
+    pub node_type: wg_2024::packet::NodeType,
+    /// How every node of the stage terminated, ordered as in the configuration: a node which
+    /// terminated before being sent the crash command is [`ShutdownStage::Graceful`].
+    pub nodes: Vec<(NodeId, ShutdownStage)>,
+}
+
+/// The outcome of [`NetworkHandle::teardown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeardownReport {
+    /// The stages, in the order in which they ran.
+    pub stages: Vec<TeardownStage>,
+}
+
+impl TeardownReport {
+    /// Returns the IDs of the nodes still running when their stage timed out.
+    pub fn abandoned(&self) -> Vec<NodeId> {
+        self.stages
+            .iter()
+            .flat_map(|stage| &stage.nodes)
+            .filter(|(_, stage)| *stage == ShutdownStage::Abandoned)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+}
+
+/// A command for a node of any type, as sent by [`NetworkHandle::send_command_acked`].
+#[derive(Debug, Clone)]
+pub enum NodeCommand {
+    Drone(DroneCommand),
+    Client(ClientCommand),
+    Server(ServerCommand),
+}
+
+/// Why a command sent by [`NetworkHandle::send_command_acked`] was not acknowledged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandError {
+    /// No node has the given ID.
+    UnknownNode(NodeId),
+    /// The command is meant for a different type of node.
+    WrongNodeType(NodeId),
+    /// The node is not receiving commands anymore.
+    Disconnected(NodeId),
+    /// The node did not take the command from its queue before the timeout.
+    Timeout(NodeId),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::UnknownNode(id) => write!(f, "Node [{}] does not exist", id),
+            CommandError::WrongNodeType(id) => {
+                write!(
+                    f,
+                    "The command is meant for a different type than node [{}]",
+                    id
+                )
+            }
+            CommandError::Disconnected(id) => {
+                write!(f, "Node [{}] is not receiving commands anymore", id)
+            }
+            CommandError::Timeout(id) => {
+                write!(f, "Node [{}] did not acknowledge the command in time", id)
+            }
+        }
+    }
+}
+
+impl Error for CommandError {}
+
+/// Why a transaction of [`NetworkHandle::mutate`] was rejected, leaving the network untouched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MutationError {
+    /// No node has the given ID.
+    UnknownNode(NodeId),
+    /// The node was crashed, by this transaction or before.
+    NodeNotRunning(NodeId),
+    /// A node cannot be linked to itself.
+    SelfLink(NodeId),
+    /// Neither endpoint of the link is a drone.
+    NoDrone(Link),
+    /// The link is already up.
+    LinkExists(Link),
+    /// The link is not up.
+    LinkMissing(Link),
+    /// The node is not a drone, so it has no packet drop rate.
+    NotADrone(NodeId),
+    /// The packet drop rate is outside of `[0, 1]`.
+    InvalidPdr(NodeId, f32),
+}
+
+impl fmt::Display for MutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MutationError::UnknownNode(id) => write!(f, "Node [{}] does not exist", id),
+            MutationError::NodeNotRunning(id) => write!(f, "Node [{}] is not running", id),
+            MutationError::SelfLink(id) => write!(f, "Node [{}] cannot be linked to itself", id),
+            MutationError::NoDrone((a, b)) => {
+                write!(f, "Neither node [{}] nor node [{}] is a drone", a, b)
+            }
+            MutationError::LinkExists((a, b)) => {
+                write!(f, "Nodes [{}] and [{}] are already linked", a, b)
+            }
+            MutationError::LinkMissing((a, b)) => {
+                write!(f, "Nodes [{}] and [{}] are not linked", a, b)
+            }
+            MutationError::NotADrone(id) => write!(f, "Node [{}] is not a drone", id),
+            MutationError::InvalidPdr(id, pdr) => {
+                write!(f, "Drone [{}] cannot have packet drop rate {}", id, pdr)
+            }
+        }
+    }
+}
+
+impl Error for MutationError {}
+
+/// A change to the topology, checked by a [`Transaction`] and applied on commit.
+#[derive(Debug, Clone)]
+enum Mutation {
+    AddLink(NodeId, NodeId),
+    RemoveLink(NodeId, NodeId),
+    /// Crashes the node, after its live neighbors are told to drop it.
+    RemoveNode {
+        id: NodeId,
+        neighbors: Vec<NodeId>,
+    },
+    SetPdr(NodeId, f32),
+}
+
+/// The changes to the topology made by [`NetworkHandle::mutate`], all applied or none.
+///
+/// Every change is checked as soon as it is made, against the topology left by the previous
+/// changes of the transaction; the first one failing its check rejects the transaction, and
+/// the later ones are ignored.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    handle: &'a NetworkHandle,
+    view: TopologyView,
+    mutations: Vec<Mutation>,
+    error: Option<MutationError>,
+}
+
+impl Transaction<'_> {
+    /// Links two running nodes, at least one of which is a drone.
+    pub fn add_link(&mut self, a: NodeId, b: NodeId) -> &mut Self {
+        self.check(|txn| {
+            if a == b {
+                return Err(MutationError::SelfLink(a));
+            }
+            let link = (a.min(b), a.max(b));
+            if !txn.running(a)?.is_drone() && !txn.running(b)?.is_drone() {
+                return Err(MutationError::NoDrone(link));
+            }
+            if txn.view.is_linked(a, b) {
+                return Err(MutationError::LinkExists(link));
+            }
+            txn.view.link(a, b);
+            Ok(Mutation::AddLink(a, b))
+        })
+    }
+
+    /// Unlinks two running nodes.
+    pub fn remove_link(&mut self, a: NodeId, b: NodeId) -> &mut Self {
+        self.check(|txn| {
+            txn.running(a)?;
+            txn.running(b)?;
+            if !txn.view.is_linked(a, b) {
+                return Err(MutationError::LinkMissing((a.min(b), a.max(b))));
+            }
+            txn.view.unlink(a, b);
+            Ok(Mutation::RemoveLink(a, b))
+        })
+    }
+
+    /// Crashes a running node, telling the nodes which can send to it to drop it first.
+    pub fn remove_node(&mut self, id: NodeId) -> &mut Self {
+        self.check(|txn| {
+            txn.running(id)?;
+            let neighbors = txn.view.senders_to(id);
+            txn.view.crash(id);
+            Ok(Mutation::RemoveNode { id, neighbors })
+        })
+    }
+
+    /// Sets the packet drop rate of a running drone.
+    pub fn set_pdr(&mut self, id: NodeId, pdr: f32) -> &mut Self {
+        self.check(|txn| {
+            if !txn.running(id)?.is_drone() {
+                return Err(MutationError::NotADrone(id));
+            }
+            if !(0.0..=1.0).contains(&pdr) {
+                return Err(MutationError::InvalidPdr(id, pdr));
+            }
+            Ok(Mutation::SetPdr(id, pdr))
+        })
+    }
+
+    /// Records a change if it passes its check, unless the transaction is already rejected.
+    fn check(
+        &mut self,
+        change: impl FnOnce(&mut Self) -> Result<Mutation, MutationError>,
+    ) -> &mut Self {
+        if self.error.is_none() {
+            match change(self) {
+                Ok(mutation) => self.mutations.push(mutation),
+                Err(error) => self.error = Some(error),
+            }
+        }
+        self
+    }
+
+    /// Returns the entry of a node which is running in the topology of the transaction.
+    fn running(&self, id: NodeId) -> Result<&NodeEntry, MutationError> {
+        let node = self
+            .handle
+            .nodes
+            .iter()
+            .find(|node| node.id == id)
+            .ok_or(MutationError::UnknownNode(id))?;
+        if !self.view.alive.contains(&id) {
+            return Err(MutationError::NodeNotRunning(id));
+        }
+        Ok(node)
+    }
+}
+
+/// Resources used by a node, as reported by [`NetworkHandle::resource_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// The ID of the node.
+    pub node: NodeId,
+    /// The CPU time used so far by the thread of the node, or `None` if it is not available,
+    /// e.g. because the thread terminated or the platform is not Linux.
+    pub cpu_time: Option<Duration>,
+    /// The estimated memory used by the queued packets and commands of the node, in bytes.
+    pub queue_bytes: usize,
+    /// The largest value of `queue_bytes` sampled so far.
+    pub peak_queue_bytes: usize,
+}
+
+/// Termination state of the thread of every node, ordered as in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminationReport {
+    pub nodes: Vec<(NodeId, ThreadState)>,
+}
+
+impl TerminationReport {
+    /// Returns the IDs of the nodes whose thread is in the given state.
+    pub fn with_state(&self, state: ThreadState) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .filter(|(_, node_state)| *node_state == state)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns `true` if every node thread is still running.
+    pub fn all_running(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|(_, state)| *state == ThreadState::Running)
+    }
+}
+
+/// Summary of a run of the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    /// Time elapsed since the network was initialized.
+    pub uptime: Duration,
+    /// Termination state of the node threads.
+    pub termination: TerminationReport,
+}
+
+impl RunSummary {
+    /// Writes the summary as CSV, with a header and one row per node.
+    ///
+    /// The columns are `node`, `thread_state` and `uptime_ms`, the latter being the same for
+    /// every row.
+    ///
+    /// # Parameters
+    /// - `writer`: The destination of the CSV.
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "node,thread_state,uptime_ms")?;
+        for (id, state) in &self.termination.nodes {
+            writeln!(
+                writer,
+                "{},{},{}",
+                id,
+                state.as_str(),
+                self.uptime.as_millis()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The channels through which a GUI talks to a client.
+#[derive(Debug, Clone)]
+pub struct GuiEndpoint {
+    /// The channel on which the GUI sends messages to the client.
+    pub sender: Sender<GuiClientMessage>,
+    /// The channel on which the GUI receives messages from the client.
+    pub receiver: Receiver<ClientGuiMessage>,
+}
+
+/// Shared termination state of the thread of a node.
+#[derive(Debug, Clone)]
+pub(crate) struct Liveness {
+    state: Arc<AtomicU8>,
+    /// The `/proc` statistics file of the thread, once it has started, on Linux.
+    stat: Arc<OnceLock<PathBuf>>,
+}
+
+impl Liveness {
+    const RUNNING: u8 = 0;
+    const RETURNED: u8 = 1;
+    const PANICKED: u8 = 2;
+
+    fn state(&self) -> ThreadState {
+        match self.state.load(Ordering::SeqCst) {
+            Self::RUNNING => ThreadState::Running,
+            Self::RETURNED => ThreadState::Returned,
+            _ => ThreadState::Panicked,
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.state() == ThreadState::Running
+    }
+
+    /// Records the statistics file of the current thread, which must be the one of the node.
+    fn record_stat(&self) {
+        if let Ok(task) = fs::read_link("/proc/thread-self") {
+            let _ = self.stat.set(Path::new("/proc").join(task).join("stat"));
+        }
+    }
+
+    /// Returns the CPU time used so far by the thread, or `None` if it is not available, e.g.
+    /// because the thread terminated or the platform is not Linux.
+    fn cpu_time(&self) -> Option<Duration> {
+        let stat = fs::read_to_string(self.stat.get()?).ok()?;
+        let ticks = parse_cpu_ticks(&stat)?;
+        Some(Duration::from_millis(ticks * 1000 / CLOCK_TICKS))
+    }
+}
+
+/// The number of clock ticks per second used by `/proc` statistics (`USER_HZ`), which is 100
+/// on every mainstream Linux architecture.
+const CLOCK_TICKS: u64 = 100;
+
+/// Returns the user and system CPU time in a `/proc/<pid>/task/<tid>/stat` line, in clock
+/// ticks.
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    // The name of the thread may contain spaces, so the fields are counted after it; `utime`
+    // and `stime` are the 14th and 15th fields, i.e. the 12th and 13th after the name.
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+    let user: u64 = fields.next()?.parse().ok()?;
+    let system: u64 = fields.next()?.parse().ok()?;
+    Some(user + system)
+}
+
+/// Records how the thread of a node terminated, when the thread unwinds or returns.
+struct LivenessGuard(Liveness);
+
+impl Drop for LivenessGuard {
+    fn drop(&mut self) {
+        let state = if thread::panicking() {
+            Liveness::PANICKED
+        } else {
+            Liveness::RETURNED
+        };
+        self.0.state.store(state, Ordering::SeqCst);
+    }
+}
+
+/// The join handles of the threads of the nodes, by node ID.
+///
+/// The handles are shared by the clones of [`crate::init::NetworkInitData`], so that every
+/// thread is joined at most once, either by [`crate::init::NetworkInitData::shutdown`] or by
+/// the caller after taking its handle.
+#[derive(Clone, Debug, Default)]
+pub struct NodeThreads(Arc<Mutex<HashMap<NodeId, JoinHandle<()>>>>);
+
+impl NodeThreads {
+    /// Records the thread of a node.
+    pub(crate) fn insert(&self, id: NodeId, thread: JoinHandle<()>) {
+        self.0.lock().unwrap().insert(id, thread);
+    }
+
+    /// Returns the IDs of the nodes whose thread was not joined or taken yet, in ascending order.
+    pub fn ids(&self) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = self.0.lock().unwrap().keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Takes the join handle of the thread of a node, which is then no longer joined by
+    /// [`crate::init::NetworkInitData::shutdown`].
+    ///
+    /// Returns `None` if the node does not exist, its thread was already joined or taken, or
+    /// its spawn was deferred by a schedule of the `det-test` feature.
+    pub fn take(&self, id: NodeId) -> Option<JoinHandle<()>> {
+        self.0.lock().unwrap().remove(&id)
+    }
+
+    /// Joins the threads of all the nodes, except the given ones.
+    ///
+    /// A thread which panicked is joined like the others: its panic is already reported by
+    /// [`NetworkHandle::termination_report`].
+    pub(crate) fn join_except(&self, skipped: &[NodeId]) {
+        let threads: Vec<(NodeId, JoinHandle<()>)> = {
+            let mut threads = self.0.lock().unwrap();
+            let ids: Vec<NodeId> = threads.keys().copied().collect();
+            ids.into_iter()
+                .filter(|id| !skipped.contains(id))
+                .filter_map(|id| Some((id, threads.remove(&id)?)))
+                .collect()
+        };
+        for (_, thread) in threads {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The threads relaying the packets, commands, events and GUI messages of the nodes of a
+/// network, stopped and joined when the network is shut down.
+///
+/// The relays share a stop channel, disconnected by [`Relays::stop`]; a relay told to stop
+/// forwards the messages still queued, and terminates.
+#[derive(Clone, Debug)]
+pub(crate) struct Relays {
+    /// The sender of the stop channel, dropped to tell the relays to stop.
+    stop: Arc<Mutex<Option<Sender<()>>>>,
+    /// The receiver of the stop channel, disconnected once the relays are told to stop.
+    stopped: Receiver<()>,
+    /// The join handles of the relays which were not joined yet.
+    threads: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl Default for Relays {
+    fn default() -> Self {
+        let (stop, stopped) = crossbeam_channel::bounded(0);
+        Self {
+            stop: Arc::new(Mutex::new(Some(stop))),
+            stopped,
+            threads: Arc::default(),
+        }
+    }
+}
+
+impl Relays {
+    /// Spawns a relay, given the receiver of the stop channel.
+    pub(crate) fn spawn<F: FnOnce(Receiver<()>) + Send + 'static>(&self, relay: F) {
+        let stopped = self.stopped.clone();
+        let thread = thread::spawn(move || relay(stopped));
+        self.threads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(thread);
+    }
+
+    /// Returns how many relays were spawned and not joined yet.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.threads.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Tells every relay to stop, and joins them.
+    ///
+    /// A relay spawned afterwards stops as soon as its queue is empty.
+    pub(crate) fn stop(&self) {
+        self.stop.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let threads = mem::take(&mut *self.threads.lock().unwrap_or_else(|e| e.into_inner()));
+        for thread in threads {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Receives the next message of a relay.
+///
+/// # Parameters
+/// - `from`: The channel relayed.
+/// - `stopped`: The stop channel of the relay.
+///
+/// Returns `None` once `from` is disconnected, or empty after the relay is told to stop.
+pub(crate) fn relay_recv<T>(from: &Receiver<T>, stopped: &Receiver<()>) -> Option<T> {
+    select! {
+        recv(from) -> message => message.ok(),
+        recv(stopped) -> _ => from.try_recv().ok(),
+    }
+}
+
+/// Sends a message from a relay, waiting for room in a bounded channel unless the relay is
+/// told to stop meanwhile.
+///
+/// # Parameters
+/// - `to`: The channel on which the message is forwarded.
+/// - `message`: The message.
+/// - `stopped`: The stop channel of the relay.
+///
+/// Returns `false` if the message was not sent.
+pub(crate) fn relay_send<T>(to: &Sender<T>, message: T, stopped: &Receiver<()>) -> bool {
+    match to.try_send(message) {
+        Ok(()) => true,
+        Err(TrySendError::Disconnected(_)) => false,
+        Err(TrySendError::Full(message)) => select! {
+            send(to, message) -> sent => sent.is_ok(),
+            recv(stopped) -> _ => false,
+        },
+    }
+}
+
+/// Spawns the thread of a node, as [`spawn_joinable_node`], dropping its join handle.
+#[cfg(test)]
+pub(crate) fn spawn_node<F: FnOnce() + Send + 'static>(run: F) -> Liveness {
+    spawn_joinable_node(thread::Builder::new(), run).0
+}
+
+/// Returns the builder of the thread of a node, named after the kind and the ID of the node,
+/// e.g. `drone-7`, so that the node can be told apart in debuggers and profilers.
+///
+/// # Parameters
+/// - `kind`: The kind of the node: `drone`, `client` or `server`.
+/// - `id`: The ID of the node.
+/// - `stack_size`: The stack size of the thread, in bytes, if not the default one.
+pub(crate) fn node_thread(kind: &str, id: NodeId, stack_size: Option<usize>) -> thread::Builder {
+    let builder = thread::Builder::new().name(format!("{}-{}", kind, id));
+    match stack_size {
+        Some(stack_size) => builder.stack_size(stack_size),
+        None => builder,
+    }
+}
+
+/// Spawns the thread of a node, tracking its termination.
+///
+/// With the `det-test` feature, the thread is created parked if a schedule is installed, and
+/// the node only starts when the schedule says so, see [`crate::sched`].
+///
+/// # Parameters
+/// - `builder`: The builder of the thread, e.g. from [`node_thread`].
+/// - `run`: The body of the thread, which instantiates and runs the node.
+///
+/// Returns the termination state of the thread, and its join handle.
+///
+/// # Panics
+/// Panics if the operating system fails to create the thread, e.g. if the stack size is too
+/// large, like [`thread::spawn`].
+pub(crate) fn spawn_joinable_node<F: FnOnce() + Send + 'static>(
+    builder: thread::Builder,
+    run: F,
+) -> (Liveness, JoinHandle<()>) {
+    let liveness = Liveness {
+        state: Arc::new(AtomicU8::new(Liveness::RUNNING)),
+        stat: Arc::default(),
+    };
+    let guard = LivenessGuard(liveness.clone());
+    let task = move || {
+        guard.0.record_stat();
+        let _guard = guard;
+        run();
+    };
+    #[cfg(feature = "det-test")]
+    let thread = crate::sched::spawn_scheduled(builder, Box::new(task));
+    #[cfg(not(feature = "det-test"))]
+    let thread = builder.spawn(task);
+    (
+        liveness,
+        thread.expect("failed to spawn the thread of a node"),
+    )
+}
+
+/// The topology intended by the commands sent through the handle: unlike the live topology
+/// of the tracker, it changes as soon as a command is sent, not when the node receives it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TopologyView {
+    /// The nodes which were not told to crash.
+    alive: BTreeSet<NodeId>,
+    /// The directed links, from a node to a neighbor it can send to.
+    links: BTreeSet<(NodeId, NodeId)>,
+}
+
+impl TopologyView {
+    /// Returns the view of a live topology.
+    fn new(topology: &Topology) -> Self {
+        Self {
+            alive: topology.nodes.iter().map(|node| node.id).collect(),
+            links: topology.edges.iter().copied().collect(),
+        }
+    }
+
+    /// Returns `true` if the nodes can send to each other.
+    fn is_linked(&self, a: NodeId, b: NodeId) -> bool {
+        self.links.contains(&(a, b)) && self.links.contains(&(b, a))
+    }
+
+    /// Returns the undirected links whose endpoints are both alive and can send to each other.
+    fn live_links(&self) -> BTreeSet<Link> {
+        (self.links.iter())
+            .filter(|(a, b)| a < b && self.is_linked(*a, *b))
+            .filter(|(a, b)| self.alive.contains(a) && self.alive.contains(b))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the live nodes which can send to a node.
+    fn senders_to(&self, id: NodeId) -> Vec<NodeId> {
+        (self.links.iter())
+            .filter(|(from, to)| *to == id && self.alive.contains(from))
+            .map(|(from, _)| *from)
+            .collect()
+    }
+
+    fn link(&mut self, a: NodeId, b: NodeId) {
+        self.links.insert((a, b));
+        self.links.insert((b, a));
+    }
+
+    fn unlink(&mut self, a: NodeId, b: NodeId) {
+        self.links.remove(&(a, b));
+        self.links.remove(&(b, a));
+    }
+
+    fn crash(&mut self, id: NodeId) {
+        self.alive.remove(&id);
+        self.links.retain(|(a, b)| *a != id && *b != id);
+    }
+}
+
+/// Compares a topology with a configuration.
+///
+/// # Parameters
+/// - `config`: The expected configuration.
+/// - `links`: The undirected links of the topology.
+/// - `alive`: The nodes of the topology.
+fn diff_topology(
+    config: &Config,
+    links: &BTreeSet<Link>,
+    alive: &BTreeSet<NodeId>,
+) -> TopologyDiff {
+    let mut diff = diff_links(&config_links(config), links);
+    let expected: BTreeSet<NodeId> = config
+        .drone
+        .iter()
+        .map(|drone| drone.id)
+        .chain(config.client.iter().map(|client| client.id))
+        .chain(config.server.iter().map(|server| server.id))
+        .collect();
+    diff.missing_nodes = expected.difference(alive).copied().collect();
+    diff.unexpected_nodes = alive.difference(&expected).copied().collect();
+    diff
+}
+
+/// Last progress observed on the queues of a node.
+#[derive(Debug)]
+struct Watchdog {
+    depth: usize,
+    since: Instant,
+}
+
+/// Channels and state tracked by the handle for a single node.
+#[derive(Clone, Debug)]
+pub(crate) struct NodeEntry {
+    id: NodeId,
+    command: Command,
+    packets: Sender<Packet>,
+    liveness: Liveness,
+    watchdog: Arc<Mutex<Watchdog>>,
+    /// The largest estimated memory used by the queues of the node, in bytes, as sampled.
+    peak_queue_bytes: Arc<AtomicUsize>,
+    /// The initial neighbors of the node.
+    neighbors: Vec<NodeId>,
+    /// The queue between the packet tap and the node, if any.
+    delivery: Option<Sender<Packet>>,
+    /// The queue between the command relay and the node, if any.
+    commands: Option<Command>,
+    /// The queue between the node and its event relay, if any.
+    events: Option<EventQueue>,
+    /// The GUI channels of the node, if it is a client.
+    gui: Option<GuiEndpoint>,
+}
+
+impl NodeEntry {
+    /// Returns a new entry for the node with the given channels and liveness flag.
+    pub(crate) fn new(
+        id: NodeId,
+        command: Command,
+        packets: Sender<Packet>,
+        liveness: Liveness,
+    ) -> Self {
+        Self {
+            id,
+            command,
+            packets,
+            liveness,
+            watchdog: Arc::new(Mutex::new(Watchdog {
+                depth: 0,
+                since: Instant::now(),
+            })),
+            peak_queue_bytes: Arc::default(),
+            neighbors: Vec::new(),
+            delivery: None,
+            commands: None,
+            events: None,
+            gui: None,
+        }
+    }
+
+    /// Returns `true` if the node is a drone.
+    fn is_drone(&self) -> bool {
+        matches!(self.command, Command::DroneCommand(_))
+    }
+
+    /// Sets the queue between the packet tap and the node, so that packets waiting in it are
+    /// counted as queued.
+    pub(crate) fn with_delivery_queue(mut self, delivery: Sender<Packet>) -> Self {
+        self.delivery = Some(delivery);
+        self
+    }
+
+    /// Sets the queue between the command relay and the node, so that commands waiting in it
+    /// are counted as pending.
+    pub(crate) fn with_command_queue(mut self, commands: Command) -> Self {
+        self.commands = Some(commands);
+        self
+    }
+
+    /// Sets the queue between the node and its event relay, so that its backlog is reported.
+    pub(crate) fn with_event_queue(mut self, events: EventQueue) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Sets the GUI channels of the node, so that a GUI can be reattached to it.
+    pub(crate) fn with_gui(mut self, gui: GuiEndpoint) -> Self {
+        self.gui = Some(gui);
+        self
+    }
+
+    /// Returns the number of packets sent to the node and not yet received.
+    fn queued_packets(&self) -> usize {
+        self.packets.len() + self.delivery.as_ref().map_or(0, Sender::len)
+    }
+
+    /// Sets the initial neighbors of the node, which are told to drop it when it is crashed.
+    pub(crate) fn with_neighbors(mut self, neighbors: Vec<NodeId>) -> Self {
+        self.neighbors = neighbors;
+        self
+    }
+
+    /// Returns the estimated memory used by the queues of the node, in bytes, updating the
+    /// sampled peak.
+    fn sample_queue_bytes(&self) -> usize {
+        let bytes = self.queued_packets() * mem::size_of::<Packet>()
+            + self.pending_commands() * mem::size_of::<DroneCommand>();
+        self.peak_queue_bytes.fetch_max(bytes, Ordering::SeqCst);
+        bytes
+    }
+
+    /// Returns the number of commands sent to the node and not yet received.
+    fn pending_commands(&self) -> usize {
+        let queued = |command: &Command| match command {
+            Command::DroneCommand(sender) => sender.len(),
+            Command::ClientCommand(sender) => sender.len(),
+            Command::ServerCommand(sender) => sender.len(),
+            Command::None => 0,
+        };
+        queued(&self.command) + self.commands.as_ref().map_or(0, queued)
+    }
+}
+
+/// State shared by the handle with the relays of a network.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Shared {
+    /// The flag shared with the GUI relays of the clients.
+    pub(crate) quiesced: Arc<AtomicBool>,
+    /// The journal of the commands sent to the nodes.
+    pub(crate) journal: CommandJournal,
+    /// The hub publishing the events emitted by the nodes.
+    pub(crate) events: EventHub,
+    /// The named channels of the network.
+    pub(crate) channels: ChannelRegistry,
+    /// The hub publishing the packets delivered to the nodes.
+    pub(crate) packets: Hub<TappedPacket>,
+    /// The implementation or type of every node.
+    pub(crate) plan: DistributionPlan,
+    /// The order in which the nodes were spawned and sent their initial commands.
+    pub(crate) spawn: SpawnPlan,
+    /// The hub publishing the topology updates caused by the commands sent to the nodes.
+    pub(crate) topology: TopologyTracker,
+    /// The virtual clock of the network.
+    pub(crate) clock: SimClock,
+    /// The transcript of the initialization, if it was recorded.
+    pub(crate) transcript: Option<Arc<Transcript>>,
+    /// The nodes whose links were removed by [`NetworkHandle::quarantine`].
+    pub(crate) quarantined: Arc<Mutex<BTreeSet<NodeId>>>,
+    /// The zone of every node which has one, as set by [`NetworkHandle::set_zones`].
+    pub(crate) zones: Arc<Mutex<HashMap<NodeId, String>>>,
+    /// The counters of the packet tap of every node.
+    pub(crate) taps: Arc<BTreeMap<NodeId, Arc<TapCounters>>>,
+    /// The topology intended by the commands of the handle, locked by every method changing
+    /// the topology, so that concurrent changes are applied one at a time.
+    pub(crate) view: Arc<Mutex<TopologyView>>,
+    /// The relays of the network, stopped when it is shut down.
+    pub(crate) relays: Relays,
+    /// Whether the commands sent to the nodes pass through relays publishing their topology
+    /// updates; otherwise, the handle publishes the updates caused by its own commands.
+    pub(crate) command_relays: bool,
+}
+
+/// Handle to a running network, used to inspect and stop its nodes.
+///
+/// The handle keeps a clone of the command and packet channels of every node, together with
+/// the liveness of its thread, so it can be cloned and used independently of the simulation
+/// controller.
+#[derive(Clone, Debug)]
+pub struct NetworkHandle {
+    nodes: Vec<NodeEntry>,
+    started: Instant,
+    shared: Shared,
+}
+
+impl NetworkHandle {
+    /// Returns a new handle over the given channels.
+    ///
+    /// # Parameters
+    /// - `nodes`: The entry of every node, ordered as in the configuration.
+    /// - `shared`: The state shared with the relays of the network.
+    pub(crate) fn new(nodes: Vec<NodeEntry>, shared: Shared) -> Self {
+        *shared.view.lock().unwrap_or_else(|e| e.into_inner()) =
+            TopologyView::new(&shared.topology.topology());
+        Self {
+            nodes,
+            started: Instant::now(),
+            shared,
+        }
+    }
+
+    /// Stops clients from generating new traffic.
+    ///
+    /// The client API has no command telling a client to stop sending, so the network is
+    /// quiesced on this side of the channels: clients only send messages when asked by the GUI,
+    /// and while the network is quiesced the GUI messages are discarded instead of being
+    /// forwarded to the clients. Every discarded message is reported to the subscribers of
+    /// [`NetworkHandle::subscribe`] as a [`NodeEvent::GuiMessageRejected`], so that the GUI can
+    /// tell the user why its request had no effect.
+    pub fn quiesce(&self) {
+        self.shared.quiesced.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if [`NetworkHandle::quiesce`] has been called.
+    pub fn is_quiesced(&self) -> bool {
+        self.shared.quiesced.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of packets waiting in the queue of every node, ordered as in the
+    /// configuration.
+    pub fn queue_depths(&self) -> Vec<(NodeId, usize)> {
+        self.nodes
+            .iter()
+            .map(|node| (node.id, node.queued_packets()))
+            .collect()
+    }
+
+    /// Returns the resources used by every node, ordered as in the configuration, e.g. to spot
+    /// drone implementations which spin or let their queues grow.
+    ///
+    /// The memory of the queues is estimated from the number of queued packets and commands,
+    /// and its peak is sampled by every call to this method and to [`NetworkHandle::probe`], so
+    /// the method should be called periodically.
+    pub fn resource_usage(&self) -> Vec<ResourceUsage> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                let queue_bytes = node.sample_queue_bytes();
+                ResourceUsage {
+                    node: node.id,
+                    cpu_time: node.liveness.cpu_time(),
+                    queue_bytes,
+                    peak_queue_bytes: node.peak_queue_bytes.load(Ordering::SeqCst),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the backlog of the events emitted by every node, ordered as in the
+    /// configuration; empty unless the network was initialized with
+    /// [`InitOptions::per_node_events`](crate::init::InitOptions::per_node_events).
+    pub fn event_backlog(&self) -> Vec<EventBacklog> {
+        self.nodes
+            .iter()
+            .filter_map(|node| {
+                let events = node.events.as_ref()?;
+                Some(EventBacklog {
+                    node: node.id,
+                    queued: events.queued(),
+                    peak: events.peak(),
+                    relayed: events.relayed(),
+                    capacity: events.capacity(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the name, length and capacity of every channel of the network, e.g. `pkt 11`
+    /// for the packets sent to node 11 or `cmd drone 7` for the commands of drone 7.
+    pub fn channels(&self) -> Vec<ChannelInfo> {
+        self.shared.channels.channels()
+    }
+
+    /// Returns a receiver of every event emitted by the nodes from now on.
+    ///
+    /// The events are still delivered to the simulation controller. The events of the nodes are
+    /// only observed with
+    /// [`InitOptions::per_node_events`](crate::init::InitOptions::per_node_events); otherwise,
+    /// only the diagnostics of the network are.
+    pub fn subscribe(&self) -> Receiver<ObservedEvent> {
+        self.shared.events.subscribe()
+    }
+
+    /// Returns a receiver of every packet delivered to the nodes from now on.
+    ///
+    /// Packets are observed when they are sent to a node, before entering its queue, by the
+    /// taps of [`InitOptions::packet_taps`](crate::init::InitOptions::packet_taps); without
+    /// them, no packet is received.
+    pub fn tap(&self) -> Receiver<TappedPacket> {
+        self.shared.packets.subscribe()
+    }
+
+    /// Returns how many packets the tap of every node observed and published, ordered by node
+    /// ID, e.g. to measure the cost of observing the network under a
+    /// [`crate::tap::TapSampling`]; empty without taps.
+    pub fn tap_stats(&self) -> Vec<TapStats> {
+        self.shared
+            .taps
+            .iter()
+            .map(|(id, counters)| counters.stats(*id))
+            .collect()
+    }
+
+    /// Returns the GUI channels of a client, so that a GUI can reconnect to it after its
+    /// previous endpoint was dropped.
+    ///
+    /// The handle keeps both channels connected for the whole run, so a client does not notice
+    /// when its GUI goes away; messages sent by the client while no GUI was attached are
+    /// delivered to the new endpoint. Only one endpoint should be in use at a time, since
+    /// endpoints compete for the messages of the client.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the client.
+    ///
+    /// Returns the endpoint, or `None` if no client has the given ID.
+    pub fn reattach_gui(&self, id: NodeId) -> Option<GuiEndpoint> {
+        self.nodes
+            .iter()
+            .find(|node| node.id == id)
+            .and_then(|node| node.gui.clone())
+    }
+
+    /// Changes the speed of the virtual clock of the network, which paces the scenarios.
+    ///
+    /// A scale of `2.0` makes simulated time run twice as fast as the wall clock, and `0.5`
+    /// half as fast; the simulated time elapsed so far is not affected.
+    ///
+    /// # Parameters
+    /// - `scale`: The new time scale, in `0.1..=10.0`.
+    ///
+    /// Returns `false`, leaving the scale unchanged, if the scale is out of range.
+    pub fn set_time_scale(&self, scale: f64) -> bool {
+        self.shared.clock.set_scale(scale)
+    }
+
+    /// Returns the current time scale.
+    pub fn time_scale(&self) -> f64 {
+        self.shared.clock.scale()
+    }
+
+    /// Returns the simulated time elapsed since the network was initialized.
+    pub fn sim_time(&self) -> Duration {
+        self.shared.clock.now()
+    }
+
+    /// Blocks the current thread until the virtual clock reaches the given simulated time.
+    pub(crate) fn sleep_until(&self, at: Duration) {
+        self.shared.clock.sleep_until(at);
+    }
+
+    /// Returns a receiver of every topology update from now on.
+    ///
+    /// Updates are published when a command is delivered to a node, whether it was sent by
+    /// the simulation controller or by the handle; the commands of the simulation controller
+    /// are only observed with
+    /// [`InitOptions::command_relays`](crate::init::InitOptions::command_relays).
+    pub fn topology_updates(&self) -> Receiver<TopologyUpdate> {
+        self.shared.topology.subscribe()
+    }
+
+    /// Returns the evolution of the topology so far: the initial snapshot, and every change
+    /// caused by the commands sent to the nodes, with its time.
+    pub fn timeline(&self) -> Timeline {
+        self.shared.topology.timeline()
+    }
+
+    /// Compares the live topology with the topology of the original configuration.
+    ///
+    /// The live topology is the initial one, changed by every command delivered to the nodes:
+    /// a node is live until it is told to crash, and a link is live if both of its endpoints
+    /// are live and can send to each other.
+    ///
+    /// # Parameters
+    /// - `config`: The configuration the network was initialized from, or last reloaded with.
+    ///
+    /// Returns the links and nodes which differ from the configuration, or an error if the
+    /// configuration is not the one the network runs, see [`Self::base_fingerprint`].
+    pub fn drift_report(&self, config: &ValidatedConfig) -> Result<TopologyDiff, String> {
+        self.check_base(config)?;
+        let topology = &self.shared.topology;
+        Ok(diff_topology(
+            config.config(),
+            &topology.links(),
+            &topology.alive(),
+        ))
+    }
+
+    /// Exports the live topology, as by [`Self::live_topology`], as the TOML representation
+    /// of a configuration, e.g. to restart a later run from the state reached by this one.
+    ///
+    /// # Parameters
+    /// - `config`: The configuration the network was initialized from, or last reloaded with.
+    ///
+    /// Returns the configuration, or an error if the given configuration is not the one the
+    /// network runs, see [`Self::base_fingerprint`].
+    pub fn export_current_toml(&self, config: &ValidatedConfig) -> Result<String, String> {
+        self.check_base(config)?;
+        let current = Config::try_from(&self.live_topology())?;
+        toml::to_string(&current).map_err(|e| format!("Unable to serialize TOML: {}", e))
+    }
+
+    /// Returns the fingerprint of the configuration the network runs: the one it was
+    /// initialized from, or the one of its last [`Self::reload`].
+    ///
+    /// Every command recorded in the [`Self::command_log`] is tagged with the fingerprint at
+    /// the time it was sent, so that the changes of a long session can be told apart by run.
+    pub fn base_fingerprint(&self) -> u64 {
+        self.shared.journal.base()
+    }
+
+    /// Checks that a configuration is the one the network runs.
+    fn check_base(&self, config: &ValidatedConfig) -> Result<(), String> {
+        let base = self.base_fingerprint();
+        if config.fingerprint() != base {
+            return Err(format!(
+                "The configuration (fingerprint {:016x}) is not the one the network runs \
+                 (fingerprint {:016x})",
+                config.fingerprint(),
+                base
+            ));
+        }
+        Ok(())
+    }
+
+    /// Makes the live network converge to a new configuration without a restart.
+    ///
+    /// The topology is compared with the configuration as by [`Self::drift_report`], taking
+    /// into account the commands sent but not delivered yet: the nodes missing from the
+    /// configuration are crashed, the missing links are added and the unexpected ones removed,
+    /// on both endpoints, and the drones whose packet drop rate changed are sent the new one,
+    /// all in a single transaction of [`Self::mutate`]. The live topology follows once the
+    /// commands are delivered.
+    ///
+    /// # Parameters
+    /// - `config`: The new configuration.
+    ///
+    /// Returns the differences which were applied, or an error, before any command is sent, if
+    /// the configuration declares nodes which are not running: threads are only spawned by the
+    /// initialization, and crashed nodes cannot be restarted.
+    ///
+    /// Once the reload is applied, the network runs the new configuration: the commands sent
+    /// from then on are tagged with its fingerprint, and [`Self::drift_report`] compares the
+    /// live topology with it.
+    pub fn reload(&self, config: &ValidatedConfig) -> Result<TopologyDiff, String> {
+        let mut view = self.shared.view.lock().unwrap_or_else(|e| e.into_inner());
+        let diff = diff_topology(config.config(), &view.live_links(), &view.alive);
+        if !diff.missing_nodes.is_empty() {
+            let ids: Vec<String> = diff
+                .missing_nodes
+                .iter()
+                .map(|id| format!("[{}]", id))
+                .collect();
+            return Err(format!(
+                "Nodes {} are not running, and cannot be spawned by a reload",
+                ids.join(", ")
+            ));
+        }
+        let live = self.live_topology();
+        self.commit(&mut view, |txn| {
+            for &(a, b) in &diff.unexpected_links {
+                txn.remove_link(a, b);
+            }
+            for &id in &diff.unexpected_nodes {
+                txn.remove_node(id);
+            }
+            for &(a, b) in &diff.missing_links {
+                txn.add_link(a, b);
+            }
+            for drone in &config.config().drone {
+                let role = Role::Drone { pdr: drone.pdr };
+                if (live.nodes.iter()).any(|node| node.id == drone.id && node.role != role) {
+                    txn.set_pdr(drone.id, drone.pdr);
+                }
+            }
+        })
+        .map_err(|error| error.to_string())?;
+        self.shared.journal.rebase(config.fingerprint());
+        Ok(diff)
+    }
+
+    /// Changes the topology of the running network in a single transaction, safe to call from
+    /// several threads, e.g. the GUI and a scenario, at the same time.
+    ///
+    /// Transactions, reloads, crashes and quarantines are applied one at a time, and every
+    /// change of a transaction is checked against the topology left by the previous ones, so
+    /// that concurrent callers cannot, e.g., link a node which another one is crashing. The
+    /// commands of a transaction are only sent once every change passed its check.
+    ///
+    /// # Parameters
+    /// - `build`: Makes the changes on the transaction; it must not call the methods of the
+    ///   handle changing the topology, which wait for the transaction to end.
+    ///
+    /// Returns the first change which did not pass its check, in which case no command is sent.
+    pub fn mutate<F: FnOnce(&mut Transaction<'_>)>(&self, build: F) -> Result<(), MutationError> {
+        let mut view = self.shared.view.lock().unwrap_or_else(|e| e.into_inner());
+        self.commit(&mut view, build)
+    }
+
+    /// Runs a transaction on the locked topology, sending its commands if every change passed
+    /// its check.
+    fn commit<F: FnOnce(&mut Transaction<'_>)>(
+        &self,
+        view: &mut TopologyView,
+        build: F,
+    ) -> Result<(), MutationError> {
+        let mut txn = Transaction {
+            handle: self,
+            view: view.clone(),
+            mutations: Vec::new(),
+            error: None,
+        };
+        build(&mut txn);
+        if let Some(error) = txn.error {
+            return Err(error);
+        }
+        for mutation in &txn.mutations {
+            self.apply(mutation);
+        }
+        *view = txn.view;
+        Ok(())
+    }
+
+    /// Sends the commands of a change which passed its check.
+    fn apply(&self, mutation: &Mutation) {
+        let entry = |id: NodeId| self.nodes.iter().find(|node| node.id == id);
+        match *mutation {
+            Mutation::AddLink(a, b) | Mutation::RemoveLink(a, b) => {
+                let (Some(a), Some(b)) = (entry(a), entry(b)) else {
+                    return;
+                };
+                let add = matches!(mutation, Mutation::AddLink(..));
+                self.send_link(a, b.id, add.then(|| b.packets.clone()), ISSUER_HANDLE);
+                self.send_link(b, a.id, add.then(|| a.packets.clone()), ISSUER_HANDLE);
+            }
+            Mutation::RemoveNode { id, ref neighbors } => {
+                for neighbor in neighbors.iter().filter_map(|neighbor| entry(*neighbor)) {
+                    self.send_link(neighbor, id, None, ISSUER_HANDLE);
+                }
+                if let Some(node) = entry(id) {
+                    self.send_crash(node, ISSUER_HANDLE);
+                }
+            }
+            Mutation::SetPdr(id, pdr) => {
+                if let Some(Command::DroneCommand(sender)) = entry(id).map(|node| &node.command) {
+                    let command = DroneCommand::SetPacketDropRate(pdr);
+                    self.send_tracked(sender, id, command, drone_update, ISSUER_HANDLE);
+                }
+            }
+        }
+    }
+
+    /// Reconciles a topology graph with the links set by the commands sent to the nodes, so
+    /// that a graph which failed to follow a command, e.g. a bit still set after a
+    /// `RemoveSender`, is flagged.
+    ///
+    /// The divergences which were not found by the previous reconciliation are published to
+    /// the subscribers of [`NetworkHandle::subscribe`] as [`NodeEvent::LinkDivergence`] bug
+    /// reports, so the method can be called after every change to the graph, e.g. for every
+    /// message of `topology_updates`. Nodes which were told to crash are ignored.
+    ///
+    /// # Parameters
+    /// - `topology`: The topology graph, indexed by node ID, e.g. the one of `InitData` kept
+    ///   up to date by the simulation controller.
+    ///
+    /// Returns every divergence, ordered by node and neighbor.
+    pub fn reconcile(&self, topology: &[(NodeType, FixedBitSet)]) -> Vec<LinkDivergence> {
+        let (divergences, new) = self.shared.topology.reconcile(topology);
+        for divergence in new {
+            self.shared.events.publish(ObservedEvent {
+                node: divergence.node,
+                at: Instant::now(),
+                event: NodeEvent::LinkDivergence(divergence),
+            });
+        }
+        divergences
+    }
+
+    /// Returns the live topology: the nodes which were not told to crash, with the current
+    /// packet drop rate of the drones, and the directed links between them.
+    pub fn live_topology(&self) -> Topology {
+        self.shared.topology.topology()
+    }
+
+    /// Returns the current most reliable path between two nodes, as a routing oracle, e.g.
+    /// for the GUI to overlay suggested routes, or for tests to compare the source routes
+    /// chosen by the clients against it.
+    ///
+    /// # Parameters
+    /// - `from`: The ID of the source node.
+    /// - `to`: The ID of the destination node.
+    ///
+    /// Returns the path, or `None` if either node crashed or no live path connects them.
+    pub fn best_path(&self, from: NodeId, to: NodeId) -> Option<ScoredPath> {
+        self.best_paths(from, to, 1).pop()
+    }
+
+    /// Returns the `k` current most reliable paths between two nodes, on the live topology,
+    /// see [`crate::analysis::k_best_paths`].
+    ///
+    /// # Parameters
+    /// - `from`: The ID of the source node.
+    /// - `to`: The ID of the destination node.
+    /// - `k`: The maximum number of paths.
+    ///
+    /// Returns the paths from the most reliable.
+    pub fn best_paths(&self, from: NodeId, to: NodeId, k: usize) -> Vec<ScoredPath> {
+        k_best_paths_in(&self.live_topology(), from, to, k)
+    }
+
+    /// Returns the state shared by the clones of the handle.
+    pub(crate) fn shared(&self) -> &Shared {
+        &self.shared
+    }
+
+    /// Returns the implementation or type of every node.
+    pub fn plan(&self) -> &DistributionPlan {
+        &self.shared.plan
+    }
+
+    /// Returns the transcript of the initialization, if a seed was given in the
+    /// [`crate::init::InitOptions`].
+    pub fn transcript(&self) -> Option<&Transcript> {
+        self.shared.transcript.as_deref()
+    }
+
+    /// Returns the order in which the nodes were spawned and sent their initial commands.
+    pub fn spawn_plan(&self) -> &SpawnPlan {
+        &self.shared.spawn
+    }
+
+    /// Returns the initial neighbors of a node, or `None` if no node has the given ID.
+    pub fn neighbors(&self, id: NodeId) -> Option<&[NodeId]> {
+        self.nodes
+            .iter()
+            .find(|node| node.id == id)
+            .map(|node| node.neighbors.as_slice())
+    }
+
+    /// Returns the termination state of every node thread.
+    ///
+    /// A drone implementation whose threads return or panic while the network is running is
+    /// exiting prematurely.
+    pub fn termination_report(&self) -> TerminationReport {
+        TerminationReport {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| (node.id, node.liveness.state()))
+                .collect(),
+        }
+    }
+
+    /// Returns a summary of the run so far.
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            uptime: self.started.elapsed(),
+            termination: self.termination_report(),
+        }
+    }
+
+    /// Returns the total number of packets waiting in the queues of the nodes.
+    pub fn queued_packets(&self) -> usize {
+        self.nodes.iter().map(NodeEntry::queued_packets).sum()
+    }
+
+    /// Probes the current state of a node.
+    ///
+    /// The state combines the liveness of the node thread, its readiness (every command and
+    /// packet sent to it has been received) and a watchdog which flags the node as unresponsive
+    /// if its queues have not shrunk for a while. The watchdog is updated by every probe, so
+    /// the node should be probed periodically.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the node.
+    ///
+    /// Returns the state of the node, or `None` if no node has the given ID.
+    pub fn probe(&self, id: NodeId) -> Option<NodeStatus> {
+        let node = self.nodes.iter().find(|node| node.id == id)?;
+        if !node.liveness.is_alive() {
+            return Some(NodeStatus::Crashed);
+        }
+
+        node.sample_queue_bytes();
+        let depth = node.pending_commands() + node.queued_packets();
+        let mut watchdog = node.watchdog.lock().unwrap_or_else(|e| e.into_inner());
+        if depth == 0 || depth < watchdog.depth {
+            watchdog.since = Instant::now();
+        }
+        watchdog.depth = depth;
+
+        Some(if depth == 0 {
+            NodeStatus::Ready
+        } else if watchdog.since.elapsed() >= UNRESPONSIVE_AFTER {
+            NodeStatus::Unresponsive
+        } else {
+            NodeStatus::Busy { queue: depth }
+        })
+    }
+
+    /// Sends a crash command to every node.
+    ///
+    /// Nodes which already terminated are silently skipped.
+    pub fn crash_all(&self) {
+        self.crash_all_as(ISSUER_HANDLE);
+    }
+
+    /// Returns the last commands sent to the nodes through the senders of this crate, from the
+    /// oldest to the newest.
+    ///
+    /// Commands sent directly by the simulation controller are not recorded.
+    pub fn command_log(&self) -> Vec<CommandRecord> {
+        self.shared.journal.records()
+    }
+
+    /// Sends a packet to a node, through its packet tap.
+    ///
+    /// # Parameters
+    /// - `to`: The ID of the node.
+    /// - `packet`: The packet.
+    ///
+    /// Returns `false` if no node has the given ID, or it is not receiving packets anymore.
+    pub fn send_packet(&self, to: NodeId, packet: Packet) -> bool {
+        self.nodes
+            .iter()
+            .find(|node| node.id == to)
+            .is_some_and(|node| node.packets.send(packet).is_ok())
+    }
+
+    /// Crashes a single node, after telling its initial neighbors to drop it.
+    ///
+    /// Links added after the initialization are not known to the handle, so the corresponding
+    /// neighbors are not notified.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the node.
+    ///
+    /// Returns `false` if no node has the given ID.
+    pub fn crash(&self, id: NodeId) -> bool {
+        self.crash_as(id, ISSUER_HANDLE)
+    }
+
+    /// Crashes a single node, recording the commands under the given issuer tag.
+    pub(crate) fn crash_as(&self, id: NodeId, issuer: &'static str) -> bool {
+        let Some(node) = self.nodes.iter().find(|node| node.id == id) else {
+            return false;
+        };
+        let mut view = self.shared.view.lock().unwrap_or_else(|e| e.into_inner());
+        for neighbor in self.initial_neighbors(node) {
+            self.send_link(neighbor, id, None, issuer);
+        }
+        self.send_crash(node, issuer);
+        view.crash(id);
+        true
+    }
+
+    /// Isolates a node without crashing it, so that it can be inspected while the rest of the
+    /// network keeps running.
+    ///
+    /// The node and its initial neighbors are told to drop each other; as for [`Self::crash`],
+    /// links added after the initialization are left in place.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the node.
+    ///
+    /// Returns `false` if no node has the given ID, or it is already quarantined.
+    pub fn quarantine(&self, id: NodeId) -> bool {
+        let Some(node) = self.nodes.iter().find(|node| node.id == id) else {
+            return false;
+        };
+        let mut view = self.shared.view.lock().unwrap_or_else(|e| e.into_inner());
+        let mut quarantined = self
+            .shared
+            .quarantined
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !quarantined.insert(id) {
+            return false;
+        }
+        for neighbor in self.initial_neighbors(node) {
+            if quarantined.contains(&neighbor.id) {
+                continue;
+            }
+            self.send_link(neighbor, id, None, ISSUER_HANDLE);
+            self.send_link(node, neighbor.id, None, ISSUER_HANDLE);
+            view.unlink(id, neighbor.id);
+        }
+        true
+    }
+
+    /// Restores the links removed by [`Self::quarantine`].
+    ///
+    /// Links towards neighbors which are still quarantined are restored when the neighbors are
+    /// released.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the node.
+    ///
+    /// Returns `false` if the node is not quarantined.
+    pub fn release(&self, id: NodeId) -> bool {
+        let Some(node) = self.nodes.iter().find(|node| node.id == id) else {
+            return false;
+        };
+        let mut view = self.shared.view.lock().unwrap_or_else(|e| e.into_inner());
+        let mut quarantined = self
+            .shared
+            .quarantined
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !quarantined.remove(&id) {
+            return false;
+        }
+        for neighbor in self.initial_neighbors(node) {
+            if quarantined.contains(&neighbor.id) {
+                continue;
+            }
+            self.send_link(neighbor, id, Some(node.packets.clone()), ISSUER_HANDLE);
+            self.send_link(
+                node,
+                neighbor.id,
+                Some(neighbor.packets.clone()),
+                ISSUER_HANDLE,
+            );
+            view.link(id, neighbor.id);
+        }
+        true
+    }
+
+    /// Returns the IDs of the quarantined nodes, in increasing order.
+    pub fn quarantined(&self) -> Vec<NodeId> {
+        let quarantined = self
+            .shared
+            .quarantined
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        quarantined.iter().copied().collect()
+    }
+
+    /// Sets the zones of the nodes, from the [`ZONE_ATTRIBUTE`] of the nodes of a topology,
+    /// so that they can be selected with [`NodeGroup::Zone`].
+    ///
+    /// # Parameters
+    /// - `topology`: The topology of the network, e.g. with the attributes of a
+    ///   [`crate::layers::LayeredConfig`].
+    pub fn set_zones(&self, topology: &Topology) {
+        let zones = topology.nodes.iter().filter_map(|node| {
+            let zone = node.attributes.get(ZONE_ATTRIBUTE)?;
+            Some((node.id, zone.clone()))
+        });
+        *self.shared.zones.lock().unwrap_or_else(|e| e.into_inner()) = zones.collect();
+    }
+
+    /// Returns the IDs of the nodes in a group, ordered as in the configuration.
+    pub fn select(&self, group: &NodeGroup) -> Vec<NodeId> {
+        let zones = self.shared.zones.lock().unwrap_or_else(|e| e.into_inner());
+        let selected = |node: &NodeEntry| match group {
+            NodeGroup::Type(node_type) => {
+                let entry_type = match node.command {
+                    Command::DroneCommand(_) => wg_2024::packet::NodeType::Drone,
+                    Command::ClientCommand(_) => wg_2024::packet::NodeType::Client,
+                    Command::ServerCommand(_) => wg_2024::packet::NodeType::Server,
+                    Command::None => return false,
+                };
+                entry_type == *node_type
+            }
+            NodeGroup::Zone(zone) => zones.get(&node.id) == Some(zone),
+            NodeGroup::Impl(drone_impl) => self
+                .shared
+                .plan
+                .drones
+                .iter()
+                .any(|(id, other)| *id == node.id && other == drone_impl),
+            NodeGroup::Ids(ids) => ids.contains(&node.id),
+        };
+        self.nodes
+            .iter()
+            .filter(|node| selected(node))
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Crashes every node of a group, as [`Self::crash`] does.
+    ///
+    /// Returns the IDs of the crashed nodes.
+    pub fn crash_group(&self, group: &NodeGroup) -> Vec<NodeId> {
+        let ids = self.select(group);
+        for id in &ids {
+            self.crash_as(*id, ISSUER_HANDLE);
+        }
+        ids
+    }
+
+    /// Sets the packet drop rate of every drone of a group.
+    ///
+    /// # Parameters
+    /// - `group`: The nodes; the ones which are not drones are skipped.
+    /// - `pdr`: The new packet drop rate, in `[0, 1]`.
+    ///
+    /// Returns the IDs of the drones which were sent the new rate; none if the rate is outside
+    /// of `[0, 1]`.
+    pub fn set_pdr_group(&self, group: &NodeGroup, pdr: f32) -> Vec<NodeId> {
+        if !(0.0..=1.0).contains(&pdr) {
+            return Vec::new();
+        }
+        let ids = self.select(group);
+        let drones = self.nodes.iter().filter(|node| ids.contains(&node.id));
+        let mut updated = Vec::with_capacity(ids.len());
+        for node in drones {
+            if let Command::DroneCommand(sender) = &node.command {
+                let command = DroneCommand::SetPacketDropRate(pdr);
+                self.send_tracked(sender, node.id, command, drone_update, ISSUER_HANDLE);
+                updated.push(node.id);
+            }
+        }
+        updated
+    }
+
+    /// Quarantines every node of a group, as [`Self::quarantine`] does.
+    ///
+    /// Returns the IDs of the nodes which were not already quarantined.
+    pub fn quarantine_group(&self, group: &NodeGroup) -> Vec<NodeId> {
+        self.select(group)
+            .into_iter()
+            .filter(|id| self.quarantine(*id))
+            .collect()
+    }
+
+    /// Returns the entries of the initial neighbors of a node.
+    fn initial_neighbors<'a>(&'a self, node: &'a NodeEntry) -> impl Iterator<Item = &'a NodeEntry> {
+        self.nodes
+            .iter()
+            .filter(|neighbor| node.neighbors.contains(&neighbor.id))
+    }
+
+    /// Tells a node to add a link towards a neighbor, or to drop it if no sender is given,
+    /// recording the command under the given issuer tag.
+    fn send_link(
+        &self,
+        node: &NodeEntry,
+        neighbor: NodeId,
+        packets: Option<Sender<Packet>>,
+        issuer: &'static str,
+    ) {
+        match &node.command {
+            Command::DroneCommand(sender) => {
+                let command = match packets {
+                    Some(packets) => DroneCommand::AddSender(neighbor, packets),
+                    None => DroneCommand::RemoveSender(neighbor),
+                };
+                self.send_tracked(sender, node.id, command, drone_update, issuer);
+            }
+            Command::ClientCommand(sender) => {
+                let command = match packets {
+                    Some(packets) => ClientCommand::AddDrone(neighbor, packets),
+                    None => ClientCommand::RemoveDrone(neighbor),
+                };
+                self.send_tracked(sender, node.id, command, client_update, issuer);
+            }
+            Command::ServerCommand(sender) => {
+                let command = match packets {
+                    Some(packets) => ServerCommand::AddDrone(neighbor, packets),
+                    None => ServerCommand::RemoveDrone(neighbor),
+                };
+                self.send_tracked(sender, node.id, command, server_update, issuer);
+            }
+            Command::None => {}
+        }
+    }
+
+    /// Sends a command to a node, recording it under the given issuer tag.
+    ///
+    /// Without command relays, the topology update caused by the command is published by the
+    /// handle itself, so that the topology tracked by the handle follows its own commands.
+    ///
+    /// # Parameters
+    /// - `sender`: The command sender of the node.
+    /// - `id`: The ID of the node.
+    /// - `command`: The command.
+    /// - `describe`: The function deriving the update caused by the command, if any.
+    /// - `issuer`: The tag of the component sending the command.
+    ///
+    /// Returns `true` if the command was sent.
+    fn send_tracked<T: fmt::Debug>(
+        &self,
+        sender: &Sender<T>,
+        id: NodeId,
+        command: T,
+        describe: fn(NodeId, &T) -> Option<TopologyUpdate>,
+        issuer: &'static str,
+    ) -> bool {
+        self.shared.journal.record(issuer, id, &command);
+        let update = describe(id, &command);
+        let sent = sender.send(command).is_ok();
+        if let Some(update) = update.filter(|_| sent && !self.shared.command_relays) {
+            self.shared.topology.apply(update);
+        }
+        sent
+    }
+
+    /// Sends a command to a node, and waits until the node takes it from its queue.
+    ///
+    /// Commands are fire-and-forget, and nodes emit no event when they apply one, so the
+    /// command is acknowledged in two steps: the topology update published by the command
+    /// relay, if any, proves that the command reached the queue of the node, and the queue then
+    /// emptying proves that the node received it. A command which does not change the topology
+    /// is only acknowledged by its queue emptying. Other commands sent to the node meanwhile
+    /// may delay the acknowledgement.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the node.
+    /// - `command`: The command, which must match the type of the node.
+    /// - `timeout`: The maximum time to wait for the acknowledgement.
+    ///
+    /// Returns an error if the command could not be sent, or was not acknowledged in time.
+    pub fn send_command_acked(
+        &self,
+        id: NodeId,
+        command: NodeCommand,
+        timeout: Duration,
+    ) -> Result<(), CommandError> {
+        let deadline = Instant::now() + timeout;
+        let node = self
+            .nodes
+            .iter()
+            .find(|node| node.id == id)
+            .ok_or(CommandError::UnknownNode(id))?;
+        let updates = self.shared.topology.subscribe();
+        let (update, sent) = match (&node.command, command) {
+            (Command::DroneCommand(sender), NodeCommand::Drone(command)) => (
+                drone_update(id, &command),
+                self.send_tracked(sender, id, command, drone_update, ISSUER_HANDLE),
+            ),
+            (Command::ClientCommand(sender), NodeCommand::Client(command)) => (
+                client_update(id, &command),
+                self.send_tracked(sender, id, command, client_update, ISSUER_HANDLE),
+            ),
+            (Command::ServerCommand(sender), NodeCommand::Server(command)) => (
+                server_update(id, &command),
+                self.send_tracked(sender, id, command, server_update, ISSUER_HANDLE),
+            ),
+            _ => return Err(CommandError::WrongNodeType(id)),
+        };
+        if !sent {
+            return Err(CommandError::Disconnected(id));
+        }
+
+        // A command leaving the topology unchanged is not published, so only its queue is
+        // watched.
+        if let Some(update) = update {
+            loop {
+                match updates.recv_deadline(deadline) {
+                    Ok(relayed) if relayed == update => break,
+                    Ok(_) => {}
+                    Err(_) => return Err(CommandError::Timeout(id)),
+                }
+            }
+        }
+        while node.pending_commands() > 0 {
+            if !node.liveness.is_alive() {
+                return Err(CommandError::Disconnected(id));
+            }
+            if Instant::now() >= deadline {
+                return Err(CommandError::Timeout(id));
+            }
+            thread::sleep(ACK_POLL_INTERVAL);
+        }
+        Ok(())
+    }
+
+    /// Sends a crash command to every node, recording it under the given issuer tag.
+    fn crash_all_as(&self, issuer: &'static str) {
+        for node in &self.nodes {
+            self.send_crash(node, issuer);
+        }
+    }
+
+    /// Sends a crash command to a node, recording it under the given issuer tag.
+    fn send_crash(&self, node: &NodeEntry, issuer: &'static str) {
+        #[cfg(feature = "telemetry")]
+        crate::telemetry::node_lifecycle(node.id, "crash_requested");
+        match &node.command {
+            Command::DroneCommand(sender) => {
+                let command = DroneCommand::Crash;
+                self.send_tracked(sender, node.id, command, drone_update, issuer);
+            }
+            Command::ClientCommand(sender) => {
+                let command = ClientCommand::Crash;
+                self.send_tracked(sender, node.id, command, client_update, issuer);
+            }
+            Command::ServerCommand(sender) => {
+                let command = ServerCommand::Crash;
+                self.send_tracked(sender, node.id, command, server_update, issuer);
+            }
+            Command::None => {}
+        }
+    }
+
+    /// Gracefully shuts the network down.
+    ///
+    /// The network is first quiesced, then the packet queues are sampled until they stay empty
+    /// or the timeout expires; only then a crash command is sent to every node, so that messages
+    /// already in flight are delivered whenever possible.
+    ///
+    /// # Parameters
+    /// - `timeout`: The maximum time to wait for the packet queues to empty.
+    ///
+    /// Returns `true` if the queues emptied before the timeout, `false` if some packets were
+    /// still queued when the nodes were crashed.
+    pub fn drain(&self, timeout: Duration) -> bool {
+        self.quiesce();
+        let drained = self.wait_drained(timeout);
+        self.crash_all_as(ISSUER_DRAIN);
+        drained
+    }
+
+    /// Shuts the network down, escalating for the nodes which do not terminate.
+    ///
+    /// The network is first drained as by [`NetworkHandle::drain`]; nodes which terminate on
+    /// their own meanwhile stop at the graceful stage. Every other node is then sent a crash
+    /// command, and the threads of the nodes still running after `policy.crash` are abandoned:
+    /// they are left running detached, since some drone implementations ignore the crash
+    /// command and would otherwise hang the shutdown forever. The relays of the network are
+    /// finally stopped and joined.
+    ///
+    /// # Parameters
+    /// - `policy`: The timeouts of the stages.
+    ///
+    /// Returns the stage at which every node terminated.
+    pub fn shutdown(&self, policy: ShutdownPolicy) -> ShutdownReport {
+        self.quiesce();
+        let drained = self.wait_drained(policy.graceful);
+        let graceful: Vec<bool> = self
+            .nodes
+            .iter()
+            .map(|node| !node.liveness.is_alive())
+            .collect();
+
+        self.crash_all_as(ISSUER_SHUTDOWN);
+        let deadline = Instant::now() + policy.crash;
+        while self.nodes.iter().any(|node| node.liveness.is_alive()) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            thread::sleep(DRAIN_POLL_INTERVAL.min(deadline - now));
+        }
+
+        let nodes = self
+            .nodes
+            .iter()
+            .zip(graceful)
+            .map(|(node, graceful)| {
+                let stage = if graceful {
+                    ShutdownStage::Graceful
+                } else if node.liveness.is_alive() {
+                    ShutdownStage::Abandoned
+                } else {
+                    ShutdownStage::Crash
+                };
+                (node.id, stage)
+            })
+            .collect();
+        self.shared.relays.stop();
+        ShutdownReport { drained, nodes }
+    }
+
+    /// Crashes the nodes one type at a time, waiting for the nodes of a type to terminate
+    /// before crashing the next one.
+    ///
+    /// The network is first quiesced. Every stage then crashes its nodes as [`Self::crash`]
+    /// does, so that their neighbors drop them, and waits until the threads of the nodes
+    /// terminate or `order.stage_timeout` expires; the nodes still running are abandoned, and
+    /// the teardown moves on to the next stage. The relays of the network are finally stopped
+    /// and joined.
+    ///
+    /// # Parameters
+    /// - `order`: The order of the stages, and their timeout.
+    ///
+    /// Returns how the nodes of every stage terminated.
+    pub fn teardown(&self, order: TeardownOrder) -> TeardownReport {
+        self.quiesce();
+        let mut node_types = order.stages;
+        for node_type in TeardownOrder::default().stages {
+            if !node_types.contains(&node_type) {
+                node_types.push(node_type);
+            }
+        }
+
+        let mut stages = Vec::new();
+        for node_type in node_types {
+            let ids = self.select(&NodeGroup::Type(node_type));
+            if ids.is_empty() {
+                continue;
+            }
+            let nodes: Vec<&NodeEntry> = (self.nodes.iter())
+                .filter(|node| ids.contains(&node.id))
+                .collect();
+            let graceful: Vec<bool> = (nodes.iter())
+                .map(|node| !node.liveness.is_alive())
+                .collect();
+            for id in &ids {
+                self.crash_as(*id, ISSUER_TEARDOWN);
+            }
+            let deadline = Instant::now() + order.stage_timeout;
+            while nodes.iter().any(|node| node.liveness.is_alive()) {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                thread::sleep(DRAIN_POLL_INTERVAL.min(deadline - now));
+            }
+
+            let nodes = (nodes.iter().zip(graceful))
+                .map(|(node, graceful)| {
+                    let stage = if graceful {
+                        ShutdownStage::Graceful
+                    } else if node.liveness.is_alive() {
+                        ShutdownStage::Abandoned
+                    } else {
+                        ShutdownStage::Crash
+                    };
+                    (node.id, stage)
+                })
+                .collect();
+            stages.push(TeardownStage { node_type, nodes });
+        }
+        self.shared.relays.stop();
+        TeardownReport { stages }
+    }
+
+    /// Samples the packet queues until they stay empty or the timeout expires.
+    ///
+    /// Returns `true` if the queues emptied before the timeout.
+    fn wait_drained(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut quiet_samples = 0;
+        loop {
+            if self.queued_packets() == 0 {
+                quiet_samples += 1;
+                if quiet_samples == DRAIN_QUIET_SAMPLES {
+                    return true;
+                }
+            } else {
+                quiet_samples = 0;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            thread::sleep(DRAIN_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+}
+
+/// Spawns a thread forwarding the GUI messages of a client, unless the network is quiesced.
+///
+/// A message received while the network is quiesced is discarded, and a
+/// [`NodeEvent::GuiMessageRejected`] is published. The thread terminates when either side of
+/// the relay is disconnected, or the relays of the network are stopped.
+///
+/// # Parameters
+/// - `id`: The ID of the client.
+/// - `quiesced`: The flag telling whether the network is quiesced.
+/// - `from`: The channel on which the GUI sends its messages.
+/// - `to`: The channel on which the client receives them.
+/// - `hub`: The hub publishing the rejections.
+/// - `relays`: The relays of the network, stopping this one on shutdown.
+pub(crate) fn spawn_gui_relay<T: Send + 'static>(
+    id: NodeId,
+    quiesced: Arc<AtomicBool>,
+    from: Receiver<T>,
+    to: Sender<T>,
+    hub: EventHub,
+    relays: &Relays,
+) {
+    relays.spawn(move |stopped| {
+        while let Some(message) = relay_recv(&from, &stopped) {
+            if quiesced.load(Ordering::SeqCst) {
+                hub.publish(ObservedEvent {
+                    node: id,
+                    at: Instant::now(),
+                    event: NodeEvent::GuiMessageRejected { id },
+                });
+                continue;
+            }
+            if !relay_send(&to, message, &stopped) {
+                break;
+            }
+        }
+    });
+}
+
+/// What the relay of a client does with its messages while its GUI is stalled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StalledGuiPolicy {
+    /// Keep every message, letting the queue of the GUI grow.
+    #[default]
+    Keep,
+    /// Discard the new messages.
+    DropNewest,
+    /// Discard the oldest queued message for every new one, keeping the queue length.
+    DropOldest,
+}
+
+/// When the GUI of a client is considered stalled, and what happens to the messages of the
+/// client meanwhile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GuiStall {
+    /// The time after which a GUI which has not received any queued message is stalled.
+    pub after: Duration,
+    /// What happens to the messages of the client while its GUI is stalled.
+    pub policy: StalledGuiPolicy,
+}
+
+impl Default for GuiStall {
+    fn default() -> Self {
+        Self {
+            after: Duration::from_secs(5),
+            policy: StalledGuiPolicy::default(),
+        }
+    }
+}
+
+/// Spawns a thread forwarding the messages of a client to its GUI, watching for a GUI which
+/// stopped receiving them, e.g. because its window is frozen or closed.
+///
+/// When the GUI has not received any of the queued messages for `stall.after`, a
+/// [`NodeEvent::GuiStalled`] event is published, and the messages are handled according to
+/// `stall.policy` until the GUI receives messages again. The thread terminates when the client
+/// drops its sender, or the relays of the network are stopped.
+///
+/// # Parameters
+/// - `id`: The ID of the client.
+/// - `from`: The channel on which the client sends its messages.
+/// - `to`: The channel on which the GUI receives them.
+/// - `gui`: A receiver of `to`, used to discard the oldest messages.
+/// - `hub`: The hub publishing the stall events.
+/// - `stall`: When the GUI is stalled, and what happens to the messages meanwhile.
+/// - `relays`: The relays of the network, stopping this one on shutdown.
+pub(crate) fn spawn_gui_watchdog<T: Send + 'static>(
+    id: NodeId,
+    from: Receiver<T>,
+    to: Sender<T>,
+    gui: Receiver<T>,
+    hub: EventHub,
+    stall: GuiStall,
+    relays: &Relays,
+) {
+    let tick = (stall.after / 4).max(Duration::from_millis(1));
+    relays.spawn(move |stopped| {
+        // The messages forwarded and discarded from the queue of the GUI so far, so that the
+        // messages received by the GUI can be told apart from new ones.
+        let (mut forwarded, mut discarded) = (0usize, 0usize);
+        let mut received = 0;
+        let mut since = Instant::now();
+        let mut stalled = false;
+        loop {
+            let message = select! {
+                recv(from) -> message => match message {
+                    Ok(message) => Some(message),
+                    Err(_) => return,
+                },
+                recv(stopped) -> _ => match from.try_recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => return,
+                },
+                default(tick) => None,
+            };
+
+            let queued = to.len();
+            let now_received = forwarded.saturating_sub(discarded + queued);
+            if queued == 0 || now_received > received {
+                since = Instant::now();
+                stalled = false;
+            }
+            received = now_received;
+            if !stalled && since.elapsed() >= stall.after {
+                stalled = true;
+                hub.publish(ObservedEvent {
+                    node: id,
+                    at: Instant::now(),
+                    event: NodeEvent::GuiStalled { id },
+                });
+            }
+
+            let Some(message) = message else { continue };
+            if stalled {
+                match stall.policy {
+                    StalledGuiPolicy::Keep => {}
+                    StalledGuiPolicy::DropNewest => continue,
+                    StalledGuiPolicy::DropOldest => {
+                        if gui.try_recv().is_ok() {
+                            discarded += 1;
+                        }
+                    }
+                }
+            }
+            if !relay_send(&to, message, &stopped) {
+                return;
+            }
+            forwarded += 1;
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use crate::events::{EventHub, NodeEvent};
+    use crate::group::{by_ids, by_impl, by_type, by_zone, ZONE_ATTRIBUTE};
+    use crate::handle::{
+        node_thread, parse_cpu_ticks, spawn_gui_relay, spawn_gui_watchdog, spawn_joinable_node,
+        spawn_node, CommandError, GuiEndpoint, GuiStall, MutationError, NetworkHandle, NodeCommand,
+        NodeEntry, NodeStatus, Relays, Shared, ShutdownPolicy, ShutdownStage, StalledGuiPolicy,
+        ThreadState,
+    };
+    use crate::journal::{CommandJournal, ISSUER_DRAIN};
+    use crate::model::{Role, Topology};
+    use crate::topology::{drone_update, spawn_command_relay, TopologyTracker};
+    use crate::validate::{config_fingerprint, network_validate_str, ValidatedConfig};
+    use crossbeam_channel::Receiver;
+    use rust_roveri_api::{ClientCommand, Command, DroneImpl};
+    use wg_2024::controller::DroneCommand;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::NodeType;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    fn ack() -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![],
+            },
+            session_id: 0,
+        }
+    }
+
+    /// Builds a handle over a single drone, whose thread waits for the returned signal.
+    fn single_drone() -> (
+        NetworkHandle,
+        Receiver<DroneCommand>,
+        Receiver<Packet>,
+        crossbeam_channel::Sender<()>,
+    ) {
+        let (sx_command, rx_command) = crossbeam_channel::unbounded();
+        let (sx_packet, rx_packet) = crossbeam_channel::unbounded();
+        let (sx_exit, rx_exit) = crossbeam_channel::unbounded::<()>();
+        let liveness = spawn_node(move || {
+            let _ = rx_exit.recv();
+        });
+        let node = NodeEntry::new(1, Command::DroneCommand(sx_command), sx_packet, liveness);
+        let handle = NetworkHandle::new(vec![node], Shared::default());
+        (handle, rx_command, rx_packet, sx_exit)
+    }
+
+    #[test]
+    fn test_drain_empty_network() {
+        let (handle, rx_command, _rx_packet, _sx_exit) = single_drone();
+
+        assert!(handle.drain(Duration::from_secs(1)));
+        assert!(handle.is_quiesced());
+        assert!(matches!(rx_command.try_recv(), Ok(DroneCommand::Crash)));
+        let log = handle.command_log();
+        assert_eq!(log.len(), 1);
+        assert_eq!((log[0].issuer, log[0].target), (ISSUER_DRAIN, 1));
+    }
+
+    #[test]
+    fn test_drain_timeout() {
+        let (handle, rx_command, _rx_packet, _sx_exit) = single_drone();
+        handle.nodes[0].packets.send(ack()).unwrap();
+
+        assert_eq!(handle.queue_depths(), vec![(1, 1)]);
+        assert!(!handle.drain(Duration::from_millis(30)));
+        assert!(matches!(rx_command.try_recv(), Ok(DroneCommand::Crash)));
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let (sx_obedient, rx_obedient) = crossbeam_channel::unbounded();
+        let (sx_stubborn, rx_stubborn) = crossbeam_channel::unbounded::<DroneCommand>();
+        let (sx_exit, rx_exit) = crossbeam_channel::unbounded::<()>();
+        let graceful = spawn_node(|| {});
+        let obedient = spawn_node(move || {
+            let _ = rx_obedient.recv();
+        });
+        let stubborn = spawn_node(move || {
+            let _rx_stubborn = rx_stubborn;
+            let _ = rx_exit.recv();
+        });
+        let nodes = vec![
+            NodeEntry::new(1, Command::None, sx_packet.clone(), graceful),
+            NodeEntry::new(
+                2,
+                Command::DroneCommand(sx_obedient),
+                sx_packet.clone(),
+                obedient,
+            ),
+            NodeEntry::new(3, Command::DroneCommand(sx_stubborn), sx_packet, stubborn),
+        ];
+        let handle = NetworkHandle::new(nodes, Shared::default());
+
+        let report = handle.shutdown(ShutdownPolicy {
+            graceful: Duration::from_millis(50),
+            crash: Duration::from_millis(200),
+        });
+        assert!(report.drained);
+        assert_eq!(report.with_stage(ShutdownStage::Graceful), vec![1]);
+        assert_eq!(report.with_stage(ShutdownStage::Crash), vec![2]);
+        assert_eq!(report.with_stage(ShutdownStage::Abandoned), vec![3]);
+        assert_eq!(report.escalated(), vec![2, 3]);
+        sx_exit.send(()).unwrap();
+    }
+
+    #[test]
+    fn test_quarantine() {
+        let (sx_command, rx_command) = crossbeam_channel::unbounded();
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let (sx_neighbor, rx_neighbor) = crossbeam_channel::unbounded();
+        let (sx_exit, rx_exit) = crossbeam_channel::unbounded::<()>();
+        let liveness = spawn_node(move || {
+            let _ = rx_exit.recv();
+        });
+        let drone = NodeEntry::new(
+            1,
+            Command::DroneCommand(sx_command),
+            sx_packet.clone(),
+            liveness.clone(),
+        )
+        .with_neighbors(vec![2]);
+        let client = NodeEntry::new(2, Command::ClientCommand(sx_neighbor), sx_packet, liveness)
+            .with_neighbors(vec![1]);
+        let handle = NetworkHandle::new(vec![drone, client], Shared::default());
+
+        assert!(handle.quarantine(1));
+        assert!(!handle.quarantine(1));
+        assert_eq!(handle.quarantined(), vec![1]);
+        assert!(matches!(
+            rx_neighbor.try_recv(),
+            Ok(ClientCommand::RemoveDrone(1))
+        ));
+        assert!(matches!(
+            rx_command.try_recv(),
+            Ok(DroneCommand::RemoveSender(2))
+        ));
+        assert!(handle.termination_report().all_running());
+
+        assert!(handle.release(1));
+        assert!(!handle.release(1));
+        assert!(handle.quarantined().is_empty());
+        assert!(matches!(
+            rx_neighbor.try_recv(),
+            Ok(ClientCommand::AddDrone(1, _))
+        ));
+        assert!(matches!(
+            rx_command.try_recv(),
+            Ok(DroneCommand::AddSender(2, _))
+        ));
+        assert_eq!(handle.command_log().len(), 4);
+        let _ = sx_exit.send(());
+    }
+
+    #[test]
+    fn test_node_groups() {
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let (sx_drone, rx_drone) = crossbeam_channel::unbounded();
+        let (sx_client, _rx_client) = crossbeam_channel::unbounded();
+        let liveness = spawn_node(|| {});
+        let drone = NodeEntry::new(
+            1,
+            Command::DroneCommand(sx_drone),
+            sx_packet.clone(),
+            liveness.clone(),
+        );
+        let client = NodeEntry::new(2, Command::ClientCommand(sx_client), sx_packet, liveness);
+        let mut shared = Shared::default();
+        shared.plan.drones.push((1, DroneImpl::RustRoveri));
+        let handle = NetworkHandle::new(vec![drone, client], shared);
+        let mut topology = Topology::default();
+        topology.add_node(2, Role::Client);
+        let zone = (ZONE_ATTRIBUTE.to_string(), "north".to_string());
+        topology.nodes[0].attributes.extend([zone]);
+        handle.set_zones(&topology);
+
+        assert_eq!(handle.select(&by_type(NodeType::Client)), vec![2]);
+        assert_eq!(handle.select(&by_zone("north")), vec![2]);
+        assert_eq!(handle.select(&by_impl(DroneImpl::RustRoveri)), vec![1]);
+        assert_eq!(handle.select(&by_ids([1, 2, 9])), vec![1, 2]);
+
+        assert_eq!(handle.set_pdr_group(&by_ids([1, 2]), 0.5), vec![1]);
+        assert!(handle.set_pdr_group(&by_ids([1, 2]), f32::NAN).is_empty());
+        assert!(handle.set_pdr_group(&by_ids([1, 2]), 1.5).is_empty());
+        assert!(matches!(
+            rx_drone.try_recv(),
+            Ok(DroneCommand::SetPacketDropRate(pdr)) if pdr == 0.5
+        ));
+        assert_eq!(handle.quarantine_group(&by_zone("north")), vec![2]);
+        assert_eq!(handle.crash_group(&by_type(NodeType::Drone)), vec![1]);
+        assert!(matches!(rx_drone.try_recv(), Ok(DroneCommand::Crash)));
+    }
+
+    #[test]
+    fn test_send_command_acked() {
+        let (sx_relay, rx_relay) = crossbeam_channel::unbounded();
+        let (sx_command, rx_command) = crossbeam_channel::unbounded();
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let (sx_applied, rx_applied) = crossbeam_channel::unbounded();
+        let shared = Shared {
+            command_relays: true,
+            ..Shared::default()
+        };
+        spawn_command_relay(
+            1,
+            rx_relay,
+            sx_command.clone(),
+            shared.topology.clone(),
+            drone_update,
+            &shared.relays,
+        );
+        let liveness = spawn_node(move || {
+            for command in rx_command {
+                if let DroneCommand::SetPacketDropRate(pdr) = command {
+                    let _ = sx_applied.send(pdr);
+                }
+            }
+        });
+        let node = NodeEntry::new(1, Command::DroneCommand(sx_relay), sx_packet, liveness)
+            .with_command_queue(Command::DroneCommand(sx_command));
+        let handle = NetworkHandle::new(vec![node], shared);
+        let timeout = Duration::from_secs(1);
+
+        let command = NodeCommand::Drone(DroneCommand::SetPacketDropRate(0.5));
+        assert_eq!(handle.send_command_acked(1, command, timeout), Ok(()));
+        assert_eq!(rx_applied.recv_timeout(timeout), Ok(0.5));
+        let command = NodeCommand::Client(ClientCommand::Crash);
+        assert_eq!(
+            handle.send_command_acked(1, command, timeout),
+            Err(CommandError::WrongNodeType(1))
+        );
+        let command = NodeCommand::Drone(DroneCommand::Crash);
+        assert_eq!(
+            handle.send_command_acked(2, command, timeout),
+            Err(CommandError::UnknownNode(2))
+        );
+    }
+
+    #[test]
+    fn test_probe() {
+        let (handle, _rx_command, rx_packet, sx_exit) = single_drone();
+
+        assert_eq!(handle.probe(2), None);
+        assert_eq!(handle.probe(1), Some(NodeStatus::Ready));
+        handle.nodes[0].packets.send(ack()).unwrap();
+        assert_eq!(handle.probe(1), Some(NodeStatus::Busy { queue: 1 }));
+        rx_packet.recv().unwrap();
+        assert_eq!(handle.probe(1), Some(NodeStatus::Ready));
+
+        sx_exit.send(()).unwrap();
+        let mut status = handle.probe(1);
+        for _ in 0..100 {
+            if status == Some(NodeStatus::Crashed) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            status = handle.probe(1);
+        }
+        assert_eq!(status, Some(NodeStatus::Crashed));
+    }
+
+    #[test]
+    fn test_resource_usage() {
+        let stat = "42 (drone 1) S 1 42 1 0 -1 4194560 0 0 0 0 250 50 0 0 20 0 1 0 100";
+        assert_eq!(parse_cpu_ticks(stat), Some(300));
+        assert_eq!(parse_cpu_ticks("42 (drone"), None);
+
+        let (handle, _rx_command, rx_packet, _sx_exit) = single_drone();
+        handle.nodes[0].packets.send(ack()).unwrap();
+        let usage = handle.resource_usage()[0];
+        assert_eq!(usage.node, 1);
+        assert!(usage.queue_bytes > 0);
+        rx_packet.recv().unwrap();
+        let usage = handle.resource_usage()[0];
+        assert_eq!(usage.queue_bytes, 0);
+        assert!(usage.peak_queue_bytes > 0);
+    }
+
+    #[test]
+    fn test_termination_report() {
+        let (sx_command, _rx_command) = crossbeam_channel::unbounded();
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let returned = spawn_node(|| {});
+        let panicked = spawn_node(|| panic!("drone exited prematurely"));
+        let nodes = vec![
+            NodeEntry::new(1, Command::None, sx_packet.clone(), returned),
+            NodeEntry::new(2, Command::DroneCommand(sx_command), sx_packet, panicked),
+        ];
+        let handle = NetworkHandle::new(nodes, Shared::default());
+
+        let mut report = handle.termination_report();
+        for _ in 0..100 {
+            if report.with_state(ThreadState::Running).is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            report = handle.termination_report();
+        }
+        assert_eq!(report.with_state(ThreadState::Returned), vec![1]);
+        assert_eq!(report.with_state(ThreadState::Panicked), vec![2]);
+        assert!(!handle.summary().termination.all_running());
+
+        let mut csv = Vec::new();
+        handle.summary().to_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let rows: Vec<_> = csv.lines().collect();
+        assert_eq!(rows[0], "node,thread_state,uptime_ms");
+        assert!(rows[1].starts_with("1,returned,"));
+        assert!(rows[2].starts_with("2,panicked,"));
+    }
+
+    #[test]
+    fn test_gui_watchdog() {
+        let hub = EventHub::default();
+        let events = hub.subscribe();
+        let (client_tx, client_rx) = crossbeam_channel::unbounded();
+        let (gui_tx, gui_rx) = crossbeam_channel::unbounded();
+        let stall = GuiStall {
+            after: Duration::from_millis(20),
+            policy: StalledGuiPolicy::DropOldest,
+        };
+        let relays = Relays::default();
+        spawn_gui_watchdog(4, client_rx, gui_tx, gui_rx.clone(), hub, stall, &relays);
+
+        client_tx.send(1).unwrap();
+        let observed = events.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(observed.node, 4);
+        assert!(matches!(observed.event, NodeEvent::GuiStalled { id: 4 }));
+
+        client_tx.send(2).unwrap();
+        client_tx.send(3).unwrap();
+        drop(client_tx);
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(gui_rx.try_iter().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn test_node_thread() {
+        let builder = node_thread("drone", 7, Some(4 * 1024 * 1024));
+        let (_, thread) = spawn_joinable_node(builder, || {
+            assert_eq!(thread::current().name(), Some("drone-7"));
+        });
+        assert_eq!(thread.thread().name(), Some("drone-7"));
+        assert!(thread.join().is_ok());
+    }
+
+    #[test]
+    fn test_gui_relay_quiesce() {
+        let shared = Shared::default();
+        let handle = NetworkHandle::new(vec![], shared.clone());
+        let (gui_tx, gui_rx) = crossbeam_channel::unbounded();
+        let (client_tx, client_rx) = crossbeam_channel::unbounded();
+        let events = handle.subscribe();
+        let relays = &shared.relays;
+        spawn_gui_relay(4, shared.quiesced, gui_rx, client_tx, shared.events, relays);
+
+        gui_tx.send(1).unwrap();
+        assert_eq!(client_rx.recv_timeout(Duration::from_secs(1)), Ok(1));
+        handle.quiesce();
+        gui_tx.send(2).unwrap();
+        drop(gui_tx);
+        assert!(client_rx.recv_timeout(Duration::from_secs(1)).is_err());
+        let observed = events.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(matches!(
+            observed.event,
+            NodeEvent::GuiMessageRejected { id: 4 }
+        ));
+    }
+
+    #[test]
+    fn test_drain_default_options() {
+        use crate::examples;
+        use crate::init::{network_init_with_options, InitOptions};
+
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let handle = network_init_with_options(&config, &InitOptions::default()).handle;
+        // Quiescing relies on the relays between the GUI and the clients, installed by default.
+        assert_eq!(handle.shared.relays.len(), config.client.len());
+
+        handle.drain(Duration::from_millis(200));
+        assert!(handle.is_quiesced());
+        let report = handle.shutdown(ShutdownPolicy::default());
+        assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+        assert_eq!(handle.shared.relays.len(), 0);
+    }
+
+    #[test]
+    fn test_reattach_gui() {
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let (gui_tx, _gui_rx) = crossbeam_channel::unbounded();
+        let (_client_tx, client_rx) = crossbeam_channel::unbounded();
+        let gui = GuiEndpoint {
+            sender: gui_tx.clone(),
+            receiver: client_rx.clone(),
+        };
+        let nodes = vec![
+            NodeEntry::new(1, Command::None, sx_packet.clone(), spawn_node(|| {})),
+            NodeEntry::new(2, Command::None, sx_packet, spawn_node(|| {})).with_gui(gui),
+        ];
+        let handle = NetworkHandle::new(nodes, Shared::default());
+
+        assert!(handle.reattach_gui(1).is_none());
+        let endpoint = handle.reattach_gui(2).unwrap();
+        assert!(endpoint.sender.same_channel(&gui_tx));
+        assert!(endpoint.receiver.same_channel(&client_rx));
+    }
+
+    /// A network of two drones, a client and a server, whose handle can change the topology.
+    const TWO_DRONES: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 10, 20]
+        pdr = 0.1
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 20]
+        pdr = 0.5
+
+        [[client]]
+        id = 10
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 20
+        connected_drone_ids = [1, 2]
+    "#;
+
+    /// Builds a handle over [`TWO_DRONES`], returning the command queues of the drones.
+    fn two_drones() -> (
+        NetworkHandle,
+        Receiver<DroneCommand>,
+        Receiver<DroneCommand>,
+    ) {
+        let config = network_validate_str(TWO_DRONES).unwrap();
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let (sx_first, rx_first) = crossbeam_channel::unbounded();
+        let (sx_second, rx_second) = crossbeam_channel::unbounded();
+        let nodes = vec![
+            NodeEntry::new(
+                1,
+                Command::DroneCommand(sx_first),
+                sx_packet.clone(),
+                spawn_node(|| {}),
+            ),
+            NodeEntry::new(
+                2,
+                Command::DroneCommand(sx_second),
+                sx_packet.clone(),
+                spawn_node(|| {}),
+            ),
+            NodeEntry::new(10, Command::None, sx_packet.clone(), spawn_node(|| {})),
+            NodeEntry::new(20, Command::None, sx_packet, spawn_node(|| {})),
+        ];
+        let shared = Shared {
+            journal: CommandJournal::new(config_fingerprint(&config)),
+            topology: TopologyTracker::new(&config),
+            ..Shared::default()
+        };
+        (NetworkHandle::new(nodes, shared), rx_first, rx_second)
+    }
+
+    #[test]
+    fn test_reload() {
+        let (handle, rx_first, rx_second) = two_drones();
+
+        // The client moves to the second drone, and the first one gets a new rate.
+        let reloaded = TWO_DRONES
+            .replace("[2, 10, 20]", "[2, 20]")
+            .replace("[1, 20]", "[1, 10, 20]")
+            .replace("connected_drone_ids = [1]", "connected_drone_ids = [2]")
+            .replace("0.1", "0.3");
+        let reloaded = ValidatedConfig::new(network_validate_str(&reloaded).unwrap()).unwrap();
+        let diff = handle.reload(&reloaded).unwrap();
+        assert_eq!(diff.missing_links, [(2, 10)].into());
+        assert_eq!(diff.unexpected_links, [(1, 10)].into());
+        assert!(diff.unexpected_nodes.is_empty());
+        assert!(matches!(
+            rx_first.try_recv(),
+            Ok(DroneCommand::RemoveSender(10))
+        ));
+        assert!(matches!(
+            rx_first.try_recv(),
+            Ok(DroneCommand::SetPacketDropRate(pdr)) if pdr == 0.3
+        ));
+        assert!(matches!(
+            rx_second.try_recv(),
+            Ok(DroneCommand::AddSender(10, _))
+        ));
+        assert!(rx_second.try_recv().is_err());
+
+        // Nodes cannot be spawned by a reload.
+        let mut grown = network_validate_str(TWO_DRONES).unwrap();
+        grown.drone.push(wg_2024::config::Drone {
+            id: 3,
+            connected_node_ids: vec![1, 2],
+            pdr: 0.0,
+        });
+        grown.drone[0].connected_node_ids.push(3);
+        grown.drone[1].connected_node_ids.push(3);
+        let grown = ValidatedConfig::new(grown).unwrap();
+        assert!(handle.reload(&grown).is_err());
+        assert!(rx_first.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_base_fingerprint() {
+        let (handle, _rx_first, _rx_second) = two_drones();
+        let original = ValidatedConfig::new(network_validate_str(TWO_DRONES).unwrap()).unwrap();
+        assert_eq!(handle.base_fingerprint(), original.fingerprint());
+        assert_eq!(
+            handle.drift_report(&original).map(|diff| diff.is_empty()),
+            Ok(true)
+        );
+        let exported = handle.export_current_toml(&original).unwrap();
+        let exported = ValidatedConfig::new(network_validate_str(&exported).unwrap()).unwrap();
+        assert_eq!(exported.fingerprint(), original.fingerprint());
+
+        // The configuration of another run is refused, until the network is reloaded with it.
+        let other = TWO_DRONES.replace("0.5", "0.4");
+        let other = ValidatedConfig::new(network_validate_str(&other).unwrap()).unwrap();
+        assert!(handle.drift_report(&other).is_err());
+        assert!(handle.export_current_toml(&other).is_err());
+        handle.reload(&other).unwrap();
+        assert_eq!(handle.base_fingerprint(), other.fingerprint());
+        assert!(handle.drift_report(&other).is_ok());
+        assert!(handle.drift_report(&original).is_err());
+        let records = handle.command_log();
+        assert!(records
+            .iter()
+            .all(|record| record.base == original.fingerprint()));
+
+        handle.crash(1);
+        let records = handle.command_log();
+        assert_eq!(records.last().unwrap().base, other.fingerprint());
+    }
+
+    #[test]
+    fn test_mutate() {
+        let (handle, rx_first, rx_second) = two_drones();
+        let rejected = [
+            handle.mutate(|txn| {
+                txn.remove_link(1, 2).add_link(2, 10).add_link(10, 20);
+            }),
+            handle.mutate(|txn| {
+                txn.remove_node(2).add_link(2, 10);
+            }),
+            handle.mutate(|txn| {
+                txn.add_link(1, 2);
+            }),
+            handle.mutate(|txn| {
+                txn.set_pdr(10, 0.5);
+            }),
+        ];
+        assert_eq!(
+            rejected,
+            [
+                Err(MutationError::NoDrone((10, 20))),
+                Err(MutationError::NodeNotRunning(2)),
+                Err(MutationError::LinkExists((1, 2))),
+                Err(MutationError::NotADrone(10)),
+            ]
+        );
+        assert!(rx_first.try_recv().is_err() && rx_second.try_recv().is_err());
+
+        let result = handle.mutate(|txn| {
+            txn.remove_link(1, 2).add_link(2, 10).set_pdr(1, 0.5);
+        });
+        assert_eq!(result, Ok(()));
+        let first: Vec<DroneCommand> = rx_first.try_iter().collect();
+        assert!(matches!(
+            first[..],
+            [
+                DroneCommand::RemoveSender(2),
+                DroneCommand::SetPacketDropRate(pdr)
+            ] if pdr == 0.5
+        ));
+        let second: Vec<DroneCommand> = rx_second.try_iter().collect();
+        assert!(matches!(
+            second[..],
+            [
+                DroneCommand::RemoveSender(1),
+                DroneCommand::AddSender(10, _)
+            ]
+        ));
+
+        // The link is checked against the committed changes, even if the drones did not
+        // receive them yet, so only one of the concurrent transactions adds it back.
+        let added = thread::scope(|scope| {
+            let threads: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        handle.mutate(|txn| {
+                            txn.add_link(1, 2);
+                        })
+                    })
+                })
+                .collect();
+            (threads.into_iter())
+                .map(|thread| thread.join().unwrap())
+                .filter(Result::is_ok)
+                .count()
+        });
+        assert_eq!(added, 1);
+        assert_eq!(rx_first.try_iter().count(), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use rust_roveri_api::MAX_NODES;
+use wg_2024::network::NodeId;
+
+use crate::validate::{ErrorCode, ValidationError};
+
+/// The number of node IDs a network can use: the IDs below `MAX_NODES` which fit in a
+/// `NodeId`.
+///
+/// The limit is fixed at compile time: `MAX_NODES` sizes the arrays of `InitData` handed to the
+/// simulation controller, and `NodeId` is the ID type of the protocol, so a larger network
+/// needs a new version of both `rust_roveri_api` and `wg_2024`.
+pub const NODE_CAPACITY: usize = if MAX_NODES < NodeId::MAX as usize + 1 {
+    MAX_NODES
+} else {
+    NodeId::MAX as usize + 1
+};
+
+/// The position of a node in the arrays indexed by node ID, such as the ones of `InitData`.
+///
+/// A `NodeIndex` can only be built for IDs below `MAX_NODES`, so indexing with it never
+/// panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeIndex(usize);
+
+impl NodeIndex {
+    /// Returns the index of a node, or `None` if its ID is not below `MAX_NODES`.
+    pub fn new(id: NodeId) -> Option<Self> {
+        let index = usize::from(id);
+        (index < MAX_NODES).then_some(Self(index))
+    }
+
+    /// Returns the index of a node from its position in an array indexed by node ID, or
+    /// `None` if the position does not correspond to a node ID.
+    pub fn from_position(position: usize) -> Option<Self> {
+        NodeId::try_from(position).ok().and_then(Self::new)
+    }
+
+    /// Returns the position of the node in an array indexed by node ID.
+    pub fn get(self) -> usize {
+        self.0
+    }
+
+    /// Returns the ID of the node.
+    pub fn id(self) -> NodeId {
+        // Positions are only built from node IDs, so they always fit.
+        self.0 as NodeId
+    }
+}
+
+impl TryFrom<NodeId> for NodeIndex {
+    type Error = ValidationError;
+
+    fn try_from(id: NodeId) -> Result<Self, Self::Error> {
+        Self::new(id).ok_or_else(|| {
+            ValidationError::new(
+                ErrorCode::IdOutOfRange,
+                format!("Node ID [{}] is not below {}", id, MAX_NODES),
+            )
+        })
+    }
+}
+
+/// A dense remapping of a set of node IDs to the positions `0..n`.
+///
+/// Useful to size per-node data by the number of nodes rather than by `MAX_NODES`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DenseIndex {
+    ids: Vec<NodeId>,
+    positions: HashMap<NodeId, usize>,
+}
+
+impl DenseIndex {
+    /// Builds the remapping of the given IDs, in order; repeated IDs keep their first position.
+    ///
+    /// # Parameters
+    /// - `ids`: The IDs to remap.
+    pub fn new(ids: impl IntoIterator<Item = NodeId>) -> Self {
+        let mut index = Self::default();
+        for id in ids {
+            index.positions.entry(id).or_insert(index.ids.len());
+            index.ids.push(id);
+        }
+        index
+    }
+
+    /// Returns the number of remapped IDs, including repeated ones.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if no ID was remapped.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the dense position of a node, if it was remapped.
+    pub fn position(&self, id: NodeId) -> Option<usize> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Returns the ID of the node at a dense position, if any.
+    pub fn id(&self, position: usize) -> Option<NodeId> {
+        self.ids.get(position).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rust_roveri_api::MAX_NODES;
+
+    use crate::index::{DenseIndex, NodeIndex, NODE_CAPACITY};
+
+    #[test]
+    fn test_node_index() {
+        let index = NodeIndex::new(42).unwrap();
+        assert_eq!((index.get(), index.id()), (42, 42));
+        assert_eq!(NodeIndex::from_position(42), Some(index));
+        assert_eq!(NodeIndex::from_position(MAX_NODES), None);
+        assert!(NodeIndex::from_position(NODE_CAPACITY - 1).is_some());
+
+        let dense = DenseIndex::new([7, 3, 7, 200]);
+        assert_eq!(dense.len(), 4);
+        assert_eq!(dense.position(7), Some(0));
+        assert_eq!(dense.position(200), Some(3));
+        assert_eq!(dense.position(1), None);
+        assert_eq!(dense.id(1), Some(3));
+        assert_eq!(dense.id(4), None);
+    }
+}
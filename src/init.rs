@@ -1,20 +1,52 @@
-use std::{collections::HashMap, thread};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fmt,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 
 use client::Client;
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, Sender, TrySendError};
 use fixedbitset::FixedBitSet;
 use rust_roveri_api::{
-    ClientChannels, ClientCommand, ClientEvent, ClientGuiMessage, ClientType, Command, Distros, DroneChannels, DroneImpl, GUIChannels, GUIRequest, GUIResponse, GuiClientMessage, InitData, NodeType, SCChannels, ServerChannels, ServerCommand, ServerEvent, ServerType, MAX_CLIENT_TYPES, MAX_IMPL, MAX_NODES, MAX_SERVER_TYPES
+    ClientChannels, ClientCommand, ClientEvent, ClientGuiMessage, ClientType, Command, Distros, DroneImpl, DroneChannels, GUIChannels, GUIRequest, GUIResponse, GuiClientMessage, InitData, NodeType, SCChannels, ServerChannels, ServerCommand, ServerEvent, ServerType, MAX_NODES
 };
 use server::Server;
 use simulation_controller::{core::sc::SimulationController, factory::function::factory_drone};
 use wg_2024::{
     config::Config,
     controller::{DroneCommand, DroneEvent},
+    drone::Drone,
     network::NodeId,
     packet::Packet,
 };
 
+use crate::analysis::TopologyDiff;
+use crate::distribution::DistributionPlan;
+use crate::events::{
+    event_channel, spawn_event_relay, EventQueue, EventScreen, Hub, NodeEvent,
+    SuspiciousEventPolicy,
+};
+use crate::handle::{
+    node_thread, spawn_gui_relay, spawn_gui_watchdog, spawn_joinable_node, GuiEndpoint, GuiStall,
+    Liveness, NetworkHandle, NodeEntry, NodeThreads, Shared, ShutdownPolicy, ShutdownReport,
+    ShutdownStage, TeardownOrder, TeardownReport,
+};
+use crate::journal::{CommandJournal, ISSUER_INIT};
+use crate::index::NodeIndex;
+use crate::model::Topology;
+use crate::spawn::{InitialCommand, SpawnPlan};
+use crate::supervise::{SupervisionEvent, SupervisionPolicy, Supervisor};
+use crate::tap::{spawn_packet_tap, TapCounters, TapSampling, TappedPacket};
+use crate::topology::{
+    client_update, drone_update, server_update, spawn_command_relay, TopologyTracker,
+    TopologyUpdate,
+};
+use crate::transcript::{Recorder, TranscriptEntry};
+use crate::validate::{config_fingerprint, duplicate_ids, out_of_range_ids, ValidatedConfig};
+
 /// Structure that encapsulates all data produced by the network initializer.
 ///
 /// This data includes the initial network topology (as an `InitData` instance), the various
@@ -31,7 +63,286 @@ pub struct NetworkInitData {
         Sender<GuiClientMessage>,
         Receiver<ClientGuiMessage>,
     )>,
-    pub gui_channels: GUIChannels
+    pub gui_channels: GUIChannels,
+    /// Handle used to inspect and stop the running nodes.
+    pub handle: NetworkHandle,
+    /// The changes to the topology made at runtime, e.g. by the simulation controller.
+    pub topology_updates: Receiver<TopologyUpdate>,
+    /// The time spent in each phase of the initialization.
+    pub timings: InitTimings,
+    /// The links whose initial command could not be delivered, as `(node, neighbor)`, see
+    /// [`RetryPolicy`].
+    pub undelivered: Vec<(NodeId, NodeId)>,
+    /// The join handles of the threads of the nodes.
+    pub threads: NodeThreads,
+    /// The panics of the node implementations, and the respawns of the nodes, see
+    /// [`InitOptions::supervision`].
+    pub supervision: Receiver<SupervisionEvent>,
+    /// The seed of the run, if one was given by [`InitOptions::seed`], e.g. by
+    /// [`network_init_seeded`].
+    pub seed: Option<u64>,
+}
+
+/// A function building a drone, with the signature of `factory_drone`.
+pub type DroneFactory = fn(
+    DroneImpl,
+    NodeId,
+    Sender<DroneEvent>,
+    Receiver<DroneCommand>,
+    Receiver<Packet>,
+    HashMap<NodeId, Sender<Packet>>,
+    f32,
+) -> Box<dyn Drone + Send>;
+
+/// A client or a server, built by a factory and then run on its own thread.
+pub trait RunNode {
+    /// Runs the node until it is crashed.
+    fn run(&mut self);
+}
+
+impl RunNode for Client {
+    fn run(&mut self) {
+        Client::run(self);
+    }
+}
+
+impl RunNode for Server {
+    fn run(&mut self) {
+        Server::run(self);
+    }
+}
+
+/// A function building a client, from its ID, type, packet queue, command queue, event
+/// sender and GUI channels.
+pub type ClientFactory = fn(
+    NodeId,
+    ClientType,
+    Receiver<Packet>,
+    Receiver<ClientCommand>,
+    Sender<ClientEvent>,
+    Receiver<GuiClientMessage>,
+    Sender<ClientGuiMessage>,
+) -> Box<dyn RunNode>;
+
+/// A function building a server, from its ID, type, command queue, packet queue and event
+/// sender.
+pub type ServerFactory = fn(
+    NodeId,
+    ServerType,
+    Receiver<ServerCommand>,
+    Receiver<Packet>,
+    Sender<ServerEvent>,
+) -> Box<dyn RunNode>;
+
+/// Options of [`network_init_with_options`].
+#[derive(Clone, Copy, Debug)]
+pub struct InitOptions {
+    /// How drones are given the senders towards their neighbors.
+    pub mode: InitMode,
+    /// The function building every drone; `factory_drone` by default, or e.g.
+    /// [`crate::stub::null_drone_factory`] for topology-only simulations.
+    pub drone_factory: DroneFactory,
+    /// The function building every client; the client of this project by default, or e.g.
+    /// [`crate::stub::ping_client_factory`] for smoke tests.
+    pub client_factory: ClientFactory,
+    /// The function building every server; the server of this project by default, or e.g.
+    /// [`crate::stub::echo_server_factory`] for smoke tests.
+    pub server_factory: ServerFactory,
+    /// The capacity of the channel on which every node sends its events; unbounded by
+    /// default. A bounded channel makes a node block while its events are not relayed, see
+    /// [`NetworkHandle::event_backlog`](crate::handle::NetworkHandle::event_backlog). Without
+    /// [`InitOptions::per_node_events`], it bounds the channel shared by the nodes of a class.
+    pub event_capacity: Option<usize>,
+    /// Whether every node sends its events on its own channel, behind a relay forwarding them
+    /// to the simulation controller; `false` by default, where the nodes of a class share the
+    /// channel of the simulation controller.
+    ///
+    /// The relays attribute every event to the node which emitted it, publish it to the
+    /// subscribers of [`NetworkHandle::subscribe`](crate::handle::NetworkHandle::subscribe),
+    /// screen it as set by [`InitOptions::suspicious_events`], and account for the backlog of
+    /// every node. Without them, the subscribers only receive the diagnostics of the network,
+    /// e.g. [`NodeEvent::GuiStalled`], and no backlog is reported.
+    pub per_node_events: bool,
+    /// The capacity of the channel on which every node receives its commands; unbounded by
+    /// default.
+    pub command_capacity: Option<usize>,
+    /// How the initial commands adding the links are retried while the command channel of a
+    /// node is full.
+    pub retry: RetryPolicy,
+    /// Whether the commands sent to every node pass through a relay, publishing the topology
+    /// update caused by each to the subscribers of
+    /// [`NetworkHandle::topology_updates`](crate::handle::NetworkHandle::topology_updates);
+    /// `false` by default, where the commands reach the nodes directly.
+    ///
+    /// Without the relays, the live topology only follows the commands sent through the
+    /// [`NetworkHandle`], and not the ones sent by the simulation controller.
+    pub command_relays: bool,
+    /// Whether the messages of every client to its GUI pass through a watchdog, reporting a
+    /// GUI which stops receiving them; `false` by default, where the client talks to the GUI
+    /// directly.
+    pub gui_watchdog: bool,
+    /// When the GUI of a client is considered stalled, and what happens to the messages of the
+    /// client meanwhile. Only enforced with [`InitOptions::gui_watchdog`].
+    pub gui_stall: GuiStall,
+    /// The seed of the run, which enables the recording of a
+    /// [`Transcript`](crate::transcript::Transcript) of the initialization, returned by
+    /// [`NetworkHandle::transcript`](crate::handle::NetworkHandle::transcript). Randomized
+    /// decisions of the initialization must derive from it, see [`network_init_seeded`].
+    pub seed: Option<u64>,
+    /// What happens to the events of a node referencing nodes which are not part of the
+    /// network; a [`NodeEvent::Suspicious`] is published to the subscribers in any case. Only
+    /// enforced with [`InitOptions::per_node_events`].
+    pub suspicious_events: SuspiciousEventPolicy,
+    /// Whether a tap is put in front of the packet queue of every node, publishing the packets
+    /// delivered to the node to the subscribers of
+    /// [`NetworkHandle::tap`](crate::handle::NetworkHandle::tap); `false` by default, where the
+    /// packets reach the queues directly and none is published.
+    pub packet_taps: bool,
+    /// Which of the packets delivered to the nodes are published by the taps of
+    /// [`InitOptions::packet_taps`]; all of them by default.
+    pub tap_sampling: TapSampling,
+    /// What happens to a node whose implementation panics; the panic is only reported by
+    /// default.
+    pub supervision: SupervisionPolicy,
+    /// The stack size of the thread of every node, in bytes; the default of the standard
+    /// library if `None`. The threads are named after their node, e.g. `drone-7` or
+    /// `client-12`.
+    pub stack_size: Option<usize>,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        Self {
+            mode: InitMode::default(),
+            drone_factory: factory_drone,
+            client_factory: build_client,
+            server_factory: build_server,
+            event_capacity: None,
+            per_node_events: false,
+            command_capacity: None,
+            retry: RetryPolicy::default(),
+            command_relays: false,
+            gui_watchdog: false,
+            gui_stall: GuiStall::default(),
+            seed: None,
+            suspicious_events: SuspiciousEventPolicy::default(),
+            packet_taps: false,
+            tap_sampling: TapSampling::default(),
+            supervision: SupervisionPolicy::default(),
+            stack_size: None,
+        }
+    }
+}
+
+/// Builds the client of this project, whatever its type.
+fn build_client(
+    id: NodeId,
+    _client_type: ClientType,
+    packet_recv: Receiver<Packet>,
+    command_recv: Receiver<ClientCommand>,
+    event_send: Sender<ClientEvent>,
+    gui_recv: Receiver<GuiClientMessage>,
+    gui_send: Sender<ClientGuiMessage>,
+) -> Box<dyn RunNode> {
+    Box::new(Client::new(
+        id,
+        packet_recv,
+        command_recv,
+        event_send,
+        gui_recv,
+        gui_send,
+    ))
+}
+
+/// Builds the server of this project.
+fn build_server(
+    id: NodeId,
+    server_type: ServerType,
+    command_recv: Receiver<ServerCommand>,
+    packet_recv: Receiver<Packet>,
+    event_send: Sender<ServerEvent>,
+) -> Box<dyn RunNode> {
+    Box::new(Server::new(
+        id,
+        command_recv,
+        packet_recv,
+        event_send,
+        server_type,
+    ))
+}
+
+/// How [`network_init_with_options`] gives drones the senders towards their neighbors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InitMode {
+    /// Drones start with no neighbor, and receive each as an `AddSender` command once every
+    /// node is spawned. Packets sent to a drone before its commands are processed are
+    /// unroutable.
+    #[default]
+    Commands,
+    /// Drones are constructed with the senders towards all of their neighbors, so that they
+    /// can route packets from the start.
+    Prebuilt,
+}
+
+/// How the initial commands adding the links are retried while the command channel of a node
+/// is full, e.g. because the node is slow to start.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of attempts to deliver each command, including the first one.
+    pub attempts: u32,
+    /// The delay before the second attempt, doubled after every further attempt.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            backoff: Duration::from_millis(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sends a command, retrying with backoff while the channel is full.
+    ///
+    /// # Parameters
+    /// - `sender`: The command channel of the node.
+    /// - `command`: The command.
+    ///
+    /// Returns `false` if the channel was still full after the last attempt, or disconnected.
+    fn send<T>(&self, sender: &Sender<T>, mut command: T) -> bool {
+        let mut delay = self.backoff;
+        for attempt in 1..=self.attempts.max(1) {
+            match sender.try_send(command) {
+                Ok(()) => return true,
+                Err(TrySendError::Disconnected(_)) => return false,
+                Err(TrySendError::Full(rejected)) => command = rejected,
+            }
+            if attempt < self.attempts {
+                thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+            }
+        }
+        false
+    }
+}
+
+/// The time spent in each phase of [`network_init`], to profile the startup of large networks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InitTimings {
+    /// Planning the distribution and creating the shared state.
+    pub plan: Duration,
+    /// Creating the channels of the drones and spawning their threads.
+    pub drones: Duration,
+    /// Creating the channels of the clients and spawning their threads.
+    pub clients: Duration,
+    /// Creating the channels of the servers and spawning their threads.
+    pub servers: Duration,
+    /// Building the topology graph and sending the initial link commands.
+    pub links: Duration,
+    /// The whole initialization, including the creation of the handle.
+    pub total: Duration,
 }
 
 impl NetworkInitData {
@@ -46,6 +357,14 @@ impl NetworkInitData {
     /// - `server_channels`: Channels used for server communication.
     /// - `list_gui_channels`: A list of tuples for each client containing its ID, type, and GUI messaging channels.
     /// - `distros`: Distribution data for node types.
+    /// - `handle`: Handle used to inspect and stop the running nodes.
+    /// - `topology_updates`: The changes to the topology made at runtime.
+    /// - `timings`: The time spent in each phase of the initialization.
+    /// - `undelivered`: The links whose initial command could not be delivered.
+    /// - `threads`: The join handles of the threads of the nodes.
+    /// - `supervision`: The panics and respawns of the nodes.
+    /// - `seed`: The seed of the run, if any.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         topology: [(NodeType, FixedBitSet); MAX_NODES],
         list_gui_channels: Vec<(
@@ -54,24 +373,171 @@ impl NetworkInitData {
             Sender<GuiClientMessage>,
             Receiver<ClientGuiMessage>,
         )>,
-        gui_channels: GUIChannels
+        gui_channels: GUIChannels,
+        handle: NetworkHandle,
+        topology_updates: Receiver<TopologyUpdate>,
+        timings: InitTimings,
+        undelivered: Vec<(NodeId, NodeId)>,
+        threads: NodeThreads,
+        supervision: Receiver<SupervisionEvent>,
+        seed: Option<u64>,
     ) -> Self {
         Self {
             topology,
             list_gui_channels,
-            gui_channels
+            gui_channels,
+            handle,
+            topology_updates,
+            timings,
+            undelivered,
+            threads,
+            supervision,
+            seed,
+        }
+    }
+
+    /// Stops the network and joins the threads of its nodes.
+    ///
+    /// The nodes are shut down by [`NetworkHandle::shutdown`], which sends a crash command to
+    /// every node and waits `policy.crash` for them to terminate. The threads of the nodes
+    /// which terminated are then joined; the abandoned ones are left detached, and their
+    /// handles stay in [`NetworkInitData::threads`].
+    ///
+    /// # Parameters
+    /// - `policy`: The timeouts of the stages of the shutdown.
+    ///
+    /// Returns the stage at which every node terminated.
+    pub fn shutdown(&self, policy: ShutdownPolicy) -> ShutdownReport {
+        let report = self.handle.shutdown(policy);
+        let abandoned = report.with_stage(ShutdownStage::Abandoned);
+        self.threads.join_except(&abandoned);
+        report
+    }
+
+    /// Crashes the nodes one type at a time, e.g. clients, then servers, then drones, and
+    /// joins the threads of the nodes.
+    ///
+    /// The nodes are crashed by [`NetworkHandle::teardown`], which waits for the nodes of a
+    /// stage to terminate before moving on to the next one. The threads of the nodes which
+    /// terminated are then joined; the abandoned ones are left detached, and their handles stay
+    /// in [`NetworkInitData::threads`].
+    ///
+    /// # Parameters
+    /// - `order`: The order of the stages, and their timeout.
+    ///
+    /// Returns how the nodes of every stage terminated.
+    pub fn teardown(&self, order: TeardownOrder) -> TeardownReport {
+        let report = self.handle.teardown(order);
+        self.threads.join_except(&report.abandoned());
+        report
+    }
+}
+
+/// The packet channels of every node of a topology, created before any node is spawned.
+///
+/// Creating every channel first lets nodes be constructed with the senders towards their
+/// neighbors, as done by [`InitMode::Prebuilt`].
+#[derive(Debug)]
+pub(crate) struct ChannelPlan {
+    /// The senders used by the neighbors of every node.
+    senders: HashMap<NodeId, Sender<Packet>>,
+    /// The packet queues of the nodes which were not spawned yet.
+    receivers: HashMap<NodeId, Receiver<Packet>>,
+}
+
+impl ChannelPlan {
+    /// Creates an unbounded packet channel for every node of the topology.
+    ///
+    /// # Parameters
+    /// - `topology`: The network topology.
+    pub fn new(topology: &Topology) -> Self {
+        let mut senders = HashMap::with_capacity(topology.nodes.len());
+        let mut receivers = HashMap::with_capacity(topology.nodes.len());
+        for node in &topology.nodes {
+            let (sender, receiver) = crossbeam_channel::unbounded::<Packet>();
+            senders.insert(node.id, sender);
+            receivers.insert(node.id, receiver);
         }
+        Self { senders, receivers }
+    }
+
+    /// Returns the sender towards a node, if it is in the topology.
+    pub fn sender(&self, id: NodeId) -> Option<&Sender<Packet>> {
+        self.senders.get(&id)
+    }
+
+    /// Returns the senders towards the given neighbors, skipping the ones not in the topology.
+    ///
+    /// # Parameters
+    /// - `neighbors`: The IDs of the neighbors.
+    pub fn neighbor_senders(&self, neighbors: &[NodeId]) -> HashMap<NodeId, Sender<Packet>> {
+        neighbors
+            .iter()
+            .filter_map(|id| Some((*id, self.sender(*id)?.clone())))
+            .collect()
+    }
+
+    /// Takes the packet queue of a node, to be given to the node when it is spawned.
+    ///
+    /// Returns `None` if the node is not in the topology, or its queue was already taken.
+    pub fn take_receiver(&mut self, id: NodeId) -> Option<Receiver<Packet>> {
+        self.receivers.remove(&id)
+    }
+
+    /// Puts a tap in front of every node, so that the senders returned from now on pass
+    /// through it.
+    ///
+    /// # Parameters
+    /// - `shared`: The state of the network: the hub publishing the packets, the tracker telling
+    ///   the taps when a node crashes, and the relays stopping the taps on shutdown.
+    /// - `sampling`: Which packets the taps publish.
+    ///
+    /// Returns the senders towards the packet queues behind the taps, and the counters of the
+    /// taps.
+    fn tap(
+        &mut self,
+        shared: &Shared,
+        sampling: TapSampling,
+    ) -> (
+        HashMap<NodeId, Sender<Packet>>,
+        BTreeMap<NodeId, Arc<TapCounters>>,
+    ) {
+        let capture = sampling.crash_capture;
+        let mut queues = HashMap::with_capacity(self.senders.len());
+        let mut counters = BTreeMap::new();
+        for (id, sender) in self.senders.iter_mut() {
+            let (sx_tap, rx_tap) = crossbeam_channel::unbounded::<Packet>();
+            let updates =
+                (capture.head > 0 || capture.tail > 0).then(|| shared.topology.subscribe());
+            let tap = spawn_packet_tap(
+                *id,
+                rx_tap,
+                sender.clone(),
+                shared.packets.clone(),
+                sampling,
+                updates,
+                &shared.relays,
+            );
+            counters.insert(*id, tap);
+            queues.insert(*id, std::mem::replace(sender, sx_tap));
+        }
+        (queues, counters)
     }
 }
 
 /// Initializes the network by spawning all node threads and constructing the data structures
 /// required both by the simulation controller and the GUI.   
 ///
-/// Returns an istance of [`NetworkInitData`].
+/// Returns an instance of [`NetworkInitData`].
 ///
 /// # Parameters
 /// - `config`: A reference to the network configuration (parsed from the user’s configuration file).
 ///
+/// # Panics
+/// Panics if the network has no drones, if some node cannot be assigned an implementation or
+/// type, or if the spawned state is inconsistent with the configuration; use
+/// [`try_network_init_with_options`] to get an [`InitConsistencyError`] instead.
+///
 /// # Behaviour
 /// This function performs the following steps:
 /// 1. **Topology Construction:**  
@@ -86,15 +552,22 @@ impl NetworkInitData {
 ///    will use to receive events.
 ///
 /// 3. **Distribution Data:**  
-///    It builds distribution arrays for drones, clients, and servers based on the configuration.
+///    It plans the implementation or type of every node with [`DistributionPlan`], and derives
+///    the distribution arrays for drones, clients, and servers from it. A plain [`Config`] does not
+///    carry the implementations chosen by the drones of the file, so the plan is the round-robin one;
+///    use [`network_init_validated`] to honor them.
 ///    
 /// 4. **GUI Channel Storage:**  
 ///    It prepares a vector of GUI channel tuples for client nodes (each containing the node ID, client type,
-///    and channels for GUI communication).
+///    and channels for GUI communication). Messages from the GUI reach the clients through a relay thread,
+///    which discards them, publishing a `GuiMessageRejected` event, once the network is quiesced by
+///    [`NetworkHandle::drain`].
 ///
 /// 5. **Node Thread Spawning:**  
 ///    For each node (drone, client, and server) defined in the configuration:
-///    - It sets up per-node command and packet channels.
+///    - It sets up per-node command and packet channels. With [`InitOptions::command_relays`], commands
+///      reach the node through a relay, which publishes the topology change caused by each on
+///      `topology_updates`. Every relay is stopped and joined when the network is shut down.
 ///    - It assigns the node type into the topology array.
 ///    - It spawns a new thread that instantiates the node and then calls its `run()` method.
 ///
@@ -104,9 +577,203 @@ impl NetworkInitData {
 ///
 /// 7. **Final Assembly:**  
 ///    Finally, it constructs an `InitData` instance from the topology, command array, and packet send map,
-///    and wraps it together with the channels and distribution data in a `NetworkInitData` instance, which
-///    is then returned.
+///    and wraps it together with the channels, distribution data and a [`NetworkHandle`] over the nodes in a
+///    `NetworkInitData` instance, which is then returned.
 pub fn network_init(config: &Config) -> NetworkInitData {
+    network_init_with_options(config, &InitOptions::default())
+}
+
+/// Initializes the network like [`network_init`], choosing how drones are built and given the
+/// senders towards their neighbors.
+///
+/// Returns an instance of [`NetworkInitData`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `options`: The options of the initialization.
+///
+/// # Panics
+/// Panics if the spawned state is inconsistent with the configuration, see
+/// [`try_network_init_with_options`].
+pub fn network_init_with_options(config: &Config, options: &InitOptions) -> NetworkInitData {
+    try_network_init_with_options(config, options).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Initializes the network like [`network_init_with_options`], checking that the spawned state
+/// is consistent with the configuration before any link is added.
+///
+/// Returns an instance of [`NetworkInitData`], or the first inconsistency found, after crashing
+/// every spawned node. A network without drones, or with a node which cannot be assigned an
+/// implementation or type, is rejected before any node is spawned.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `options`: The options of the initialization.
+pub fn try_network_init_with_options(
+    config: &Config,
+    options: &InitOptions,
+) -> Result<NetworkInitData, InitConsistencyError> {
+    let plan = DistributionPlan::try_new(config)
+        .map_err(|error| InitConsistencyError::TypeUnavailable(error.message))?;
+    try_network_init_with_plan(config, &plan, options)
+}
+
+/// Initializes the network like [`network_init_with_options`], honoring the implementation
+/// chosen by the drones of the configuration file, see [`crate::distribution::IMPL_KEY`].
+///
+/// A plain [`Config`] does not carry these choices, so they are only honored when the
+/// configuration is read with [`ValidatedConfig::from_file`] (or through its cache, see
+/// [`crate::cache::network_validate_cached`]) and initialized with this function.
+///
+/// Returns an instance of [`NetworkInitData`].
+///
+/// # Parameters
+/// - `config`: A reference to the validated network configuration.
+/// - `options`: The options of the initialization.
+///
+/// # Panics
+/// Panics if the spawned state is inconsistent with the configuration, see
+/// [`try_network_init_validated`].
+pub fn network_init_validated(config: &ValidatedConfig, options: &InitOptions) -> NetworkInitData {
+    try_network_init_validated(config, options).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Initializes the network like [`network_init_validated`], checking that the spawned state
+/// is consistent with the configuration before any link is added, as
+/// [`try_network_init_with_options`].
+///
+/// # Parameters
+/// - `config`: A reference to the validated network configuration.
+/// - `options`: The options of the initialization.
+pub fn try_network_init_validated(
+    config: &ValidatedConfig,
+    options: &InitOptions,
+) -> Result<NetworkInitData, InitConsistencyError> {
+    let plan = config
+        .plan()
+        .map_err(|error| InitConsistencyError::TypeUnavailable(error.message))?;
+    try_network_init_with_plan(config.config(), &plan, options)
+}
+
+/// Initializes the network like [`network_init`], deriving every randomized decision from a
+/// seed, so that a run can be reproduced bit-for-bit, e.g. during grading.
+///
+/// The implementations and types are assigned by [`DistributionPlan::try_seeded`], and the
+/// seed is recorded in [`NetworkInitData::seed`] and in the transcript of the initialization,
+/// see [`InitOptions::seed`].
+/// The rules of a validation policy on the distribution, e.g. the unused implementations, can
+/// be checked against the same plan with [`crate::validate::check_config_with_plan`].
+///
+/// Returns an instance of [`NetworkInitData`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `seed`: The seed of the run.
+///
+/// # Panics
+/// Panics if some node cannot be assigned an implementation or type, or if the spawned state
+/// is inconsistent with the configuration, see [`try_network_init_with_plan`].
+pub fn network_init_seeded(config: &Config, seed: u64) -> NetworkInitData {
+    let plan =
+        DistributionPlan::try_seeded(config, seed).unwrap_or_else(|error| panic!("{}", error));
+    let options = InitOptions {
+        seed: Some(seed),
+        ..InitOptions::default()
+    };
+    network_init_with_plan(config, &plan, &options)
+}
+
+/// Initializes the network like [`network_init_with_options`], with the given implementation
+/// or type of every node instead of the round-robin, e.g. as chosen by the configuration file
+/// and read by [`crate::distribution::network_validate_plan`].
+///
+/// Returns an instance of [`NetworkInitData`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `plan`: The distribution of the configuration.
+/// - `options`: The options of the initialization.
+///
+/// # Panics
+/// Panics if the plan does not list the nodes of the configuration, or if the spawned state
+/// is inconsistent with the configuration, see [`try_network_init_with_plan`].
+pub fn network_init_with_plan(
+    config: &Config,
+    plan: &DistributionPlan,
+    options: &InitOptions,
+) -> NetworkInitData {
+    try_network_init_with_plan(config, plan, options).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Initializes the network like [`network_init_with_plan`], checking that the spawned state
+/// is consistent with the configuration before any link is added, as
+/// [`try_network_init_with_options`].
+///
+/// A configuration declaring a node ID more than once, or a plan which does not list the nodes
+/// of the configuration, in order, is rejected before any node is spawned.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `plan`: The distribution of the configuration.
+/// - `options`: The options of the initialization.
+pub fn try_network_init_with_plan(
+    config: &Config,
+    plan: &DistributionPlan,
+    options: &InitOptions,
+) -> Result<NetworkInitData, InitConsistencyError> {
+    let InitOptions {
+        mode,
+        drone_factory,
+        client_factory,
+        server_factory,
+        event_capacity,
+        per_node_events,
+        command_capacity,
+        retry,
+        command_relays,
+        gui_watchdog,
+        gui_stall,
+        seed,
+        suspicious_events,
+        packet_taps,
+        tap_sampling,
+        supervision,
+        stack_size,
+    } = *options;
+    let out_of_range = out_of_range_ids(config);
+    if !out_of_range.is_empty() {
+        return Err(InitConsistencyError::IdOutOfRange(
+            out_of_range.into_iter().collect(),
+        ));
+    }
+    let duplicates = duplicate_ids(config);
+    if !duplicates.is_empty() {
+        return Err(InitConsistencyError::DuplicateId(
+            duplicates.into_iter().collect(),
+        ));
+    }
+    if config.drone.is_empty() {
+        return Err(InitConsistencyError::NoDrones);
+    }
+    let drones = config.drone.iter().map(|drone| drone.id);
+    let clients = config.client.iter().map(|client| client.id);
+    let servers = config.server.iter().map(|server| server.id);
+    if !drones.eq(plan.drones.iter().map(|(id, _)| *id))
+        || !clients.eq(plan.clients.iter().map(|(id, _)| *id))
+        || !servers.eq(plan.servers.iter().map(|(id, _)| *id))
+    {
+        return Err(InitConsistencyError::PlanMismatch);
+    }
+    let start = Instant::now();
+    let mut timings = InitTimings::default();
+    let mut phase = start;
+    let mut lap = |phase_time: &mut Duration| {
+        let now = Instant::now();
+        *phase_time = now - phase;
+        phase = now;
+    };
+    let n_nodes = config.drone.len() + config.client.len() + config.server.len();
+
     // Create network topology data for the simulation controller:
     let mut topology: [(NodeType, FixedBitSet); MAX_NODES] =
         std::array::from_fn(|_index| (NodeType::None, FixedBitSet::with_capacity(MAX_NODES)));
@@ -114,15 +781,49 @@ pub fn network_init(config: &Config) -> NetworkInitData {
     let mut packet_send_map: [Option<Sender<Packet>>; MAX_NODES] =
         std::array::from_fn(|_index| None);
 
-    // Create channels for the simulation controller to handle node events:
-    let (drone_sender, drone_receiver) = crossbeam_channel::unbounded::<DroneEvent>();
-    let (client_sender, client_receiver) = crossbeam_channel::unbounded::<ClientEvent>();
-    let (server_sender, server_receiver) = crossbeam_channel::unbounded::<ServerEvent>();
+    // Screen the node events against the nodes of the network:
+    let known: BTreeSet<NodeId> = (config.drone.iter().map(|drone| drone.id))
+        .chain(config.client.iter().map(|client| client.id))
+        .chain(config.server.iter().map(|server| server.id))
+        .collect();
+    let screen = EventScreen::new(Arc::new(known), suspicious_events);
+
+    // Create channels for the simulation controller to handle node events; the nodes send on
+    // them directly, unless every node gets its own channel behind a relay.
+    let class_capacity = if per_node_events {
+        None
+    } else {
+        event_capacity
+    };
+    let (drone_sender, drone_receiver) = event_channel::<DroneEvent>(class_capacity);
+    let (client_sender, client_receiver) = event_channel::<ClientEvent>(class_capacity);
+    let (server_sender, server_receiver) = event_channel::<ServerEvent>(class_capacity);
 
-    // Create distributions for the simulation controller:
-    let mut drones_distro: [usize; MAX_IMPL] = std::array::from_fn(|_index| 0);
-    let mut clients_distro: [usize; MAX_CLIENT_TYPES] = std::array::from_fn(|_index| 0);
-    let mut servers_distro: [usize; MAX_SERVER_TYPES] = std::array::from_fn(|_index| 0);
+    // Create the state shared by the handle with the relays: the flag used to stop the GUI from
+    // generating new traffic, the command journal, and the hubs publishing events and packets.
+    let mut shared = Shared {
+        journal: CommandJournal::new(config_fingerprint(config)),
+        plan: plan.clone(),
+        spawn: SpawnPlan::new(config, mode),
+        topology: TopologyTracker::new(config),
+        command_relays,
+        ..Shared::default()
+    };
+    // Record the steps of the initialization, if a seed is given.
+    let mut transcript = Recorder::new(seed);
+    // Create a map to store the packet queues behind the taps.
+    let mut delivery_queues: HashMap<NodeId, Sender<Packet>> = HashMap::with_capacity(n_nodes);
+    // Create a map to store the command queues behind the relays.
+    let mut command_queues: HashMap<NodeId, Command> = HashMap::with_capacity(n_nodes);
+    // Create a map to store the GUI channels kept by the handle, so that GUIs can reattach.
+    let mut gui_endpoints: HashMap<NodeId, GuiEndpoint> =
+        HashMap::with_capacity(config.client.len());
+    // Create a map to store the event queues in front of the relays.
+    let mut event_queues: HashMap<NodeId, EventQueue> = HashMap::with_capacity(n_nodes);
+    // Create a map to store the liveness of the node threads.
+    let mut liveness: HashMap<NodeId, Liveness> = HashMap::with_capacity(n_nodes);
+    let threads = NodeThreads::default();
+    let (supervisor, supervision) = Supervisor::new(supervision);
 
     // Create an array to store the GUI channels for client nodes.
     let mut list_gui_channels: Vec<(
@@ -132,140 +833,429 @@ pub fn network_init(config: &Config) -> NetworkInitData {
         Receiver<ClientGuiMessage>,
     )> = Vec::with_capacity(config.client.len());
 
-    let mut index_drone_impl = 0;
-    let mut index_client_types = 0;
-    let mut index_server_types = 0;
+    // Create the packet channels of every node up front, so that drones can be constructed
+    // with the senders towards their neighbors, and put a tap in front of each.
+    let network = Topology::from(config);
+    let mut channels = ChannelPlan::new(&network);
+    if packet_taps {
+        let (queues, taps) = channels.tap(&shared, tap_sampling);
+        delivery_queues.extend(queues);
+        shared.taps = Arc::new(taps);
+    }
+    for node in &network.nodes {
+        packet_send_map[slot(node.id)] = channels.sender(node.id).cloned();
+        if let Some(sender) = channels.sender(node.id) {
+            shared.channels.register(
+                transcript.channel(format!("pkt {}", node.id)),
+                sender.clone(),
+            );
+        }
+        if let Some(delivery) = delivery_queues.get(&node.id) {
+            shared.channels.register(
+                transcript.channel(format!("pkt tap {}", node.id)),
+                delivery.clone(),
+            );
+        }
+    }
+    if !per_node_events {
+        let name = transcript.channel("evt drones".to_string());
+        shared.channels.register(name, drone_receiver.clone());
+        let name = transcript.channel("evt clients".to_string());
+        shared.channels.register(name, client_receiver.clone());
+        let name = transcript.channel("evt servers".to_string());
+        shared.channels.register(name, server_receiver.clone());
+    }
+    lap(&mut timings.plan);
 
     // Spawn drone threads.
-    for drone in config.drone.iter().cloned() {
-        let (sx_command, rx_command) = crossbeam_channel::unbounded::<DroneCommand>();
-        let (sx_packet, rx_packet) = crossbeam_channel::unbounded::<Packet>();
+    let drones = config.drone.iter().zip(plan.drones.iter().copied());
+    for (drone, (_, drone_impl)) in drones {
+        let (sx_command, rx_command) = shared.channels.channel::<DroneCommand>(
+            transcript.channel(format!("cmd drone {}", drone.id)),
+            command_capacity,
+        );
+        let sx_relay = if command_relays {
+            let (sx_relay, rx_relay) = shared.channels.channel::<DroneCommand>(
+                transcript.channel(format!("cmd relay drone {}", drone.id)),
+                None,
+            );
+            spawn_command_relay(
+                drone.id,
+                rx_relay,
+                sx_command.clone(),
+                shared.topology.clone(),
+                drone_update,
+                &shared.relays,
+            );
+            sx_relay
+        } else {
+            sx_command.clone()
+        };
+        command_queues.insert(drone.id, Command::DroneCommand(sx_command));
+        let rx_packet = (channels.take_receiver(drone.id))
+            .ok_or_else(|| InitConsistencyError::DuplicateId(vec![drone.id]))?;
 
-        senders[drone.id as usize] = Command::DroneCommand(sx_command);
-        packet_send_map[drone.id as usize] = Some(sx_packet);
-        let drone_impl = DroneImpl::from_code(index_drone_impl).unwrap();
-        drones_distro[index_drone_impl] += 1;
-        index_drone_impl = (index_drone_impl + 1) % MAX_IMPL;
-        topology[drone.id as usize].0 = NodeType::Drone(drone.pdr, drone_impl);
+        senders[slot(drone.id)] = Command::DroneCommand(sx_relay);
+        topology[slot(drone.id)].0 = NodeType::Drone(drone.pdr, drone_impl);
+        transcript.record(|| TranscriptEntry::Assigned {
+            node: drone.id,
+            assignment: format!("{:?}", topology[slot(drone.id)].0),
+        });
 
         // Spawn drone thread.
-        let sender = drone_sender.clone();
-        thread::spawn(move || {
-            let mut drone = factory_drone(
-                drone_impl,
+        let sender = if per_node_events {
+            let (sender, rx_event) = event_channel::<DroneEvent>(event_capacity);
+            let name = transcript.channel(format!("evt drone {}", drone.id));
+            shared.channels.register(name, rx_event.clone());
+            let event_queue = spawn_event_relay(
                 drone.id,
-                sender,
-                rx_command,
-                rx_packet,
-                HashMap::new(),
-                drone.pdr,
+                rx_event,
+                drone_sender.clone(),
+                shared.events.clone(),
+                NodeEvent::Drone,
+                screen.clone(),
+                &shared.relays,
             );
-            drone.run();
-        });
+            event_queues.insert(drone.id, event_queue);
+            sender
+        } else {
+            drone_sender.clone()
+        };
+        let (drone_id, pdr) = (drone.id, drone.pdr);
+        let packet_send = match mode {
+            // Size the neighbor map for the senders added once every node is spawned.
+            InitMode::Commands => HashMap::with_capacity(drone.connected_node_ids.len()),
+            InitMode::Prebuilt => channels.neighbor_senders(&drone.connected_node_ids),
+        };
+        transcript.record(|| TranscriptEntry::Spawned { node: drone_id });
+        let supervisor = supervisor.clone();
+        let (drone_liveness, drone_thread) = spawn_joinable_node(
+            node_thread("drone", drone_id, stack_size),
+            move || {
+                supervisor.run(drone_id, || {
+                    let mut drone = drone_factory(
+                        drone_impl,
+                        drone_id,
+                        sender.clone(),
+                        rx_command.clone(),
+                        rx_packet.clone(),
+                        packet_send.clone(),
+                        pdr,
+                    );
+                    drone.run();
+                })
+            },
+        );
+        liveness.insert(drone_id, drone_liveness);
+        threads.insert(drone_id, drone_thread);
     }
+    lap(&mut timings.drones);
 
     // Spawn client threads.
-    for client in config.client.iter().cloned() {
-        let (sx_command, rx_command) = crossbeam_channel::unbounded::<ClientCommand>();
-        let (sx_packet, rx_packet) = crossbeam_channel::unbounded::<Packet>();
+    let clients = config.client.iter().zip(plan.clients.iter().copied());
+    for (client, (_, client_type)) in clients {
+        let (sx_command, rx_command) = shared.channels.channel::<ClientCommand>(
+            transcript.channel(format!("cmd client {}", client.id)),
+            command_capacity,
+        );
+        let sx_relay = if command_relays {
+            let (sx_relay, rx_relay) = shared.channels.channel::<ClientCommand>(
+                transcript.channel(format!("cmd relay client {}", client.id)),
+                None,
+            );
+            spawn_command_relay(
+                client.id,
+                rx_relay,
+                sx_command.clone(),
+                shared.topology.clone(),
+                client_update,
+                &shared.relays,
+            );
+            sx_relay
+        } else {
+            sx_command.clone()
+        };
+        command_queues.insert(client.id, Command::ClientCommand(sx_command));
+        let rx_packet = (channels.take_receiver(client.id))
+            .ok_or_else(|| InitConsistencyError::DuplicateId(vec![client.id]))?;
         let (message_sender_tx, message_sender_rx) =
             crossbeam_channel::unbounded::<GuiClientMessage>();
-        let (message_receiver_tx, message_receiver_rx) =
-            crossbeam_channel::unbounded::<ClientGuiMessage>();
-
-        senders[client.id as usize] = Command::ClientCommand(sx_command);
-        packet_send_map[client.id as usize] = Some(sx_packet);
-        let client_type = ClientType::from_code(index_client_types).unwrap();
-        clients_distro[index_client_types] += 1;
-        index_client_types = (index_client_types + 1) % MAX_CLIENT_TYPES;
-        topology[client.id as usize].0 = NodeType::Client(client_type);
+        let (gui_message_tx, gui_message_rx) = crossbeam_channel::unbounded::<GuiClientMessage>();
+        let (client_gui_tx, client_gui_rx) = crossbeam_channel::unbounded::<ClientGuiMessage>();
+        // Put the watchdog, if any, between the client and the GUI.
+        let message_receiver_rx = if gui_watchdog {
+            let (message_receiver_tx, message_receiver_rx) =
+                crossbeam_channel::unbounded::<ClientGuiMessage>();
+            spawn_gui_watchdog(
+                client.id,
+                client_gui_rx,
+                message_receiver_tx,
+                message_receiver_rx.clone(),
+                shared.events.clone(),
+                gui_stall,
+                &shared.relays,
+            );
+            message_receiver_rx
+        } else {
+            client_gui_rx
+        };
+
+        senders[slot(client.id)] = Command::ClientCommand(sx_relay);
+        topology[slot(client.id)].0 = NodeType::Client(client_type);
+        transcript.record(|| TranscriptEntry::Assigned {
+            node: client.id,
+            assignment: format!("{:?}", topology[slot(client.id)].0),
+        });
+        gui_endpoints.insert(
+            client.id,
+            GuiEndpoint {
+                sender: gui_message_tx.clone(),
+                receiver: message_receiver_rx.clone(),
+            },
+        );
         list_gui_channels.push((
             client.id,
             client_type,
-            message_sender_tx,
+            gui_message_tx,
             message_receiver_rx,
         ));
+        spawn_gui_relay(
+            client.id,
+            Arc::clone(&shared.quiesced),
+            gui_message_rx,
+            message_sender_tx,
+            shared.events.clone(),
+            &shared.relays,
+        );
 
         // Spawn client thread.
-        let sender = client_sender.clone();
-        thread::spawn(move || {
-            let mut client = Client::new(
+        let sender = if per_node_events {
+            let (sender, rx_event) = event_channel::<ClientEvent>(event_capacity);
+            let name = transcript.channel(format!("evt client {}", client.id));
+            shared.channels.register(name, rx_event.clone());
+            let event_queue = spawn_event_relay(
                 client.id,
-                rx_packet,
-                rx_command,
-                sender,
-                message_sender_rx,
-                message_receiver_tx,
+                rx_event,
+                client_sender.clone(),
+                shared.events.clone(),
+                NodeEvent::Client,
+                screen.clone(),
+                &shared.relays,
             );
-            client.run();
-        });
+            event_queues.insert(client.id, event_queue);
+            sender
+        } else {
+            client_sender.clone()
+        };
+        let client_id = client.id;
+        transcript.record(|| TranscriptEntry::Spawned { node: client_id });
+        let supervisor = supervisor.clone();
+        let (client_liveness, client_thread) = spawn_joinable_node(
+            node_thread("client", client_id, stack_size),
+            move || {
+                supervisor.run(client_id, || {
+                    let mut client = client_factory(
+                        client_id,
+                        client_type,
+                        rx_packet.clone(),
+                        rx_command.clone(),
+                        sender.clone(),
+                        message_sender_rx.clone(),
+                        client_gui_tx.clone(),
+                    );
+                    client.run();
+                })
+            },
+        );
+        liveness.insert(client_id, client_liveness);
+        threads.insert(client_id, client_thread);
     }
+    lap(&mut timings.clients);
 
     // Spawn server threads.
-    for server in config.server.iter().cloned() {
-        let (sx_command, rx_command) = crossbeam_channel::unbounded::<ServerCommand>();
-        let (sx_packet, rx_packet) = crossbeam_channel::unbounded::<Packet>();
+    let servers = config.server.iter().zip(plan.servers.iter().copied());
+    for (server, (_, server_type)) in servers {
+        let (sx_command, rx_command) = shared.channels.channel::<ServerCommand>(
+            transcript.channel(format!("cmd server {}", server.id)),
+            command_capacity,
+        );
+        let sx_relay = if command_relays {
+            let (sx_relay, rx_relay) = shared.channels.channel::<ServerCommand>(
+                transcript.channel(format!("cmd relay server {}", server.id)),
+                None,
+            );
+            spawn_command_relay(
+                server.id,
+                rx_relay,
+                sx_command.clone(),
+                shared.topology.clone(),
+                server_update,
+                &shared.relays,
+            );
+            sx_relay
+        } else {
+            sx_command.clone()
+        };
+        command_queues.insert(server.id, Command::ServerCommand(sx_command));
+        let rx_packet = (channels.take_receiver(server.id))
+            .ok_or_else(|| InitConsistencyError::DuplicateId(vec![server.id]))?;
 
-        senders[server.id as usize] = Command::ServerCommand(sx_command);
-        packet_send_map[server.id as usize] = Some(sx_packet);
-        let server_type = ServerType::from_code(index_server_types).unwrap();
-        servers_distro[index_server_types] += 1;
-        index_server_types = (index_server_types + 1) % MAX_SERVER_TYPES;
-        topology[server.id as usize].0 = NodeType::Server(server_type);
+        senders[slot(server.id)] = Command::ServerCommand(sx_relay);
+        topology[slot(server.id)].0 = NodeType::Server(server_type);
+        transcript.record(|| TranscriptEntry::Assigned {
+            node: server.id,
+            assignment: format!("{:?}", topology[slot(server.id)].0),
+        });
 
         // Spawn server thread.
-        let sender = server_sender.clone();
-        thread::spawn(move || {
-            let mut server = Server::new(server.id, rx_command, rx_packet, sender, server_type);
-            server.run();
-        });
+        let sender = if per_node_events {
+            let (sender, rx_event) = event_channel::<ServerEvent>(event_capacity);
+            let name = transcript.channel(format!("evt server {}", server.id));
+            shared.channels.register(name, rx_event.clone());
+            let event_queue = spawn_event_relay(
+                server.id,
+                rx_event,
+                server_sender.clone(),
+                shared.events.clone(),
+                NodeEvent::Server,
+                screen.clone(),
+                &shared.relays,
+            );
+            event_queues.insert(server.id, event_queue);
+            sender
+        } else {
+            server_sender.clone()
+        };
+        let server_id = server.id;
+        transcript.record(|| TranscriptEntry::Spawned { node: server_id });
+        let supervisor = supervisor.clone();
+        let (server_liveness, server_thread) = spawn_joinable_node(
+            node_thread("server", server_id, stack_size),
+            move || {
+                supervisor.run(server_id, || {
+                    let mut server = server_factory(
+                        server_id,
+                        server_type,
+                        rx_command.clone(),
+                        rx_packet.clone(),
+                        sender.clone(),
+                    );
+                    server.run();
+                })
+            },
+        );
+        liveness.insert(server_id, server_liveness);
+        threads.insert(server_id, server_thread);
     }
+    lap(&mut timings.servers);
 
-    // Update topology graph for drones.
-    for drone in config.drone.iter().cloned() {
-        for neighbor in &drone.connected_node_ids {
-            topology[drone.id as usize].1.insert(*neighbor as usize);
-            if let Command::DroneCommand(sender) = &senders[drone.id as usize] {
-                let _ = sender.send(DroneCommand::AddSender(
-                    *neighbor,
-                    packet_send_map[*neighbor as usize]
-                        .as_ref()
-                        .unwrap()
-                        .clone(),
-                ));
-            }
-        }
+    // Update topology graph.
+    for (from, to) in &network.edges {
+        topology[slot(*from)].1.insert(slot(*to));
     }
-    // Update topology graph for clients.
-    for client in config.client.iter() {
-        for neighbor in &client.connected_drone_ids {
-            topology[client.id as usize].1.insert(*neighbor as usize);
-            if let Command::ClientCommand(sender) = &senders[client.id as usize] {
-                let _ = sender.send(ClientCommand::AddDrone(
-                    *neighbor,
-                    packet_send_map[*neighbor as usize]
-                        .as_ref()
-                        .unwrap()
-                        .clone(),
-                ));
+    // Check the spawned state against the configuration, before linking the nodes.
+    let consistency = check_consistency(&network, &topology, &senders, &packet_send_map);
+    if let Err(error) = consistency {
+        for command in command_queues.values() {
+            match command {
+                Command::DroneCommand(sender) => drop(sender.send(DroneCommand::Crash)),
+                Command::ClientCommand(sender) => drop(sender.send(ClientCommand::Crash)),
+                Command::ServerCommand(sender) => drop(sender.send(ServerCommand::Crash)),
+                Command::None => {}
             }
         }
+        shared.relays.stop();
+        return Err(error);
     }
-    // Update topology graph for servers.
-    for server in config.server.iter() {
-        for neighbor in &server.connected_drone_ids {
-            topology[server.id as usize].1.insert(*neighbor as usize);
-            if let Command::ServerCommand(sender) = &senders[server.id as usize] {
-                let _ = sender.send(ServerCommand::AddDrone(
-                    *neighbor,
-                    packet_send_map[*neighbor as usize]
-                        .as_ref()
-                        .unwrap()
-                        .clone(),
-                ));
+    // Collect the links whose command could not be delivered, as (node, neighbor).
+    let mut undelivered: Vec<(NodeId, NodeId)> = Vec::new();
+    // Add the neighbors of every node, in the order of the spawn plan; drones built with
+    // their neighbors receive no command.
+    for &InitialCommand { node, neighbor } in &shared.spawn.commands {
+        let neighbor_sender = packet_send_map[slot(neighbor)]
+            .clone()
+            .expect("every node has a packet sender, as checked above");
+        // With the `det-test` feature, every send is a scheduling point of the installed
+        // schedule, if any.
+        let send = || match command_queues.get(&node) {
+            Some(Command::DroneCommand(sender)) => {
+                let command = DroneCommand::AddSender(neighbor, neighbor_sender);
+                shared.journal.record(ISSUER_INIT, node, &command);
+                retry.send(sender, command)
+            }
+            Some(Command::ClientCommand(sender)) => {
+                let command = ClientCommand::AddDrone(neighbor, neighbor_sender);
+                shared.journal.record(ISSUER_INIT, node, &command);
+                retry.send(sender, command)
+            }
+            Some(Command::ServerCommand(sender)) => {
+                let command = ServerCommand::AddDrone(neighbor, neighbor_sender);
+                shared.journal.record(ISSUER_INIT, node, &command);
+                retry.send(sender, command)
             }
+            Some(Command::None) | None => false,
+        };
+        #[cfg(feature = "det-test")]
+        let delivered = crate::sched::channel_op(send);
+        #[cfg(not(feature = "det-test"))]
+        let delivered = send();
+        transcript.record(|| TranscriptEntry::CommandSent { node, neighbor });
+        if !delivered {
+            undelivered.push((node, neighbor));
         }
     }
+    for (node, neighbor) in &undelivered {
+        log::warn!(
+            node = node, neighbor = neighbor;
+            "Could not add neighbor [{}] to node [{}]", neighbor, node
+        );
+    }
+
+    lap(&mut timings.links);
+
+    // Create the handle over the channels of every node, before they are moved to the SC.
+    let nodes = shared
+        .spawn
+        .order
+        .iter()
+        .filter_map(|id| {
+            let entry = NodeEntry::new(
+                *id,
+                senders[slot(*id)].clone(),
+                packet_send_map[slot(*id)].clone()?,
+                liveness.remove(id)?,
+            )
+            .with_neighbors(
+                topology[slot(*id)]
+                    .1
+                    .ones()
+                    .filter_map(NodeIndex::from_position)
+                    .map(NodeIndex::id)
+                    .collect(),
+            );
+            let entry = match delivery_queues.remove(id) {
+                Some(delivery) => entry.with_delivery_queue(delivery),
+                None => entry,
+            };
+            // Without relays, the command queue is the command sender of the node.
+            let entry = match command_queues.remove(id).filter(|_| command_relays) {
+                Some(commands) => entry.with_command_queue(commands),
+                None => entry,
+            };
+            let entry = match event_queues.remove(id) {
+                Some(event_queue) => entry.with_event_queue(event_queue),
+                None => entry,
+            };
+            Some(match gui_endpoints.remove(id) {
+                Some(gui) => entry.with_gui(gui),
+                None => entry,
+            })
+        })
+        .collect();
+    // Subscribe to the topology updates, now that the initial links have been added.
+    let topology_updates = shared.topology.subscribe();
+    shared.transcript = transcript.finish().map(Arc::new);
+    let handle = NetworkHandle::new(nodes, shared);
 
     // Create the initial data structure for the simulation controller.
     let init_data = InitData::new(topology.clone(), senders, packet_send_map);
@@ -274,7 +1264,11 @@ pub fn network_init(config: &Config) -> NetworkInitData {
     let client_channels = ClientChannels::new(client_receiver, client_sender);
     let server_channels = ServerChannels::new(server_receiver, server_sender);
     // Create distribution data.
-    let distros = Distros::new(drones_distro, clients_distro, servers_distro);
+    let distros = Distros::new(
+        plan.drones_distro(),
+        plan.clients_distro(),
+        plan.servers_distro(),
+    );
 
     let (sx_gui_request, rx_gui_request) = crossbeam_channel::unbounded::<GUIRequest>();
     let (sx_gui_response, rx_gui_response) = crossbeam_channel::unbounded::<GUIResponse>();
@@ -294,9 +1288,441 @@ pub fn network_init(config: &Config) -> NetworkInitData {
         sc.run();
     });
 
-    NetworkInitData::new(
+    timings.total = start.elapsed();
+    #[cfg(feature = "telemetry")]
+    crate::telemetry::init_completed(&timings, n_nodes);
+    Ok(NetworkInitData::new(
         topology,
         list_gui_channels,
-        gui_channels
-    )
+        gui_channels,
+        handle,
+        topology_updates,
+        timings,
+        undelivered,
+        threads,
+        supervision,
+        seed,
+    ))
+}
+
+/// Makes a running network converge to a new configuration without a full restart, see
+/// [`NetworkHandle::reload`].
+///
+/// # Parameters
+/// - `data`: The data of the running network.
+/// - `config`: The new configuration.
+///
+/// Returns the nodes and links which were crashed, added or removed, or an error if the
+/// configuration is invalid or declares nodes which are not running.
+pub fn network_reload(data: &NetworkInitData, config: &Config) -> Result<TopologyDiff, String> {
+    let config = ValidatedConfig::new(config.clone())?;
+    data.handle.reload(&config)
+}
+
+/// An inconsistency between a configuration and the state spawned for it, which would
+/// otherwise only show up as a node silently missing from the simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitConsistencyError {
+    /// The node has no command sender.
+    MissingCommandSender(NodeId),
+    /// The node has no packet sender.
+    MissingPacketSender(NodeId),
+    /// The node has a different number of neighbors in the topology graph than declared.
+    NeighborCount {
+        node: NodeId,
+        expected: usize,
+        found: usize,
+    },
+    /// The network has no drones, so its clients and servers could never communicate.
+    NoDrones,
+    /// The node IDs, or neighbor IDs, which are not below `MAX_NODES`, in order.
+    IdOutOfRange(Vec<NodeId>),
+    /// The node IDs declared by more than one node, in order.
+    DuplicateId(Vec<NodeId>),
+    /// The distribution plan does not list the nodes of the configuration, in order.
+    PlanMismatch,
+    /// Some node cannot be assigned an implementation or type, as described.
+    TypeUnavailable(String),
+}
+
+impl fmt::Display for InitConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitConsistencyError::MissingCommandSender(id) => {
+                write!(f, "Node [{}] has no command sender", id)
+            }
+            InitConsistencyError::MissingPacketSender(id) => {
+                write!(f, "Node [{}] has no packet sender", id)
+            }
+            InitConsistencyError::NeighborCount {
+                node,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Node [{}] has {} neighbors in the topology, instead of {}",
+                node, found, expected
+            ),
+            InitConsistencyError::NoDrones => write!(f, "The network has no drones"),
+            InitConsistencyError::IdOutOfRange(ids) => {
+                let ids: Vec<String> = ids.iter().map(|id| format!("[{}]", id)).collect();
+                write!(f, "Node IDs {} are not below {}", ids.join(", "), MAX_NODES)
+            }
+            InitConsistencyError::DuplicateId(ids) => {
+                let ids: Vec<String> = ids.iter().map(|id| format!("[{}]", id)).collect();
+                write!(f, "Node IDs {} are declared more than once", ids.join(", "))
+            }
+            InitConsistencyError::PlanMismatch => {
+                write!(f, "The distribution plan does not match the configuration")
+            }
+            InitConsistencyError::TypeUnavailable(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for InitConsistencyError {}
+
+/// Checks that every node of a topology has a command sender, a packet sender and as many
+/// neighbors in the topology graph as it declares.
+///
+/// # Parameters
+/// - `network`: The topology of the configuration.
+/// - `topology`: The topology graph, indexed by node ID.
+/// - `senders`: The command senders, indexed by node ID.
+/// - `packet_send_map`: The packet senders, indexed by node ID.
+///
+/// Returns the first inconsistency found, in declaration order.
+fn check_consistency(
+    network: &Topology,
+    topology: &[(NodeType, FixedBitSet)],
+    senders: &[Command],
+    packet_send_map: &[Option<Sender<Packet>>],
+) -> Result<(), InitConsistencyError> {
+    let neighbors = network.neighbors();
+    for node in &network.nodes {
+        let index = slot(node.id);
+        if matches!(senders[index], Command::None) {
+            return Err(InitConsistencyError::MissingCommandSender(node.id));
+        }
+        if packet_send_map[index].is_none() {
+            return Err(InitConsistencyError::MissingPacketSender(node.id));
+        }
+        let expected = neighbors.get(&node.id).map_or(0, Vec::len);
+        let found = topology[index].1.count_ones(..);
+        if found != expected {
+            return Err(InitConsistencyError::NeighborCount {
+                node: node.id,
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the position of a node in the arrays of `InitData`.
+///
+/// # Panics
+/// Panics if the ID is not below `MAX_NODES`, which validated configurations rule out.
+fn slot(id: NodeId) -> usize {
+    NodeIndex::new(id)
+        .expect("validated configurations only have node IDs below MAX_NODES")
+        .get()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crossbeam_channel::{bounded, unbounded};
+    use fixedbitset::FixedBitSet;
+    use rust_roveri_api::{Command, NodeType, MAX_NODES};
+    use wg_2024::{config::Config, packet::Packet};
+
+    use crate::distribution::DistributionPlan;
+    use crate::examples;
+    use crate::handle::{ShutdownPolicy, ShutdownStage, TeardownOrder};
+    use crate::init::{
+        check_consistency, network_init, network_init_seeded, network_init_validated,
+        network_init_with_options, try_network_init_with_options, try_network_init_with_plan,
+        InitConsistencyError, InitMode, InitOptions, RetryPolicy,
+    };
+    use crate::model::Topology;
+    use crate::stub::{echo_server_factory, ping_client_factory};
+    use crate::transcript::assert_transcripts_equal;
+    use crate::validate::{network_validate_str, ValidatedConfig};
+
+    #[test]
+    fn test_check_consistency() {
+        let config: Config = toml::from_str(
+            r#"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 3]
+            pdr = 0.1
+
+            [[client]]
+            id = 2
+            connected_drone_ids = [1]
+
+            [[server]]
+            id = 3
+            connected_drone_ids = [1]
+            "#,
+        )
+        .unwrap();
+        let network = Topology::from(&config);
+        let mut topology: Vec<(NodeType, FixedBitSet)> =
+            vec![(NodeType::None, FixedBitSet::with_capacity(MAX_NODES)); MAX_NODES];
+        let mut senders: Vec<Command> = vec![Command::None; MAX_NODES];
+        let mut packet_send_map: Vec<_> = vec![None; MAX_NODES];
+        let check = |topology: &[_], senders: &[_], packet_send_map: &[_]| {
+            check_consistency(&network, topology, senders, packet_send_map)
+        };
+
+        assert_eq!(
+            check(&topology, &senders, &packet_send_map),
+            Err(InitConsistencyError::MissingCommandSender(1))
+        );
+        senders[1] = Command::DroneCommand(unbounded().0);
+        senders[2] = Command::ClientCommand(unbounded().0);
+        senders[3] = Command::ServerCommand(unbounded().0);
+        assert_eq!(
+            check(&topology, &senders, &packet_send_map),
+            Err(InitConsistencyError::MissingPacketSender(1))
+        );
+        packet_send_map[1] = Some(unbounded::<Packet>().0);
+        packet_send_map[2] = Some(unbounded::<Packet>().0);
+        packet_send_map[3] = Some(unbounded::<Packet>().0);
+        topology[1].1.insert(2);
+        topology[1].1.insert(3);
+        assert_eq!(
+            check(&topology, &senders, &packet_send_map),
+            Err(InitConsistencyError::NeighborCount {
+                node: 2,
+                expected: 1,
+                found: 0
+            })
+        );
+        topology[2].1.insert(1);
+        topology[3].1.insert(1);
+        assert_eq!(check(&topology, &senders, &packet_send_map), Ok(()));
+    }
+
+    #[test]
+    fn test_init_no_drones() {
+        let config = Config {
+            drone: vec![],
+            client: vec![],
+            server: vec![],
+        };
+        let result = try_network_init_with_options(&config, &InitOptions::default());
+        assert_eq!(result.err(), Some(InitConsistencyError::NoDrones));
+    }
+
+    #[test]
+    fn test_init_duplicate_id() {
+        let mut config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let mut duplicate = config.drone[1].clone();
+        duplicate.connected_node_ids.clear();
+        config.drone.push(duplicate);
+        let result = try_network_init_with_options(&config, &InitOptions::default());
+        assert_eq!(result.err(), Some(InitConsistencyError::DuplicateId(vec![2])));
+    }
+
+    #[test]
+    fn test_init_plan_mismatch() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let mut plan = DistributionPlan::new(&config);
+        plan.drones.pop();
+        let result = try_network_init_with_plan(&config, &plan, &InitOptions::default());
+        assert_eq!(result.err(), Some(InitConsistencyError::PlanMismatch));
+    }
+
+    #[test]
+    fn test_init_validated() {
+        use rust_roveri_api::DroneImpl;
+        use std::{env, fs};
+
+        let path = env::temp_dir().join(format!("init_validated_{}.toml", std::process::id()));
+        let config_data = examples::get("star10").unwrap().replacen("pdr", "impl = 2\npdr", 1);
+        fs::write(&path, config_data).unwrap();
+        let config = ValidatedConfig::from_file(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        let data = network_init_validated(&config.unwrap(), &InitOptions::default());
+        assert_eq!(data.handle.plan().drones[0], (1, DroneImpl::from_code(2).unwrap()));
+        let report = data.shutdown(ShutdownPolicy::default());
+        assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+    }
+
+    #[test]
+    fn test_init_seeded() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let first = network_init_seeded(&config, 7);
+        let second = network_init_seeded(&config, 7);
+        assert_eq!((first.seed, second.seed), (Some(7), Some(7)));
+        assert_eq!(first.handle.plan(), second.handle.plan());
+        assert_eq!(
+            first.handle.plan(),
+            &DistributionPlan::try_seeded(&config, 7).unwrap()
+        );
+        assert_transcripts_equal(
+            first.handle.transcript().unwrap(),
+            second.handle.transcript().unwrap(),
+        );
+        for data in [first, second] {
+            let report = data.shutdown(ShutdownPolicy::default());
+            assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_shutdown_joins_threads() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let options = InitOptions {
+            mode: InitMode::Prebuilt,
+            client_factory: ping_client_factory,
+            server_factory: echo_server_factory,
+            ..InitOptions::default()
+        };
+        let data = network_init_with_options(&config, &options);
+        let n_nodes = config.drone.len() + config.client.len() + config.server.len();
+        assert_eq!(data.threads.ids().len(), n_nodes);
+
+        // A taken handle is left to the caller.
+        let id = config.drone[0].id;
+        let thread = data.threads.take(id).unwrap();
+        let report = data.clone().shutdown(ShutdownPolicy::default());
+        assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+        assert!(data.threads.ids().is_empty());
+        assert!(thread.join().is_ok());
+    }
+
+    #[test]
+    fn test_per_node_events() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let n_nodes = config.drone.len() + config.client.len() + config.server.len();
+        let has_channel = |data: &crate::init::NetworkInitData, name: &str| {
+            (data.handle.channels().iter()).any(|channel| channel.name == name)
+        };
+
+        // By default, the nodes of a class share the channel of the simulation controller.
+        let data = network_init(&config);
+        assert!(data.handle.event_backlog().is_empty());
+        assert!(has_channel(&data, "evt drones"));
+        data.shutdown(ShutdownPolicy::default());
+
+        let options = InitOptions {
+            per_node_events: true,
+            event_capacity: Some(8),
+            ..InitOptions::default()
+        };
+        let data = network_init_with_options(&config, &options);
+        let backlog = data.handle.event_backlog();
+        assert_eq!(backlog.len(), n_nodes);
+        assert!(backlog.iter().all(|node| node.capacity == Some(8)));
+        assert!(!has_channel(&data, "evt drones"));
+        data.shutdown(ShutdownPolicy::default());
+    }
+
+    #[test]
+    fn test_packet_taps() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let n_nodes = config.drone.len() + config.client.len() + config.server.len();
+
+        // By default, the packets reach the queues of the nodes directly.
+        let data = network_init(&config);
+        assert!(data.handle.tap_stats().is_empty());
+        data.shutdown(ShutdownPolicy::default());
+
+        let options = InitOptions {
+            packet_taps: true,
+            ..InitOptions::default()
+        };
+        let data = network_init_with_options(&config, &options);
+        assert_eq!(data.handle.tap_stats().len(), n_nodes);
+        // The shutdown stops and joins the taps, although the senders of the simulation
+        // controller are still alive.
+        let report = data.shutdown(ShutdownPolicy::default());
+        assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+    }
+
+    #[test]
+    fn test_command_relays() {
+        use crate::topology::TopologyUpdate;
+
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let n_nodes = config.drone.len() + config.client.len() + config.server.len();
+        let relayed = |data: &crate::init::NetworkInitData| {
+            (data.handle.channels().iter())
+                .filter(|channel| channel.name.starts_with("cmd relay"))
+                .count()
+        };
+
+        // By default, no relay is spawned, and the handle publishes the updates of its own
+        // commands.
+        let data = network_init(&config);
+        assert_eq!(relayed(&data), 0);
+        let updates = data.handle.topology_updates();
+        let id = config.drone[0].id;
+        assert!(data.handle.crash(id));
+        let crashed = TopologyUpdate::Crashed { node: id };
+        assert!(updates.try_iter().any(|update| update == crashed));
+        data.shutdown(ShutdownPolicy::default());
+
+        let options = InitOptions {
+            command_relays: true,
+            ..InitOptions::default()
+        };
+        let data = network_init_with_options(&config, &options);
+        assert_eq!(relayed(&data), n_nodes);
+        // The shutdown stops and joins the relays, although the senders of the simulation
+        // controller are still alive.
+        let report = data.shutdown(ShutdownPolicy::default());
+        assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+    }
+
+    #[test]
+    fn test_teardown() {
+        use wg_2024::packet::NodeType;
+
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let data = network_init(&config);
+        let report = data.teardown(TeardownOrder::default());
+        let node_types: Vec<NodeType> = report.stages.iter().map(|s| s.node_type).collect();
+        assert_eq!(node_types, [NodeType::Client, NodeType::Server, NodeType::Drone]);
+        assert_eq!(report.stages[2].nodes.len(), config.drone.len());
+        assert!(report.abandoned().is_empty());
+        assert!(data.threads.ids().is_empty());
+
+        // The crash commands follow the stages.
+        let crashed: Vec<u8> = (data.handle.command_log().iter())
+            .filter(|record| record.issuer == "teardown" && record.command.contains("Crash"))
+            .map(|record| record.target)
+            .collect();
+        let expected: Vec<u8> = (report.stages.iter())
+            .flat_map(|stage| stage.nodes.iter().map(|(id, _)| *id))
+            .collect();
+        assert_eq!(crashed, expected);
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let retry = RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(5),
+        };
+        let (sender, receiver) = bounded(1);
+        assert!(retry.send(&sender, 1));
+
+        let start = Instant::now();
+        assert!(!retry.send(&sender, 2));
+        assert!(start.elapsed() >= Duration::from_millis(15));
+
+        assert_eq!(receiver.recv(), Ok(1));
+        assert!(retry.send(&sender, 3));
+        drop(receiver);
+        assert!(!retry.send(&sender, 4));
+    }
 }
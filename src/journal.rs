@@ -0,0 +1,126 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+use wg_2024::network::NodeId;
+
+/// Maximum number of commands kept in the journal; older commands are discarded first.
+pub const COMMAND_LOG_CAPACITY: usize = 1024;
+
+/// Issuer tag of the commands sent by [`crate::init::network_init`] to set up the links.
+pub const ISSUER_INIT: &str = "network_init";
+
+/// Issuer tag of the commands sent by [`crate::handle::NetworkHandle`] methods.
+pub const ISSUER_HANDLE: &str = "handle";
+
+/// Issuer tag of the commands sent by [`crate::handle::NetworkHandle::drain`].
+pub const ISSUER_DRAIN: &str = "drain";
+
+/// Issuer tag of the commands sent by [`crate::handle::NetworkHandle::shutdown`].
+pub const ISSUER_SHUTDOWN: &str = "shutdown";
+
+/// Issuer tag of the commands sent by [`crate::handle::NetworkHandle::teardown`].
+pub const ISSUER_TEARDOWN: &str = "teardown";
+
+/// Issuer tag of the commands sent by [`crate::scenario::Scenario::run`].
+pub const ISSUER_SCENARIO: &str = "scenario";
+
+/// A command sent to a node through the senders of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandRecord {
+    /// When the command was sent.
+    pub at: SystemTime,
+    /// The tag of the component which sent the command.
+    pub issuer: &'static str,
+    /// The ID of the node receiving the command.
+    pub target: NodeId,
+    /// The debug representation of the command.
+    pub command: String,
+    /// The fingerprint of the configuration the network was running when the command was
+    /// sent, see [`crate::validate::ValidatedConfig::fingerprint`].
+    pub base: u64,
+}
+
+/// Ring buffer of the last [`COMMAND_LOG_CAPACITY`] commands sent to the nodes.
+///
+/// Clones share the same buffer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommandJournal {
+    records: Arc<Mutex<VecDeque<CommandRecord>>>,
+    /// The fingerprint of the configuration the network is running, tagging every command.
+    base: Arc<AtomicU64>,
+}
+
+impl CommandJournal {
+    /// Returns an empty journal, tagging the commands with the given fingerprint.
+    pub(crate) fn new(base: u64) -> Self {
+        Self {
+            records: Arc::default(),
+            base: Arc::new(AtomicU64::new(base)),
+        }
+    }
+
+    /// Returns the fingerprint tagging the commands.
+    pub(crate) fn base(&self) -> u64 {
+        self.base.load(Ordering::SeqCst)
+    }
+
+    /// Tags the commands recorded from now on with the fingerprint of another configuration,
+    /// e.g. after the network was reloaded with it.
+    pub(crate) fn rebase(&self, base: u64) {
+        self.base.store(base, Ordering::SeqCst);
+    }
+
+    /// Records a command, discarding the oldest one if the journal is full.
+    ///
+    /// # Parameters
+    /// - `issuer`: The tag of the component sending the command.
+    /// - `target`: The ID of the node receiving the command.
+    /// - `command`: The command.
+    pub(crate) fn record(&self, issuer: &'static str, target: NodeId, command: &impl Debug) {
+        let record = CommandRecord {
+            at: SystemTime::now(),
+            issuer,
+            target,
+            command: format!("{:?}", command),
+            base: self.base(),
+        };
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        if records.len() == COMMAND_LOG_CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Returns the recorded commands, from the oldest to the newest.
+    pub(crate) fn records(&self) -> Vec<CommandRecord> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::journal::{CommandJournal, COMMAND_LOG_CAPACITY, ISSUER_HANDLE};
+
+    #[test]
+    fn test_journal_ring_buffer() {
+        let journal = CommandJournal::new(42);
+        for index in 0..COMMAND_LOG_CAPACITY + 2 {
+            journal.record(ISSUER_HANDLE, 7, &index);
+        }
+
+        let records = journal.records();
+        assert_eq!(records.len(), COMMAND_LOG_CAPACITY);
+        assert_eq!(records[0].command, "2");
+        assert_eq!(records[0].issuer, ISSUER_HANDLE);
+        assert_eq!(records[0].target, 7);
+        assert_eq!(records[0].base, 42);
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::Receiver;
+use wg_2024::{
+    network::{NodeId, SourceRoutingHeader},
+    packet::{Ack, Packet, PacketType},
+};
+
+use crate::handle::NetworkHandle;
+use crate::tap::TappedPacket;
+
+/// First session ID used by the probes, chosen far above the IDs used by the clients and by
+/// the discovery harness so that the probes are not mistaken for other traffic.
+pub const PROBE_SESSION_BASE: u64 = 3 << 62;
+
+/// Maximum time a probe may take to cross its path before it is considered lost.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Next session ID to be used by a probe, shared by every measurement.
+static NEXT_PROBE_SESSION: AtomicU64 = AtomicU64::new(PROBE_SESSION_BASE);
+
+/// Round-trip times measured along a path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RttStats {
+    /// The path of the probes, from the source to the destination.
+    pub path: Vec<NodeId>,
+    /// The round-trip time of every probe which came back, in order of measurement.
+    pub samples: Vec<Duration>,
+    /// The number of probes which were lost in either direction.
+    pub lost: usize,
+}
+
+impl RttStats {
+    /// Returns the shortest round-trip time, if any probe came back.
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().copied()
+    }
+
+    /// Returns the longest round-trip time, if any probe came back.
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    /// Returns the mean round-trip time, if any probe came back.
+    pub fn mean(&self) -> Option<Duration> {
+        let total: Duration = self.samples.iter().sum();
+        (!self.samples.is_empty()).then(|| total / self.samples.len() as u32)
+    }
+}
+
+/// Measures the round-trip time between two nodes of a live network.
+///
+/// Each sample sends a probe (an ACK with a reserved session ID) from `from` to `to` along the
+/// shortest path through drones, then a second one back along the reversed path; the time of
+/// each leg is measured from the injection of the probe into the first hop to its delivery to
+/// the last one, as observed by the packet tap. The endpoints receive the probes like any
+/// other ACK.
+///
+/// # Parameters
+/// - `handle`: The handle of the network, initialized with
+///   [`InitOptions::packet_taps`](crate::init::InitOptions::packet_taps).
+/// - `from`: The ID of the source node.
+/// - `to`: The ID of the destination node.
+/// - `n_samples`: The number of round trips to measure.
+///
+/// Returns the statistics of the measured path, or `None` if the nodes are not connected
+/// through drones.
+///
+/// # Performance
+/// Each sample takes at most twice the probe timeout of one second.
+pub fn measure_rtt(
+    handle: &NetworkHandle,
+    from: NodeId,
+    to: NodeId,
+    n_samples: usize,
+) -> Option<RttStats> {
+    let path = shortest_path(handle, from, to)?;
+    let reversed: Vec<NodeId> = path.iter().rev().copied().collect();
+    let tap = handle.tap();
+    let mut stats = RttStats {
+        path,
+        ..RttStats::default()
+    };
+    for _ in 0..n_samples {
+        let rtt = send_probe(handle, &tap, &stats.path)
+            .and_then(|out| Some(out + send_probe(handle, &tap, &reversed)?));
+        match rtt {
+            Some(rtt) => stats.samples.push(rtt),
+            None => stats.lost += 1,
+        }
+    }
+    Some(stats)
+}
+
+/// Sends a probe along a path, waiting for its delivery to the last hop.
+///
+/// Returns the time taken by the probe, or `None` if it was lost.
+fn send_probe(
+    handle: &NetworkHandle,
+    tap: &Receiver<TappedPacket>,
+    hops: &[NodeId],
+) -> Option<Duration> {
+    let (Some(first_hop), Some(last_hop)) = (hops.get(1), hops.last()) else {
+        return Some(Duration::ZERO);
+    };
+    let session_id = NEXT_PROBE_SESSION.fetch_add(1, Ordering::Relaxed);
+    let packet = Packet {
+        pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+        routing_header: SourceRoutingHeader {
+            hop_index: 1,
+            hops: hops.to_vec(),
+        },
+        session_id,
+    };
+    let sent = Instant::now();
+    if !handle.send_packet(*first_hop, packet) {
+        return None;
+    }
+    let deadline = sent + PROBE_TIMEOUT;
+    loop {
+        let tapped = tap.recv_deadline(deadline).ok()?;
+        if tapped.to == *last_hop && tapped.packet.session_id == session_id {
+            return Some(tapped.at.duration_since(sent));
+        }
+    }
+}
+
+/// Returns the shortest path between two nodes, only going through drones.
+pub(crate) fn shortest_path(
+    handle: &NetworkHandle,
+    from: NodeId,
+    to: NodeId,
+) -> Option<Vec<NodeId>> {
+    handle.neighbors(from)?;
+    let drones: HashSet<NodeId> = handle.plan().drones.iter().map(|(id, _)| *id).collect();
+    let mut previous: HashMap<NodeId, NodeId> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    let mut visited = HashSet::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut path = vec![to];
+            while let Some(prev) = previous.get(path.last()?) {
+                path.push(*prev);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if node != from && !drones.contains(&node) {
+            continue;
+        }
+        for neighbor in handle.neighbors(node).unwrap_or_default() {
+            if visited.insert(*neighbor) {
+                previous.insert(*neighbor, node);
+                queue.push_back(*neighbor);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::latency::RttStats;
+
+    #[test]
+    fn test_rtt_stats() {
+        let stats = RttStats {
+            path: vec![10, 1, 20],
+            samples: vec![
+                Duration::from_millis(4),
+                Duration::from_millis(2),
+                Duration::from_millis(6),
+            ],
+            lost: 1,
+        };
+
+        assert_eq!(stats.min(), Some(Duration::from_millis(2)));
+        assert_eq!(stats.max(), Some(Duration::from_millis(6)));
+        assert_eq!(stats.mean(), Some(Duration::from_millis(4)));
+        assert_eq!(RttStats::default().mean(), None);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use wg_2024::{
+    config::{Client, Config, Drone, Server},
+    network::NodeId,
+};
+
+use crate::analysis::{Link, TopologyDiff};
+use crate::model::Topology;
+use crate::validate::{check_config, ValidationError};
+
+/// Attribute of a [`Topology`] node holding the file the node was defined in.
+pub const SOURCE_ATTRIBUTE: &str = "source";
+/// Attribute of a [`Topology`] node holding the layer the node was defined in.
+pub const LAYER_ATTRIBUTE: &str = "layer";
+
+/// Where the definition of a node came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// The file defining the node.
+    pub file: PathBuf,
+    /// The index of the file among the layers, `0` being the base configuration.
+    pub layer: usize,
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (layer {})", self.file.display(), self.layer)
+    }
+}
+
+/// The nodes defined by a single layer; every section is optional.
+#[derive(Deserialize)]
+struct Layer {
+    #[serde(default)]
+    drone: Vec<Drone>,
+    #[serde(default)]
+    client: Vec<Client>,
+    #[serde(default)]
+    server: Vec<Server>,
+}
+
+/// A configuration assembled from a base file and override layers, remembering where every
+/// node was defined.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    config: Config,
+    provenance: BTreeMap<NodeId, Provenance>,
+}
+
+impl LayeredConfig {
+    /// Reads a base configuration file and the files overriding it.
+    ///
+    /// Every file lists some nodes, with the same format as a configuration file, but any
+    /// section may be missing. A node defined by a later layer replaces the node with the same
+    /// ID, whatever its type, keeping its position in the configuration; new nodes are
+    /// appended.
+    ///
+    /// # Parameters
+    /// - `files`: The base configuration file, followed by the overrides, in order.
+    ///
+    /// Returns the assembled configuration, which is not validated, or an error naming the
+    /// file which is unreadable or malformed.
+    pub fn load(files: &[&Path]) -> Result<Self, String> {
+        let mut layered = Self {
+            config: Config {
+                drone: Vec::new(),
+                client: Vec::new(),
+                server: Vec::new(),
+            },
+            provenance: BTreeMap::new(),
+        };
+        for (layer, file) in files.iter().enumerate() {
+            let data = fs::read_to_string(file)
+                .map_err(|_| format!("Unable to read configuration file {}", file.display()))?;
+            let nodes: Layer = toml::from_str(&data)
+                .map_err(|e| format!("Invalid configuration file {}: {}", file.display(), e))?;
+            let provenance = Provenance {
+                file: file.to_path_buf(),
+                layer,
+            };
+            layered.apply(nodes, provenance);
+        }
+        Ok(layered)
+    }
+
+    /// Merges the nodes of a layer into the configuration.
+    fn apply(&mut self, layer: Layer, provenance: Provenance) {
+        let config = &mut self.config;
+        for drone in layer.drone {
+            config.client.retain(|other| other.id != drone.id);
+            config.server.retain(|other| other.id != drone.id);
+            self.provenance.insert(drone.id, provenance.clone());
+            match config.drone.iter_mut().find(|other| other.id == drone.id) {
+                Some(other) => *other = drone,
+                None => config.drone.push(drone),
+            }
+        }
+        for client in layer.client {
+            config.drone.retain(|other| other.id != client.id);
+            config.server.retain(|other| other.id != client.id);
+            self.provenance.insert(client.id, provenance.clone());
+            match config.client.iter_mut().find(|other| other.id == client.id) {
+                Some(other) => *other = client,
+                None => config.client.push(client),
+            }
+        }
+        for server in layer.server {
+            config.drone.retain(|other| other.id != server.id);
+            config.client.retain(|other| other.id != server.id);
+            self.provenance.insert(server.id, provenance.clone());
+            match config.server.iter_mut().find(|other| other.id == server.id) {
+                Some(other) => *other = server,
+                None => config.server.push(server),
+            }
+        }
+    }
+
+    /// Returns the assembled configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns where the node with the given ID was last defined, if it exists.
+    pub fn provenance(&self, id: NodeId) -> Option<&Provenance> {
+        self.provenance.get(&id)
+    }
+
+    /// Validates the assembled configuration.
+    ///
+    /// Returns an error if the checks are not passed, whose message is followed by where every
+    /// node it references, as `[id]`, was defined.
+    pub fn check(&self) -> Result<(), ValidationError> {
+        check_config(&self.config).map_err(|error| {
+            let message = self.annotate(&error.message, &referenced_ids(&error.message));
+            ValidationError::new(error.code, message)
+        })
+    }
+
+    /// Returns the topology of the assembled configuration, where every node has the
+    /// [`SOURCE_ATTRIBUTE`] and [`LAYER_ATTRIBUTE`] attributes.
+    pub fn topology(&self) -> Topology {
+        let mut topology = Topology::from(&self.config);
+        for node in &mut topology.nodes {
+            if let Some(provenance) = self.provenance.get(&node.id) {
+                let file = provenance.file.display().to_string();
+                node.attributes.insert(SOURCE_ATTRIBUTE.to_string(), file);
+                let layer = provenance.layer.to_string();
+                node.attributes.insert(LAYER_ATTRIBUTE.to_string(), layer);
+            }
+        }
+        topology
+    }
+
+    /// Describes the differences between the assembled configuration and an observed topology,
+    /// one per line, with where the nodes involved were defined.
+    ///
+    /// # Parameters
+    /// - `diff`: The differences, with the assembled configuration as the expected topology.
+    pub fn describe_diff(&self, diff: &TopologyDiff) -> Vec<String> {
+        let links = |kind: &str, links: &BTreeSet<Link>| {
+            links
+                .iter()
+                .map(|&(a, b)| self.annotate(&format!("{} link [{}]-[{}]", kind, a, b), &[a, b]))
+                .collect::<Vec<_>>()
+        };
+        let nodes = |kind: &str, nodes: &BTreeSet<NodeId>| {
+            nodes
+                .iter()
+                .map(|&id| self.annotate(&format!("{} node [{}]", kind, id), &[id]))
+                .collect::<Vec<_>>()
+        };
+        let mut lines = links("missing", &diff.missing_links);
+        lines.extend(links("unexpected", &diff.unexpected_links));
+        lines.extend(nodes("missing", &diff.missing_nodes));
+        lines.extend(nodes("unexpected", &diff.unexpected_nodes));
+        lines
+    }
+
+    /// Appends to a message where the given nodes were defined, skipping unknown ones.
+    fn annotate(&self, message: &str, ids: &[NodeId]) -> String {
+        let sources: Vec<String> = ids
+            .iter()
+            .filter_map(|id| Some(format!("[{}] from {}", id, self.provenance.get(id)?)))
+            .collect();
+        if sources.is_empty() {
+            message.to_string()
+        } else {
+            format!("{} ({})", message, sources.join(", "))
+        }
+    }
+}
+
+/// Returns the distinct node IDs referenced by a message, written as `[id]`, in order.
+fn referenced_ids(message: &str) -> Vec<NodeId> {
+    let mut ids = Vec::new();
+    for part in message.split('[').skip(1) {
+        let Some((id, _)) = part.split_once(']') else {
+            continue;
+        };
+        if let Ok(id) = id.trim().parse() {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::analysis::TopologyDiff;
+    use crate::layers::{LayeredConfig, LAYER_ATTRIBUTE, SOURCE_ATTRIBUTE};
+    use crate::validate::ErrorCode;
+
+    const BASE: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.1
+
+        [[drone]]
+        id = 4
+        connected_node_ids = [1, 3]
+        pdr = 0.1
+
+        [[client]]
+        id = 2
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 3
+        connected_drone_ids = [1, 4]
+    "#;
+
+    const OVERRIDE: &str = r#"
+        [[drone]]
+        id = 4
+        connected_node_ids = [1, 3]
+        pdr = 1.5
+    "#;
+
+    #[test]
+    fn test_layered_config() {
+        let dir = env::temp_dir();
+        let base = dir.join(format!("layers_base_{}.toml", std::process::id()));
+        let layer = dir.join(format!("layers_override_{}.toml", std::process::id()));
+        fs::write(&base, BASE).unwrap();
+        fs::write(&layer, OVERRIDE).unwrap();
+        let layered = LayeredConfig::load(&[&base, &layer]);
+        fs::remove_file(&base).unwrap();
+        fs::remove_file(&layer).unwrap();
+        let layered = layered.unwrap();
+
+        assert_eq!(layered.config().drone.len(), 2);
+        assert_eq!(layered.config().drone[1].pdr, 1.5);
+        assert_eq!(layered.provenance(1).unwrap().layer, 0);
+        assert_eq!(layered.provenance(4).unwrap().file, layer);
+
+        let error = layered.check().unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidPdr);
+        assert!(error.message.contains("[4] from"), "{}", error.message);
+        assert!(error.message.contains("(layer 1)"), "{}", error.message);
+
+        let topology = layered.topology();
+        let attributes = &topology.node(4).unwrap().attributes;
+        assert_eq!(attributes[LAYER_ATTRIBUTE], "1");
+        assert_eq!(attributes[SOURCE_ATTRIBUTE], layer.display().to_string());
+
+        let mut diff = TopologyDiff::default();
+        diff.missing_links.insert((1, 4));
+        let lines = layered.describe_diff(&diff);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("missing link [1]-[4] ([1] from"));
+    }
+}
@@ -9,19 +9,196 @@
 //!     - Each drone, client, and server has valid parameters (e.g., proper packet drop rate, no self-loops,
 //!       and no duplicate neighbor entries).
 //!     - There are no duplicate node IDs across the entire network.
-//!     - Every client and server is connected only to drones.
+//!     - Every node ID is below `MAX_NODES`, so that it can index the arrays of [`index::NodeIndex`] positions.
+//!     - The network has at least one drone, and every client and server is connected only to drones.
 //!     - The overall network graph is bidirectional and connected.
 //!
+//!   Nodes may optionally declare planar `x`/`y` coordinates; [`geo::network_validate_geo`] additionally checks
+//!   that linked nodes are close enough to each other and [`geo::link_latencies`] derives per-link latencies.
+//!
+//!   Links may carry attributes, declared by either endpoint and read by [`links::parse_link_attributes`];
+//!   endpoints declaring different values are flagged, and resolved by a [`links::LinkConflictPolicy`].
+//!   [`links::link_parameters`] turns them into the effective drop rate of every link in each direction, from the
+//!   `pdr` attribute of the link or else from the receiving drone, to show what link shaping should apply.
+//!
+//!   Families of related topologies can share one template, with `{{ expr }}` placeholders resolved from a
+//!   `[vars]` table or from caller-supplied variables by [`template::network_validate_template`].
+//!
+//!   Configuration files may declare the version of the format with a top-level `version` key; files of older
+//!   versions are upgraded by the registered [`migrate::Migrations`] before being validated.
+//!
+//!   Non-fatal findings are also emitted through the `log` crate, with the violated rule as structured
+//!   `code` context, so they show up in any logger installed by the embedder.
+//!   [`explain::explain`] describes why each rule exists and how to fix a violation, e.g. for a help panel.
+//!   Tools can run cheap targeted checks, e.g. on a single edited node, with [`validate::validate_scope`].
+//!   [`validate::network_validate_all`] keeps going after the first violation, and reports all of them at once.
+//!   [`validate::validation_report`] also warns about valid but suspicious settings, e.g. a drone dropping every
+//!   fragment, so that a GUI can show errors and warnings together before the simulation is launched.
+//!   [`validate::network_validate_many`] does the same for many files at once, optionally in parallel.
+//!   Configurations merged from several sources may repeat a neighbor; a [`validate::DuplicateEdgePolicy`] can
+//!   drop the duplicates, with or without a warning, instead of rejecting the file.
+//!   The report also summarizes every node, with its degree and how many neighbors it may still gain or lose.
+//!   [`dot::topology_to_dot`] renders a configuration, valid or not, as a Graphviz graph, drawing one-way
+//!   neighbors and unknown nodes in red.
+//!
+//!   A configuration can also be assembled from a base file and override layers by
+//!   [`layers::LayeredConfig::load`], which remembers the file each node came from and reports it in
+//!   validation errors, topology snapshots and diffs.
+//!   Configurations to be merged, or run side by side and bridged, can be checked for colliding node IDs by
+//!   [`federate::check_disjoint_ids`], which suggests remappings to apply with [`federate::remap_ids`].
+//!   [`locate::find_config`] finds the configuration file in the standard locations (an explicit path, the
+//!   `NETINIT_CONFIG` environment variable, the working directory, and the crate), reporting which one it chose.
+//!   A few canonical configurations are embedded in the crate, and returned by name by [`examples::get`]
+//!   (e.g. `star10`), so that tests and documentation do not depend on the working directory.
+//!   The `netinit` binary validates a file or an embedded example, e.g. `netinit validate --example star10`,
+//!   and with `--dot out.dot` also renders its topology with [`dot::topology_to_dot`], even if it is invalid.
+//!
+//!   For repeated runs of huge topologies, [`cache::network_validate_cached`] keeps validated configurations in a
+//!   compact binary cache, skipping parsing and validation while the configuration file is unchanged.
+//!
+//!   With the `remote` feature, configuration files can also be fetched over HTTP(S), optionally verifying
+//!   their SHA-256, by `remote::network_validate_url`.
+//!   With the `signed` feature, `signed::network_validate_signed` rejects configuration files whose detached
+//!   ed25519 signature does not match the given public key.
+//!
 //! - **Initialize the Network:**  
 //!   The function [`network_init`] builds the network topology by:
 //!     - Creating arrays for node types (as `(NodeType, FixedBitSet)`), command channels, and packet send channels.
 //!     - Setting up unbounded channels for drones, clients, and servers.
-//!     - Constructing distribution data for node types (drones, clients, servers).
+//!     - Constructing distribution data for node types (drones, clients, servers), planned ahead of spawning by
+//!       [`distribution::DistributionPlan`] and checkable against project rules with
+//!       [`distribution::verify_distribution`].
+//!       A drone can choose its implementation with an `impl` key, by name or code, kept by
+//!       [`validate::ValidatedConfig::from_file`] and honored by [`init::network_init_validated`].
 //!     - Spawning threads for each node (using functions such as `factory_drone` for drones, and similar
 //!       routines for clients and servers).
 //!     - Updating the topology graph by inserting neighbor edges and sending initial commands to add links.
-//!     - Assembling all of the data into a `NetworkInitData` structure, which is then used by both the simulation
-//!       controller and the GUI.
+//!       With [`init::InitMode::Prebuilt`], drones are instead constructed with the senders towards their neighbors.
+//!       The order of the spawns and initial commands is described by a [`spawn::SpawnPlan`], also returned by
+//!       [`handle::NetworkHandle::spawn_plan`] for debugging startup races.
+//!       When a seed is given, every step is recorded in a [`transcript::Transcript`], and
+//!       [`transcript::assert_transcripts_equal`] proves that two initializations were identical.
+//!       [`init::network_init_seeded`] derives every randomized decision, such as the order in which the
+//!       implementations and types are assigned, from the seed, and records it in the returned data.
+//!       [`validate::check_config_with_plan`] checks the policy on the distribution, e.g. the unused
+//!       implementations, against such a seeded plan.
+//!       Before linking the nodes, the spawned state is checked against the configuration, and
+//!       [`init::try_network_init_with_options`] reports any inconsistency as an [`init::InitConsistencyError`].
+//!       Commands adding the links are retried with backoff while the (optionally bounded) command channel of a
+//!       node is full, per the [`init::RetryPolicy`]; the links left undelivered are logged and returned.
+//!     - Building drones through the factory of the [`init::InitOptions`], e.g. [`stub::null_drone_factory`] for
+//!       topology-only simulations which need no real drone implementation.
+//!       Clients and servers are built by factories as well, e.g. [`stub::ping_client_factory`] and
+//!       [`stub::echo_server_factory`] for end-to-end smoke tests of packets traversing the drones.
+//!     - Assembling all of the data, along with the [`init::InitTimings`] of every phase, into a `NetworkInitData`
+//!       structure, which is then used by both the simulation controller and the GUI.
+//!
+//!   The returned [`handle::NetworkHandle`] exposes the packet queue depths and the observed state of the
+//!   nodes (via [`handle::NetworkHandle::probe`]), and [`handle::NetworkHandle::drain`], which stops new
+//!   traffic and waits for in-flight packets before crashing every node. The client API has no quiesce command,
+//!   so new traffic is stopped by rejecting the GUI messages, each reported with a `GuiMessageRejected` event.
+//!   [`handle::NetworkHandle::shutdown`] escalates from draining to crashing to abandoning the threads of the
+//!   nodes which ignore the crash command, reporting the stage at which every node terminated.
+//!   [`init::NetworkInitData::teardown`] crashes the nodes one type at a time, clients first and drones last by
+//!   default, waiting for the nodes of a type to terminate before crashing the next one.
+//!   A suspected-misbehaving node can be isolated without crashing it by [`handle::NetworkHandle::quarantine`],
+//!   and linked back to its neighbors by [`handle::NetworkHandle::release`].
+//!   Groups of nodes, e.g. [`group::by_zone`] or [`group::by_impl`], are crashed, given a new packet drop rate
+//!   or quarantined at once by the bulk operations of the handle, such as [`handle::NetworkHandle::crash_group`].
+//!   The CPU time and queue memory of every node are sampled by [`handle::NetworkHandle::resource_usage`].
+//!   With the `telemetry` feature, the validation, the phases of the initialization, the node lifecycle and the
+//!   scenario steps are reported as spans and metrics to the sink installed by `telemetry::set_sink`.
+//!   A panic of a node implementation is reported on [`init::NetworkInitData::supervision`], and the node can
+//!   be respawned with its original channels, see [`supervise::SupervisionPolicy`].
+//!   With [`init::InitOptions::gui_watchdog`], a client whose GUI stops receiving its messages is reported
+//!   with a `GuiStalled` event, and its messages can be discarded meanwhile, see [`handle::GuiStall`].
+//!   [`handle::NetworkHandle::send_command_acked`] sends a command and waits until the node takes it from its
+//!   queue, reporting a [`handle::CommandError`] on timeout.
+//!   Every command sent by this crate is recorded, with its issuer, in the journal returned by
+//!   [`handle::NetworkHandle::command_log`].
+//!   Runtime changes to the topology (links, packet drop rates and crashes) are published as
+//!   [`topology::TopologyUpdate`]s, so that the GUI can update its view incrementally; the commands of the
+//!   simulation controller are only observed with [`init::InitOptions::command_relays`].
+//!   They are also recorded with their time in a [`topology::Timeline`], returned by
+//!   [`handle::NetworkHandle::timeline`] and exported as JSON for the GUI to scrub through after the run.
+//!   [`handle::NetworkHandle::drift_report`] compares the live topology with the original configuration.
+//!   The handle remembers the [`validate::ValidatedConfig::fingerprint`] of the configuration it runs, tags every
+//!   journaled command with it, and refuses to report drift or export the live topology as TOML
+//!   ([`handle::NetworkHandle::export_current_toml`]) against the configuration of another run.
+//!   [`init::network_reload`] makes a running network converge to an edited configuration without a restart.
+//!   [`handle::NetworkHandle::mutate`] adds and removes links and nodes in all-or-nothing transactions, applied
+//!   one at a time, so that the GUI and the scenarios can change the topology concurrently.
+//!   [`handle::NetworkHandle::reconcile`] flags the links on which a topology graph, e.g. the one kept by the
+//!   simulation controller, departs from the link commands sent to the nodes.
+//!   [`handle::NetworkHandle::best_path`] answers with the current most reliable path between two nodes on the
+//!   live topology and packet drop rates, for the GUI to overlay suggested routes.
+//!   The events emitted by the nodes can be observed through [`handle::NetworkHandle::subscribe`], and
+//!   asserted on in end-to-end tests with [`expect::Expectations`].
+//!   [`aggregate::aggregate_stream`] coalesces bursts of identical events, e.g. thousands of drops from the same
+//!   drone, into summarized updates, keeping a GUI responsive during stress tests.
+//!   With [`init::InitOptions::per_node_events`], every node emits its events on its own channel, whose
+//!   backlog is reported by [`handle::NetworkHandle::event_backlog`], and the events referencing nodes
+//!   which are not part of the network are flagged, or dropped, with a [`events::SuspiciousEvent`]
+//!   diagnostic, see [`events::SuspiciousEventPolicy`].
+//!   Every channel is tagged with a human-readable name (e.g. `pkt 11` or `cmd drone 7`), and listed with its
+//!   length by [`handle::NetworkHandle::channels`] for profilers and queue-depth dashboards.
+//!   Since drone implementations emit events with subtly different semantics, [`normalize::normalize_stream`]
+//!   maps them into canonical events, reporting the ones that depart from the specification.
+//!   With [`init::InitOptions::packet_taps`], every packet delivered to a node passes through a tap
+//!   ([`handle::NetworkHandle::tap`]), which feeds the online protocol checker of the `conformance` module;
+//!   its violations are published to the subscribers as `ProtocolViolation` events.
+//!   On busy networks the taps can publish a sample of the packets, see [`tap::TapSampling`], with the packets
+//!   around crashes captured in full; [`handle::NetworkHandle::tap_stats`] measures what was skipped.
+//!   [`discovery::record_flood_discovery`] floods the network on behalf of every client and diffs the
+//!   topology discovered by the floods against the configured one.
+//!   Round-trip times between arbitrary nodes can be measured with [`latency::measure_rtt`].
+//!   [`soak::soak`] runs continuous traffic for a while, flagging the queues, or the memory of the process, which
+//!   keep growing, to catch leaky drone implementations.
+//!   Before the real simulation starts, [`selftest::self_test`] pushes a test packet across every configured link,
+//!   producing a [`selftest::LinkTestReport`] which proves that the spawned network matches the topology.
+//!   Run statistics can be exported as CSV, either at the end of a run
+//!   ([`handle::RunSummary::to_csv`]) or periodically ([`stats::CsvAppender`]).
+//!
+//! - **Generate Topologies:**  
+//!   The `generator` module builds random, always-valid configurations (e.g. from a prescribed drone degree
+//!   sequence via [`generator::generate_from_degrees`]), which is useful for stress testing and experiments.
+//!   [`generator::generate_random_topology`] only needs the node counts, an average degree and a PDR range.
+//!   With the `self-check` feature, the generated configurations are validated again as a post-condition,
+//!   panicking on a violation, to catch bugs in the generators during tests.
+//!
+//! - **Analyze Topologies:**  
+//!   The `analysis` module provides structural comparisons between configurations, such as
+//!   [`analysis::is_isomorphic`].
+//!   [`analysis::color_drones`] assigns non-interfering channels to the drones, and
+//!   [`analysis::color_drones_with`] checks that a given number of channels suffices.
+//!   [`analysis::k_best_paths`] lists the most reliable paths between two nodes, weighting every drone by its
+//!   packet drop rate, as an oracle for the source routes chosen by the clients.
+//!   [`analysis::analyze_topology`] finds the cut drones and bridges of the drone subgraph, and the drones whose
+//!   crash would partition clients from servers; the optional strict check
+//!   [`validate::ValidationPolicy::single_points_of_failure`] warns about, or rejects, such topologies.
+//!   [`analysis::topology_report`] measures the diameter, degrees, drone connectivity and disjoint client-server
+//!   paths of a validated topology, to compare candidate topologies quantitatively before running them.
+//!   [`difficulty::difficulty`] scores how hard a topology is to route through (size, redundancy, packet drop
+//!   rates, diameter and bottlenecks), so that the topologies used to grade different groups are comparable.
+//!   [`anonymize::anonymize`] shuffles the node IDs of a topology, preserving its structure, so that it can be
+//!   shared publicly; the returned [`anonymize::IdMapping`] reverses the anonymization.
+//!   Internally, validation, generation, analysis and initialization all work on [`model::Topology`], a model
+//!   of nodes, roles, edges and attributes which converts from and into `Config`.
+//!
+//! - **Run Scenarios:**  
+//!   The `scenario` module bundles the standard crash sequences used for grading (e.g.
+//!   [`scenario::Scenario::crash_highest_degree`]), computed from the analyzed topology and run on a live
+//!   [`handle::NetworkHandle`].
+//!   Drones can be targeted by their centrality (degree, betweenness or closeness), as ranked by
+//!   [`analysis::rank_drones`], e.g. with [`scenario::Scenario::crash_most_central`].
+//!   [`scenario::Scenario::run_with_coverage`] reports which protocol-relevant situations (drops, NACKs, crashes
+//!   during a transfer, route changes mid-flood) the run exercised, see [`conformance::CoverageReport`].
+//!   Scenarios are timed on a virtual clock, whose speed can be changed with
+//!   [`handle::NetworkHandle::set_time_scale`] to slow demos down or speed experiments up.
+//!   [`batch::run_batch`] runs a scenario on the network of every configuration file of a batch, e.g. to
+//!   sweep over many generated topologies, optionally running a few networks at the same time.
+//!   [`sweep::sweep`] runs a scenario, with the same seed, on every variant of a base configuration over a
+//!   grid of parameters (e.g. packet drop rates and numbers of drones), collecting a [`sweep::SweepTable`].
 //!
 //! ## Overview
 //!
@@ -40,6 +217,9 @@
 //!    The `NetworkInitData` is y the simulatithen used bon controller and the GUI to reflect the network as specified
 //!    by the user.
 //!
+//! The [`prelude`] is the only public module of the crate: it re-exports every item named above, so that
+//! downstream crates depend on it instead of the internals of `validate` and `init`.
+//!
 //! ## Performance
 //!
 //! Most validation routines in this crate operate in `O(n + m)` time, where `n` is the number of nodes and `m` is the
@@ -49,5 +229,56 @@ use init::network_init;
 use std::env;
 use validate::network_validate;
 
-pub mod init;
-pub mod validate;
+pub(crate) mod aggregate;
+pub(crate) mod analysis;
+pub(crate) mod anonymize;
+pub(crate) mod batch;
+pub(crate) mod cache;
+pub(crate) mod channels;
+pub(crate) mod clock;
+pub(crate) mod conformance;
+pub(crate) mod difficulty;
+pub(crate) mod discovery;
+pub(crate) mod distribution;
+pub(crate) mod dot;
+pub(crate) mod events;
+pub(crate) mod examples;
+pub(crate) mod expect;
+pub(crate) mod explain;
+pub(crate) mod federate;
+pub(crate) mod generator;
+pub(crate) mod geo;
+pub(crate) mod group;
+pub(crate) mod handle;
+pub(crate) mod index;
+pub(crate) mod init;
+pub(crate) mod journal;
+pub(crate) mod latency;
+pub(crate) mod layers;
+pub(crate) mod links;
+pub(crate) mod locate;
+pub(crate) mod migrate;
+pub(crate) mod model;
+pub(crate) mod normalize;
+pub mod prelude;
+#[cfg(feature = "remote")]
+pub(crate) mod remote;
+pub(crate) mod scenario;
+pub(crate) mod selftest;
+#[cfg(feature = "signed")]
+pub(crate) mod signed;
+#[cfg(feature = "det-test")]
+pub(crate) mod sched;
+pub(crate) mod soak;
+pub(crate) mod spawn;
+pub(crate) mod stats;
+pub(crate) mod stub;
+pub(crate) mod supervise;
+pub(crate) mod sweep;
+pub(crate) mod tap;
+#[cfg(feature = "telemetry")]
+pub(crate) mod telemetry;
+pub(crate) mod template;
+pub(crate) mod topology;
+pub(crate) mod transcript;
+pub(crate) mod validate;
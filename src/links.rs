@@ -0,0 +1,351 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    fmt, fs,
+};
+
+use serde::Deserialize;
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::analysis::{config_links, Link};
+use crate::model::Attributes;
+use crate::validate::{network_validate_str, ErrorCode};
+
+/// Attribute of a link holding its packet drop rate, applied in both directions.
+pub const PDR_ATTRIBUTE: &str = "pdr";
+
+/// Attributes of the links, declared through the optional `links` table of the entry of
+/// either endpoint, e.g. `links = { 2 = { latency_ms = 5 } }`, keyed by link.
+pub type LinkAttributes = BTreeMap<Link, Attributes>;
+
+/// The effective parameters of every link of a configuration, keyed by link.
+pub type LinkTable = BTreeMap<Link, LinkParameters>;
+
+/// The effective parameters of a link, i.e. what shaping the link should apply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkParameters {
+    /// The probability that a packet from the endpoint with the smaller ID to the other one is
+    /// dropped.
+    pub pdr_forward: f32,
+    /// The probability that a packet in the other direction is dropped.
+    pub pdr_backward: f32,
+    /// Whether the drop rates come from the [`PDR_ATTRIBUTE`] of the link; otherwise a packet
+    /// is dropped by the drone receiving it, with the PDR of the drone.
+    pub declared: bool,
+    /// The attributes of the link, once the conflicts are resolved.
+    pub attributes: Attributes,
+}
+
+impl fmt::Display for LinkParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = if self.declared { "link" } else { "drones" };
+        write!(
+            f,
+            "pdr {} / {} ({})",
+            self.pdr_forward, self.pdr_backward, source
+        )?;
+        for (key, value) in &self.attributes {
+            if key != PDR_ATTRIBUTE {
+                write!(f, ", {} = {}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How [`parse_link_attributes`] resolves a link whose endpoints both declare the same
+/// attribute with different values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkConflictPolicy {
+    /// The attributes declared by the first endpoint are kept, and the ones declared by the
+    /// other endpoint are discarded.
+    #[default]
+    FirstWins,
+    /// An inconsistent link is an error.
+    Error,
+    /// The attributes declared by both endpoints are merged, keeping the value declared by the
+    /// first endpoint for a conflicting attribute.
+    Merge,
+}
+
+/// An attribute of a link declared by both its endpoints, with different values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkConflict {
+    /// The link.
+    pub link: Link,
+    /// The name of the attribute.
+    pub key: String,
+    /// The endpoint declared first and its value.
+    pub first: (NodeId, String),
+    /// The other endpoint and its value.
+    pub second: (NodeId, String),
+}
+
+impl fmt::Display for LinkConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The link between [{}] and [{}] has inconsistent `{}`: {} according to [{}], {} according to [{}]",
+            self.link.0,
+            self.link.1,
+            self.key,
+            self.first.1,
+            self.first.0,
+            self.second.1,
+            self.second.0
+        )
+    }
+}
+
+/// The link view of a single node entry; every other key is ignored.
+#[derive(Deserialize)]
+struct LinkNode {
+    id: NodeId,
+    #[serde(default, alias = "connected_drone_ids")]
+    connected_node_ids: Vec<NodeId>,
+    #[serde(default)]
+    links: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+/// The link view of a configuration file.
+#[derive(Deserialize)]
+struct LinkConfig {
+    #[serde(default)]
+    drone: Vec<LinkNode>,
+    #[serde(default)]
+    client: Vec<LinkNode>,
+    #[serde(default)]
+    server: Vec<LinkNode>,
+}
+
+/// Reads and validates a configuration file, together with the attributes of its links.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+/// - `policy`: How inconsistent links are resolved; since the [`PDR_ATTRIBUTE`] applies to
+///   both directions, endpoints declaring different drop rates are resolved like any other
+///   attribute.
+///
+/// Returns the configuration and the effective parameters of every link if the
+/// configuration file provided is valid, an error otherwise.
+pub fn network_validate_links(
+    file_path: &str,
+    policy: LinkConflictPolicy,
+) -> Result<(Config, LinkTable), String> {
+    let config_data = fs::read_to_string(file_path)
+        .map_err(|_| "Unable to read configuration file".to_string())?;
+    let config = network_validate_str(&config_data)?;
+    let (attributes, _) = parse_link_attributes(&config_data, policy)?;
+    let table = link_parameters(&config, &attributes)?;
+    Ok((config, table))
+}
+
+/// Computes the effective parameters of every link of a configuration.
+///
+/// # Parameters
+/// - `config`: The network configuration.
+/// - `attributes`: The attributes of the links, e.g. from [`parse_link_attributes`].
+///
+/// Returns the parameters of every link, or an error if a link declares a
+/// [`PDR_ATTRIBUTE`] which is not a number between 0 and 1.
+///
+/// # Performance
+/// `O(n + m log m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn link_parameters(config: &Config, attributes: &LinkAttributes) -> Result<LinkTable, String> {
+    let drone_pdrs: HashMap<NodeId, f32> = config
+        .drone
+        .iter()
+        .map(|drone| (drone.id, drone.pdr))
+        .collect();
+    let received_pdr = |id: NodeId| drone_pdrs.get(&id).copied().unwrap_or(0.0);
+
+    let mut table = LinkTable::new();
+    for link in config_links(config) {
+        let attributes = attributes.get(&link).cloned().unwrap_or_default();
+        let declared = match attributes.get(PDR_ATTRIBUTE) {
+            Some(value) => Some(
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|pdr| (0.0..=1.0).contains(pdr))
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid PDR for the link between [{}] and [{}]: {}",
+                            link.0, link.1, value
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+        let parameters = LinkParameters {
+            pdr_forward: declared.unwrap_or_else(|| received_pdr(link.1)),
+            pdr_backward: declared.unwrap_or_else(|| received_pdr(link.0)),
+            declared: declared.is_some(),
+            attributes,
+        };
+        table.insert(link, parameters);
+    }
+    Ok(table)
+}
+
+/// Extracts the link attributes from the TOML representation of a configuration.
+///
+/// A link may be described by both its endpoints; an attribute declared by both with
+/// different values is resolved by the policy, the first endpoint being the one declared
+/// first, drones before clients and clients before servers. Unless the policy makes them
+/// errors, the conflicts are also emitted through the `log` crate.
+///
+/// # Parameters
+/// - `config_data`: The contents of a configuration file.
+/// - `policy`: How inconsistent links are resolved.
+///
+/// Returns the attributes of the links which have any, together with the conflicts found, or
+/// an error if a node describes a link it does not declare, or the policy rejects a
+/// conflict.
+pub fn parse_link_attributes(
+    config_data: &str,
+    policy: LinkConflictPolicy,
+) -> Result<(LinkAttributes, Vec<LinkConflict>), String> {
+    let view: LinkConfig =
+        toml::from_str(config_data).map_err(|e| format!("Failed to deserialize TOML: {}", e))?;
+
+    let mut attributes = LinkAttributes::new();
+    let mut declared_by: BTreeMap<Link, NodeId> = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    for node in view.drone.iter().chain(&view.client).chain(&view.server) {
+        for (neighbor, values) in &node.links {
+            let neighbor: NodeId = neighbor
+                .parse()
+                .ok()
+                .filter(|id| node.connected_node_ids.contains(id))
+                .ok_or_else(|| {
+                    format!(
+                        "Node [{}] describes the link towards [{}], which it does not declare",
+                        node.id, neighbor
+                    )
+                })?;
+            let link = (node.id.min(neighbor), node.id.max(neighbor));
+            let values: Attributes = values
+                .iter()
+                .map(|(key, value)| (key.clone(), attribute_value(value)))
+                .collect();
+
+            let first = match declared_by.entry(link) {
+                Entry::Vacant(entry) => {
+                    entry.insert(node.id);
+                    attributes.insert(link, values);
+                    continue;
+                }
+                Entry::Occupied(entry) => *entry.get(),
+            };
+            let kept = attributes.get_mut(&link).unwrap();
+            for (key, value) in values {
+                match kept.get(&key) {
+                    Some(other) if *other != value => {
+                        let conflict = LinkConflict {
+                            link,
+                            key,
+                            first: (first, other.clone()),
+                            second: (node.id, value),
+                        };
+                        if policy == LinkConflictPolicy::Error {
+                            return Err(conflict.to_string());
+                        }
+                        log::warn!(code:? = ErrorCode::InconsistentLink; "{}", conflict);
+                        conflicts.push(conflict);
+                    }
+                    Some(_) => {}
+                    None if policy == LinkConflictPolicy::Merge => {
+                        kept.insert(key, value);
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+    Ok((attributes, conflicts))
+}
+
+/// Returns the value of an attribute as a string, without quotes for strings.
+fn attribute_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::links::{link_parameters, parse_link_attributes, LinkConflictPolicy};
+    use crate::validate::network_validate_str;
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.1
+        links = { 2 = { latency_ms = 5, medium = "radio" }, 3 = { latency_ms = 1 } }
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 3, 4]
+        pdr = 0.1
+        links = { 1 = { latency_ms = 7, band = "5GHz" } }
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1, 2]
+        links = { 1 = { latency_ms = 1 } }
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [1, 2]
+    "#;
+
+    #[test]
+    fn test_link_attributes() {
+        let (attributes, conflicts) =
+            parse_link_attributes(CONFIG, LinkConflictPolicy::FirstWins).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].link, (1, 2));
+        assert_eq!(conflicts[0].first, (1, "5".to_string()));
+        assert_eq!(conflicts[0].second, (2, "7".to_string()));
+        assert_eq!(attributes[&(1, 2)]["latency_ms"], "5");
+        assert_eq!(attributes[&(1, 2)]["medium"], "radio");
+        assert!(!attributes[&(1, 2)].contains_key("band"));
+        assert_eq!(attributes[&(1, 3)]["latency_ms"], "1");
+
+        let (attributes, _) = parse_link_attributes(CONFIG, LinkConflictPolicy::Merge).unwrap();
+        assert_eq!(attributes[&(1, 2)]["latency_ms"], "5");
+        assert_eq!(attributes[&(1, 2)]["band"], "5GHz");
+
+        let error = parse_link_attributes(CONFIG, LinkConflictPolicy::Error).unwrap_err();
+        assert!(error.contains("inconsistent `latency_ms`"), "{}", error);
+
+        let undeclared = CONFIG.replace("links = { 1 = { latency_ms = 1 } }", "links = { 4 = {} }");
+        assert!(parse_link_attributes(&undeclared, LinkConflictPolicy::Merge).is_err());
+    }
+
+    #[test]
+    fn test_link_parameters() {
+        let config = network_validate_str(CONFIG).unwrap();
+        let with_pdr = CONFIG.replace("band = \"5GHz\"", "pdr = 0.5");
+        let (attributes, _) = parse_link_attributes(&with_pdr, LinkConflictPolicy::Merge).unwrap();
+        let table = link_parameters(&config, &attributes).unwrap();
+        assert_eq!(table.len(), 5);
+        assert!(table[&(1, 2)].declared);
+        assert_eq!(table[&(1, 2)].pdr_forward, 0.5);
+        assert_eq!(table[&(1, 2)].pdr_backward, 0.5);
+        // Without a declared drop rate, the drone receiving the packet drops it.
+        assert!(!table[&(2, 3)].declared);
+        assert_eq!(table[&(2, 3)].pdr_forward, 0.0);
+        assert_eq!(table[&(2, 3)].pdr_backward, 0.1);
+        assert_eq!(
+            table[&(1, 3)].to_string(),
+            "pdr 0 / 0.1 (drones), latency_ms = 1"
+        );
+
+        let invalid = CONFIG.replace("band = \"5GHz\"", "pdr = 1.5");
+        let (attributes, _) = parse_link_attributes(&invalid, LinkConflictPolicy::Merge).unwrap();
+        assert!(link_parameters(&config, &attributes).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    env,
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable holding the path of the configuration file.
+pub const CONFIG_ENV: &str = "NETINIT_CONFIG";
+
+/// The path of the configuration file shipped with the crate, used when no other location
+/// holds a configuration.
+const CRATE_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/config.toml");
+
+/// Where [`find_config`] found the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The path was given explicitly, e.g. as a command line argument.
+    Argument,
+    /// The path was given by the [`CONFIG_ENV`] environment variable.
+    Environment,
+    /// The file is `config.toml` in the working directory.
+    WorkingDir,
+    /// The file is `config/config.toml` in the working directory.
+    ConfigDir,
+    /// The file is the configuration shipped with the crate.
+    Crate,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self {
+            ConfigSource::Argument => "argument",
+            ConfigSource::Environment => CONFIG_ENV,
+            ConfigSource::WorkingDir => "working directory",
+            ConfigSource::ConfigDir => "config directory",
+            ConfigSource::Crate => "crate",
+        };
+        write!(f, "{}", source)
+    }
+}
+
+/// A configuration file found by [`find_config`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLocation {
+    /// The path of the file.
+    pub path: PathBuf,
+    /// Where the file was found.
+    pub source: ConfigSource,
+}
+
+/// Finds the configuration file, independently of the working directory of the caller.
+///
+/// The locations are searched in order: the explicit `arg`, the [`CONFIG_ENV`] environment
+/// variable, `./config.toml`, `./config/config.toml`, and finally the configuration shipped
+/// with the crate. An explicit path, from the argument or the environment, is used even if
+/// the file does not exist, so that a typo is reported instead of silently falling back.
+///
+/// # Parameters
+/// - `arg`: The path given explicitly, e.g. as a command line argument, if any.
+///
+/// Returns the chosen path and where it was found, or an error if no location holds a
+/// configuration.
+pub fn find_config(arg: Option<&Path>) -> Result<ConfigLocation, String> {
+    let dir = env::current_dir().map_err(|_| "Unable to read working directory".to_string())?;
+    locate(arg, env::var_os(CONFIG_ENV), &dir, Path::new(CRATE_CONFIG))
+}
+
+/// Searches the locations described by [`find_config`], given the environment variable, the
+/// working directory and the crate configuration.
+fn locate(
+    arg: Option<&Path>,
+    var: Option<OsString>,
+    dir: &Path,
+    crate_config: &Path,
+) -> Result<ConfigLocation, String> {
+    let explicit = arg
+        .map(|path| (path.to_path_buf(), ConfigSource::Argument))
+        .or_else(|| {
+            var.filter(|var| !var.is_empty())
+                .map(|var| (PathBuf::from(var), ConfigSource::Environment))
+        });
+    if let Some((path, source)) = explicit {
+        return Ok(ConfigLocation { path, source });
+    }
+
+    let candidates = [
+        (dir.join("config.toml"), ConfigSource::WorkingDir),
+        (
+            dir.join("config").join("config.toml"),
+            ConfigSource::ConfigDir,
+        ),
+        (crate_config.to_path_buf(), ConfigSource::Crate),
+    ];
+    candidates
+        .into_iter()
+        .find(|(path, _)| path.is_file())
+        .map(|(path, source)| ConfigLocation { path, source })
+        .ok_or_else(|| format!("No configuration file found in {}", dir.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path};
+
+    use crate::locate::{find_config, locate, ConfigSource, CRATE_CONFIG};
+
+    #[test]
+    fn test_locate() {
+        let dir = env::temp_dir().join(format!("locate_{}", std::process::id()));
+        fs::create_dir_all(dir.join("config")).unwrap();
+        let missing = Path::new("missing.toml");
+
+        let location = locate(Some(missing), Some("env.toml".into()), &dir, missing).unwrap();
+        assert_eq!(location.source, ConfigSource::Argument);
+        let location = locate(None, Some("env.toml".into()), &dir, missing).unwrap();
+        assert_eq!(location.source, ConfigSource::Environment);
+        assert!(locate(None, Some("".into()), &dir, missing).is_err());
+
+        fs::write(dir.join("config").join("config.toml"), "").unwrap();
+        let location = locate(None, None, &dir, missing).unwrap();
+        assert_eq!(location.source, ConfigSource::ConfigDir);
+        fs::write(dir.join("config.toml"), "").unwrap();
+        let location = locate(None, None, &dir, missing).unwrap();
+        assert_eq!(location.path, dir.join("config.toml"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let crate_config = Path::new(CRATE_CONFIG);
+        let location = locate(None, None, &dir, crate_config).unwrap();
+        assert_eq!(location.source, ConfigSource::Crate);
+        assert!(find_config(Some(crate_config)).is_ok());
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use toml::{Table, Value};
+
+/// Name of the key declaring the version of the configuration format.
+const VERSION_KEY: &str = "version";
+
+/// The version of the configuration format read by this crate.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// A function upgrading a configuration, as a TOML table, by one version of the format.
+pub type Migration = fn(&mut Table) -> Result<(), String>;
+
+/// The migrations run on configurations of older versions of the format.
+///
+/// Configurations which do not declare a version are version 1, the format used before the
+/// `version` key was introduced.
+#[derive(Debug, Clone)]
+pub struct Migrations {
+    /// The migration from every version to the next one.
+    steps: BTreeMap<u32, Migration>,
+}
+
+impl Default for Migrations {
+    fn default() -> Self {
+        Self {
+            steps: BTreeMap::from([(1, migrate_v1 as Migration)]),
+        }
+    }
+}
+
+impl Migrations {
+    /// Registers a migration from version `from` to version `from + 1`, replacing the one
+    /// already registered, if any.
+    ///
+    /// # Parameters
+    /// - `from`: The version upgraded by the migration.
+    /// - `migration`: The migration.
+    pub fn register(mut self, from: u32, migration: Migration) -> Self {
+        self.steps.insert(from, migration);
+        self
+    }
+
+    /// Upgrades a configuration to [`CONFIG_VERSION`], then removes its version key.
+    ///
+    /// # Parameters
+    /// - `table`: The configuration, as a TOML table.
+    ///
+    /// Returns an error if the version is invalid, newer than [`CONFIG_VERSION`], or cannot
+    /// be upgraded by the registered migrations.
+    pub fn migrate(&self, table: &mut Table) -> Result<(), String> {
+        let version = match table.remove(VERSION_KEY) {
+            None => 1,
+            Some(Value::Integer(version)) => u32::try_from(version)
+                .ok()
+                .filter(|version| *version > 0)
+                .ok_or_else(|| format!("Invalid configuration version {}", version))?,
+            Some(_) => return Err("The configuration version must be an integer".to_string()),
+        };
+        if version > CONFIG_VERSION {
+            return Err(format!(
+                "Configuration version {} is newer than the supported version {}",
+                version, CONFIG_VERSION
+            ));
+        }
+        for from in version..CONFIG_VERSION {
+            let migration = self
+                .steps
+                .get(&from)
+                .ok_or_else(|| format!("No migration from configuration version {}", from))?;
+            log::info!(from = from, to = from + 1; "Migrating the configuration format");
+            migration(table)
+                .map_err(|e| format!("Failed to migrate configuration version {}: {}", from, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Upgrades a configuration from version 1, whose layout is the same as version 2.
+fn migrate_v1(_table: &mut Table) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use toml::{Table, Value};
+
+    use crate::migrate::Migrations;
+
+    fn rename_pdr(table: &mut Table) -> Result<(), String> {
+        let drones = table
+            .get_mut("drone")
+            .and_then(Value::as_array_mut)
+            .ok_or("missing drones")?;
+        for drone in drones.iter_mut().filter_map(Value::as_table_mut) {
+            if let Some(pdr) = drone.remove("drop_rate") {
+                drone.insert("pdr".to_string(), pdr);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate() {
+        let migrations = Migrations::default().register(1, rename_pdr);
+
+        let mut old: Table = "[[drone]]\ndrop_rate = 0.5".parse().unwrap();
+        migrations.migrate(&mut old).unwrap();
+        assert_eq!(old["drone"][0]["pdr"].as_float(), Some(0.5));
+
+        let mut current: Table = "version = 2\n[[drone]]\ndrop_rate = 0.5".parse().unwrap();
+        migrations.migrate(&mut current).unwrap();
+        assert!(current.get("version").is_none());
+        assert!(current["drone"][0].get("pdr").is_none());
+    }
+
+    #[test]
+    fn test_migrate_unsupported() {
+        let migrations = Migrations::default();
+
+        let mut newer: Table = "version = 3".parse().unwrap();
+        assert!(migrations.migrate(&mut newer).is_err());
+        let mut invalid: Table = "version = \"2\"".parse().unwrap();
+        assert!(migrations.migrate(&mut invalid).is_err());
+        let mut zero: Table = "version = 0".parse().unwrap();
+        assert!(migrations.migrate(&mut zero).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use wg_2024::{
+    config::{Client, Config, Drone, Server},
+    network::NodeId,
+    packet::NodeType,
+};
+
+use crate::analysis::Link;
+
+/// Free-form attributes of a node, e.g. a name or a zone.
+pub type Attributes = BTreeMap<String, String>;
+
+/// The role of a node, with the parameters which only apply to that role.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    /// A drone, forwarding packets with the given packet drop rate.
+    Drone { pdr: f32 },
+    /// A client.
+    Client,
+    /// A server.
+    Server,
+}
+
+impl Role {
+    /// Returns the type of the node, without the parameters of the role.
+    pub fn node_type(&self) -> NodeType {
+        match self {
+            Role::Drone { .. } => NodeType::Drone,
+            Role::Client => NodeType::Client,
+            Role::Server => NodeType::Server,
+        }
+    }
+}
+
+/// A node of a [`Topology`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    /// The ID of the node.
+    pub id: NodeId,
+    /// The role of the node.
+    pub role: Role,
+    /// The attributes of the node, which have no counterpart in [`Config`].
+    pub attributes: Attributes,
+}
+
+/// A network topology, independent of the configuration format.
+///
+/// Nodes are kept in the order in which they were declared, and edges are directed, in the
+/// order in which the neighbors of each node were declared, so that a [`Config`] survives a
+/// round trip unchanged, including any inconsistency the validation must report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Topology {
+    /// The nodes of the topology.
+    pub nodes: Vec<Node>,
+    /// The edges of the topology, from a node to one of its declared neighbors.
+    pub edges: Vec<(NodeId, NodeId)>,
+}
+
+impl Topology {
+    /// Adds a node without attributes.
+    ///
+    /// # Parameters
+    /// - `id`: The ID of the node.
+    /// - `role`: The role of the node.
+    pub fn add_node(&mut self, id: NodeId, role: Role) {
+        self.nodes.push(Node {
+            id,
+            role,
+            attributes: Attributes::new(),
+        });
+    }
+
+    /// Adds an undirected link, as an edge in each direction.
+    ///
+    /// # Parameters
+    /// - `a`: One endpoint of the link.
+    /// - `b`: The other endpoint of the link.
+    pub fn add_link(&mut self, a: NodeId, b: NodeId) {
+        self.edges.push((a, b));
+        self.edges.push((b, a));
+    }
+
+    /// Returns the node with the given ID, if any.
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns the nodes with the given type, in declaration order.
+    pub fn nodes_of_type(&self, node_type: NodeType) -> impl Iterator<Item = &Node> {
+        self.nodes
+            .iter()
+            .filter(move |node| node.role.node_type() == node_type)
+    }
+
+    /// Returns the declared neighbors of every node which has any, in declaration order.
+    ///
+    /// # Performance
+    /// `O(m)`, where `m` is the number of edges.
+    pub fn neighbors(&self) -> HashMap<NodeId, Vec<NodeId>> {
+        let mut neighbors: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for (from, to) in &self.edges {
+            neighbors.entry(*from).or_default().push(*to);
+        }
+        neighbors
+    }
+
+    /// Returns the undirected links of the topology.
+    pub fn links(&self) -> BTreeSet<Link> {
+        self.edges
+            .iter()
+            .map(|(a, b)| (*a.min(b), *a.max(b)))
+            .collect()
+    }
+}
+
+impl From<&Config> for Topology {
+    fn from(config: &Config) -> Self {
+        let mut topology = Topology::default();
+        for drone in &config.drone {
+            topology.add_node(drone.id, Role::Drone { pdr: drone.pdr });
+            let edges = drone.connected_node_ids.iter().map(|id| (drone.id, *id));
+            topology.edges.extend(edges);
+        }
+        for client in &config.client {
+            topology.add_node(client.id, Role::Client);
+            let edges = client.connected_drone_ids.iter().map(|id| (client.id, *id));
+            topology.edges.extend(edges);
+        }
+        for server in &config.server {
+            topology.add_node(server.id, Role::Server);
+            let edges = server.connected_drone_ids.iter().map(|id| (server.id, *id));
+            topology.edges.extend(edges);
+        }
+        topology
+    }
+}
+
+impl TryFrom<&Topology> for Config {
+    type Error = String;
+
+    /// Converts a topology into a configuration, dropping the attributes of the nodes.
+    ///
+    /// Returns an error if two nodes share an ID, or an edge starts from a node which is not
+    /// in the topology, as neither can be represented by a configuration.
+    fn try_from(topology: &Topology) -> Result<Self, Self::Error> {
+        let mut seen = HashSet::new();
+        if let Some(node) = topology.nodes.iter().find(|node| !seen.insert(node.id)) {
+            return Err(format!("Duplicate node ID found: [{}]", node.id));
+        }
+        let mut neighbors = topology.neighbors();
+
+        let mut config = Config {
+            drone: Vec::new(),
+            client: Vec::new(),
+            server: Vec::new(),
+        };
+        for node in &topology.nodes {
+            let ids = neighbors.remove(&node.id).unwrap_or_default();
+            match node.role {
+                Role::Drone { pdr } => config.drone.push(Drone {
+                    id: node.id,
+                    connected_node_ids: ids,
+                    pdr,
+                }),
+                Role::Client => config.client.push(Client {
+                    id: node.id,
+                    connected_drone_ids: ids,
+                }),
+                Role::Server => config.server.push(Server {
+                    id: node.id,
+                    connected_drone_ids: ids,
+                }),
+            }
+        }
+        if let Some(from) = neighbors.keys().min() {
+            return Err(format!(
+                "Node [{}] has neighbors, but does not exist in the topology",
+                from
+            ));
+        }
+        Ok(config)
+    }
+}
+
+impl TryFrom<Topology> for Config {
+    type Error = String;
+
+    fn try_from(topology: Topology) -> Result<Self, Self::Error> {
+        Config::try_from(&topology)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wg_2024::config::Config;
+
+    use crate::model::{Role, Topology};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [3, 2, 4]
+        pdr = 0.1
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 4]
+        pdr = 0.2
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [2, 1]
+    "#;
+
+    #[test]
+    fn test_topology_roundtrip() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let mut topology = Topology::from(&config);
+        assert_eq!(topology.nodes.len(), 4);
+        assert_eq!(topology.node(2).unwrap().role, Role::Drone { pdr: 0.2 });
+        assert_eq!(topology.links().len(), 4);
+
+        for node in &mut topology.nodes {
+            node.attributes
+                .insert("zone".to_string(), "north".to_string());
+        }
+        let converted = Config::try_from(&topology).unwrap();
+        assert_eq!(format!("{:?}", converted), format!("{:?}", config));
+
+        topology.edges.push((9, 1));
+        assert!(Config::try_from(&topology).is_err());
+        topology.edges.pop();
+        topology.add_node(1, Role::Client);
+        assert!(Config::try_from(topology).is_err());
+    }
+}
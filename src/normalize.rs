@@ -0,0 +1,176 @@
+use std::thread;
+
+use crossbeam_channel::Receiver;
+use wg_2024::{
+    controller::DroneEvent,
+    network::NodeId,
+    packet::{Packet, PacketType},
+};
+
+use crate::events::{NodeEvent, ObservedEvent};
+
+/// A drone event with implementation-independent semantics.
+#[derive(Debug, Clone)]
+pub enum CanonicalEvent {
+    /// The drone forwarded a packet.
+    ///
+    /// The routing header of the packet always points at the receiver, which is reported as
+    /// `next_hop` unless the packet is a flood request.
+    Forwarded {
+        node: NodeId,
+        next_hop: Option<NodeId>,
+        packet: Packet,
+    },
+    /// The drone dropped a packet.
+    Dropped { node: NodeId, packet: Packet },
+    /// The drone asked the controller to deliver a packet.
+    Shortcut { node: NodeId, packet: Packet },
+}
+
+/// A way in which a drone event departs from the protocol specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpecViolation {
+    /// A packet other than a fragment was reported as dropped.
+    DroppedNonFragment,
+    /// A fragment or a flood request was sent to the controller.
+    ShortcutNotAllowed,
+    /// A forwarded packet was reported before advancing its hop index; the canonical event
+    /// has the hop index advanced.
+    HopIndexNotAdvanced,
+    /// A forwarded packet does not list the drone as its previous hop.
+    NotOnRoute,
+}
+
+/// The canonical form of a drone event, with the violations found while normalizing it.
+#[derive(Debug, Clone)]
+pub struct Normalized {
+    pub event: CanonicalEvent,
+    pub warnings: Vec<SpecViolation>,
+}
+
+/// Maps a raw drone event into its canonical form.
+///
+/// # Parameters
+/// - `node`: The ID of the drone which emitted the event.
+/// - `event`: The raw event.
+///
+/// Returns the canonical event, along with the violations of the specification.
+pub fn normalize(node: NodeId, event: DroneEvent) -> Normalized {
+    let mut warnings = Vec::new();
+    let event = match event {
+        DroneEvent::PacketSent(mut packet) => {
+            let next_hop = if matches!(packet.pack_type, PacketType::FloodRequest(_)) {
+                None
+            } else {
+                let header = &mut packet.routing_header;
+                if header.hops.get(header.hop_index) == Some(&node) {
+                    warnings.push(SpecViolation::HopIndexNotAdvanced);
+                    header.hop_index += 1;
+                }
+                let previous = header
+                    .hop_index
+                    .checked_sub(1)
+                    .and_then(|index| header.hops.get(index));
+                if previous != Some(&node) {
+                    warnings.push(SpecViolation::NotOnRoute);
+                }
+                header.hops.get(header.hop_index).copied()
+            };
+            CanonicalEvent::Forwarded {
+                node,
+                next_hop,
+                packet,
+            }
+        }
+        DroneEvent::PacketDropped(packet) => {
+            if !matches!(packet.pack_type, PacketType::MsgFragment(_)) {
+                warnings.push(SpecViolation::DroppedNonFragment);
+            }
+            CanonicalEvent::Dropped { node, packet }
+        }
+        DroneEvent::ControllerShortcut(packet) => {
+            if matches!(
+                packet.pack_type,
+                PacketType::MsgFragment(_) | PacketType::FloodRequest(_)
+            ) {
+                warnings.push(SpecViolation::ShortcutNotAllowed);
+            }
+            CanonicalEvent::Shortcut { node, packet }
+        }
+    };
+    Normalized { event, warnings }
+}
+
+/// Spawns a thread normalizing the drone events of an event stream, discarding the events of
+/// clients and servers.
+///
+/// The thread terminates when the stream is disconnected or the returned receiver is dropped.
+///
+/// # Parameters
+/// - `events`: The raw event stream, e.g. from [`crate::handle::NetworkHandle::subscribe`].
+///
+/// Returns the normalized event stream.
+pub fn normalize_stream(events: Receiver<ObservedEvent>) -> Receiver<Normalized> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    thread::spawn(move || {
+        for observed in events {
+            if let NodeEvent::Drone(event) = observed.event {
+                if sender.send(normalize(observed.node, event)).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod test {
+    use crate::normalize::{normalize, CanonicalEvent, SpecViolation};
+    use wg_2024::controller::DroneEvent;
+    use wg_2024::network::SourceRoutingHeader;
+    use wg_2024::packet::{Ack, Packet, PacketType};
+
+    fn ack(hop_index: usize) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index,
+                hops: vec![10, 1, 2, 20],
+            },
+            session_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_normalize_forwarded() {
+        let normalized = normalize(1, DroneEvent::PacketSent(ack(2)));
+        assert!(normalized.warnings.is_empty());
+        assert!(matches!(
+            normalized.event,
+            CanonicalEvent::Forwarded {
+                next_hop: Some(2),
+                ..
+            }
+        ));
+
+        let quirky = normalize(1, DroneEvent::PacketSent(ack(1)));
+        assert_eq!(quirky.warnings, vec![SpecViolation::HopIndexNotAdvanced]);
+        let CanonicalEvent::Forwarded { packet, .. } = quirky.event else {
+            panic!("expected a forwarded packet");
+        };
+        assert_eq!(packet.routing_header.hop_index, 2);
+
+        let off_route = normalize(2, DroneEvent::PacketSent(ack(1)));
+        assert_eq!(off_route.warnings, vec![SpecViolation::NotOnRoute]);
+    }
+
+    #[test]
+    fn test_normalize_out_of_spec() {
+        let dropped = normalize(1, DroneEvent::PacketDropped(ack(1)));
+        assert_eq!(dropped.warnings, vec![SpecViolation::DroppedNonFragment]);
+
+        let shortcut = normalize(1, DroneEvent::ControllerShortcut(ack(1)));
+        assert!(shortcut.warnings.is_empty());
+    }
+}
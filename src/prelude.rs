@@ -0,0 +1,123 @@
+//! The public surface of the crate, for downstream crates such as the simulation controller
+//! and the GUI.
+//!
+//! ```ignore
+//! use network_initializer::prelude::*;
+//! ```
+//!
+//! The modules implementing these items are private to the crate, so that their internals can
+//! be refactored without breaking the crates depending on it.
+
+// Validation of configuration files.
+pub use crate::cache::network_validate_cached;
+pub use crate::dot::topology_to_dot;
+pub use crate::examples::{get as example, names as example_names};
+pub use crate::explain::{explain, Explanation};
+pub use crate::federate::{check_disjoint_ids, remap_ids, DisjointReport, IdCollision};
+pub use crate::geo::{
+    link_latencies, network_validate_geo, parse_positions, validate_link_distances, LatencyModel,
+    Position, Positions,
+};
+pub use crate::layers::{LayeredConfig, Provenance, LAYER_ATTRIBUTE, SOURCE_ATTRIBUTE};
+pub use crate::links::{
+    link_parameters, network_validate_links, parse_link_attributes, LinkAttributes, LinkConflict,
+    LinkConflictPolicy, LinkParameters, LinkTable, PDR_ATTRIBUTE,
+};
+pub use crate::locate::{find_config, ConfigLocation, ConfigSource, CONFIG_ENV};
+pub use crate::migrate::{Migration, Migrations, CONFIG_VERSION};
+#[cfg(feature = "remote")]
+pub use crate::remote::network_validate_url;
+#[cfg(feature = "signed")]
+pub use crate::signed::network_validate_signed;
+pub use crate::template::{network_validate_template, render};
+pub use crate::validate::{
+    check_config, check_config_all, check_config_with_plan, check_config_with_policy,
+    dedup_neighbors, network_validate, network_validate_all, network_validate_many,
+    network_validate_str, network_validate_with_migrations, network_validate_with_policy,
+    unused_impls, validate_scope, validation_report, DuplicateEdgePolicy, ErrorCode, NodeSummary,
+    Scope, Severity, ValidatedConfig, ValidationError, ValidationPolicy, ValidationReport,
+    ValidationWarning, HIGH_AVERAGE_PDR,
+};
+
+// Generation and analysis of topologies.
+pub use crate::analysis::{
+    analyze_topology, color_drones, color_drones_with, config_links, critical_drones, diff_links,
+    is_isomorphic, is_isomorphic_with_tolerance, k_best_paths, k_best_paths_in, rank_drones,
+    topology_report, Centrality, Link, ScoredPath, TopologyAnalysis, TopologyDiff, TopologyReport,
+    DEFAULT_PDR_TOLERANCE,
+};
+pub use crate::anonymize::{anonymize, anonymize_topology, IdMapping};
+pub use crate::difficulty::{difficulty, DifficultyScore};
+pub use crate::generator::{
+    generate_from_degrees, generate_invalid, generate_random_topology, generate_scale_free,
+    generate_small_world, ViolationKind,
+};
+pub use crate::index::{DenseIndex, NodeIndex, NODE_CAPACITY};
+pub use crate::model::{Attributes, Node, Role, Topology};
+
+// Initialization of the network.
+pub use crate::distribution::{
+    impl_name, network_validate_plan, parse_drone_impls, verify_distribution, DistributionPlan,
+    DistroRequirements, IMPL_KEY,
+};
+pub use crate::init::{
+    network_init, network_init_seeded, network_init_validated, network_init_with_options,
+    network_init_with_plan, network_reload, try_network_init_validated,
+    try_network_init_with_options, try_network_init_with_plan, ClientFactory, DroneFactory,
+    InitConsistencyError, InitMode, InitOptions, InitTimings, NetworkInitData, RetryPolicy,
+    RunNode, ServerFactory,
+};
+#[cfg(feature = "det-test")]
+pub use crate::sched::{explore, run_with_schedule, TraceEvent};
+pub use crate::spawn::{InitialCommand, SpawnPlan};
+pub use crate::stub::{
+    echo_server_factory, null_drone_factory, ping_client_factory, EchoServer, NullDrone, PingClient,
+};
+pub use crate::supervise::{SupervisionEvent, SupervisionPolicy};
+pub use crate::transcript::{assert_transcripts_equal, Transcript, TranscriptEntry};
+
+// The running network.
+pub use crate::aggregate::{aggregate_stream, Aggregator, EventKind, GuiUpdate, DEFAULT_WINDOW};
+pub use crate::channels::ChannelInfo;
+pub use crate::clock::{MAX_TIME_SCALE, MIN_TIME_SCALE};
+pub use crate::conformance::{
+    spawn_conformance_monitor, ConformanceMonitor, CoverageReport, ProtocolViolation, Rule,
+    Situation,
+};
+pub use crate::events::{NodeEvent, ObservedEvent, SuspiciousEvent, SuspiciousEventPolicy};
+pub use crate::expect::{Event, Expectation, Expectations};
+pub use crate::group::{by_ids, by_impl, by_type, by_zone, NodeGroup, ZONE_ATTRIBUTE};
+pub use crate::handle::{
+    CommandError, EventBacklog, GuiEndpoint, GuiStall, MutationError, NetworkHandle, NodeCommand,
+    NodeStatus, NodeThreads, ResourceUsage, RunSummary, ShutdownPolicy, ShutdownReport,
+    ShutdownStage, StalledGuiPolicy, TeardownOrder, TeardownReport, TeardownStage,
+    TerminationReport, ThreadState, Transaction,
+};
+pub use crate::journal::{
+    CommandRecord, COMMAND_LOG_CAPACITY, ISSUER_DRAIN, ISSUER_HANDLE, ISSUER_INIT, ISSUER_SCENARIO,
+    ISSUER_SHUTDOWN, ISSUER_TEARDOWN,
+};
+pub use crate::normalize::{
+    normalize, normalize_stream, CanonicalEvent, Normalized, SpecViolation,
+};
+pub use crate::tap::{CrashCapture, TapSampling, TapStats, TappedPacket};
+#[cfg(feature = "telemetry")]
+pub use crate::telemetry::{
+    clear_sink, set_sink, Span, TelemetryAttributes, TelemetrySink, ATTR_LIFECYCLE_EVENT,
+    ATTR_NODE_ID, METRIC_NODE_LIFECYCLE,
+};
+pub use crate::topology::{
+    DivergenceKind, LinkDivergence, Timeline, TimelineDelta, TopologySnapshot, TopologyUpdate,
+};
+
+// Experiments on the running network.
+pub use crate::batch::{run_batch, BatchOptions};
+pub use crate::discovery::{record_flood_discovery, FloodRecording, DISCOVERY_FLOOD_ID_BASE};
+pub use crate::latency::{measure_rtt, RttStats, PROBE_SESSION_BASE};
+pub use crate::scenario::{Action, Scenario, Step};
+pub use crate::selftest::{self_test, LinkTestReport, SELF_TEST_SESSION_BASE};
+pub use crate::soak::{
+    soak, Leak, SoakReport, SoakSample, TrafficProfile, MIN_LEAK_SAMPLES, SOAK_SESSION_BASE,
+};
+pub use crate::stats::CsvAppender;
+pub use crate::sweep::{sweep, SweepGrid, SweepOptions, SweepRow, SweepTable};
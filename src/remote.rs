@@ -0,0 +1,55 @@
+use sha2::{Digest, Sha256};
+use wg_2024::config::Config;
+
+use crate::validate::network_validate_str;
+
+/// Fetches a configuration file over HTTP(S) and validates it.
+///
+/// # Parameters
+/// - `url`: The URL of the configuration file.
+/// - `checksum`: The expected SHA-256 of the file, as a hexadecimal string, if it must be
+///   verified.
+///
+/// Returns the configuration, as `Config`, if the file was fetched, matches the checksum and
+/// is valid, an error otherwise.
+pub fn network_validate_url(url: &str, checksum: Option<&str>) -> Result<Config, String> {
+    let config_data = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Unable to fetch configuration file: {}", e))?
+        .into_string()
+        .map_err(|e| format!("Unable to read configuration file: {}", e))?;
+
+    if let Some(checksum) = checksum {
+        verify_checksum(config_data.as_bytes(), checksum)?;
+    }
+    network_validate_str(&config_data)
+}
+
+/// Verifies that the SHA-256 of some data matches the expected one.
+fn verify_checksum(data: &[u8], expected: &str) -> Result<(), String> {
+    let actual: String = Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(format!(
+            "Checksum mismatch: expected {}, got {}",
+            expected.trim(),
+            actual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::remote::verify_checksum;
+
+    #[test]
+    fn test_verify_checksum() {
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(verify_checksum(b"abc", abc), Ok(()));
+        assert_eq!(verify_checksum(b"abc", &abc.to_uppercase()), Ok(()));
+        assert!(verify_checksum(b"abd", abc).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! Deterministic scheduling mode for exploring the ordering races of the init sequence.
+//!
+//! [`crate::init::network_init`] spawns every node and then fans out the commands adding the
+//! links, so a node may start running before, during or after the fan-out. While a schedule
+//! is installed on the current thread, the thread of every node is created parked, and every
+//! command of the fan-out is sent through [`channel_op`], which makes it a scheduling point:
+//! every parked node is started exactly at the scheduling point chosen by the schedule (that
+//! is, after a chosen number of commands has been sent), so each interleaving of node startups
+//! and fan-out can be reproduced and, for small networks, explored exhaustively with
+//! [`explore`].
+//!
+//! Since the threads are created right away, their join handles are kept by the network as
+//! usual. Only the startup order is controlled: once started, nodes run on regular threads.
+
+use std::cell::RefCell;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::thread::{self, JoinHandle, Thread};
+
+/// The body of the thread of a node.
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// A step of an execution under a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// The spawn of the given task was deferred.
+    Spawn(usize),
+    /// The given scheduling point was reached, after a channel operation.
+    Point(usize),
+    /// The given task was started.
+    Start(usize),
+}
+
+struct Scheduler {
+    /// The scheduling point at which every task is started, indexed by task.
+    start_points: Vec<usize>,
+    /// The number of scheduling points reached so far.
+    points: usize,
+    /// The spawned tasks which were not started yet, with their parked threads and the flags
+    /// releasing them.
+    deferred: Vec<(usize, Thread, Arc<AtomicBool>)>,
+    /// The number of spawned tasks.
+    spawned: usize,
+    trace: Vec<TraceEvent>,
+}
+
+impl Scheduler {
+    /// Starts every deferred task whose scheduling point has been reached.
+    fn start_due(&mut self, force: bool) {
+        let mut index = 0;
+        while index < self.deferred.len() {
+            let task_id = self.deferred[index].0;
+            let due = self.start_points.get(task_id).copied().unwrap_or(0) <= self.points;
+            if force || due {
+                let (task_id, thread, released) = self.deferred.remove(index);
+                self.trace.push(TraceEvent::Start(task_id));
+                released.store(true, Ordering::SeqCst);
+                thread.unpark();
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+thread_local! {
+    static SCHEDULER: RefCell<Option<Scheduler>> = const { RefCell::new(None) };
+}
+
+/// Runs `body` with the given schedule installed on the current thread.
+///
+/// The `k`-th node spawned by `body` is started once `start_points[k]` scheduling points have
+/// been reached (tasks without an entry are started immediately); tasks still deferred when
+/// `body` returns are started at that point.
+///
+/// # Parameters
+/// - `start_points`: The scheduling point at which every node is started.
+/// - `body`: The code to run, typically a call to [`crate::init::network_init`].
+///
+/// Returns the result of `body` and the trace of the execution.
+pub fn run_with_schedule<R>(
+    start_points: &[usize],
+    body: impl FnOnce() -> R,
+) -> (R, Vec<TraceEvent>) {
+    SCHEDULER.with(|scheduler| {
+        *scheduler.borrow_mut() = Some(Scheduler {
+            start_points: start_points.to_vec(),
+            points: 0,
+            deferred: Vec::new(),
+            spawned: 0,
+            trace: Vec::new(),
+        })
+    });
+    let result = body();
+    let mut scheduler = SCHEDULER
+        .with(|scheduler| scheduler.borrow_mut().take())
+        .expect("the schedule was uninstalled while running");
+    scheduler.start_due(true);
+    (result, scheduler.trace)
+}
+
+/// Calls `body` once for every schedule of `n_tasks` node spawns over `n_points` scheduling
+/// points, i.e. for every assignment of a start point in `0..=n_points` to each task.
+///
+/// # Performance
+/// `O((n_points + 1)^n_tasks)` calls of `body`.
+pub fn explore(n_tasks: usize, n_points: usize, mut body: impl FnMut(&[usize])) {
+    let mut start_points = vec![0; n_tasks];
+    loop {
+        body(&start_points);
+        let Some(index) = start_points.iter().position(|point| *point < n_points) else {
+            return;
+        };
+        start_points[index] += 1;
+        start_points[..index]
+            .iter_mut()
+            .for_each(|point| *point = 0);
+    }
+}
+
+/// Spawns the thread of a node, parked until the schedule installed on the current thread, if
+/// any, starts it.
+///
+/// # Parameters
+/// - `builder`: The builder of the thread.
+/// - `task`: The body of the thread.
+///
+/// Returns the join handle of the thread, or the error of the operating system if the thread
+/// cannot be created.
+pub(crate) fn spawn_scheduled(
+    builder: thread::Builder,
+    task: Task,
+) -> std::io::Result<JoinHandle<()>> {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        let Some(scheduler) = scheduler.as_mut() else {
+            return builder.spawn(task);
+        };
+        let released = Arc::new(AtomicBool::new(false));
+        let gate = Arc::clone(&released);
+        let thread = builder.spawn(move || {
+            // Parking may wake up spuriously, so wait for the flag.
+            while !gate.load(Ordering::SeqCst) {
+                thread::park();
+            }
+            task();
+        })?;
+        let task_id = scheduler.spawned;
+        scheduler.spawned += 1;
+        scheduler.trace.push(TraceEvent::Spawn(task_id));
+        scheduler
+            .deferred
+            .push((task_id, thread.thread().clone(), released));
+        scheduler.start_due(false);
+        Ok(thread)
+    })
+}
+
+/// Performs a channel operation of the init sequence, e.g. sending a command of the fan-out,
+/// as a scheduling point: once the operation is done, the deferred nodes which are due are
+/// started.
+///
+/// # Parameters
+/// - `op`: The channel operation.
+///
+/// Returns the result of the operation.
+pub(crate) fn channel_op<R>(op: impl FnOnce() -> R) -> R {
+    let result = op();
+    SCHEDULER.with(|scheduler| {
+        if let Some(scheduler) = scheduler.borrow_mut().as_mut() {
+            scheduler.trace.push(TraceEvent::Point(scheduler.points));
+            scheduler.points += 1;
+            scheduler.start_due(false);
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::examples;
+    use crate::handle::{spawn_joinable_node, ShutdownPolicy, ShutdownStage};
+    use crate::init::network_init;
+    use crate::sched::{channel_op, explore, run_with_schedule, TraceEvent};
+    use crate::validate::network_validate_str;
+
+    #[test]
+    fn test_run_with_schedule() {
+        let (started_tx, started_rx) = mpsc::channel();
+        let ((first, second), trace) = run_with_schedule(&[1, 0], || {
+            let started = started_tx.clone();
+            let (_, first) =
+                spawn_joinable_node(thread::Builder::new(), move || started.send(0).unwrap());
+            let (_, second) =
+                spawn_joinable_node(thread::Builder::new(), move || started_tx.send(1).unwrap());
+            assert_eq!(started_rx.recv_timeout(Duration::from_secs(1)), Ok(1));
+            // The first node is parked until the first scheduling point.
+            assert!(started_rx.recv_timeout(Duration::from_millis(50)).is_err());
+            channel_op(|| ());
+            assert_eq!(started_rx.recv_timeout(Duration::from_secs(1)), Ok(0));
+            channel_op(|| ());
+            (first, second)
+        });
+        // The threads are joinable, whenever they were started.
+        assert!(first.join().is_ok());
+        assert!(second.join().is_ok());
+
+        assert_eq!(
+            trace,
+            vec![
+                TraceEvent::Spawn(0),
+                TraceEvent::Spawn(1),
+                TraceEvent::Start(1),
+                TraceEvent::Point(0),
+                TraceEvent::Start(0),
+                TraceEvent::Point(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schedule_init() {
+        let config = network_validate_str(examples::get("ring8").unwrap()).unwrap();
+        let n_nodes = config.drone.len() + config.client.len() + config.server.len();
+        let (data, trace) = run_with_schedule(&[3, 0, 1], || network_init(&config));
+        assert_eq!(trace[0], TraceEvent::Spawn(0));
+        assert!(trace.contains(&TraceEvent::Start(0)));
+
+        // Every node is joined, including the ones started late.
+        assert_eq!(data.threads.ids().len(), n_nodes);
+        let report = data.shutdown(ShutdownPolicy::default());
+        assert!(report.with_stage(ShutdownStage::Abandoned).is_empty());
+        assert!(data.threads.ids().is_empty());
+    }
+
+    #[test]
+    fn test_explore() {
+        let mut schedules = Vec::new();
+        explore(2, 2, |start_points| schedules.push(start_points.to_vec()));
+
+        assert_eq!(schedules.len(), 9);
+        assert_eq!(schedules[0], vec![0, 0]);
+        assert_eq!(schedules[8], vec![2, 2]);
+    }
+}
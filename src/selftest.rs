@@ -0,0 +1,165 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+use wg_2024::{
+    network::{NodeId, SourceRoutingHeader},
+    packet::{Ack, Packet, PacketType},
+};
+
+use crate::analysis::{config_links, Link};
+use crate::handle::NetworkHandle;
+use crate::validate::ValidatedConfig;
+
+/// First session ID used by the test packets, chosen apart from the IDs used by the clients
+/// and by the latency probes so that the test packets are not mistaken for other traffic.
+pub const SELF_TEST_SESSION_BASE: u64 = 5 << 61;
+
+/// The outcome of [`self_test`], by link.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkTestReport {
+    /// The links crossed by their test packet, with the time the packet took.
+    pub delivered: BTreeMap<Link, Duration>,
+    /// The links whose test packet was not delivered before the timeout.
+    pub failed: BTreeSet<Link>,
+    /// The links which could not be tested, since neither end is a drone.
+    pub untested: BTreeSet<Link>,
+}
+
+impl LinkTestReport {
+    /// Returns `true` if every link of the configuration was crossed by its test packet.
+    pub fn passed(&self) -> bool {
+        self.failed.is_empty() && self.untested.is_empty()
+    }
+}
+
+/// Pushes a test packet across every link of the configuration, proving that the spawned
+/// network matches the validated topology before the real simulation starts.
+///
+/// The test packet of a link is an ACK with a reserved session ID, delivered to a drone at
+/// one end with the other end as the next hop; the link works if the tap observes the packet
+/// being delivered to the other end. The packets of all the links are sent at once, and the
+/// receivers get them like any other ACK.
+///
+/// # Parameters
+/// - `handle`: The handle of the network, initialized with
+///   [`InitOptions::packet_taps`](crate::init::InitOptions::packet_taps).
+/// - `config`: The configuration the network was initialized from.
+/// - `timeout`: The time given to the test packets to cross their links.
+///
+/// Returns the outcome of every link.
+pub fn self_test(
+    handle: &NetworkHandle,
+    config: &ValidatedConfig,
+    timeout: Duration,
+) -> LinkTestReport {
+    let config = config.config();
+    let drones: BTreeSet<NodeId> = config.drone.iter().map(|drone| drone.id).collect();
+    let tap = handle.tap();
+    let mut report = LinkTestReport::default();
+    let mut pending: HashMap<u64, (Link, NodeId)> = HashMap::new();
+
+    let sent = Instant::now();
+    for (session_id, link) in (SELF_TEST_SESSION_BASE..).zip(config_links(config)) {
+        let (from, to) = match link {
+            (a, b) if drones.contains(&a) => (a, b),
+            (a, b) if drones.contains(&b) => (b, a),
+            _ => {
+                report.untested.insert(link);
+                continue;
+            }
+        };
+        let packet = Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![from, to],
+            },
+            session_id,
+        };
+        if handle.send_packet(from, packet) {
+            pending.insert(session_id, (link, to));
+        } else {
+            report.failed.insert(link);
+        }
+    }
+
+    let deadline = sent + timeout;
+    while !pending.is_empty() {
+        let Ok(tapped) = tap.recv_deadline(deadline) else {
+            break;
+        };
+        let session_id = tapped.packet.session_id;
+        if pending
+            .get(&session_id)
+            .is_some_and(|(_, to)| *to == tapped.to)
+        {
+            let (link, _) = pending.remove(&session_id).unwrap();
+            report
+                .delivered
+                .insert(link, tapped.at.duration_since(sent));
+        }
+    }
+    report
+        .failed
+        .extend(pending.into_values().map(|(link, _)| link));
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::init::{network_init_with_options, InitMode, InitOptions};
+    use crate::selftest::self_test;
+    use crate::stub::{echo_server_factory, null_drone_factory, ping_client_factory};
+    use crate::validate::{network_validate_str, ValidatedConfig};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.0
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 4]
+        pdr = 0.0
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [1, 2]
+    "#;
+
+    #[test]
+    fn test_self_test() {
+        let config = ValidatedConfig::new(network_validate_str(CONFIG).unwrap()).unwrap();
+        let options = InitOptions {
+            mode: InitMode::Prebuilt,
+            client_factory: ping_client_factory,
+            server_factory: echo_server_factory,
+            packet_taps: true,
+            ..InitOptions::default()
+        };
+        let handle = network_init_with_options(config.config(), &options).handle;
+        let report = self_test(&handle, &config, Duration::from_secs(5));
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.delivered.len(), 4);
+        handle.crash_all();
+
+        // Drones which forward nothing fail every link.
+        let options = InitOptions {
+            drone_factory: null_drone_factory,
+            ..options
+        };
+        let handle = network_init_with_options(config.config(), &options).handle;
+        let report = self_test(&handle, &config, Duration::from_millis(100));
+        assert_eq!(report.failed.len(), 4);
+        handle.crash_all();
+    }
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use wg_2024::config::Config;
+
+use crate::validate::network_validate_str;
+
+/// Extension of the detached signature of a configuration file, appended to its path.
+const SIGNATURE_EXTENSION: &str = ".sig";
+
+/// Reads a configuration file, verifies its detached signature, and validates it.
+///
+/// The signature is read from the file with the same path plus a `.sig` extension (e.g.
+/// `topology.toml.sig`), and holds the 64 raw bytes of the ed25519 signature of the file.
+/// The file is not parsed unless the signature is valid.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+/// - `public_key`: The ed25519 public key of the signer.
+///
+/// Returns the configuration, as `Config`, if the signature is valid and so is the
+/// configuration, an error otherwise.
+pub fn network_validate_signed(file_path: &str, public_key: &[u8; 32]) -> Result<Config, String> {
+    let config_data =
+        fs::read(file_path).map_err(|_| "Unable to read configuration file".to_string())?;
+    let signature = fs::read(format!("{}{}", file_path, SIGNATURE_EXTENSION))
+        .map_err(|_| "Unable to read signature file".to_string())?;
+
+    let public_key =
+        VerifyingKey::from_bytes(public_key).map_err(|e| format!("Invalid public key: {}", e))?;
+    let signature =
+        Signature::from_slice(&signature).map_err(|e| format!("Invalid signature: {}", e))?;
+    public_key
+        .verify_strict(&config_data, &signature)
+        .map_err(|_| "The signature does not match the configuration file".to_string())?;
+
+    let config_data = String::from_utf8(config_data)
+        .map_err(|_| "The configuration file is not valid UTF-8".to_string())?;
+    network_validate_str(&config_data)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::signed::network_validate_signed;
+
+    #[test]
+    fn test_validate_signed_malformed() {
+        let path = env::temp_dir().join(format!("signed_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, "").unwrap();
+
+        let error = network_validate_signed(path, &[0; 32]).unwrap_err();
+        assert_eq!(error, "Unable to read signature file");
+
+        fs::write(format!("{}.sig", path), [0; 10]).unwrap();
+        let error = network_validate_signed(path, &[0; 32]).unwrap_err();
+        assert!(error.starts_with("Invalid"));
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(format!("{}.sig", path)).unwrap();
+    }
+}
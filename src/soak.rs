@@ -0,0 +1,311 @@
+use std::{
+    fs, thread,
+    time::{Duration, Instant},
+};
+
+use wg_2024::{
+    network::{NodeId, SourceRoutingHeader},
+    packet::{Ack, Packet, PacketType},
+};
+
+use crate::handle::NetworkHandle;
+use crate::latency::shortest_path;
+
+/// First session ID used by the soak traffic, chosen far above the IDs used by the clients so
+/// that the traffic is not mistaken for theirs.
+pub const SOAK_SESSION_BASE: u64 = 1 << 62;
+
+/// The smallest number of samples over which a metric must grow to be reported as a leak.
+pub const MIN_LEAK_SAMPLES: usize = 4;
+
+/// The size of a memory page, assumed by the RSS read from `/proc/self/statm`.
+const PAGE_SIZE: u64 = 4096;
+
+/// The traffic sent by [`soak`], and how often the metrics are sampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrafficProfile {
+    /// The number of packets sent every second, spread evenly over the client-server pairs.
+    pub packets_per_second: u32,
+    /// The interval between two samples of the metrics.
+    pub sample_interval: Duration,
+}
+
+impl Default for TrafficProfile {
+    fn default() -> Self {
+        Self {
+            packets_per_second: 100,
+            sample_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The metrics sampled by [`soak`] at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoakSample {
+    /// The time since the start of the soak.
+    pub at: Duration,
+    /// The number of packets waiting in the queue of every node, ordered as in the
+    /// configuration.
+    pub queue_depths: Vec<(NodeId, usize)>,
+    /// The resident memory of the process, in bytes, or `None` if it is not available, e.g.
+    /// because the platform is not Linux.
+    pub rss_bytes: Option<u64>,
+}
+
+/// A metric which grew at every sample of a [`soak`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leak {
+    /// The packet queue of the given node.
+    QueueDepth(NodeId),
+    /// The resident memory of the process.
+    Rss,
+}
+
+/// The outcome of a [`soak`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoakReport {
+    /// The number of packets sent.
+    pub sent: usize,
+    /// The metrics, in order of sampling.
+    pub samples: Vec<SoakSample>,
+    /// The metrics which grew at every sample.
+    pub leaks: Vec<Leak>,
+}
+
+impl SoakReport {
+    /// Returns `true` if no leak was detected.
+    pub fn passed(&self) -> bool {
+        self.leaks.is_empty()
+    }
+}
+
+/// Runs continuous traffic through a live network, sampling its queues and the memory of the
+/// process, to catch leaky drone implementations before they are used for real.
+///
+/// The traffic is made of ACKs with reserved session IDs, sent from every client to every
+/// server along the shortest path through drones, in turn. A metric is reported as a leak if
+/// it grew at every one of at least [`MIN_LEAK_SAMPLES`] samples.
+///
+/// # Parameters
+/// - `handle`: The handle of the network.
+/// - `duration`: How long the traffic runs.
+/// - `profile`: The traffic, and how often the metrics are sampled.
+///
+/// Returns the samples and the leaks detected.
+pub fn soak(handle: &NetworkHandle, duration: Duration, profile: TrafficProfile) -> SoakReport {
+    let plan = handle.plan();
+    let paths: Vec<Vec<NodeId>> = plan
+        .clients
+        .iter()
+        .flat_map(|(client, _)| {
+            plan.servers
+                .iter()
+                .filter_map(move |(server, _)| shortest_path(handle, *client, *server))
+        })
+        .filter(|path| path.len() > 1)
+        .collect();
+    let packet_interval = Duration::from_secs(1) / profile.packets_per_second.max(1);
+
+    let start = Instant::now();
+    let mut report = SoakReport::default();
+    let mut next_packet = start;
+    let mut next_sample = start;
+    while start.elapsed() < duration {
+        let now = Instant::now();
+        if now >= next_sample {
+            report.samples.push(SoakSample {
+                at: now - start,
+                queue_depths: handle.queue_depths(),
+                rss_bytes: resident_memory(),
+            });
+            next_sample += profile.sample_interval;
+        }
+        if now >= next_packet && !paths.is_empty() {
+            let path = &paths[report.sent % paths.len()];
+            let packet = Packet {
+                pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+                routing_header: SourceRoutingHeader {
+                    hop_index: 1,
+                    hops: path.clone(),
+                },
+                session_id: SOAK_SESSION_BASE + report.sent as u64,
+            };
+            handle.send_packet(path[1], packet);
+            report.sent += 1;
+            next_packet += packet_interval;
+        }
+        let wake = next_sample.min(if paths.is_empty() {
+            next_sample
+        } else {
+            next_packet
+        });
+        thread::sleep(wake.saturating_duration_since(Instant::now()));
+    }
+
+    report.leaks = find_leaks(&report.samples);
+    report
+}
+
+/// Returns the metrics which grew at every sample.
+fn find_leaks(samples: &[SoakSample]) -> Vec<Leak> {
+    if samples.len() < MIN_LEAK_SAMPLES {
+        return Vec::new();
+    }
+    let grows = |values: Vec<Option<u64>>| {
+        values
+            .windows(2)
+            .all(|pair| matches!(pair, [Some(a), Some(b)] if a < b))
+    };
+
+    let mut leaks: Vec<Leak> = samples[0]
+        .queue_depths
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            grows(
+                samples
+                    .iter()
+                    .map(|sample| sample.queue_depths.get(*index).map(|(_, d)| *d as u64))
+                    .collect(),
+            )
+        })
+        .map(|(_, (node, _))| Leak::QueueDepth(*node))
+        .collect();
+    if grows(samples.iter().map(|sample| sample.rss_bytes).collect()) {
+        leaks.push(Leak::Rss);
+    }
+    leaks
+}
+
+/// Returns the resident memory of the process, in bytes, on Linux.
+fn resident_memory() -> Option<u64> {
+    let statm = fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, time::Duration};
+
+    use crossbeam_channel::{Receiver, Sender};
+    use rust_roveri_api::DroneImpl;
+    use wg_2024::{
+        controller::{DroneCommand, DroneEvent},
+        drone::Drone,
+        network::NodeId,
+        packet::Packet,
+    };
+
+    use crate::init::{network_init_with_options, InitMode, InitOptions};
+    use crate::soak::{soak, Leak, TrafficProfile};
+    use crate::stub::{echo_server_factory, ping_client_factory};
+    use crate::validate::network_validate_str;
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.0
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 4]
+        pdr = 0.0
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [1, 2]
+    "#;
+
+    /// A drone which never takes the packets from its queue.
+    struct DeafDrone {
+        controller_recv: Receiver<DroneCommand>,
+        _packet_recv: Receiver<Packet>,
+    }
+
+    impl Drone for DeafDrone {
+        fn new(
+            _id: NodeId,
+            _controller_send: Sender<DroneEvent>,
+            controller_recv: Receiver<DroneCommand>,
+            packet_recv: Receiver<Packet>,
+            _packet_send: HashMap<NodeId, Sender<Packet>>,
+            _pdr: f32,
+        ) -> Self {
+            Self {
+                controller_recv,
+                _packet_recv: packet_recv,
+            }
+        }
+
+        fn run(&mut self) {
+            for command in self.controller_recv.iter() {
+                if let DroneCommand::Crash = command {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn deaf_drone_factory(
+        _drone_impl: DroneImpl,
+        id: NodeId,
+        controller_send: Sender<DroneEvent>,
+        controller_recv: Receiver<DroneCommand>,
+        packet_recv: Receiver<Packet>,
+        packet_send: HashMap<NodeId, Sender<Packet>>,
+        pdr: f32,
+    ) -> Box<dyn Drone + Send> {
+        Box::new(DeafDrone::new(
+            id,
+            controller_send,
+            controller_recv,
+            packet_recv,
+            packet_send,
+            pdr,
+        ))
+    }
+
+    #[test]
+    fn test_soak() {
+        let config = network_validate_str(CONFIG).unwrap();
+        let options = InitOptions {
+            mode: InitMode::Prebuilt,
+            client_factory: ping_client_factory,
+            server_factory: echo_server_factory,
+            ..InitOptions::default()
+        };
+        let profile = TrafficProfile {
+            packets_per_second: 500,
+            sample_interval: Duration::from_millis(50),
+        };
+
+        let handle = network_init_with_options(&config, &options).handle;
+        let report = soak(&handle, Duration::from_millis(300), profile);
+        assert!(report.sent > 0);
+        assert!(report.samples.len() >= 5);
+        assert!(!report
+            .leaks
+            .iter()
+            .any(|leak| matches!(leak, Leak::QueueDepth(_))));
+        handle.crash_all();
+
+        let options = InitOptions {
+            drone_factory: deaf_drone_factory,
+            ..options
+        };
+        let handle = network_init_with_options(&config, &options).handle;
+        let report = soak(&handle, Duration::from_millis(300), profile);
+        assert!(
+            report.leaks.contains(&Leak::QueueDepth(1)),
+            "{:?}",
+            report.leaks
+        );
+        handle.crash_all();
+    }
+}
@@ -0,0 +1,128 @@
+use wg_2024::{config::Config, network::NodeId};
+
+use crate::init::InitMode;
+
+/// An initial command sent by [`crate::init::network_init`], adding a neighbor to a node.
+///
+/// The command carries the packet sender of the neighbor, so the node depends on the neighbor
+/// for the corresponding link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InitialCommand {
+    /// The ID of the node receiving the command.
+    pub node: NodeId,
+    /// The ID of the neighbor added by the command.
+    pub neighbor: NodeId,
+}
+
+/// The order in which the initialization spawns the nodes and sends the initial commands.
+///
+/// Every node is spawned before any command is sent. With the `det-test` feature, the `k`-th
+/// spawned node is the `k`-th task of a schedule, and every command is sent as a scheduling
+/// point, so a plan gives the bounds to pass to `sched::explore`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpawnPlan {
+    /// The nodes, in spawn order.
+    pub order: Vec<NodeId>,
+    /// The initial commands, in sending order.
+    pub commands: Vec<InitialCommand>,
+}
+
+impl SpawnPlan {
+    /// Plans the initialization of a network.
+    ///
+    /// # Parameters
+    /// - `config`: The network configuration.
+    /// - `mode`: How drones are given the senders towards their neighbors; with
+    ///   [`InitMode::Prebuilt`], drones receive no initial command.
+    ///
+    /// Returns the plan followed by the initialization with the given mode.
+    pub fn new(config: &Config, mode: InitMode) -> Self {
+        let order = config
+            .drone
+            .iter()
+            .map(|drone| drone.id)
+            .chain(config.client.iter().map(|client| client.id))
+            .chain(config.server.iter().map(|server| server.id))
+            .collect();
+
+        let drones = config
+            .drone
+            .iter()
+            .filter(|_| mode == InitMode::Commands)
+            .map(|drone| (drone.id, &drone.connected_node_ids));
+        let clients = config
+            .client
+            .iter()
+            .map(|client| (client.id, &client.connected_drone_ids));
+        let servers = config
+            .server
+            .iter()
+            .map(|server| (server.id, &server.connected_drone_ids));
+        let commands = drones
+            .chain(clients)
+            .chain(servers)
+            .flat_map(|(node, neighbors)| {
+                neighbors.iter().map(move |neighbor| InitialCommand {
+                    node,
+                    neighbor: *neighbor,
+                })
+            })
+            .collect();
+
+        Self { order, commands }
+    }
+
+    /// Returns the neighbors added to a node by its initial commands, in sending order.
+    pub fn received(&self, id: NodeId) -> Vec<NodeId> {
+        self.commands
+            .iter()
+            .filter(|command| command.node == id)
+            .map(|command| command.neighbor)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wg_2024::config::Config;
+
+    use crate::init::InitMode;
+    use crate::spawn::{InitialCommand, SpawnPlan};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3]
+        pdr = 0.1
+
+        [[client]]
+        id = 2
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 3
+        connected_drone_ids = [1]
+    "#;
+
+    #[test]
+    fn test_spawn_plan() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+
+        let plan = SpawnPlan::new(&config, InitMode::Commands);
+        assert_eq!(plan.order, vec![1, 2, 3]);
+        assert_eq!(plan.commands.len(), 4);
+        assert_eq!(
+            plan.commands[2],
+            InitialCommand {
+                node: 2,
+                neighbor: 1
+            }
+        );
+        assert_eq!(plan.received(1), vec![2, 3]);
+
+        let plan = SpawnPlan::new(&config, InitMode::Prebuilt);
+        assert_eq!(plan.order, vec![1, 2, 3]);
+        assert!(plan.received(1).is_empty());
+        assert_eq!(plan.commands.len(), 2);
+    }
+}
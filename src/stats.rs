@@ -0,0 +1,135 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::handle::NetworkHandle;
+
+/// Periodic writer of the state of every node as CSV rows, running on its own thread.
+///
+/// The appender stops when [`CsvAppender::stop`] is called or when it is dropped.
+#[derive(Debug)]
+pub struct CsvAppender {
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl CsvAppender {
+    /// Starts appending the state of the nodes of a network to a writer.
+    ///
+    /// A header is written first; then, every `interval`, one row per node with the columns
+    /// `elapsed_ms`, `node`, `status` and `queue`, where `elapsed_ms` is the time since the
+    /// appender was started and `queue` is the number of packets waiting for the node.
+    ///
+    /// # Parameters
+    /// - `handle`: The handle of the network.
+    /// - `writer`: The destination of the CSV.
+    /// - `interval`: The time between two samples.
+    pub fn start<W: Write + Send + 'static>(
+        handle: NetworkHandle,
+        mut writer: W,
+        interval: Duration,
+    ) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = Arc::clone(&stopped);
+        let thread = thread::spawn(move || {
+            let started = Instant::now();
+            writeln!(writer, "elapsed_ms,node,status,queue")?;
+            while !thread_stopped.load(Ordering::SeqCst) {
+                let elapsed = started.elapsed().as_millis();
+                for (id, queue) in handle.queue_depths() {
+                    let Some(status) = handle.probe(id) else {
+                        continue;
+                    };
+                    writeln!(writer, "{},{},{},{}", elapsed, id, status.as_str(), queue)?;
+                }
+                writer.flush()?;
+                thread::park_timeout(interval);
+            }
+            Ok(())
+        });
+        Self {
+            stopped,
+            thread: Some(thread),
+        }
+    }
+
+    /// Stops the appender, waiting for the rows being written.
+    ///
+    /// Returns the first error encountered while writing, if any.
+    pub fn stop(mut self) -> io::Result<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> io::Result<()> {
+        self.stopped.store(true, Ordering::SeqCst);
+        let Some(thread) = self.thread.take() else {
+            return Ok(());
+        };
+        thread.thread().unpark();
+        thread
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("the CSV appender panicked")))
+    }
+}
+
+impl Drop for CsvAppender {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::handle::{spawn_node, NetworkHandle, NodeEntry, Shared};
+    use crate::stats::CsvAppender;
+    use rust_roveri_api::Command;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_csv_appender() {
+        let (sx_packet, _rx_packet) = crossbeam_channel::unbounded();
+        let nodes = vec![NodeEntry::new(
+            1,
+            Command::None,
+            sx_packet,
+            spawn_node(|| {}),
+        )];
+        let handle = NetworkHandle::new(nodes, Shared::default());
+        let buffer = SharedBuffer::default();
+
+        let appender = CsvAppender::start(handle, buffer.clone(), Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(30));
+        appender.stop().unwrap();
+
+        let csv = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("elapsed_ms,node,status,queue"));
+        let rows: Vec<_> = lines.collect();
+        assert!(rows.len() >= 2);
+        assert!(rows.iter().all(|row| row.split(',').nth(1) == Some("1")));
+    }
+}
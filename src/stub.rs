@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+
+use crossbeam_channel::{select, Receiver, Sender};
+use rust_roveri_api::{
+    ClientCommand, ClientEvent, ClientGuiMessage, ClientType, DroneImpl, GuiClientMessage,
+    ServerCommand, ServerEvent, ServerType,
+};
+use wg_2024::{
+    controller::{DroneCommand, DroneEvent},
+    drone::Drone,
+    network::NodeId,
+    packet::Packet,
+};
+
+use crate::init::RunNode;
+
+/// A drone which forwards nothing, for simulations exercising only the topology features of
+/// the simulation controller and the GUI.
+///
+/// The drone keeps track of its neighbors and packet drop rate as commanded, discards every
+/// packet it receives, and stops when it is crashed or its channels are closed.
+pub struct NullDrone {
+    id: NodeId,
+    controller_recv: Receiver<DroneCommand>,
+    packet_recv: Receiver<Packet>,
+    packet_send: HashMap<NodeId, Sender<Packet>>,
+    pdr: f32,
+}
+
+impl NullDrone {
+    /// Returns the ID of the drone.
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Returns the IDs of the current neighbors of the drone, in ascending order.
+    pub fn neighbors(&self) -> Vec<NodeId> {
+        let mut neighbors: Vec<NodeId> = self.packet_send.keys().copied().collect();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    /// Returns the current packet drop rate of the drone.
+    pub fn pdr(&self) -> f32 {
+        self.pdr
+    }
+
+    /// Applies a command, returning `false` if the drone must stop.
+    fn handle_command(&mut self, command: DroneCommand) -> bool {
+        match command {
+            DroneCommand::AddSender(id, sender) => {
+                self.packet_send.insert(id, sender);
+            }
+            DroneCommand::RemoveSender(id) => {
+                self.packet_send.remove(&id);
+            }
+            DroneCommand::SetPacketDropRate(pdr) => self.pdr = pdr,
+            DroneCommand::Crash => return false,
+        }
+        true
+    }
+}
+
+impl Drone for NullDrone {
+    fn new(
+        id: NodeId,
+        _controller_send: Sender<DroneEvent>,
+        controller_recv: Receiver<DroneCommand>,
+        packet_recv: Receiver<Packet>,
+        packet_send: HashMap<NodeId, Sender<Packet>>,
+        pdr: f32,
+    ) -> Self {
+        Self {
+            id,
+            controller_recv,
+            packet_recv,
+            packet_send,
+            pdr,
+        }
+    }
+
+    fn run(&mut self) {
+        loop {
+            select! {
+                recv(self.controller_recv) -> command => {
+                    let Ok(command) = command else { return };
+                    if !self.handle_command(command) {
+                        return;
+                    }
+                }
+                recv(self.packet_recv) -> packet => {
+                    if packet.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A drone factory building a [`NullDrone`] whatever the implementation, with the same
+/// signature as `factory_drone`.
+pub fn null_drone_factory(
+    _drone_impl: DroneImpl,
+    id: NodeId,
+    controller_send: Sender<DroneEvent>,
+    controller_recv: Receiver<DroneCommand>,
+    packet_recv: Receiver<Packet>,
+    packet_send: HashMap<NodeId, Sender<Packet>>,
+    pdr: f32,
+) -> Box<dyn Drone + Send> {
+    Box::new(NullDrone::new(
+        id,
+        controller_send,
+        controller_recv,
+        packet_recv,
+        packet_send,
+        pdr,
+    ))
+}
+
+/// The neighbors of a client or a server stub, through which it sends packets along their
+/// source route.
+struct Router {
+    id: NodeId,
+    neighbors: HashMap<NodeId, Sender<Packet>>,
+}
+
+impl Router {
+    /// Returns `true` if the node is the destination of the packet.
+    fn is_destination(&self, packet: &Packet) -> bool {
+        let header = &packet.routing_header;
+        header.hops.last() == Some(&self.id) && header.hop_index + 1 == header.hops.len()
+    }
+
+    /// Sends a packet to the hop following the node, returning `false` if it is not a
+    /// neighbor.
+    fn send_next(&self, mut packet: Packet) -> bool {
+        packet.routing_header.hop_index += 1;
+        let next = packet
+            .routing_header
+            .hops
+            .get(packet.routing_header.hop_index);
+        match next.and_then(|id| self.neighbors.get(id)) {
+            Some(sender) => sender.send(packet).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// Runs the loop of a client or server stub until it is crashed or its channels are closed.
+///
+/// Pending commands are applied before every packet, so that a neighbor added just before a
+/// packet arrives is already known when the packet is routed.
+fn run_stub<S, C>(
+    stub: &mut S,
+    commands: &Receiver<C>,
+    packets: &Receiver<Packet>,
+    handle_command: fn(&mut S, C) -> bool,
+    handle_packet: fn(&mut S, Packet),
+) {
+    loop {
+        select! {
+            recv(commands) -> command => {
+                let Ok(command) = command else { return };
+                if !handle_command(stub, command) {
+                    return;
+                }
+            }
+            recv(packets) -> packet => {
+                let Ok(packet) = packet else { return };
+                while let Ok(command) = commands.try_recv() {
+                    if !handle_command(stub, command) {
+                        return;
+                    }
+                }
+                handle_packet(stub, packet);
+            }
+        }
+    }
+}
+
+/// A client sending pings along the source routes it is given, for smoke tests.
+///
+/// A packet delivered to the client with the client as its first hop is a ping: it is sent on
+/// to the second hop. A packet reaching the client as its destination is a reply.
+pub struct PingClient {
+    router: Router,
+    command_recv: Receiver<ClientCommand>,
+    packet_recv: Receiver<Packet>,
+    _event_send: Sender<ClientEvent>,
+    _gui_recv: Receiver<GuiClientMessage>,
+    _gui_send: Sender<ClientGuiMessage>,
+    replies: usize,
+}
+
+impl PingClient {
+    /// Returns the number of replies received by the client.
+    pub fn replies(&self) -> usize {
+        self.replies
+    }
+
+    /// Applies a command, returning `false` if the client must stop.
+    fn handle_command(&mut self, command: ClientCommand) -> bool {
+        match command {
+            ClientCommand::AddDrone(id, sender) => {
+                self.router.neighbors.insert(id, sender);
+            }
+            ClientCommand::RemoveDrone(id) => {
+                self.router.neighbors.remove(&id);
+            }
+            ClientCommand::Crash => return false,
+        }
+        true
+    }
+
+    fn handle_packet(&mut self, packet: Packet) {
+        let header = &packet.routing_header;
+        if self.router.is_destination(&packet) {
+            self.replies += 1;
+        } else if header.hop_index == 0 && header.hops.first() == Some(&self.router.id) {
+            self.router.send_next(packet);
+        }
+    }
+}
+
+impl RunNode for PingClient {
+    fn run(&mut self) {
+        let commands = self.command_recv.clone();
+        let packets = self.packet_recv.clone();
+        run_stub(
+            self,
+            &commands,
+            &packets,
+            Self::handle_command,
+            Self::handle_packet,
+        );
+    }
+}
+
+/// A server sending every packet it receives back to its source, along the reversed source
+/// route, for smoke tests.
+pub struct EchoServer {
+    router: Router,
+    command_recv: Receiver<ServerCommand>,
+    packet_recv: Receiver<Packet>,
+    _event_send: Sender<ServerEvent>,
+}
+
+impl EchoServer {
+    /// Applies a command, returning `false` if the server must stop.
+    fn handle_command(&mut self, command: ServerCommand) -> bool {
+        match command {
+            ServerCommand::AddDrone(id, sender) => {
+                self.router.neighbors.insert(id, sender);
+            }
+            ServerCommand::RemoveDrone(id) => {
+                self.router.neighbors.remove(&id);
+            }
+            ServerCommand::Crash => return false,
+        }
+        true
+    }
+
+    fn handle_packet(&mut self, mut packet: Packet) {
+        if self.router.is_destination(&packet) {
+            packet.routing_header.hops.reverse();
+            packet.routing_header.hop_index = 0;
+            self.router.send_next(packet);
+        }
+    }
+}
+
+impl RunNode for EchoServer {
+    fn run(&mut self) {
+        let commands = self.command_recv.clone();
+        let packets = self.packet_recv.clone();
+        run_stub(
+            self,
+            &commands,
+            &packets,
+            Self::handle_command,
+            Self::handle_packet,
+        );
+    }
+}
+
+/// A client factory building a [`PingClient`] whatever the type, with the signature of
+/// [`ClientFactory`](crate::init::ClientFactory).
+pub fn ping_client_factory(
+    id: NodeId,
+    _client_type: ClientType,
+    packet_recv: Receiver<Packet>,
+    command_recv: Receiver<ClientCommand>,
+    event_send: Sender<ClientEvent>,
+    gui_recv: Receiver<GuiClientMessage>,
+    gui_send: Sender<ClientGuiMessage>,
+) -> Box<dyn RunNode> {
+    Box::new(PingClient {
+        router: Router {
+            id,
+            neighbors: HashMap::new(),
+        },
+        command_recv,
+        packet_recv,
+        _event_send: event_send,
+        _gui_recv: gui_recv,
+        _gui_send: gui_send,
+        replies: 0,
+    })
+}
+
+/// A server factory building an [`EchoServer`] whatever the type, with the signature of
+/// [`ServerFactory`](crate::init::ServerFactory).
+pub fn echo_server_factory(
+    id: NodeId,
+    _server_type: ServerType,
+    command_recv: Receiver<ServerCommand>,
+    packet_recv: Receiver<Packet>,
+    event_send: Sender<ServerEvent>,
+) -> Box<dyn RunNode> {
+    Box::new(EchoServer {
+        router: Router {
+            id,
+            neighbors: HashMap::new(),
+        },
+        command_recv,
+        packet_recv,
+        _event_send: event_send,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use wg_2024::{
+        config::Config,
+        controller::DroneCommand,
+        drone::Drone,
+        network::SourceRoutingHeader,
+        packet::{Ack, Packet, PacketType},
+    };
+
+    use crate::init::{network_init_with_options, InitMode, InitOptions};
+    use crate::stub::{echo_server_factory, ping_client_factory, NullDrone};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3, 4]
+        pdr = 0.0
+
+        [[drone]]
+        id = 2
+        connected_node_ids = [1, 4]
+        pdr = 0.0
+
+        [[client]]
+        id = 3
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 4
+        connected_drone_ids = [1, 2]
+    "#;
+
+    #[test]
+    fn test_null_drone() {
+        let (sx_event, _rx_event) = crossbeam_channel::unbounded();
+        let (sx_command, rx_command) = crossbeam_channel::unbounded();
+        let (sx_packet, rx_packet) = crossbeam_channel::unbounded();
+        let (sx_neighbor, rx_neighbor) = crossbeam_channel::unbounded();
+        let mut drone = NullDrone::new(1, sx_event, rx_command, rx_packet, HashMap::new(), 0.1);
+
+        sx_command
+            .send(DroneCommand::AddSender(2, sx_neighbor))
+            .unwrap();
+        sx_command
+            .send(DroneCommand::SetPacketDropRate(0.5))
+            .unwrap();
+        sx_packet
+            .send(Packet {
+                pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+                routing_header: SourceRoutingHeader {
+                    hop_index: 0,
+                    hops: vec![1, 2],
+                },
+                session_id: 0,
+            })
+            .unwrap();
+        sx_command.send(DroneCommand::Crash).unwrap();
+
+        let drone = thread::spawn(move || {
+            drone.run();
+            drone
+        })
+        .join()
+        .unwrap();
+        assert_eq!(drone.id(), 1);
+        assert_eq!(drone.neighbors(), vec![2]);
+        assert_eq!(drone.pdr(), 0.5);
+        assert!(rx_neighbor.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_ping_echo() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let options = InitOptions {
+            mode: InitMode::Prebuilt,
+            client_factory: ping_client_factory,
+            server_factory: echo_server_factory,
+            packet_taps: true,
+            ..InitOptions::default()
+        };
+        let handle = network_init_with_options(&config, &options).handle;
+        let tap = handle.tap();
+
+        let ping = Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![3, 1, 4],
+            },
+            session_id: 7,
+        };
+        assert!(handle.send_packet(3, ping));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let reply = loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let tapped = tap
+                .recv_timeout(timeout)
+                .expect("no reply before the timeout");
+            if tapped.to == 3 && tapped.packet.routing_header.hop_index == 2 {
+                break tapped.packet;
+            }
+        };
+        handle.crash_all();
+
+        assert_eq!(reply.routing_header.hops, vec![4, 1, 3]);
+        assert_eq!(reply.session_id, 7);
+    }
+}
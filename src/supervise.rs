@@ -0,0 +1,124 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crossbeam_channel::{Receiver, Sender};
+use wg_2024::network::NodeId;
+
+/// A change to the state of the thread of a node, reported on
+/// [`crate::init::NetworkInitData::supervision`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisionEvent {
+    /// The implementation of the node panicked.
+    NodeCrashed(NodeId),
+    /// The node was built again after a panic, with its original channels.
+    NodeRespawned {
+        node: NodeId,
+        /// The number of respawns of the node so far, this one included.
+        respawns: usize,
+    },
+}
+
+/// What happens to a node whose implementation panics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SupervisionPolicy {
+    /// The panic is reported, and the thread of the node terminates.
+    #[default]
+    Report,
+    /// The panic is reported, and the node is built again by its factory with its original
+    /// channels, up to the given number of times.
+    ///
+    /// A respawned node starts over from its initial neighbors: the senders added and removed
+    /// by later commands, e.g. the ones adding the links in [`crate::init::InitMode::Commands`],
+    /// are not replayed.
+    Respawn { max: usize },
+}
+
+/// Runs the nodes, reporting their panics and respawning them according to the policy.
+#[derive(Debug, Clone)]
+pub(crate) struct Supervisor {
+    policy: SupervisionPolicy,
+    events: Sender<SupervisionEvent>,
+}
+
+impl Supervisor {
+    /// Returns a supervisor, and the receiver of its events.
+    pub(crate) fn new(policy: SupervisionPolicy) -> (Self, Receiver<SupervisionEvent>) {
+        let (events, receiver) = crossbeam_channel::unbounded();
+        (Self { policy, events }, receiver)
+    }
+
+    /// Runs a node on the current thread, catching its panics.
+    ///
+    /// Once the node is not respawned anymore, its last panic is resumed, so that the thread
+    /// is still reported as panicked by [`crate::handle::NetworkHandle::termination_report`].
+    ///
+    /// # Parameters
+    /// - `node`: The ID of the node.
+    /// - `run`: Builds the node from clones of its channels, and runs it.
+    pub(crate) fn run<F: FnMut()>(&self, node: NodeId, mut run: F) {
+        let mut respawns = 0;
+        #[cfg(feature = "telemetry")]
+        crate::telemetry::node_lifecycle(node, "started");
+        loop {
+            let Err(payload) = panic::catch_unwind(AssertUnwindSafe(&mut run)) else {
+                return;
+            };
+            let _ = self.events.send(SupervisionEvent::NodeCrashed(node));
+            #[cfg(feature = "telemetry")]
+            crate::telemetry::node_lifecycle(node, "panicked");
+            match self.policy {
+                SupervisionPolicy::Respawn { max } if respawns < max => {
+                    respawns += 1;
+                    log::warn!(node = node; "Node panicked, respawning it ({}/{})", respawns, max);
+                    #[cfg(feature = "telemetry")]
+                    crate::telemetry::node_lifecycle(node, "respawned");
+                    let respawned = SupervisionEvent::NodeRespawned { node, respawns };
+                    let _ = self.events.send(respawned);
+                }
+                _ => panic::resume_unwind(payload),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{panic, sync::mpsc};
+
+    use crate::supervise::{SupervisionEvent, SupervisionPolicy, Supervisor};
+
+    #[test]
+    fn test_supervisor() {
+        let (supervisor, events) = Supervisor::new(SupervisionPolicy::Respawn { max: 2 });
+        let (sender, runs) = mpsc::channel();
+        let result = panic::catch_unwind(|| {
+            supervisor.run(3, || {
+                sender.send(()).unwrap();
+                panic!("node 3 failed");
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(runs.try_iter().count(), 3);
+        let events: Vec<SupervisionEvent> = events.try_iter().collect();
+        assert_eq!(
+            events,
+            [
+                SupervisionEvent::NodeCrashed(3),
+                SupervisionEvent::NodeRespawned {
+                    node: 3,
+                    respawns: 1
+                },
+                SupervisionEvent::NodeCrashed(3),
+                SupervisionEvent::NodeRespawned {
+                    node: 3,
+                    respawns: 2
+                },
+                SupervisionEvent::NodeCrashed(3),
+            ]
+        );
+
+        // A node returning normally is not reported.
+        let (supervisor, events) = Supervisor::new(SupervisionPolicy::Report);
+        supervisor.run(4, || {});
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use std::io::{self, Write};
+
+use wg_2024::config::Config;
+
+use crate::batch::run_config;
+use crate::generator::generate_small_world;
+use crate::handle::{RunSummary, ShutdownPolicy, ThreadState};
+use crate::init::InitOptions;
+use crate::scenario::Scenario;
+use crate::validate::ValidatedConfig;
+
+/// The values taken by the parameters of a [`sweep`].
+///
+/// Every combination of the values is a variant of the base configuration; an empty list
+/// keeps the parameter of the base configuration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SweepGrid {
+    /// The packet drop rates, given to every drone of a variant.
+    pub pdr: Vec<f32>,
+    /// The numbers of drones; a variant with a different number of drones is generated as a
+    /// small world with the clients and servers of the base configuration.
+    pub drones: Vec<usize>,
+}
+
+/// Options of [`sweep`].
+#[derive(Clone, Copy, Debug)]
+pub struct SweepOptions {
+    /// The options used to initialize every network; the seed is replaced by the seed of the
+    /// sweep.
+    pub init: InitOptions,
+    /// How every network is torn down once its scenario is over.
+    pub shutdown: ShutdownPolicy,
+    /// The seed shared by every variant, used to generate the topologies and to record the
+    /// transcript of the initializations.
+    pub seed: u64,
+    /// The mean drone degree of the generated topologies; it must be even.
+    pub degree: usize,
+    /// The rewiring probability of the generated topologies.
+    pub beta: f64,
+}
+
+impl Default for SweepOptions {
+    fn default() -> Self {
+        Self {
+            init: InitOptions::default(),
+            shutdown: ShutdownPolicy::default(),
+            seed: 0,
+            degree: 4,
+            beta: 0.1,
+        }
+    }
+}
+
+/// The run of a variant of a [`sweep`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepRow {
+    /// The number of drones of the variant.
+    pub drones: usize,
+    /// The packet drop rate of every drone, or `None` if the rates of the base configuration
+    /// were kept.
+    pub pdr: Option<f32>,
+    /// The summary of the run, or an error if the variant could not be generated, validated
+    /// or initialized.
+    pub result: Result<RunSummary, String>,
+}
+
+/// The runs of every variant of a [`sweep`], in grid order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SweepTable {
+    pub rows: Vec<SweepRow>,
+}
+
+impl SweepTable {
+    /// Writes the table as CSV, with a header and one row per variant.
+    ///
+    /// The columns are `drones`, `pdr`, `uptime_ms`, the number of node threads `running`,
+    /// `returned` and `panicked` after the teardown, and the `error` of the variant; the
+    /// columns which do not apply to a row are empty.
+    ///
+    /// # Parameters
+    /// - `writer`: The destination of the CSV.
+    pub fn to_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "drones,pdr,uptime_ms,running,returned,panicked,error"
+        )?;
+        for row in &self.rows {
+            let pdr = row.pdr.map(|pdr| pdr.to_string()).unwrap_or_default();
+            match &row.result {
+                Ok(summary) => {
+                    let count = |state| summary.termination.with_state(state).len();
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{},{},",
+                        row.drones,
+                        pdr,
+                        summary.uptime.as_millis(),
+                        count(ThreadState::Running),
+                        count(ThreadState::Returned),
+                        count(ThreadState::Panicked)
+                    )?;
+                }
+                Err(err) => {
+                    let err = err.replace(['"', '\n'], " ");
+                    writeln!(writer, "{},{},,,,,\"{}\"", row.drones, pdr, err)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs the same scenario, with the same seed, on every variant of a base configuration, and
+/// collects the results into a comparison table.
+///
+/// Variants are run one at a time, by number of drones and then by packet drop rate.
+///
+/// # Parameters
+/// - `base`: The base configuration.
+/// - `grid`: The values taken by the parameters.
+/// - `scenario`: Builds the scenario to run on the network of a variant.
+/// - `options`: The options of the sweep.
+///
+/// Returns the table with a row per variant.
+pub fn sweep<S>(base: &Config, grid: &SweepGrid, scenario: S, options: &SweepOptions) -> SweepTable
+where
+    S: Fn(&Config) -> Scenario,
+{
+    let init = InitOptions {
+        seed: Some(options.seed),
+        ..options.init
+    };
+    let drones = match grid.drones.as_slice() {
+        [] => vec![base.drone.len()],
+        drones => drones.to_vec(),
+    };
+    let pdrs = match grid.pdr.as_slice() {
+        [] => vec![None],
+        pdrs => pdrs.iter().copied().map(Some).collect(),
+    };
+
+    let mut rows = Vec::with_capacity(drones.len() * pdrs.len());
+    for n_drones in drones {
+        let topology = if grid.drones.is_empty() {
+            Ok(base.clone())
+        } else {
+            generate_small_world(
+                n_drones,
+                options.degree,
+                options.beta,
+                base.client.len(),
+                base.server.len(),
+                options.seed,
+            )
+        };
+        for &pdr in &pdrs {
+            let result = topology.clone().and_then(|mut config| {
+                if let Some(pdr) = pdr {
+                    config.drone.iter_mut().for_each(|drone| drone.pdr = pdr);
+                }
+                let config = ValidatedConfig::new(config)?;
+                run_config(&config, &scenario, &init, options.shutdown)
+            });
+            rows.push(SweepRow {
+                drones: n_drones,
+                pdr,
+                result,
+            });
+        }
+    }
+    SweepTable { rows }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generator::generate_small_world;
+    use crate::init::InitOptions;
+    use crate::scenario::Scenario;
+    use crate::stub::{echo_server_factory, null_drone_factory, ping_client_factory};
+    use crate::sweep::{sweep, SweepGrid, SweepOptions};
+
+    #[test]
+    fn test_sweep() {
+        let base = generate_small_world(6, 2, 0.0, 1, 1, 1).unwrap();
+        let grid = SweepGrid {
+            pdr: vec![0.0, 0.5, 2.0],
+            drones: vec![6, 8],
+        };
+        let options = SweepOptions {
+            init: InitOptions {
+                drone_factory: null_drone_factory,
+                client_factory: ping_client_factory,
+                server_factory: echo_server_factory,
+                ..InitOptions::default()
+            },
+            degree: 2,
+            ..SweepOptions::default()
+        };
+
+        let table = sweep(&base, &grid, Scenario::crash_critical_path, &options);
+        assert_eq!(table.rows.len(), 6);
+        assert_eq!(table.rows[3].drones, 8);
+        assert_eq!(table.rows[3].pdr, Some(0.0));
+        let summary = table.rows[4].result.as_ref().unwrap();
+        assert_eq!(summary.termination.nodes.len(), 10);
+        assert!(table.rows[5].result.is_err());
+
+        let mut csv = Vec::new();
+        table.to_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 7);
+        assert!(csv.lines().nth(5).unwrap().starts_with("8,0.5,"));
+        assert!(csv.lines().nth(6).unwrap().starts_with("8,2,,,,,\""));
+    }
+}
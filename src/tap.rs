@@ -0,0 +1,293 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{select, Receiver, Sender};
+use wg_2024::{network::NodeId, packet::Packet};
+
+use crate::events::Hub;
+use crate::handle::Relays;
+use crate::topology::TopologyUpdate;
+
+/// A packet observed while being delivered to a node.
+#[derive(Debug, Clone)]
+pub struct TappedPacket {
+    /// The ID of the node receiving the packet.
+    pub to: NodeId,
+    /// When the packet was observed.
+    pub at: Instant,
+    /// The packet, as sent to the node.
+    pub packet: Packet,
+}
+
+/// Which of the packets delivered to a node are published by its tap, bounding the cost of
+/// observing a busy network; every packet is delivered to the node anyway.
+///
+/// The default sampling publishes every packet; checkers relying on every packet, e.g. the
+/// [`crate::conformance::ConformanceMonitor`], may report spurious violations under any other sampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TapSampling {
+    /// Publishes one packet out of every `every` delivered to a node; `0` is the same as `1`.
+    pub every: usize,
+    /// The maximum number of packets published by the tap of a node every second, if any.
+    pub max_per_second: Option<usize>,
+    /// The packets published around a crash, whatever the sampling.
+    pub crash_capture: CrashCapture,
+}
+
+impl Default for TapSampling {
+    fn default() -> Self {
+        Self {
+            every: 1,
+            max_per_second: None,
+            crash_capture: CrashCapture::default(),
+        }
+    }
+}
+
+/// The packets published by every tap when a node of the network is told to crash, in
+/// addition to the sampled ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CrashCapture {
+    /// How many of the last packets skipped by the sampling before the crash are published.
+    pub head: usize,
+    /// How many of the packets delivered after the crash are published.
+    pub tail: usize,
+}
+
+/// How many packets the tap of a node observed and published, see
+/// [`crate::handle::NetworkHandle::tap_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TapStats {
+    /// The ID of the node.
+    pub node: NodeId,
+    /// The packets delivered to the node.
+    pub observed: usize,
+    /// The packets published to the subscribers.
+    pub published: usize,
+}
+
+/// The counters of the tap of a node, updated by its thread.
+#[derive(Debug, Default)]
+pub(crate) struct TapCounters {
+    observed: AtomicUsize,
+    published: AtomicUsize,
+}
+
+impl TapCounters {
+    /// Returns the current value of the counters.
+    pub(crate) fn stats(&self, node: NodeId) -> TapStats {
+        TapStats {
+            node,
+            observed: self.observed.load(Ordering::Relaxed),
+            published: self.published.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Decides which packets a tap publishes.
+struct Sampler {
+    sampling: TapSampling,
+    /// The packets observed so far.
+    observed: usize,
+    /// When the current rate window started, and how many packets it published.
+    window: Option<(Instant, usize)>,
+    /// The last packets skipped by the sampling, at most `crash_capture.head`.
+    head: VecDeque<TappedPacket>,
+    /// How many packets are still to be published after the last crash.
+    tail: usize,
+}
+
+impl Sampler {
+    fn new(sampling: TapSampling) -> Self {
+        Self {
+            sampling,
+            observed: 0,
+            window: None,
+            head: VecDeque::with_capacity(sampling.crash_capture.head),
+            tail: 0,
+        }
+    }
+
+    /// Observes a packet, returning a copy of it if it is to be published; the packet is only
+    /// copied if it is published or kept for a crash capture.
+    fn observe(&mut self, to: NodeId, at: Instant, packet: &Packet) -> Option<TappedPacket> {
+        self.observed += 1;
+        let tapped = || TappedPacket {
+            to,
+            at,
+            packet: packet.clone(),
+        };
+        if self.tail > 0 {
+            self.tail -= 1;
+            return Some(tapped());
+        }
+        if self.sampled(at) {
+            return Some(tapped());
+        }
+        if self.sampling.crash_capture.head > 0 {
+            if self.head.len() == self.sampling.crash_capture.head {
+                self.head.pop_front();
+            }
+            self.head.push_back(tapped());
+        }
+        None
+    }
+
+    /// Returns `true` if the current packet, observed at the given time, passes the sampling.
+    fn sampled(&mut self, at: Instant) -> bool {
+        if !(self.observed - 1).is_multiple_of(self.sampling.every.max(1)) {
+            return false;
+        }
+        let Some(max) = self.sampling.max_per_second else {
+            return true;
+        };
+        let window = match &mut self.window {
+            Some(window) if at.duration_since(window.0) < Duration::from_secs(1) => window,
+            window => window.insert((at, 0)),
+        };
+        if window.1 >= max {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+
+    /// Reacts to a crash, returning the skipped packets to publish.
+    fn crash(&mut self) -> Vec<TappedPacket> {
+        self.tail = self.sampling.crash_capture.tail;
+        self.head.drain(..).collect()
+    }
+}
+
+/// Spawns a thread forwarding the packets sent to a node, publishing a copy of the sampled
+/// ones to the subscribers of the hub.
+///
+/// Every node gets its own tap, placed between the senders held by its neighbors (and by the
+/// simulation controller) and its own packet queue. The thread terminates when every sender
+/// is dropped, the node drops its receiver, or the relays of the network are stopped.
+///
+/// # Parameters
+/// - `node`: The ID of the node.
+/// - `from`: The channel on which the packets for the node are sent.
+/// - `to`: The packet queue of the node.
+/// - `hub`: The hub publishing the packets to the subscribers.
+/// - `sampling`: Which packets are published.
+/// - `updates`: The topology updates, telling when a node crashes, if the sampling captures
+///   packets around crashes.
+/// - `relays`: The relays of the network, stopping this one on shutdown.
+///
+/// Returns the counters of the tap.
+pub(crate) fn spawn_packet_tap(
+    node: NodeId,
+    from: Receiver<Packet>,
+    to: Sender<Packet>,
+    hub: Hub<TappedPacket>,
+    sampling: TapSampling,
+    updates: Option<Receiver<TopologyUpdate>>,
+    relays: &Relays,
+) -> Arc<TapCounters> {
+    let counters = Arc::new(TapCounters::default());
+    let thread_counters = Arc::clone(&counters);
+    relays.spawn(move |stopped| {
+        let mut sampler = Sampler::new(sampling);
+        let mut updates = updates.unwrap_or_else(crossbeam_channel::never);
+        let publish = |tapped: TappedPacket| {
+            thread_counters.published.fetch_add(1, Ordering::Relaxed);
+            hub.publish(tapped);
+        };
+        loop {
+            let packet = select! {
+                recv(from) -> packet => packet.ok(),
+                recv(stopped) -> _ => from.try_recv().ok(),
+                recv(updates) -> update => {
+                    match update {
+                        Ok(TopologyUpdate::Crashed { .. }) => {
+                            sampler.crash().into_iter().for_each(publish)
+                        }
+                        Ok(_) => {}
+                        Err(_) => updates = crossbeam_channel::never(),
+                    }
+                    continue;
+                }
+            };
+            let Some(packet) = packet else {
+                break;
+            };
+            thread_counters.observed.fetch_add(1, Ordering::Relaxed);
+            if let Some(tapped) = sampler.observe(node, Instant::now(), &packet) {
+                publish(tapped);
+            }
+            if to.send(packet).is_err() {
+                break;
+            }
+        }
+    });
+    counters
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use wg_2024::{
+        network::SourceRoutingHeader,
+        packet::{Ack, Packet, PacketType},
+    };
+
+    use crate::tap::{CrashCapture, Sampler, TapSampling};
+
+    fn ack(session_id: u64) -> Packet {
+        Packet {
+            pack_type: PacketType::Ack(Ack { fragment_index: 0 }),
+            routing_header: SourceRoutingHeader {
+                hop_index: 0,
+                hops: vec![2, 1],
+            },
+            session_id,
+        }
+    }
+
+    #[test]
+    fn test_tap_sampling() {
+        let start = Instant::now();
+        let session_ids = |sampler: &mut Sampler, range: std::ops::Range<u64>| -> Vec<u64> {
+            range
+                .filter_map(|id| sampler.observe(1, start, &ack(id)))
+                .map(|tapped| tapped.packet.session_id)
+                .collect()
+        };
+
+        let mut sampler = Sampler::new(TapSampling::default());
+        assert_eq!(session_ids(&mut sampler, 0..3), [0, 1, 2]);
+
+        let mut sampler = Sampler::new(TapSampling {
+            every: 3,
+            crash_capture: CrashCapture { head: 2, tail: 2 },
+            ..TapSampling::default()
+        });
+        assert_eq!(session_ids(&mut sampler, 0..7), [0, 3, 6]);
+        let head: Vec<u64> = sampler
+            .crash()
+            .iter()
+            .map(|tapped| tapped.packet.session_id)
+            .collect();
+        assert_eq!(head, [4, 5]);
+        assert_eq!(session_ids(&mut sampler, 7..12), [7, 8, 9]);
+
+        // The rate cap restarts every second.
+        let mut sampler = Sampler::new(TapSampling {
+            max_per_second: Some(2),
+            ..TapSampling::default()
+        });
+        assert_eq!(session_ids(&mut sampler, 0..4), [0, 1]);
+        let later = sampler.observe(1, start + Duration::from_secs(1), &ack(4));
+        assert!(later.is_some());
+    }
+}
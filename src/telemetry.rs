@@ -0,0 +1,233 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::init::InitTimings;
+
+/// The name of the counter of the node lifecycle events, told apart by the
+/// [`ATTR_LIFECYCLE_EVENT`] attribute.
+pub const METRIC_NODE_LIFECYCLE: &str = "network.node.lifecycle";
+
+/// The attribute holding the ID of a node.
+pub const ATTR_NODE_ID: &str = "network.node.id";
+
+/// The attribute holding the lifecycle event of a node: `started`, `panicked`, `respawned` or
+/// `crash_requested`.
+pub const ATTR_LIFECYCLE_EVENT: &str = "network.node.lifecycle.event";
+
+/// The attributes of a span or of a metric point, as `(key, value)` pairs.
+///
+/// Keys follow the naming of the OpenTelemetry semantic conventions, e.g. `network.node.id`, so
+/// that a sink can hand them over to an OpenTelemetry tracer or meter as they are.
+pub type TelemetryAttributes = Vec<(&'static str, String)>;
+
+/// A completed unit of work, e.g. the validation of a configuration, a phase of the
+/// initialization or a step of a scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    /// The name of the span, e.g. `network.validate`.
+    pub name: &'static str,
+    /// When the work started, on the wall clock.
+    pub start: SystemTime,
+    /// How long the work took.
+    pub duration: Duration,
+    /// The attributes of the span.
+    pub attributes: TelemetryAttributes,
+}
+
+/// Receives the spans and metrics reported by the crate, e.g. to export them to a collector
+/// aggregating the simulations run on shared machines.
+///
+/// The spans are:
+/// - `network.validate`: the validation of a configuration, with the number of nodes and the
+///   outcome, i.e. `ok` or the violated [`crate::validate::ErrorCode`].
+/// - `network.init`, and its phases `network.init.plan`, `network.init.drones`,
+///   `network.init.clients`, `network.init.servers` and `network.init.links`, as measured by
+///   [`InitTimings`].
+/// - `network.scenario.step`: a step of a [`crate::scenario::Scenario`], with the name of the
+///   scenario, the action and the target node.
+///
+/// The node lifecycle is counted by the [`METRIC_NODE_LIFECYCLE`] counter.
+///
+/// The sink is called on the threads doing the work, node threads included, so it should hand
+/// the data over quickly.
+pub trait TelemetrySink: Send + Sync {
+    /// Records a completed span.
+    fn span(&self, span: &Span);
+
+    /// Adds to a monotonic counter.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the counter.
+    /// - `increment`: The amount added.
+    /// - `attributes`: The attributes of the point.
+    fn counter(&self, name: &'static str, increment: u64, attributes: &TelemetryAttributes);
+}
+
+/// The sink installed by [`set_sink`], if any.
+static SINK: RwLock<Option<Arc<dyn TelemetrySink>>> = RwLock::new(None);
+
+/// Installs the sink receiving the telemetry of every network of the process, replacing the
+/// previous one.
+pub fn set_sink(sink: Arc<dyn TelemetrySink>) {
+    *SINK.write().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+}
+
+/// Removes the installed sink, if any; the telemetry is then discarded.
+pub fn clear_sink() {
+    *SINK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Returns the installed sink, if any.
+fn sink() -> Option<Arc<dyn TelemetrySink>> {
+    SINK.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The start of a span, measured on both the wall clock and the monotonic clock.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SpanStart {
+    wall: SystemTime,
+    monotonic: Instant,
+}
+
+impl SpanStart {
+    /// Starts a span now.
+    pub(crate) fn now() -> Self {
+        Self {
+            wall: SystemTime::now(),
+            monotonic: Instant::now(),
+        }
+    }
+
+    /// Ends the span now, reporting it to the installed sink, if any.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the span.
+    /// - `attributes`: Builds the attributes of the span; only called if a sink is installed.
+    pub(crate) fn end(self, name: &'static str, attributes: impl FnOnce() -> TelemetryAttributes) {
+        let Some(sink) = sink() else {
+            return;
+        };
+        sink.span(&Span {
+            name,
+            start: self.wall,
+            duration: self.monotonic.elapsed(),
+            attributes: attributes(),
+        });
+    }
+}
+
+/// Counts a lifecycle event of a node.
+///
+/// # Parameters
+/// - `node`: The ID of the node.
+/// - `event`: The event, see [`ATTR_LIFECYCLE_EVENT`].
+pub(crate) fn node_lifecycle(node: wg_2024::network::NodeId, event: &'static str) {
+    let Some(sink) = sink() else {
+        return;
+    };
+    let attributes = vec![
+        (ATTR_NODE_ID, node.to_string()),
+        (ATTR_LIFECYCLE_EVENT, event.to_string()),
+    ];
+    sink.counter(METRIC_NODE_LIFECYCLE, 1, &attributes);
+}
+
+/// Reports the span of an initialization which just completed, and of its phases.
+///
+/// # Parameters
+/// - `timings`: The time spent in each phase.
+/// - `nodes`: The number of nodes of the network.
+pub(crate) fn init_completed(timings: &InitTimings, nodes: usize) {
+    let Some(sink) = sink() else {
+        return;
+    };
+    let start = SystemTime::now()
+        .checked_sub(timings.total)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let phases = [
+        ("network.init.plan", timings.plan),
+        ("network.init.drones", timings.drones),
+        ("network.init.clients", timings.clients),
+        ("network.init.servers", timings.servers),
+        ("network.init.links", timings.links),
+    ];
+    let mut phase_start = start;
+    for (name, duration) in phases {
+        sink.span(&Span {
+            name,
+            start: phase_start,
+            duration,
+            attributes: Vec::new(),
+        });
+        phase_start += duration;
+    }
+    sink.span(&Span {
+        name: "network.init",
+        start,
+        duration: timings.total,
+        attributes: vec![("network.nodes", nodes.to_string())],
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::telemetry::{
+        clear_sink, set_sink, Span, TelemetryAttributes, TelemetrySink, ATTR_LIFECYCLE_EVENT,
+        METRIC_NODE_LIFECYCLE,
+    };
+    use crate::validate::network_validate_str;
+    use crate::{examples, init::network_init};
+
+    #[derive(Default)]
+    struct Recorder {
+        spans: Mutex<Vec<Span>>,
+        counters: Mutex<Vec<(&'static str, TelemetryAttributes)>>,
+    }
+
+    impl TelemetrySink for Recorder {
+        fn span(&self, span: &Span) {
+            self.spans.lock().unwrap().push(span.clone());
+        }
+
+        fn counter(&self, name: &'static str, _increment: u64, attributes: &TelemetryAttributes) {
+            self.counters
+                .lock()
+                .unwrap()
+                .push((name, attributes.clone()));
+        }
+    }
+
+    #[test]
+    fn test_telemetry() {
+        let recorder = Arc::new(Recorder::default());
+        set_sink(recorder.clone());
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let data = network_init(&config);
+        data.handle.crash(2);
+
+        data.handle.drain(Duration::from_secs(1));
+        clear_sink();
+
+        // Other tests may report to the sink meanwhile, so only the presence is checked.
+        let spans = recorder.spans.lock().unwrap().clone();
+        let span = |name: &str| spans.iter().find(|span| span.name == name);
+        assert!(spans.iter().any(|span| span.name == "network.validate"
+            && span
+                .attributes
+                .contains(&("network.validation.outcome", "ok".into()))));
+        assert!(span("network.init.links").is_some());
+        assert!(span("network.init").is_some());
+        let counters = recorder.counters.lock().unwrap().clone();
+        assert!(counters.iter().any(|(name, attributes)| {
+            *name == METRIC_NODE_LIFECYCLE
+                && attributes.contains(&(ATTR_LIFECYCLE_EVENT, "crash_requested".into()))
+        }));
+    }
+}
@@ -0,0 +1,259 @@
+use std::{collections::HashMap, fs, iter::Peekable, str::Chars};
+
+use wg_2024::config::Config;
+
+use crate::validate::network_validate_str;
+
+/// Name of the table of a template which defines its variables.
+const VARS_TABLE: &str = "[vars]";
+
+/// Reads a configuration template, renders it, and validates the resulting configuration.
+///
+/// See [`render`] for the template syntax.
+///
+/// # Parameters
+/// - `file_path`: The path of the template.
+/// - `vars`: Variables overriding the ones defined by the template.
+///
+/// Returns the configuration, as `Config`, if the template renders to a valid configuration,
+/// an error otherwise.
+pub fn network_validate_template(
+    file_path: &str,
+    vars: &HashMap<String, f64>,
+) -> Result<Config, String> {
+    let template = fs::read_to_string(file_path)
+        .map_err(|_| "Unable to read configuration file".to_string())?;
+    network_validate_str(&render(&template, vars)?)
+}
+
+/// Renders a configuration template into TOML.
+///
+/// Every `{{ expr }}` is replaced by the value of an arithmetic expression over numbers and
+/// variables, with `+`, `-`, `*`, `/` and parentheses. A quoted string which only holds a
+/// placeholder, such as `pdr = "{{ base_pdr * 2 }}"`, is replaced by the number itself.
+///
+/// Variables are defined, one per line, in a `[vars]` table, which is removed from the output;
+/// a variable may be defined by an expression over the variables defined before it. Variables
+/// supplied by the caller override the ones of the template.
+///
+/// # Parameters
+/// - `template`: The contents of the template.
+/// - `vars`: Variables overriding the ones defined by the template.
+///
+/// Returns the rendered TOML, or an error naming the offending expression.
+pub fn render(template: &str, vars: &HashMap<String, f64>) -> Result<String, String> {
+    let mut scope = vars.clone();
+    let mut output = String::with_capacity(template.len());
+    let mut in_vars = false;
+    for line in template.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_vars = trimmed == VARS_TABLE;
+        }
+        if in_vars {
+            if let Some((name, value)) = trimmed.split_once('=') {
+                let name = name.trim().to_string();
+                let value = evaluate(unquote(value.trim()), &scope)?;
+                scope.entry(name).or_insert(value);
+            }
+            continue;
+        }
+        output.push_str(&substitute(line, &scope)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Strips the quotes and the placeholder braces around a variable definition, if any.
+fn unquote(value: &str) -> &str {
+    let value = value.trim_matches('"').trim();
+    value
+        .strip_prefix("{{")
+        .and_then(|value| value.strip_suffix("}}"))
+        .unwrap_or(value)
+}
+
+/// Replaces the placeholders of a line with their values.
+fn substitute(line: &str, scope: &HashMap<String, f64>) -> Result<String, String> {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .map(|end| start + end)
+            .ok_or_else(|| format!("Unterminated placeholder in line: {}", line.trim()))?;
+        let value = format_number(evaluate(&rest[start + 2..end], scope)?);
+        let quoted = rest[..start].ends_with('"') && rest[end + 2..].starts_with('"');
+        if quoted {
+            output.push_str(&rest[..start - 1]);
+            output.push_str(&value);
+            rest = &rest[end + 3..];
+        } else {
+            output.push_str(&rest[..start]);
+            output.push_str(&value);
+            rest = &rest[end + 2..];
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Formats a number as a TOML integer if it is integral, as a TOML float otherwise.
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Evaluates an arithmetic expression.
+fn evaluate(expression: &str, scope: &HashMap<String, f64>) -> Result<f64, String> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+        scope,
+    };
+    let value = parser.expression();
+    parser.skip_whitespace();
+    match (value, parser.chars.peek()) {
+        (Ok(value), None) if value.is_finite() => Ok(value),
+        (Ok(_), None) => Err(format!("Expression `{}` is not finite", expression.trim())),
+        (Ok(_), Some(c)) => Err(format!(
+            "Unexpected `{}` in expression `{}`",
+            c,
+            expression.trim()
+        )),
+        (Err(e), _) => Err(format!("{} in expression `{}`", e, expression.trim())),
+    }
+}
+
+/// Recursive descent parser of arithmetic expressions.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    scope: &'a HashMap<String, f64>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    /// Parses a sum of terms.
+    fn expression(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('+') => {
+                    self.chars.next();
+                    value += self.term()?;
+                }
+                Some('-') => {
+                    self.chars.next();
+                    value -= self.term()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// Parses a product of factors.
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.factor()?;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('*') => {
+                    self.chars.next();
+                    value *= self.factor()?;
+                }
+                Some('/') => {
+                    self.chars.next();
+                    value /= self.factor()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// Parses a number, a variable, a negated factor or a parenthesized expression.
+    fn factor(&mut self) -> Result<f64, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('-') => {
+                self.chars.next();
+                Ok(-self.factor()?)
+            }
+            Some('(') => {
+                self.chars.next();
+                let value = self.expression()?;
+                self.skip_whitespace();
+                match self.chars.next() {
+                    Some(')') => Ok(value),
+                    _ => Err("Missing `)`".to_string()),
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(c);
+                }
+                number
+                    .parse()
+                    .map_err(|_| format!("Invalid number `{}`", number))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                self.scope
+                    .get(&name)
+                    .copied()
+                    .ok_or_else(|| format!("Undefined variable `{}`", name))
+            }
+            Some(c) => Err(format!("Unexpected `{}`", c)),
+            None => Err("Unexpected end".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::template::render;
+
+    #[test]
+    fn test_render() {
+        let template = "\
+[vars]
+base_pdr = 0.1
+first = \"{{ base_pdr * 10 }}\"
+
+[[drone]]
+id = {{ first }}
+pdr = \"{{ base_pdr * 2 }}\"
+name = \"drone {{ (first + 1) * 2 }}\"
+";
+        let rendered = render(template, &HashMap::new()).unwrap();
+        assert_eq!(
+            rendered,
+            "[[drone]]\nid = 1\npdr = 0.2\nname = \"drone 4\"\n"
+        );
+
+        let vars = HashMap::from([("base_pdr".to_string(), 0.25)]);
+        let rendered = render(template, &vars).unwrap();
+        assert!(rendered.contains("pdr = 0.5\n"));
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let vars = HashMap::new();
+        assert!(render("id = {{ missing }}", &vars).is_err());
+        assert!(render("id = {{ 1 + }}", &vars).is_err());
+        assert!(render("id = {{ (1 + 2 }}", &vars).is_err());
+        assert!(render("id = {{ 1 / 0 }}", &vars).is_err());
+        assert!(render("id = {{ 1", &vars).is_err());
+    }
+}
@@ -0,0 +1,688 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use fixedbitset::FixedBitSet;
+use rust_roveri_api::{ClientCommand, NodeType, ServerCommand};
+use wg_2024::{config::Config, controller::DroneCommand, network::NodeId};
+
+use crate::analysis::{config_links, Link};
+use crate::events::Hub;
+use crate::handle::{relay_recv, relay_send, Relays};
+use crate::index::NodeIndex;
+use crate::model::{Role, Topology};
+
+/// A change to the topology of a running network, derived from a command sent to a node.
+///
+/// The nodes of a network are fixed at initialization, so nodes only leave the topology by
+/// crashing. Links are directed: a link from `node` to `neighbor` means that `node` can send
+/// packets to `neighbor`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TopologyUpdate {
+    /// A node was given a channel to a neighbor.
+    LinkAdded { node: NodeId, neighbor: NodeId },
+    /// A node was told to drop its channel to a neighbor.
+    LinkRemoved { node: NodeId, neighbor: NodeId },
+    /// The packet drop rate of a drone was changed.
+    PdrChanged { node: NodeId, pdr: f32 },
+    /// A node was told to crash.
+    Crashed { node: NodeId },
+}
+
+/// How a topology graph departs from the links set by the commands sent to the nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DivergenceKind {
+    /// The link was added by a command, but its bit is not set in the topology.
+    MissingInTopology,
+    /// The link was never added or was removed by a command, but its bit is set in the
+    /// topology.
+    StaleInTopology,
+}
+
+/// A directed link on which a topology graph departs from the links set by the commands sent
+/// to the nodes, as reported by [`crate::handle::NetworkHandle::reconcile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LinkDivergence {
+    /// The node which can, or cannot, send packets to the neighbor.
+    pub node: NodeId,
+    /// The neighbor.
+    pub neighbor: NodeId,
+    /// How the topology departs from the commands.
+    pub kind: DivergenceKind,
+}
+
+/// The state of the topology of a network at some point of a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologySnapshot {
+    /// The nodes which were not told to crash.
+    pub alive: BTreeSet<NodeId>,
+    /// The directed links, as `(node, neighbor)`.
+    pub links: BTreeSet<(NodeId, NodeId)>,
+}
+
+/// A change to the topology of a network, with the time at which it was applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineDelta {
+    /// The time elapsed since the network was initialized.
+    pub at: Duration,
+    /// The change.
+    pub update: TopologyUpdate,
+}
+
+/// The evolution of the topology of a network over a run, as returned by
+/// [`crate::handle::NetworkHandle::timeline`].
+///
+/// Only the updates which changed the topology are recorded, e.g. the commands adding the
+/// initial links are not, as the links are already part of the initial snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timeline {
+    /// The topology of the network when it was initialized.
+    pub initial: TopologySnapshot,
+    /// The changes to the topology, in the order in which they were applied.
+    pub deltas: Vec<TimelineDelta>,
+}
+
+impl Timeline {
+    /// Returns the topology at the given time, by applying every delta up to it to the
+    /// initial snapshot.
+    ///
+    /// # Parameters
+    /// - `at`: The time elapsed since the network was initialized.
+    pub fn snapshot_at(&self, at: Duration) -> TopologySnapshot {
+        let mut snapshot = self.initial.clone();
+        for delta in self.deltas.iter().take_while(|delta| delta.at <= at) {
+            match delta.update {
+                TopologyUpdate::LinkAdded { node, neighbor } => {
+                    snapshot.links.insert((node, neighbor));
+                }
+                TopologyUpdate::LinkRemoved { node, neighbor } => {
+                    snapshot.links.remove(&(node, neighbor));
+                }
+                TopologyUpdate::PdrChanged { .. } => {}
+                TopologyUpdate::Crashed { node } => {
+                    snapshot.alive.remove(&node);
+                }
+            }
+        }
+        snapshot
+    }
+
+    /// Writes the timeline as JSON, for the GUI to scrub through after the run.
+    ///
+    /// The document has an `initial` object, with the `alive` nodes and the directed `links`
+    /// as `[node, neighbor]` pairs, and a `deltas` array, whose objects have the time `at_us`
+    /// in microseconds, a `type` among `link_added`, `link_removed`, `pdr_changed` and
+    /// `crashed`, the `node`, and the `neighbor` or `pdr` where relevant.
+    ///
+    /// # Parameters
+    /// - `writer`: The destination of the JSON.
+    pub fn to_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let alive: Vec<String> = self.initial.alive.iter().map(u8::to_string).collect();
+        let links: Vec<String> = self
+            .initial
+            .links
+            .iter()
+            .map(|(node, neighbor)| format!("[{},{}]", node, neighbor))
+            .collect();
+        write!(
+            writer,
+            "{{\"initial\":{{\"alive\":[{}],\"links\":[{}]}},\"deltas\":[",
+            alive.join(","),
+            links.join(",")
+        )?;
+        for (index, delta) in self.deltas.iter().enumerate() {
+            if index > 0 {
+                write!(writer, ",")?;
+            }
+            let at = delta.at.as_micros();
+            match delta.update {
+                TopologyUpdate::LinkAdded { node, neighbor } => write!(
+                    writer,
+                    "{{\"at_us\":{},\"type\":\"link_added\",\"node\":{},\"neighbor\":{}}}",
+                    at, node, neighbor
+                )?,
+                TopologyUpdate::LinkRemoved { node, neighbor } => write!(
+                    writer,
+                    "{{\"at_us\":{},\"type\":\"link_removed\",\"node\":{},\"neighbor\":{}}}",
+                    at, node, neighbor
+                )?,
+                TopologyUpdate::PdrChanged { node, pdr } => {
+                    // JSON has no NaN nor infinity, which a raw command can still carry.
+                    let pdr = if pdr.is_finite() {
+                        pdr.to_string()
+                    } else {
+                        "null".to_string()
+                    };
+                    write!(
+                        writer,
+                        "{{\"at_us\":{},\"type\":\"pdr_changed\",\"node\":{},\"pdr\":{}}}",
+                        at, node, pdr
+                    )?
+                }
+                TopologyUpdate::Crashed { node } => write!(
+                    writer,
+                    "{{\"at_us\":{},\"type\":\"crashed\",\"node\":{}}}",
+                    at, node
+                )?,
+            }
+        }
+        writeln!(writer, "]}}")
+    }
+}
+
+/// The topology of a running network, as changed by the commands sent to its nodes.
+#[derive(Debug, Default)]
+struct LiveTopology {
+    /// The nodes which were not told to crash.
+    alive: BTreeSet<NodeId>,
+    /// The directed links, as `(node, neighbor)`.
+    links: BTreeSet<(NodeId, NodeId)>,
+    /// The role of every node, with the current packet drop rate of the drones.
+    roles: BTreeMap<NodeId, Role>,
+    /// The divergences found by the last reconciliation.
+    divergences: BTreeSet<LinkDivergence>,
+    /// The evolution of the topology since the tracker was created.
+    timeline: Timeline,
+}
+
+/// Tracker of the topology of a running network, publishing every change to the subscribers.
+///
+/// Clones share the same state and subscribers.
+#[derive(Debug, Clone)]
+pub(crate) struct TopologyTracker {
+    hub: Hub<TopologyUpdate>,
+    live: Arc<Mutex<LiveTopology>>,
+    /// When the tracker was created, from which the times of the timeline are measured.
+    started: Instant,
+}
+
+impl Default for TopologyTracker {
+    fn default() -> Self {
+        Self {
+            hub: Hub::default(),
+            live: Arc::default(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl TopologyTracker {
+    /// Returns a tracker starting from the topology of a configuration.
+    pub(crate) fn new(config: &Config) -> Self {
+        let alive: BTreeSet<_> = config
+            .drone
+            .iter()
+            .map(|drone| drone.id)
+            .chain(config.client.iter().map(|client| client.id))
+            .chain(config.server.iter().map(|server| server.id))
+            .collect();
+        let links: BTreeSet<_> = config_links(config)
+            .into_iter()
+            .flat_map(|(a, b)| [(a, b), (b, a)])
+            .collect();
+        let initial = TopologySnapshot {
+            alive: alive.clone(),
+            links: links.clone(),
+        };
+        Self {
+            hub: Hub::default(),
+            live: Arc::new(Mutex::new(LiveTopology {
+                alive,
+                links,
+                roles: Topology::from(config)
+                    .nodes
+                    .into_iter()
+                    .map(|node| (node.id, node.role))
+                    .collect(),
+                timeline: Timeline {
+                    initial,
+                    deltas: Vec::new(),
+                },
+                ..LiveTopology::default()
+            })),
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns a receiver of every update from now on.
+    pub(crate) fn subscribe(&self) -> Receiver<TopologyUpdate> {
+        self.hub.subscribe()
+    }
+
+    /// Applies an update to the tracked topology, then publishes it.
+    pub(crate) fn apply(&self, update: TopologyUpdate) {
+        {
+            let mut live = self.live.lock().unwrap_or_else(|e| e.into_inner());
+            let changed = match update {
+                TopologyUpdate::LinkAdded { node, neighbor } => live.links.insert((node, neighbor)),
+                TopologyUpdate::LinkRemoved { node, neighbor } => {
+                    live.links.remove(&(node, neighbor))
+                }
+                TopologyUpdate::PdrChanged { node, pdr } => {
+                    if let Some(Role::Drone { pdr: current }) = live.roles.get_mut(&node) {
+                        *current = pdr;
+                    }
+                    true
+                }
+                TopologyUpdate::Crashed { node } => live.alive.remove(&node),
+            };
+            if changed {
+                let delta = TimelineDelta {
+                    at: self.started.elapsed(),
+                    update: update.clone(),
+                };
+                live.timeline.deltas.push(delta);
+            }
+        }
+        self.hub.publish(update);
+    }
+
+    /// Returns the evolution of the topology since the tracker was created.
+    pub(crate) fn timeline(&self) -> Timeline {
+        self.live
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .timeline
+            .clone()
+    }
+
+    /// Returns the nodes which were not told to crash.
+    pub(crate) fn alive(&self) -> BTreeSet<NodeId> {
+        self.live
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .alive
+            .clone()
+    }
+
+    /// Returns the undirected links whose endpoints are both alive and can send to each other.
+    pub(crate) fn links(&self) -> BTreeSet<Link> {
+        let live = self.live.lock().unwrap_or_else(|e| e.into_inner());
+        live.links
+            .iter()
+            .filter(|(a, b)| a < b && live.links.contains(&(*b, *a)))
+            .filter(|(a, b)| live.alive.contains(a) && live.alive.contains(b))
+            .copied()
+            .collect()
+    }
+
+    /// Returns the live topology: the nodes which were not told to crash, with the current
+    /// packet drop rate of the drones, and the directed links between them.
+    pub(crate) fn topology(&self) -> Topology {
+        let live = self.live.lock().unwrap_or_else(|e| e.into_inner());
+        let mut topology = Topology::default();
+        for (&id, &role) in &live.roles {
+            if live.alive.contains(&id) {
+                topology.add_node(id, role);
+            }
+        }
+        topology.edges = live
+            .links
+            .iter()
+            .filter(|(a, b)| live.alive.contains(a) && live.alive.contains(b))
+            .copied()
+            .collect();
+        topology
+    }
+
+    /// Compares a topology graph with the live directed links, ignoring the nodes which were
+    /// told to crash.
+    ///
+    /// # Parameters
+    /// - `topology`: The topology graph, indexed by node ID, e.g. the one of `InitData` kept
+    ///   up to date by the simulation controller.
+    ///
+    /// Returns every divergence, and the ones which were not found by the previous
+    /// reconciliation.
+    pub(crate) fn reconcile(
+        &self,
+        topology: &[(NodeType, FixedBitSet)],
+    ) -> (Vec<LinkDivergence>, Vec<LinkDivergence>) {
+        let mut live = self.live.lock().unwrap_or_else(|e| e.into_inner());
+        let is_set = |node: NodeId, neighbor: NodeId| {
+            let (node, neighbor) = (NodeIndex::new(node)?, NodeIndex::new(neighbor)?);
+            let (_, neighbors) = topology.get(node.get())?;
+            Some(neighbors.contains(neighbor.get()))
+        };
+
+        let mut divergences = BTreeSet::new();
+        let links = live
+            .links
+            .iter()
+            .filter(|(node, neighbor)| live.alive.contains(node) && live.alive.contains(neighbor));
+        for &(node, neighbor) in links {
+            if is_set(node, neighbor) == Some(false) {
+                divergences.insert(LinkDivergence {
+                    node,
+                    neighbor,
+                    kind: DivergenceKind::MissingInTopology,
+                });
+            }
+        }
+        for (position, (_, neighbors)) in topology.iter().enumerate() {
+            let Some(node) = NodeIndex::from_position(position).map(NodeIndex::id) else {
+                continue;
+            };
+            let neighbors = neighbors
+                .ones()
+                .filter_map(NodeIndex::from_position)
+                .map(NodeIndex::id);
+            for neighbor in neighbors {
+                let alive = live.alive.contains(&node) && live.alive.contains(&neighbor);
+                if alive && !live.links.contains(&(node, neighbor)) {
+                    divergences.insert(LinkDivergence {
+                        node,
+                        neighbor,
+                        kind: DivergenceKind::StaleInTopology,
+                    });
+                }
+            }
+        }
+
+        let new = divergences.difference(&live.divergences).copied().collect();
+        let all = divergences.iter().copied().collect();
+        live.divergences = divergences;
+        (all, new)
+    }
+}
+
+/// Returns the update caused by a command sent to a drone.
+pub(crate) fn drone_update(node: NodeId, command: &DroneCommand) -> Option<TopologyUpdate> {
+    let update = match command {
+        DroneCommand::AddSender(neighbor, _) => TopologyUpdate::LinkAdded {
+            node,
+            neighbor: *neighbor,
+        },
+        DroneCommand::RemoveSender(neighbor) => TopologyUpdate::LinkRemoved {
+            node,
+            neighbor: *neighbor,
+        },
+        DroneCommand::SetPacketDropRate(pdr) => TopologyUpdate::PdrChanged { node, pdr: *pdr },
+        DroneCommand::Crash => TopologyUpdate::Crashed { node },
+    };
+    Some(update)
+}
+
+/// Returns the update caused by a command sent to a client, or `None` if the command does not
+/// change the topology.
+pub(crate) fn client_update(node: NodeId, command: &ClientCommand) -> Option<TopologyUpdate> {
+    #[allow(unreachable_patterns)]
+    let update = match command {
+        ClientCommand::AddDrone(neighbor, _) => TopologyUpdate::LinkAdded {
+            node,
+            neighbor: *neighbor,
+        },
+        ClientCommand::RemoveDrone(neighbor) => TopologyUpdate::LinkRemoved {
+            node,
+            neighbor: *neighbor,
+        },
+        ClientCommand::Crash => TopologyUpdate::Crashed { node },
+        // The commands of the API which do not touch the links, if any.
+        _ => return None,
+    };
+    Some(update)
+}
+
+/// Returns the update caused by a command sent to a server, or `None` if the command does not
+/// change the topology.
+pub(crate) fn server_update(node: NodeId, command: &ServerCommand) -> Option<TopologyUpdate> {
+    #[allow(unreachable_patterns)]
+    let update = match command {
+        ServerCommand::AddDrone(neighbor, _) => TopologyUpdate::LinkAdded {
+            node,
+            neighbor: *neighbor,
+        },
+        ServerCommand::RemoveDrone(neighbor) => TopologyUpdate::LinkRemoved {
+            node,
+            neighbor: *neighbor,
+        },
+        ServerCommand::Crash => TopologyUpdate::Crashed { node },
+        // The commands of the API which do not touch the links, if any.
+        _ => return None,
+    };
+    Some(update)
+}
+
+/// Spawns a thread forwarding the commands sent to a node, publishing the topology update
+/// caused by each to the tracker.
+///
+/// Every node gets its own relay, placed between the senders held by the simulation controller
+/// (and by the [`crate::handle::NetworkHandle`]) and its own command queue. The thread
+/// terminates when every sender is dropped, the node drops its receiver, or the relays of the
+/// network are stopped.
+///
+/// # Parameters
+/// - `node`: The ID of the node.
+/// - `from`: The channel on which the commands for the node are sent.
+/// - `to`: The command queue of the node.
+/// - `tracker`: The tracker applying and publishing the updates.
+/// - `describe`: The function deriving the update caused by a command, if any.
+/// - `relays`: The relays of the network, stopping this one on shutdown.
+pub(crate) fn spawn_command_relay<T: Send + 'static>(
+    node: NodeId,
+    from: Receiver<T>,
+    to: Sender<T>,
+    tracker: TopologyTracker,
+    describe: fn(NodeId, &T) -> Option<TopologyUpdate>,
+    relays: &Relays,
+) {
+    relays.spawn(move |stopped| {
+        while let Some(command) = relay_recv(&from, &stopped) {
+            let update = describe(node, &command);
+            if !relay_send(&to, command, &stopped) {
+                break;
+            }
+            if let Some(update) = update {
+                tracker.apply(update);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use fixedbitset::FixedBitSet;
+    use rust_roveri_api::{NodeType, MAX_NODES};
+
+    use crate::analysis::k_best_paths_in;
+    use crate::generator::generate_small_world;
+    use crate::handle::Relays;
+    use crate::topology::{
+        drone_update, spawn_command_relay, DivergenceKind, LinkDivergence, TopologyTracker,
+        TopologyUpdate,
+    };
+    use crate::validate::network_validate_str;
+    use wg_2024::controller::DroneCommand;
+
+    #[test]
+    fn test_command_relay() {
+        let tracker = TopologyTracker::default();
+        let updates = tracker.subscribe();
+        let (sc_tx, sc_rx) = crossbeam_channel::unbounded();
+        let (node_tx, node_rx) = crossbeam_channel::unbounded();
+        let relays = Relays::default();
+        spawn_command_relay(3, sc_rx, node_tx, tracker, drone_update, &relays);
+
+        sc_tx.send(DroneCommand::RemoveSender(4)).unwrap();
+        sc_tx.send(DroneCommand::SetPacketDropRate(0.5)).unwrap();
+
+        let timeout = Duration::from_secs(1);
+        assert!(matches!(
+            node_rx.recv_timeout(timeout),
+            Ok(DroneCommand::RemoveSender(4))
+        ));
+        assert_eq!(
+            updates.recv_timeout(timeout),
+            Ok(TopologyUpdate::LinkRemoved {
+                node: 3,
+                neighbor: 4
+            })
+        );
+        assert_eq!(
+            updates.recv_timeout(timeout),
+            Ok(TopologyUpdate::PdrChanged { node: 3, pdr: 0.5 })
+        );
+
+        // Stopping the relays forwards the queued commands, and joins the relay although its
+        // sender is still alive.
+        sc_tx.send(DroneCommand::Crash).unwrap();
+        relays.stop();
+        assert!(matches!(
+            node_rx.try_iter().last(),
+            Some(DroneCommand::Crash)
+        ));
+        assert!(sc_tx.send(DroneCommand::Crash).is_err());
+    }
+
+    #[test]
+    fn test_tracker_links() {
+        let config = generate_small_world(6, 2, 0.0, 1, 1, 1).unwrap();
+        let tracker = TopologyTracker::new(&config);
+        let (a, b) = *tracker.links().first().unwrap();
+
+        tracker.apply(TopologyUpdate::LinkRemoved {
+            node: a,
+            neighbor: b,
+        });
+        assert!(!tracker.links().contains(&(a, b)));
+        tracker.apply(TopologyUpdate::LinkAdded {
+            node: a,
+            neighbor: b,
+        });
+        assert!(tracker.links().contains(&(a, b)));
+
+        tracker.apply(TopologyUpdate::Crashed { node: a });
+        assert!(!tracker.alive().contains(&a));
+        assert!(tracker.links().iter().all(|(x, y)| *x != a && *y != a));
+    }
+
+    #[test]
+    fn test_timeline() {
+        let config = generate_small_world(6, 2, 0.0, 1, 1, 1).unwrap();
+        let tracker = TopologyTracker::new(&config);
+        let (a, b) = *tracker.links().first().unwrap();
+        tracker.apply(TopologyUpdate::LinkAdded {
+            node: a,
+            neighbor: b,
+        });
+        tracker.apply(TopologyUpdate::LinkRemoved {
+            node: a,
+            neighbor: b,
+        });
+        tracker.apply(TopologyUpdate::PdrChanged {
+            node: a,
+            pdr: f32::NAN,
+        });
+        tracker.apply(TopologyUpdate::Crashed { node: b });
+
+        let timeline = tracker.timeline();
+        assert_eq!(timeline.deltas.len(), 3);
+        assert!(timeline.initial.links.contains(&(a, b)));
+        let last = timeline.deltas[2].at;
+        let snapshot = timeline.snapshot_at(last);
+        assert!(!snapshot.links.contains(&(a, b)));
+        assert!(!snapshot.alive.contains(&b));
+
+        let mut json = Vec::new();
+        timeline.to_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with("{\"initial\":{\"alive\":["));
+        assert!(json.contains(&format!(
+            "\"type\":\"link_removed\",\"node\":{},\"neighbor\":{}}}",
+            a, b
+        )));
+        assert!(json
+            .trim_end()
+            .ends_with(&format!("\"type\":\"crashed\",\"node\":{}}}]}}", b)));
+
+        // The export is valid JSON, even with a rate which is not a number.
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["deltas"][1]["pdr"], serde_json::Value::Null);
+        assert_eq!(parsed["deltas"][2]["node"], u64::from(b));
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let config = generate_small_world(6, 2, 0.0, 1, 1, 1).unwrap();
+        let tracker = TopologyTracker::new(&config);
+        let mut topology = vec![(NodeType::None, FixedBitSet::with_capacity(MAX_NODES)); MAX_NODES];
+        for (a, b) in tracker.links() {
+            topology[usize::from(a)].1.insert(usize::from(b));
+            topology[usize::from(b)].1.insert(usize::from(a));
+        }
+        assert_eq!(tracker.reconcile(&topology), (vec![], vec![]));
+
+        let (a, b) = *tracker.links().first().unwrap();
+        tracker.apply(TopologyUpdate::LinkRemoved {
+            node: a,
+            neighbor: b,
+        });
+        let stale = LinkDivergence {
+            node: a,
+            neighbor: b,
+            kind: DivergenceKind::StaleInTopology,
+        };
+        assert_eq!(tracker.reconcile(&topology), (vec![stale], vec![stale]));
+        assert_eq!(tracker.reconcile(&topology), (vec![stale], vec![]));
+
+        topology[usize::from(a)].1.set(usize::from(b), false);
+        topology[usize::from(b)].1.set(usize::from(a), false);
+        let missing = LinkDivergence {
+            node: b,
+            neighbor: a,
+            kind: DivergenceKind::MissingInTopology,
+        };
+        assert_eq!(tracker.reconcile(&topology), (vec![missing], vec![missing]));
+    }
+
+    #[test]
+    fn test_live_topology() {
+        let config = network_validate_str(
+            r#"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 10, 20]
+            pdr = 0.1
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [1, 10, 20]
+            pdr = 0.5
+
+            [[client]]
+            id = 10
+            connected_drone_ids = [1, 2]
+
+            [[server]]
+            id = 20
+            connected_drone_ids = [1, 2]
+            "#,
+        )
+        .unwrap();
+        let tracker = TopologyTracker::new(&config);
+        let best = |tracker: &TopologyTracker| {
+            let path = k_best_paths_in(&tracker.topology(), 10, 20, 1).pop();
+            path.map(|path| path.hops)
+        };
+        assert_eq!(best(&tracker), Some(vec![10, 1, 20]));
+
+        tracker.apply(TopologyUpdate::PdrChanged { node: 1, pdr: 0.9 });
+        assert_eq!(best(&tracker), Some(vec![10, 2, 20]));
+
+        tracker.apply(TopologyUpdate::Crashed { node: 2 });
+        assert_eq!(best(&tracker), Some(vec![10, 1, 20]));
+
+        tracker.apply(TopologyUpdate::LinkRemoved {
+            node: 1,
+            neighbor: 20,
+        });
+        assert_eq!(best(&tracker), None);
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use wg_2024::network::NodeId;
+
+/// A step of the initialization of a network, as recorded in a [`Transcript`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TranscriptEntry {
+    /// A channel was created, see [`crate::channels::ChannelInfo`] for the names.
+    ChannelCreated { name: String },
+    /// A node was assigned its implementation or type.
+    Assigned { node: NodeId, assignment: String },
+    /// The thread of a node was spawned.
+    Spawned { node: NodeId },
+    /// An initial command adding a neighbor was sent to a node.
+    CommandSent { node: NodeId, neighbor: NodeId },
+}
+
+impl fmt::Display for TranscriptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptEntry::ChannelCreated { name } => write!(f, "channel {}", name),
+            TranscriptEntry::Assigned { node, assignment } => {
+                write!(f, "assign {} {}", node, assignment)
+            }
+            TranscriptEntry::Spawned { node } => write!(f, "spawn {}", node),
+            TranscriptEntry::CommandSent { node, neighbor } => {
+                write!(f, "command {} +{}", node, neighbor)
+            }
+        }
+    }
+}
+
+/// The ordered steps of the initialization of a network, recorded when a seed is given in the
+/// [`crate::init::InitOptions`].
+///
+/// The initialization is deterministic, so two initializations of the same configuration with
+/// the same options produce the same transcript; the seed identifies the run the transcript
+/// belongs to, e.g. a grading run, and is only recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    /// The seed of the run.
+    pub seed: u64,
+    /// The steps of the initialization, in order.
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// Records the transcript of an initialization, if a seed was given.
+#[derive(Debug)]
+pub(crate) struct Recorder(Option<Transcript>);
+
+impl Recorder {
+    /// Returns a recorder, which only records if a seed is given.
+    pub(crate) fn new(seed: Option<u64>) -> Self {
+        Self(seed.map(|seed| Transcript {
+            seed,
+            entries: Vec::new(),
+        }))
+    }
+
+    /// Records a step, built only if the recorder is recording.
+    pub(crate) fn record(&mut self, entry: impl FnOnce() -> TranscriptEntry) {
+        if let Some(transcript) = &mut self.0 {
+            transcript.entries.push(entry());
+        }
+    }
+
+    /// Records the creation of a channel, returning its name.
+    pub(crate) fn channel(&mut self, name: String) -> String {
+        self.record(|| TranscriptEntry::ChannelCreated { name: name.clone() });
+        name
+    }
+
+    /// Returns the recorded transcript, if any.
+    pub(crate) fn finish(self) -> Option<Transcript> {
+        self.0
+    }
+}
+
+/// Asserts that two transcripts are equal, e.g. to prove that two initializations of the same
+/// configuration with the same seed were identical.
+///
+/// # Panics
+/// Panics with the first difference if the transcripts differ.
+pub fn assert_transcripts_equal(left: &Transcript, right: &Transcript) {
+    assert_eq!(
+        left.seed, right.seed,
+        "The transcripts have different seeds"
+    );
+    let steps = left.entries.iter().zip(&right.entries).enumerate();
+    for (index, (left, right)) in steps {
+        assert!(
+            left == right,
+            "The transcripts differ at step {}: `{}` != `{}`",
+            index,
+            left,
+            right
+        );
+    }
+    assert_eq!(
+        left.entries.len(),
+        right.entries.len(),
+        "One transcript is a prefix of the other"
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use wg_2024::config::Config;
+
+    use crate::init::{network_init_with_options, InitOptions};
+    use crate::stub::{echo_server_factory, null_drone_factory, ping_client_factory};
+    use crate::transcript::{assert_transcripts_equal, TranscriptEntry};
+
+    const CONFIG: &str = r#"
+        [[drone]]
+        id = 1
+        connected_node_ids = [2, 3]
+        pdr = 0.1
+
+        [[client]]
+        id = 2
+        connected_drone_ids = [1]
+
+        [[server]]
+        id = 3
+        connected_drone_ids = [1]
+    "#;
+
+    #[test]
+    fn test_transcript() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let options = InitOptions {
+            drone_factory: null_drone_factory,
+            client_factory: ping_client_factory,
+            server_factory: echo_server_factory,
+            seed: Some(7),
+            ..InitOptions::default()
+        };
+        let init = || {
+            let handle = network_init_with_options(&config, &options).handle;
+            handle.crash_all();
+            handle.transcript().cloned().unwrap()
+        };
+
+        let transcript = init();
+        assert_eq!(transcript.seed, 7);
+        assert!(transcript
+            .entries
+            .contains(&TranscriptEntry::Spawned { node: 3 }));
+        assert!(transcript.entries.contains(&TranscriptEntry::CommandSent {
+            node: 2,
+            neighbor: 1
+        }));
+        assert_transcripts_equal(&transcript, &init());
+
+        let mut other = transcript.clone();
+        other.entries.swap(0, 1);
+        let result = std::panic::catch_unwind(|| assert_transcripts_equal(&transcript, &other));
+        assert!(result.is_err());
+    }
+}
@@ -1,41 +1,1096 @@
 use fixedbitset::FixedBitSet;
-use rust_roveri_api::MAX_NODES;
-use std::{collections::VecDeque, fs};
-use wg_2024::config::{Client, Config, Drone, Server};
+use rust_roveri_api::{DroneImpl, MAX_IMPL, MAX_NODES};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+use wg_2024::{
+    config::{Client, Config, Drone, Server},
+    network::NodeId,
+    packet::NodeType,
+};
+
+use crate::analysis::critical_drones;
+use crate::distribution::{drone_impls_from_table, DistributionPlan};
+use crate::index::NodeIndex;
+use crate::migrate::Migrations;
+use crate::model::{Node, Role, Topology};
 
 type Graph = [FixedBitSet; MAX_NODES];
 
+/// The minimum number of drones a client is connected to.
+const MIN_CLIENT_DRONES: usize = 1;
+/// The maximum number of drones a client is connected to.
+const MAX_CLIENT_DRONES: usize = 2;
+/// The minimum number of drones a server is connected to.
+const MIN_SERVER_DRONES: usize = 2;
+
+/// Identifies the rule that a configuration violates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A drone has a packet drop rate outside of `[0, 1]`.
+    InvalidPdr,
+    /// A node lists itself as a neighbor.
+    SelfConnection,
+    /// A node lists the same neighbor more than once.
+    DuplicateNeighbor,
+    /// A client is not connected to one or two drones.
+    ClientNeighborCount,
+    /// A server is connected to less than two drones.
+    ServerNeighborCount,
+    /// Two nodes share the same ID.
+    DuplicateId,
+    /// A client or a server is connected to a node which is not a drone.
+    NeighborNotDrone,
+    /// A node lists a neighbor which does not exist in the topology.
+    UnknownNeighbor,
+    /// An edge is declared by only one of its endpoints.
+    NotBidirectional,
+    /// The topology is not connected.
+    NotConnected,
+    /// Clients and servers are not all on the edge of the network.
+    NotAtEdge,
+    /// Two linked nodes are placed further apart than the allowed radius.
+    LinkTooLong,
+    /// The number of nodes of a type is outside of the limits of the validation policy.
+    NodeCount,
+    /// Some drone implementation would not be assigned to any drone.
+    UnusedImpl,
+    /// The planned distribution of implementations or types is too uneven.
+    DistributionImbalance,
+    /// The node to validate does not exist in the topology.
+    UnknownNode,
+    /// A node ID is too large to index the arrays sized by `MAX_NODES`.
+    IdOutOfRange,
+    /// The endpoints of a link declare different values for the same link attribute.
+    InconsistentLink,
+    /// The network has no drones.
+    NoDrones,
+    /// The configuration file cannot be read, or is not a valid configuration.
+    Malformed,
+    /// A drone chooses an implementation which does not exist.
+    UnknownImpl,
+    /// The crash of a single drone would disconnect some client from some server.
+    SinglePointOfFailure,
+    /// Some node cannot be assigned a drone implementation, client type or server type.
+    TypeUnavailable,
+    /// A drone has a packet drop rate of exactly 1, so it drops every fragment.
+    DropsEverything,
+    /// A drone is only connected to clients and servers, although the network has other drones.
+    NoDroneNeighbors,
+    /// The average packet drop rate of the drones is above [`HIGH_AVERAGE_PDR`].
+    HighAveragePdr,
+}
+
+/// A violation found while validating a configuration.
+///
+/// Pairs the violated rule, as an [`ErrorCode`], with a human-readable message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The rule that was violated.
+    pub code: ErrorCode,
+    /// A description of the violation, referencing the offending nodes.
+    pub message: String,
+}
+
+impl ValidationError {
+    /// Returns a new `ValidationError` with the given code and message.
+    pub fn new(code: ErrorCode, message: String) -> Self {
+        Self { code, message }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<ValidationError> for String {
+    fn from(error: ValidationError) -> Self {
+        error.message
+    }
+}
+
+/// A non-fatal finding produced while validating a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    /// The rule that was violated.
+    pub code: ErrorCode,
+    /// A description of the finding, referencing the offending nodes.
+    pub message: String,
+}
+
+/// The average packet drop rate of the drones above which [`validation_report`] warns.
+pub const HIGH_AVERAGE_PDR: f32 = 0.5;
+
+/// The outcome of a validation.
+///
+/// The reports returned by [`check_config_with_policy`] never hold errors, while the ones
+/// returned by [`validation_report`] hold every error and warning at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The rules which were violated, in the order in which the checks run.
+    pub errors: Vec<ValidationError>,
+    /// The rules which were violated, but only produced a warning under the validation policy.
+    pub warnings: Vec<ValidationWarning>,
+    /// A summary of every node, in the order in which they appear in the configuration.
+    pub nodes: Vec<NodeSummary>,
+}
+
+impl ValidationReport {
+    /// Adds a warning to the report, also emitting it through the `log` crate.
+    pub(crate) fn warn(&mut self, code: ErrorCode, message: String) {
+        log::warn!(code:? = code; "{}", message);
+        self.warnings.push(ValidationWarning { code, message });
+    }
+
+    /// Returns whether the configuration passed the validation, i.e. there are no errors.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Informational summary of a node, e.g. to guide interactive editing of the topology.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeSummary {
+    /// The ID of the node.
+    pub id: NodeId,
+    /// The type of the node.
+    pub node_type: NodeType,
+    /// The number of neighbors of the node.
+    pub degree: usize,
+    /// The number of neighbors which are drones.
+    pub drone_neighbors: usize,
+    /// The number of neighbors which are clients.
+    pub client_neighbors: usize,
+    /// The number of neighbors which are servers.
+    pub server_neighbors: usize,
+    /// How many more neighbors the node may be connected to, or `None` if there is no limit.
+    pub can_add: Option<usize>,
+    /// How many neighbors the node may be disconnected from, without violating the
+    /// constraints on its own neighbor count.
+    pub can_remove: usize,
+}
+
+/// How a rule of the validation policy reacts to a violation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Severity {
+    /// The violation is ignored.
+    #[default]
+    Allow,
+    /// The violation is reported as a warning.
+    Warn,
+    /// The violation is reported as an error.
+    Deny,
+}
+
+/// Additional rules enforced on top of the structural checks performed by [`check_config`].
+///
+/// The default policy enforces no additional rule.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationPolicy {
+    /// The minimum number of drones, if any.
+    pub min_drones: Option<usize>,
+    /// The maximum number of drones, if any.
+    pub max_drones: Option<usize>,
+    /// The minimum number of clients, if any.
+    pub min_clients: Option<usize>,
+    /// The maximum number of clients, if any.
+    pub max_clients: Option<usize>,
+    /// The minimum number of servers, if any.
+    pub min_servers: Option<usize>,
+    /// The maximum number of servers, if any.
+    pub max_servers: Option<usize>,
+    /// How to react if some drone implementation would not be assigned to any drone.
+    pub unused_impls: Severity,
+    /// How to react if a node lists the same neighbor more than once.
+    pub on_duplicate_edge: DuplicateEdgePolicy,
+    /// How to react if the crash of a single drone would disconnect some client from some
+    /// server, see [`crate::analysis::analyze_topology`].
+    pub single_points_of_failure: Severity,
+}
+
+/// How the validation reacts to a node listing the same neighbor more than once, e.g. in a
+/// configuration generated by merging several sources.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateEdgePolicy {
+    /// The duplicate is a violation of [`ErrorCode::DuplicateNeighbor`].
+    #[default]
+    Error,
+    /// The duplicate is removed, and reported as a warning.
+    Warn,
+    /// The duplicate is removed silently.
+    Silent,
+}
+
+/// The part of a configuration checked by [`validate_scope`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The fields of every node, and the uniqueness of their IDs.
+    NodesOnly,
+    /// The links between the nodes, skipping the fields of the nodes.
+    GraphOnly,
+    /// The fields of a single node, and its links to the other nodes.
+    Node(NodeId),
+}
+
+/// A configuration which passed [`check_config`].
+///
+/// Holding a `ValidatedConfig` proves that the configuration was validated, so that it can be
+/// passed around without validating it again. A configuration read from a file also keeps the
+/// implementations chosen by its drones, see [`crate::distribution::IMPL_KEY`].
+#[derive(Debug, Clone)]
+pub struct ValidatedConfig {
+    config: Config,
+    /// The implementation chosen by some drones, by drone ID.
+    drone_impls: BTreeMap<NodeId, DroneImpl>,
+    /// The hash of the file the configuration was read from, if any.
+    pub(crate) source_hash: Option<u64>,
+}
+
+impl ValidatedConfig {
+    /// Validates a configuration.
+    ///
+    /// # Parameters
+    /// - `config`: The network configuration.
+    ///
+    /// Returns the validated configuration, or an error if the checks are not passed.
+    pub fn new(config: Config) -> Result<Self, ValidationError> {
+        check_config(&config)?;
+        Ok(Self {
+            config,
+            drone_impls: BTreeMap::new(),
+            source_hash: None,
+        })
+    }
+
+    /// Reads and validates a configuration file, remembering its hash so that the
+    /// configuration can be cached with [`ValidatedConfig::save_cache`].
+    ///
+    /// # Parameters
+    /// - `file_path`: The path of the configuration file.
+    ///
+    /// Returns the validated configuration, or an error if the file is unreadable or invalid,
+    /// e.g. because some drone chooses an unknown implementation.
+    pub fn from_file(file_path: &str) -> Result<Self, String> {
+        let config_data = fs::read_to_string(file_path)
+            .map_err(|_| "Unable to read configuration file".to_string())?;
+        let (config, drone_impls) = parse_migrate_and_validate(
+            &config_data,
+            &ValidationPolicy::default(),
+            &Migrations::default(),
+        )?;
+        Ok(Self {
+            config,
+            drone_impls,
+            source_hash: Some(source_hash(config_data.as_bytes())),
+        })
+    }
+
+    /// Wraps a configuration which is known to be valid, e.g. because it was read from a cache.
+    pub(crate) fn new_unchecked(
+        config: Config,
+        drone_impls: BTreeMap<NodeId, DroneImpl>,
+        source_hash: Option<u64>,
+    ) -> Self {
+        Self {
+            config,
+            drone_impls,
+            source_hash,
+        }
+    }
+
+    /// Returns the validated configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the validated configuration, consuming the wrapper.
+    pub fn into_config(self) -> Config {
+        self.config
+    }
+
+    /// Returns the implementation chosen by some drones of the configuration, by drone ID.
+    pub fn drone_impls(&self) -> &BTreeMap<NodeId, DroneImpl> {
+        &self.drone_impls
+    }
+
+    /// Plans the distribution of the configuration, honoring the implementation chosen by its
+    /// drones, see [`DistributionPlan::try_with_drone_impls`].
+    ///
+    /// Returns the distribution, or an error if some node cannot be assigned an implementation
+    /// or type.
+    pub fn plan(&self) -> Result<DistributionPlan, ValidationError> {
+        DistributionPlan::try_with_drone_impls(&self.config, &self.drone_impls)
+    }
+
+    /// Returns the fingerprint of the configuration: a hash of its nodes, their packet drop
+    /// rates and their links, independent of the order in which they are declared and of the
+    /// formatting of the file they were read from.
+    ///
+    /// A network remembers the fingerprint of the configuration it runs, see
+    /// [`crate::handle::NetworkHandle::base_fingerprint`].
+    pub fn fingerprint(&self) -> u64 {
+        config_fingerprint(&self.config)
+    }
+}
+
+/// Returns the fingerprint of a configuration, see [`ValidatedConfig::fingerprint`].
+pub(crate) fn config_fingerprint(config: &Config) -> u64 {
+    let topology = Topology::from(config);
+    let mut nodes: Vec<&Node> = topology.nodes.iter().collect();
+    nodes.sort_by_key(|node| node.id);
+    let mut edges = topology.edges.clone();
+    edges.sort_unstable();
+
+    let mut data = Vec::new();
+    data.extend((nodes.len() as u32).to_le_bytes());
+    for node in nodes {
+        data.push(node.id);
+        match node.role {
+            Role::Drone { pdr } => {
+                data.push(b'd');
+                data.extend(pdr.to_bits().to_le_bytes());
+            }
+            Role::Client => data.push(b'c'),
+            Role::Server => data.push(b's'),
+        }
+    }
+    for (a, b) in edges {
+        data.extend([a, b]);
+    }
+    source_hash(&data)
+}
+
+/// Returns the 64-bit FNV-1a hash of the contents of a configuration file.
+///
+/// The hash is stable across platforms and compiler versions, so it can be stored in caches.
+pub(crate) fn source_hash(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    data.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
 /// Reads and validates the network configuration file.   
 ///
 /// This function attempts to read the configuration file from the given `file_path`,
 /// deserializes its contents as TOML into a `Config` instance, and then verifies that
 /// the encoded topology is valid.
 ///
+/// The implementations chosen by the drones are validated too, but a `Config` cannot carry
+/// them: read the file with [`ValidatedConfig::from_file`] and initialize it with
+/// [`crate::init::network_init_validated`] to honor them.
+///
 /// # Parameters
 /// - `file_path`: The path of the configuration file.
 ///
 /// Returns the configuration, as `Config`, if the configuration file provided is valid, an error otherwise.
 pub fn network_validate(file_path: &str) -> Result<Config, String> {
+    network_validate_with_policy(file_path, &ValidationPolicy::default())
+}
+
+/// Reads and validates the network configuration file, enforcing the given policy.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+/// - `policy`: The additional rules to enforce.
+///
+/// Returns the configuration, as `Config`, if the configuration file provided is valid, an error otherwise.
+pub fn network_validate_with_policy(
+    file_path: &str,
+    policy: &ValidationPolicy,
+) -> Result<Config, String> {
     // Read the configuration file as a string.
     let config_data = fs::read_to_string(file_path)
         .map_err(|_| "Unable to read configuration file".to_string())?;
 
-    // Deserialize the TOML data into a Config.
-    let config: Config =
-        toml::from_str(&config_data).map_err(|e| format!("Failed to deserialize TOML: {}", e))?;
+    parse_and_validate(&config_data, policy)
+}
+
+/// Parses and validates a network configuration from its TOML representation.
+///
+/// # Parameters
+/// - `config_data`: The contents of a configuration file.
+///
+/// Returns the configuration, as `Config`, if it is valid, an error otherwise.
+pub fn network_validate_str(config_data: &str) -> Result<Config, String> {
+    parse_and_validate(config_data, &ValidationPolicy::default())
+}
+
+/// Reads the network configuration file, upgrades it with the given migrations if it uses an
+/// older version of the format, and validates it.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+/// - `migrations`: The migrations to run, usually [`Migrations::default`] with some custom ones
+///   registered.
+///
+/// Returns the configuration, as `Config`, if the configuration file provided is valid, an error otherwise.
+pub fn network_validate_with_migrations(
+    file_path: &str,
+    migrations: &Migrations,
+) -> Result<Config, String> {
+    let config_data = fs::read_to_string(file_path)
+        .map_err(|_| "Unable to read configuration file".to_string())?;
+
+    parse_migrate_and_validate(&config_data, &ValidationPolicy::default(), migrations)
+        .map(|(config, _)| config)
+}
+
+/// Reads and validates the network configuration file, reporting every violation instead of
+/// only the first one, e.g. to fix a hand-written configuration in a single round.
+///
+/// # Parameters
+/// - `file_path`: The path of the configuration file.
+///
+/// Returns the configuration, as `Config`, if the configuration file provided is valid, all the
+/// violations found by [`check_config_all`] otherwise. A file which cannot be read or
+/// deserialized is reported as a single [`ErrorCode::Malformed`] error.
+pub fn network_validate_all(file_path: &str) -> Result<Config, Vec<ValidationError>> {
+    validate_file_all(Path::new(file_path)).map(ValidatedConfig::into_config)
+}
+
+/// Reads and validates many configuration files, e.g. every topology of a repository, as
+/// [`network_validate_all`].
+///
+/// # Parameters
+/// - `paths`: The paths of the configuration files.
+/// - `parallelism`: The maximum number of files validated at the same time; `1` validates them
+///   sequentially.
+///
+/// Returns the outcome of every file, in the order of `paths`: the validated configuration,
+/// which can be cached with [`ValidatedConfig::save_cache`], or the [`validation_report`] of
+/// the file, with all the violations found and the warnings. A file which cannot be read or
+/// deserialized is reported as a single [`ErrorCode::Malformed`] error.
+pub fn network_validate_many(
+    paths: &[PathBuf],
+    parallelism: usize,
+) -> Vec<(PathBuf, Result<ValidatedConfig, ValidationReport>)> {
+    let results = Mutex::new(vec![None; paths.len()]);
+    let next = AtomicUsize::new(0);
+    let workers = parallelism.clamp(1, paths.len().max(1));
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let result = validate_file_report(path);
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+    let results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+    paths
+        .iter()
+        .cloned()
+        .zip(results)
+        .map(|(path, result)| {
+            (
+                path,
+                result.expect("Every file is validated by some worker"),
+            )
+        })
+        .collect()
+}
+
+/// Reads and validates a configuration file, as described by [`network_validate_many`].
+fn validate_file_report(path: &Path) -> Result<ValidatedConfig, ValidationReport> {
+    let malformed = |message: String| ValidationReport {
+        errors: vec![ValidationError::new(ErrorCode::Malformed, message)],
+        ..ValidationReport::default()
+    };
+    let config_data = fs::read_to_string(path)
+        .map_err(|_| malformed("Unable to read configuration file".to_string()))?;
+    let (mut config, drone_impls) =
+        parse_config(&config_data, &Migrations::default()).map_err(|error| ValidationReport {
+            errors: vec![error],
+            ..ValidationReport::default()
+        })?;
+
+    let report = validation_report(&config, &ValidationPolicy::default());
+    if report.is_valid() {
+        dedup_neighbors(&mut config);
+        let hash = source_hash(config_data.as_bytes());
+        Ok(ValidatedConfig::new_unchecked(
+            config,
+            drone_impls,
+            Some(hash),
+        ))
+    } else {
+        Err(report)
+    }
+}
+
+/// Reads and validates a configuration file, as described by [`network_validate_all`].
+fn validate_file_all(path: &Path) -> Result<ValidatedConfig, Vec<ValidationError>> {
+    let malformed = |message: String| vec![ValidationError::new(ErrorCode::Malformed, message)];
+    let config_data = fs::read_to_string(path)
+        .map_err(|_| malformed("Unable to read configuration file".to_string()))?;
+    let (config, drone_impls) =
+        parse_config(&config_data, &Migrations::default()).map_err(|error| vec![error])?;
+
+    let errors = check_config_all(&config);
+    if errors.is_empty() {
+        let hash = source_hash(config_data.as_bytes());
+        Ok(ValidatedConfig::new_unchecked(
+            config,
+            drone_impls,
+            Some(hash),
+        ))
+    } else {
+        Err(errors)
+    }
+}
 
-    // Validate the configuration.
-    validate_config(&config)?;
+/// Deserializes the TOML representation of a configuration and validates it.
+fn parse_and_validate(config_data: &str, policy: &ValidationPolicy) -> Result<Config, String> {
+    parse_migrate_and_validate(config_data, policy, &Migrations::default())
+        .map(|(config, _)| config)
+}
+
+/// Deserializes the TOML representation of a configuration, upgrades it to the current version
+/// of the format, and validates it, along with the implementations chosen by its drones.
+fn parse_migrate_and_validate(
+    config_data: &str,
+    policy: &ValidationPolicy,
+    migrations: &Migrations,
+) -> Result<(Config, BTreeMap<NodeId, DroneImpl>), String> {
+    let (mut config, drone_impls) = parse_config(config_data, migrations)?;
+
+    // Validate the configuration, then drop the duplicate neighbors tolerated by the policy.
+    check_config_with_impls(&config, &drone_impls, policy)?;
+    dedup_neighbors(&mut config);
+
+    Ok((config, drone_impls))
+}
+
+/// Deserializes the TOML representation of a configuration, upgrading it to the current
+/// version of the format, without validating it.
+///
+/// Returns the configuration and the implementations chosen by its drones, or an
+/// [`ErrorCode::Malformed`] error if it cannot be deserialized, or an [`ErrorCode::UnknownImpl`]
+/// error if some drone chooses an unknown implementation.
+fn parse_config(
+    config_data: &str,
+    migrations: &Migrations,
+) -> Result<(Config, BTreeMap<NodeId, DroneImpl>), ValidationError> {
+    let malformed = |message: String| ValidationError::new(ErrorCode::Malformed, message);
+    // Parse the TOML data and upgrade it to the current version of the format.
+    let mut table: toml::Table = toml::from_str(config_data)
+        .map_err(|e| malformed(format!("Failed to deserialize TOML: {}", e)))?;
+    migrations.migrate(&mut table).map_err(malformed)?;
+    let drone_impls = drone_impls_from_table(&table)?;
 
-    Ok(config)
+    // Deserialize the TOML data into a Config.
+    let config = toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| malformed(format!("Failed to deserialize TOML: {}", e)))?;
+    Ok((config, drone_impls))
 }
 
 /// Validates the entire network configuration.
 ///
+/// This is the same as [`check_config`], but reports the violation as a plain message.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// Returns an error if the checks are not passed.
+pub(crate) fn validate_config(config: &Config) -> Result<(), String> {
+    check_config(config).map_err(String::from)
+}
+
+/// Checks that a configuration produced by this crate violates the expected rule, if any,
+/// catching bugs in the code paths producing configurations.
+///
+/// The check only runs with the `self-check` feature, as it validates the configuration again.
+///
+/// # Parameters
+/// - `origin`: The function which produced the configuration, for the panic message.
+/// - `config`: The produced configuration.
+/// - `expected`: The rule the configuration is meant to violate, or `None` if it is meant to
+///   be valid.
+///
+/// # Panics
+/// With the `self-check` feature, panics if the configuration does not violate exactly the
+/// expected rule.
+pub(crate) fn self_check(origin: &str, config: &Config, expected: Option<ErrorCode>) {
+    if !cfg!(feature = "self-check") {
+        return;
+    }
+    let found = check_config(config).err();
+    assert!(
+        found.as_ref().map(|err| err.code) == expected,
+        "{} produced a configuration violating {:?} instead of {:?}: {:?}",
+        origin,
+        found.as_ref().map(|err| err.code),
+        expected,
+        found.map(|err| err.message)
+    );
+}
+
+/// Validates the entire network configuration, reporting which rule was violated.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn check_config(config: &Config) -> Result<(), ValidationError> {
+    check_config_with_policy(config, &ValidationPolicy::default()).map(|_| ())
+}
+
+/// Validates the entire network configuration, then enforces the rules of the given policy.
+///
+/// Unless the policy rejects duplicate neighbors, the configuration is validated as if they
+/// were removed; the configuration itself is left untouched, so it should go through
+/// [`dedup_neighbors`] before being initialized.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `policy`: The additional rules to enforce.
+///
+/// Returns a report of the non-fatal findings, or an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn check_config_with_policy(
+    config: &Config,
+    policy: &ValidationPolicy,
+) -> Result<ValidationReport, ValidationError> {
+    check_config_with_impls(config, &BTreeMap::new(), policy)
+}
+
+/// Validates the entire network configuration, then enforces the rules of the given policy,
+/// as [`check_config_with_policy`], against the distribution the network is initialized with.
+///
+/// The rules on the distribution, e.g. [`ValidationPolicy::unused_impls`], are checked against
+/// `plan` instead of the round-robin one, e.g. for a network initialized with
+/// [`crate::init::network_init_seeded`], whose plan is drawn from the seed by
+/// [`DistributionPlan::try_seeded`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `plan`: The distribution of the configuration.
+/// - `policy`: The additional rules to enforce.
+///
+/// Returns a report of the non-fatal findings, or an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn check_config_with_plan(
+    config: &Config,
+    plan: &DistributionPlan,
+    policy: &ValidationPolicy,
+) -> Result<ValidationReport, ValidationError> {
+    check_config_planned(config, &BTreeMap::new(), Some(plan), policy)
+}
+
+/// Validates the entire network configuration, then enforces the rules of the given policy,
+/// as [`check_config_with_policy`], with the implementation chosen by some drones.
+fn check_config_with_impls(
+    config: &Config,
+    drone_impls: &BTreeMap<NodeId, DroneImpl>,
+    policy: &ValidationPolicy,
+) -> Result<ValidationReport, ValidationError> {
+    check_config_planned(config, drone_impls, None, policy)
+}
+
+/// Validates the entire network configuration, then enforces the rules of the given policy,
+/// against the given plan or, if none, the one honoring the implementation chosen by some
+/// drones.
+fn check_config_planned(
+    config: &Config,
+    drone_impls: &BTreeMap<NodeId, DroneImpl>,
+    plan: Option<&DistributionPlan>,
+    policy: &ValidationPolicy,
+) -> Result<ValidationReport, ValidationError> {
+    #[cfg(feature = "telemetry")]
+    let start = crate::telemetry::SpanStart::now();
+    let result = enforce_policy(config, drone_impls, plan, policy);
+    #[cfg(feature = "telemetry")]
+    start.end("network.validate", || {
+        let nodes = config.drone.len() + config.client.len() + config.server.len();
+        let outcome = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(error) => format!("{:?}", error.code),
+        };
+        vec![
+            ("network.nodes", nodes.to_string()),
+            ("network.validation.outcome", outcome),
+        ]
+    });
+    result
+}
+
+/// Validates the entire network configuration, then enforces the rules of the given policy,
+/// as [`check_config_planned`].
+fn enforce_policy(
+    config: &Config,
+    drone_impls: &BTreeMap<NodeId, DroneImpl>,
+    plan: Option<&DistributionPlan>,
+    policy: &ValidationPolicy,
+) -> Result<ValidationReport, ValidationError> {
+    let mut report = ValidationReport::default();
+    let deduplicated;
+    let config = if policy.on_duplicate_edge == DuplicateEdgePolicy::Error {
+        config
+    } else {
+        let mut copy = config.clone();
+        for (id, neighbor) in dedup_neighbors(&mut copy) {
+            if policy.on_duplicate_edge == DuplicateEdgePolicy::Warn {
+                report.warn(
+                    ErrorCode::DuplicateNeighbor,
+                    format!(
+                        "Node [{}] has duplicate neighbor [{}], which is ignored",
+                        id, neighbor
+                    ),
+                );
+            }
+        }
+        deduplicated = copy;
+        &deduplicated
+    };
+    validate_structure(config)?;
+    validate_node_counts(config, policy)?;
+    let planned;
+    let plan = match plan {
+        Some(plan) => plan,
+        None => {
+            planned = DistributionPlan::try_with_drone_impls(config, drone_impls)?;
+            &planned
+        }
+    };
+    validate_impl_coverage(plan, policy, &mut report)?;
+    validate_redundancy(config, policy, &mut report)?;
+    report.nodes = summarize_nodes(&Topology::from(config));
+    Ok(report)
+}
+
+/// Validates the entire network configuration, collecting every violation in a single pass.
+///
+/// This runs the same checks as [`check_config`], but keeps going after a violation: every
+/// invalid node, duplicate ID, non-drone neighbor and one-way edge is reported. A node is
+/// reported once, for its first invalid field, and the graph-wide checks, i.e. connectivity
+/// and clients and servers at the edge, are reported once each.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// Returns the violations, in the order in which the checks run, which is empty if the
+/// configuration is valid.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn check_config_all(config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut node_ids = FixedBitSet::with_capacity(MAX_NODES);
+    let mut drone_ids = FixedBitSet::with_capacity(MAX_NODES);
+
+    // Validate every node, and check that there are no duplicate node IDs.
+    let drones = config
+        .drone
+        .iter()
+        .map(|d| (d.id, NodeType::Drone, validate_drone(d)));
+    let clients = config
+        .client
+        .iter()
+        .map(|c| (c.id, NodeType::Client, validate_client(c)));
+    let servers = config
+        .server
+        .iter()
+        .map(|s| (s.id, NodeType::Server, validate_server(s)));
+    let nodes = drones.chain(clients).chain(servers);
+    for (id, node_type, result) in nodes {
+        errors.extend(result.err());
+        let index = match NodeIndex::try_from(id) {
+            Ok(index) => index.get(),
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        if node_ids.put(index) {
+            errors.push(ValidationError::new(
+                ErrorCode::DuplicateId,
+                format!("Duplicate node ID found: [{}]", id),
+            ));
+        } else if node_type == NodeType::Drone {
+            drone_ids.insert(index);
+        }
+    }
+
+    // Validate the links between the nodes.
+    let topology = Topology::from(config);
+    let n_nodes = node_ids.count_ones(..);
+    let n_drones = drone_ids.count_ones(..);
+    errors.extend(validate_has_drones(&topology).err());
+    let mut graph: Graph = std::array::from_fn(|_| FixedBitSet::with_capacity(MAX_NODES));
+    if let Err(error) = collect_neighbors_not_drones(&topology, &drone_ids, &mut errors)
+        .and_then(|_| compute_init_graph(&mut graph, &topology))
+    {
+        // The graph is incomplete, and the remaining checks would be misleading.
+        errors.push(error);
+        return errors;
+    }
+    collect_one_way_edges(&graph, &node_ids, &mut errors);
+    errors.extend(validate_connected_graph(&graph, &node_ids, n_nodes).err());
+    errors.extend(validate_edges_clients_servers(&graph, &drone_ids, n_nodes, n_drones).err());
+    errors
+}
+
+/// Validates the entire network configuration, collecting every violation and every finding
+/// which is not an error but is almost certainly a mistake, e.g. for a GUI to show them before
+/// the simulation is launched.
+///
+/// The violations are the ones of [`check_config_all`], or the one of
+/// [`check_config_with_policy`] if the structural checks are passed. On top of the warnings of
+/// the policy, the report warns about:
+/// - drones with a packet drop rate of exactly 1, as [`ErrorCode::DropsEverything`];
+/// - drones connected to no other drone, as [`ErrorCode::NoDroneNeighbors`];
+/// - an average packet drop rate above [`HIGH_AVERAGE_PDR`], as [`ErrorCode::HighAveragePdr`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `policy`: The additional rules to enforce.
+///
+/// Returns the report, whose nodes are only summarized if there are no errors.
+///
+/// # Performance
+/// The same as [`check_config_with_policy`].
+pub fn validation_report(config: &Config, policy: &ValidationPolicy) -> ValidationReport {
+    let mut report = match check_config_with_policy(config, policy) {
+        Ok(report) => report,
+        Err(error) => {
+            let mut errors = if policy.on_duplicate_edge == DuplicateEdgePolicy::Error {
+                check_config_all(config)
+            } else {
+                let mut copy = config.clone();
+                dedup_neighbors(&mut copy);
+                check_config_all(&copy)
+            };
+            if errors.is_empty() {
+                // Only the rules of the policy are violated.
+                errors.push(error);
+            }
+            ValidationReport {
+                errors,
+                ..ValidationReport::default()
+            }
+        }
+    };
+    lint_drones(config, &mut report);
+    report
+}
+
+/// Warns about the drones which are valid, but almost certainly misconfigured, as
+/// [`validation_report`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `report`: The report collecting the warnings.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn lint_drones(config: &Config, report: &mut ValidationReport) {
+    let drone_ids: BTreeSet<NodeId> = config.drone.iter().map(|drone| drone.id).collect();
+    for drone in &config.drone {
+        if drone.pdr == 1_f32 {
+            report.warn(
+                ErrorCode::DropsEverything,
+                format!(
+                    "Drone [{}] has a PDR of 1, so it drops every fragment",
+                    drone.id
+                ),
+            );
+        }
+        let has_drone_neighbor =
+            (drone.connected_node_ids.iter()).any(|id| *id != drone.id && drone_ids.contains(id));
+        if drone_ids.len() > 1 && !has_drone_neighbor {
+            report.warn(
+                ErrorCode::NoDroneNeighbors,
+                format!(
+                    "Drone [{}] is not connected to any other drone, so it cannot forward packets",
+                    drone.id
+                ),
+            );
+        }
+    }
+    if config.drone.is_empty() {
+        return;
+    }
+    let average =
+        config.drone.iter().map(|drone| drone.pdr).sum::<f32>() / config.drone.len() as f32;
+    if average > HIGH_AVERAGE_PDR {
+        report.warn(
+            ErrorCode::HighAveragePdr,
+            format!(
+                "The average PDR of the drones is {:.2}, above {}",
+                average, HIGH_AVERAGE_PDR
+            ),
+        );
+    }
+}
+
+/// Removes the repeated entries from the neighbor lists of every node, keeping the first one.
+///
+/// # Parameters
+/// - `config`: A mutable reference to the network configuration.
+///
+/// Returns the removed entries, as `(node, neighbor)` pairs, in the order in which they appear
+/// in the configuration.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn dedup_neighbors(config: &mut Config) -> Vec<(NodeId, NodeId)> {
+    let mut removed = Vec::new();
+    let drones = config
+        .drone
+        .iter_mut()
+        .map(|d| (d.id, &mut d.connected_node_ids));
+    let clients = config
+        .client
+        .iter_mut()
+        .map(|c| (c.id, &mut c.connected_drone_ids));
+    let servers = config
+        .server
+        .iter_mut()
+        .map(|s| (s.id, &mut s.connected_drone_ids));
+    for (id, neighbors) in drones.chain(clients).chain(servers) {
+        let mut seen = FixedBitSet::with_capacity(MAX_NODES);
+        neighbors.retain(|&neighbor| {
+            // IDs out of range are kept, and reported by the validation.
+            let Some(index) = NodeIndex::new(neighbor) else {
+                return true;
+            };
+            let first = !seen.put(index.get());
+            if !first {
+                removed.push((id, neighbor));
+            }
+            first
+        });
+    }
+    removed
+}
+
+/// Summarizes the degree of every node, and its slack against the neighbor count constraints.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration, which must be valid.
+///
+/// Returns the summaries, in the order in which the nodes appear in the configuration.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn summarize_nodes(topology: &Topology) -> Vec<NodeSummary> {
+    let types = node_types(topology);
+    let neighbors = topology.neighbors();
+
+    let summarize = |node: &Node| {
+        let neighbors = neighbors.get(&node.id).map_or(&[][..], Vec::as_slice);
+        let count = |t: NodeType| {
+            neighbors
+                .iter()
+                .filter_map(|n| NodeIndex::new(*n))
+                .filter(|n| types[n.get()] == Some(t))
+                .count()
+        };
+        let node_type = node.role.node_type();
+        let degree = neighbors.len();
+        let (can_add, can_remove) = match node_type {
+            NodeType::Drone => (None, degree),
+            NodeType::Client => (
+                Some(MAX_CLIENT_DRONES.saturating_sub(degree)),
+                degree.saturating_sub(MIN_CLIENT_DRONES),
+            ),
+            NodeType::Server => (None, degree.saturating_sub(MIN_SERVER_DRONES)),
+        };
+        NodeSummary {
+            id: node.id,
+            node_type,
+            degree,
+            drone_neighbors: count(NodeType::Drone),
+            client_neighbors: count(NodeType::Client),
+            server_neighbors: count(NodeType::Server),
+            can_add,
+            can_remove,
+        }
+    };
+
+    topology.nodes.iter().map(summarize).collect()
+}
+
+/// Returns the type of every node of the topology, indexed by node ID.
+fn node_types(topology: &Topology) -> [Option<NodeType>; MAX_NODES] {
+    let mut types = [None; MAX_NODES];
+    for node in &topology.nodes {
+        if let Some(index) = NodeIndex::new(node.id) {
+            types[index.get()] = Some(node.role.node_type());
+        }
+    }
+    types
+}
+
+/// Validates only a part of the network configuration.
+///
+/// This is useful for cheap targeted checks, e.g. running the graph checks only on generated
+/// topologies whose nodes are known to be valid. A configuration is valid if it passes every
+/// scope, which is the same as passing [`check_config`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `scope`: The checks to run.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub fn validate_scope(config: &Config, scope: Scope) -> Result<(), ValidationError> {
+    match scope {
+        Scope::NodesOnly => validate_nodes(config).map(|_| ()),
+        Scope::GraphOnly => {
+            let topology = Topology::from(config);
+            let (node_ids, drone_ids) = collect_ids(&topology)?;
+            validate_graph(&topology, &node_ids, &drone_ids)
+        }
+        Scope::Node(id) => validate_single_node(config, id),
+    }
+}
+
+/// Returns the drone implementations which would not be assigned to any drone by
+/// [`DistributionPlan::new`], see [`DistributionPlan::unused_impls`].
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// Returns the unused implementations, ordered by code, or none if the configuration cannot
+/// be planned.
+pub fn unused_impls(config: &Config) -> Vec<DroneImpl> {
+    DistributionPlan::try_new(config)
+        .map(|plan| plan.unused_impls())
+        .unwrap_or_default()
+}
+
+/// Validates the structure of the network configuration.
+///
 /// This function checks that:
+/// - Every node ID, and every neighbor ID, is below `MAX_NODES`.
 /// - Each drone, client, and server is valid individually.
 /// - There are no duplicate node IDs across all node types.
-/// - Every client and server connects only to drones.
+/// - Every client and server connects only to drones, and there is at least one drone.
 /// - The constructed network graph is bidirectional, connected,
 ///   and clients/servers are at the network edge.
 ///
@@ -45,61 +1100,422 @@ pub fn network_validate(file_path: &str) -> Result<Config, String> {
 /// Returns an error if the checks are not passed.
 ///
 /// # Performance
-/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
-fn validate_config(config: &Config) -> Result<(), String> {
-    let mut n_nodes = 0;
-    let mut node_ids = FixedBitSet::with_capacity(MAX_NODES);
-
-    // Validate drones.
-    for drone in &config.drone {
-        validate_drone(drone)?;
-        if node_ids.contains(drone.id as usize) {
-            return Err(format!("Duplicate node ID found: [{}]", drone.id));
-        } else {
-            node_ids.insert(drone.id as usize);
-            n_nodes += 1;
-        }
-    }
-    let drone_ids = node_ids.clone();
-    let n_drones = n_nodes;
-
-    // Validate clients.
-    for client in &config.client {
-        validate_client(client)?;
-        if node_ids.contains(client.id as usize) {
-            return Err(format!("Duplicate node ID found: [{}]", client.id));
-        } else {
-            node_ids.insert(client.id as usize);
-            n_nodes += 1;
-        }
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn validate_structure(config: &Config) -> Result<(), ValidationError> {
+    validate_id_range(config)?;
+    let (node_ids, drone_ids) = validate_nodes(config)?;
+    validate_graph(&Topology::from(config), &node_ids, &drone_ids)
+}
+
+/// Returns the node IDs, and the neighbor IDs, of a configuration which are not below
+/// `MAX_NODES`, so cannot index the per-node arrays.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+pub(crate) fn out_of_range_ids(config: &Config) -> BTreeSet<NodeId> {
+    let drones = config
+        .drone
+        .iter()
+        .flat_map(|d| std::iter::once(&d.id).chain(&d.connected_node_ids));
+    let clients = config
+        .client
+        .iter()
+        .flat_map(|c| std::iter::once(&c.id).chain(&c.connected_drone_ids));
+    let servers = config
+        .server
+        .iter()
+        .flat_map(|s| std::iter::once(&s.id).chain(&s.connected_drone_ids));
+    drones
+        .chain(clients)
+        .chain(servers)
+        .copied()
+        .filter(|&id| NodeIndex::new(id).is_none())
+        .collect()
+}
+
+/// Returns the node IDs declared by more than one node of a configuration.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// # Performance
+/// `O(n log n)`, where `n` is the number of nodes.
+pub(crate) fn duplicate_ids(config: &Config) -> BTreeSet<NodeId> {
+    let mut seen = BTreeSet::new();
+    (config.drone.iter().map(|d| d.id))
+        .chain(config.client.iter().map(|c| c.id))
+        .chain(config.server.iter().map(|s| s.id))
+        .filter(|&id| !seen.insert(id))
+        .collect()
+}
+
+/// Validates that every node ID, and every neighbor ID, is below `MAX_NODES`.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// Returns an error listing every out-of-range ID, if any.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn validate_id_range(config: &Config) -> Result<(), ValidationError> {
+    let ids = out_of_range_ids(config);
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<String> = ids.iter().map(|id| format!("[{}]", id)).collect();
+    Err(ValidationError::new(
+        ErrorCode::IdOutOfRange,
+        format!("Node IDs {} are not below {}", ids.join(", "), MAX_NODES),
+    ))
+}
+
+/// Validates every node individually, and checks that there are no duplicate node IDs.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+///
+/// Returns the IDs of all nodes and of the drones, or an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn validate_nodes(config: &Config) -> Result<(FixedBitSet, FixedBitSet), ValidationError> {
+    let mut n_nodes = 0;
+    let mut node_ids = FixedBitSet::with_capacity(MAX_NODES);
+
+    // Validate drones.
+    for drone in &config.drone {
+        validate_drone(drone)?;
+        let index = NodeIndex::try_from(drone.id)?.get();
+        if node_ids.contains(index) {
+            return Err(ValidationError::new(
+                ErrorCode::DuplicateId,
+                format!("Duplicate node ID found: [{}]", drone.id),
+            ));
+        } else {
+            node_ids.insert(index);
+            n_nodes += 1;
+        }
+    }
+    let drone_ids = node_ids.clone();
+
+    // Validate clients.
+    for client in &config.client {
+        validate_client(client)?;
+        let index = NodeIndex::try_from(client.id)?.get();
+        if node_ids.contains(index) {
+            return Err(ValidationError::new(
+                ErrorCode::DuplicateId,
+                format!("Duplicate node ID found: [{}]", client.id),
+            ));
+        } else {
+            node_ids.insert(index);
+            n_nodes += 1;
+        }
+    }
+
+    // Validate servers.
+    for server in &config.server {
+        validate_server(server)?;
+        let index = NodeIndex::try_from(server.id)?.get();
+        if node_ids.contains(index) {
+            return Err(ValidationError::new(
+                ErrorCode::DuplicateId,
+                format!("Duplicate node ID found: [{}]", server.id),
+            ));
+        } else {
+            node_ids.insert(index);
+            n_nodes += 1;
+        }
+    }
+
+    // Confirm that the total node count matches.
+    assert_eq!(
+        n_nodes,
+        config.drone.len() + config.client.len() + config.server.len()
+    );
+
+    Ok((node_ids, drone_ids))
+}
+
+/// Validates that the network has at least one drone.
+///
+/// Clients and servers only communicate through drones, so a network without drones, even an
+/// empty one, cannot be simulated; it is rejected before the graph is checked, instead of
+/// failing some later check with a misleading error, or passing the checks which only concern
+/// drones.
+///
+/// # Parameters
+/// - `topology`: A reference to the network topology.
+///
+/// Returns an error if the network has no drones.
+fn validate_has_drones(topology: &Topology) -> Result<(), ValidationError> {
+    if topology.nodes_of_type(NodeType::Drone).next().is_none() {
+        return Err(ValidationError::new(
+            ErrorCode::NoDrones,
+            format!(
+                "The network has no drones, so its {} clients and {} servers cannot communicate",
+                topology.nodes_of_type(NodeType::Client).count(),
+                topology.nodes_of_type(NodeType::Server).count()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the links between the nodes.
+///
+/// This function checks that every client and server connects only to drones, that there is
+/// at least one drone, and that the network graph is bidirectional, connected, and has
+/// clients/servers at the network edge.
+///
+/// # Parameters
+/// - `topology`: A reference to the network topology.
+/// - `node_ids`: A FixedBitSet containing all valid node IDs.
+/// - `drone_ids`: A FixedBitSet containing all drone IDs.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn validate_graph(
+    topology: &Topology,
+    node_ids: &FixedBitSet,
+    drone_ids: &FixedBitSet,
+) -> Result<(), ValidationError> {
+    let n_nodes = node_ids.count_ones(..);
+    let n_drones = drone_ids.count_ones(..);
+
+    // Check that there are drones, and that all clients and servers connect only to them.
+    validate_has_drones(topology)?;
+    validate_all_neighbors_are_drones(topology, drone_ids)?;
+
+    let mut graph: Graph = std::array::from_fn(|_| FixedBitSet::with_capacity(MAX_NODES));
+    compute_init_graph(&mut graph, topology)?;
+    validate_bidirectional_graph(&graph, node_ids)?;
+    validate_connected_graph(&graph, node_ids, n_nodes)?;
+    validate_edges_clients_servers(&graph, drone_ids, n_nodes, n_drones)?;
+
+    Ok(())
+}
+
+/// Validates a single node: its fields, and its links to the other nodes.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `id`: The ID of the node.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn validate_single_node(config: &Config, id: NodeId) -> Result<(), ValidationError> {
+    let topology = Topology::from(config);
+    let (node_ids, drone_ids) = collect_ids(&topology)?;
+    let (node_type, neighbors) = if let Some(drone) = config.drone.iter().find(|d| d.id == id) {
+        validate_drone(drone)?;
+        (NodeType::Drone, &drone.connected_node_ids)
+    } else if let Some(client) = config.client.iter().find(|c| c.id == id) {
+        validate_client(client)?;
+        (NodeType::Client, &client.connected_drone_ids)
+    } else if let Some(server) = config.server.iter().find(|s| s.id == id) {
+        validate_server(server)?;
+        (NodeType::Server, &server.connected_drone_ids)
+    } else {
+        return Err(ValidationError::new(
+            ErrorCode::UnknownNode,
+            format!("Node [{}] does not exist in the topology", id),
+        ));
+    };
+
+    let mut graph: Graph = std::array::from_fn(|_| FixedBitSet::with_capacity(MAX_NODES));
+    compute_init_graph(&mut graph, &topology)?;
+    for neighbor in neighbors {
+        let index = NodeIndex::new(*neighbor).map(NodeIndex::get);
+        if !index.is_some_and(|index| node_ids.contains(index)) {
+            return Err(ValidationError::new(
+                ErrorCode::UnknownNeighbor,
+                format!(
+                    "Node [{}] has [{}] as neighbor, which does not exist in the topology.",
+                    id, neighbor
+                ),
+            ));
+        }
+        if node_type != NodeType::Drone && !index.is_some_and(|index| drone_ids.contains(index)) {
+            return Err(ValidationError::new(
+                ErrorCode::NeighborNotDrone,
+                format!(
+                    "{:?} [{}] is connected to [{}], which is not a drone",
+                    node_type, id, neighbor
+                ),
+            ));
+        }
+    }
+    let index = NodeIndex::try_from(id)?.get();
+    for other in node_ids.ones() {
+        let (from, to) = if graph[index].contains(other) {
+            (index, other)
+        } else if graph[other].contains(index) {
+            (other, index)
+        } else {
+            continue;
+        };
+        if !graph[to].contains(from) {
+            return Err(ValidationError::new(
+                ErrorCode::NotBidirectional,
+                format!(
+                    "The topology is not bidirectional: node [{}] is reachable from [{}], but not vice versa.",
+                    to, from
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the IDs of all nodes and of the drones, only checking that the IDs are in range.
+fn collect_ids(topology: &Topology) -> Result<(FixedBitSet, FixedBitSet), ValidationError> {
+    let mut node_ids = FixedBitSet::with_capacity(MAX_NODES);
+    let mut drone_ids = FixedBitSet::with_capacity(MAX_NODES);
+    for node in &topology.nodes {
+        let index = NodeIndex::try_from(node.id)?.get();
+        node_ids.insert(index);
+        if node.role.node_type() == NodeType::Drone {
+            drone_ids.insert(index);
+        }
+    }
+    Ok((node_ids, drone_ids))
+}
+
+/// Validates that the number of nodes of each type is within the limits of the policy.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `policy`: The policy defining the limits.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(1)`.
+fn validate_node_counts(config: &Config, policy: &ValidationPolicy) -> Result<(), ValidationError> {
+    let limits = [
+        (
+            "drones",
+            config.drone.len(),
+            policy.min_drones,
+            policy.max_drones,
+        ),
+        (
+            "clients",
+            config.client.len(),
+            policy.min_clients,
+            policy.max_clients,
+        ),
+        (
+            "servers",
+            config.server.len(),
+            policy.min_servers,
+            policy.max_servers,
+        ),
+    ];
+    for (kind, count, min, max) in limits {
+        if let Some(min) = min.filter(|min| count < *min) {
+            return Err(ValidationError::new(
+                ErrorCode::NodeCount,
+                format!(
+                    "The network has {} {}, but at least {} are required",
+                    count, kind, min
+                ),
+            ));
+        }
+        if let Some(max) = max.filter(|max| count > *max) {
+            return Err(ValidationError::new(
+                ErrorCode::NodeCount,
+                format!(
+                    "The network has {} {}, but at most {} are allowed",
+                    count, kind, max
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validates that every drone implementation is assigned to at least one drone.
+///
+/// # Parameters
+/// - `plan`: The distribution the configuration is initialized with.
+/// - `policy`: The policy defining how to react to unused implementations.
+/// - `report`: The report collecting the warnings.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(n)`, where `n` is the number of drones.
+fn validate_impl_coverage(
+    plan: &DistributionPlan,
+    policy: &ValidationPolicy,
+    report: &mut ValidationReport,
+) -> Result<(), ValidationError> {
+    if policy.unused_impls == Severity::Allow {
+        return Ok(());
+    }
+    let unused = plan.unused_impls();
+    if unused.is_empty() {
+        return Ok(());
+    }
+    let message = format!(
+        "The network has {} drones, so {} of the {} drone implementations would be unused: {:?}",
+        plan.drones.len(),
+        unused.len(),
+        MAX_IMPL,
+        unused
+    );
+    if policy.unused_impls == Severity::Deny {
+        return Err(ValidationError::new(ErrorCode::UnusedImpl, message));
+    }
+    report.warn(ErrorCode::UnusedImpl, message);
+    Ok(())
+}
+
+/// Validates that no single drone crash would disconnect some client from some server.
+///
+/// # Parameters
+/// - `config`: A reference to the network configuration.
+/// - `policy`: The policy defining how to react to single points of failure.
+/// - `report`: The report collecting the warnings.
+///
+/// Returns an error if the checks are not passed.
+///
+/// # Performance
+/// `O(d * c * (n + m))`, as [`critical_drones`].
+fn validate_redundancy(
+    config: &Config,
+    policy: &ValidationPolicy,
+    report: &mut ValidationReport,
+) -> Result<(), ValidationError> {
+    if policy.single_points_of_failure == Severity::Allow {
+        return Ok(());
     }
-
-    // Validate servers.
-    for server in &config.server {
-        validate_server(server)?;
-        if node_ids.contains(server.id as usize) {
-            return Err(format!("Duplicate node ID found: [{}]", server.id));
-        } else {
-            node_ids.insert(server.id as usize);
-            n_nodes += 1;
-        }
+    let critical = critical_drones(config);
+    if critical.is_empty() {
+        return Ok(());
     }
-
-    // Confirm that the total node count matches.
-    assert_eq!(
-        n_nodes,
-        config.drone.len() + config.client.len() + config.server.len()
+    let ids: Vec<String> = critical.iter().map(|id| format!("[{}]", id)).collect();
+    let message = format!(
+        "Drones {} would each disconnect some client from some server if crashed",
+        ids.join(", ")
     );
-
-    // Check that all clients and servers connect only to drones.
-    validate_all_neighbors_are_drones(config, &drone_ids)?;
-
-    let mut graph: Graph = std::array::from_fn(|_| FixedBitSet::with_capacity(MAX_NODES));
-    compute_init_graph(&mut graph, config);
-    validate_bidirectional_graph(&graph, &node_ids)?;
-    validate_connected_graph(&graph, &node_ids, n_nodes)?;
-    validate_edges_clients_servers(&graph, &drone_ids, n_nodes, n_drones)?;
-
+    if policy.single_points_of_failure == Severity::Deny {
+        return Err(ValidationError::new(
+            ErrorCode::SinglePointOfFailure,
+            message,
+        ));
+    }
+    report.warn(ErrorCode::SinglePointOfFailure, message);
     Ok(())
 }
 
@@ -116,25 +1532,32 @@ fn validate_config(config: &Config) -> Result<(), String> {
 ///
 /// # Performance
 /// `O(n)`, where `n` is the number of neighbors.
-fn validate_drone(drone: &Drone) -> Result<(), String> {
+fn validate_drone(drone: &Drone) -> Result<(), ValidationError> {
     if drone.pdr < 0_f32 || drone.pdr > 1_f32 {
-        return Err(format!(
-            "Invalid PDR for drone [{}]: {}",
-            drone.id, drone.pdr
+        return Err(ValidationError::new(
+            ErrorCode::InvalidPdr,
+            format!("Invalid PDR for drone [{}]: {}", drone.id, drone.pdr),
         ));
     }
     let mut set = FixedBitSet::with_capacity(MAX_NODES);
     for connected_id in &drone.connected_node_ids {
         if *connected_id == drone.id {
-            return Err(format!("Drone [{}] is connected to itself", drone.id));
+            return Err(ValidationError::new(
+                ErrorCode::SelfConnection,
+                format!("Drone [{}] is connected to itself", drone.id),
+            ));
         }
-        if set.contains(*connected_id as usize) {
-            return Err(format!(
-                "Drone [{}] has duplicate neighbor [{}]",
-                drone.id, *connected_id
+        let index = NodeIndex::try_from(*connected_id)?.get();
+        if set.contains(index) {
+            return Err(ValidationError::new(
+                ErrorCode::DuplicateNeighbor,
+                format!(
+                    "Drone [{}] has duplicate neighbor [{}]",
+                    drone.id, *connected_id
+                ),
             ));
         }
-        set.insert(*connected_id as usize);
+        set.insert(index);
     }
     Ok(())
 }
@@ -151,25 +1574,41 @@ fn validate_drone(drone: &Drone) -> Result<(), String> {
 ///
 /// # Performance
 /// `O(n)`, where `n` is the number of neighbors.
-fn validate_client(client: &Client) -> Result<(), String> {
-    if client.connected_drone_ids.is_empty() {
-        return Err(format!("Client [{}] is connected to 0 drones", client.id));
+fn validate_client(client: &Client) -> Result<(), ValidationError> {
+    if client.connected_drone_ids.len() < MIN_CLIENT_DRONES {
+        return Err(ValidationError::new(
+            ErrorCode::ClientNeighborCount,
+            format!("Client [{}] is connected to 0 drones", client.id),
+        ));
     }
-    if client.connected_drone_ids.len() > 2 {
-        return Err(format!("Client [{}] has more than 2 neighbors", client.id));
+    if client.connected_drone_ids.len() > MAX_CLIENT_DRONES {
+        return Err(ValidationError::new(
+            ErrorCode::ClientNeighborCount,
+            format!(
+                "Client [{}] has more than {} neighbors",
+                client.id, MAX_CLIENT_DRONES
+            ),
+        ));
     }
     let mut set = FixedBitSet::with_capacity(MAX_NODES);
     for connected_id in &client.connected_drone_ids {
         if *connected_id == client.id {
-            return Err(format!("Client [{}] is connected to itself", client.id));
+            return Err(ValidationError::new(
+                ErrorCode::SelfConnection,
+                format!("Client [{}] is connected to itself", client.id),
+            ));
         }
-        if set.contains(*connected_id as usize) {
-            return Err(format!(
-                "Client [{}] has duplicate neighbor [{}]",
-                client.id, *connected_id
+        let index = NodeIndex::try_from(*connected_id)?.get();
+        if set.contains(index) {
+            return Err(ValidationError::new(
+                ErrorCode::DuplicateNeighbor,
+                format!(
+                    "Client [{}] has duplicate neighbor [{}]",
+                    client.id, *connected_id
+                ),
             ));
         }
-        set.insert(*connected_id as usize);
+        set.insert(index);
     }
     Ok(())
 }
@@ -186,33 +1625,46 @@ fn validate_client(client: &Client) -> Result<(), String> {
 ///
 /// # Performance
 /// `O(n)`, where `n` is the number of neighbors.
-fn validate_server(server: &Server) -> Result<(), String> {
-    if server.connected_drone_ids.len() < 2 {
-        return Err(format!("Server [{}] has less than 2 neighbors", server.id));
+fn validate_server(server: &Server) -> Result<(), ValidationError> {
+    if server.connected_drone_ids.len() < MIN_SERVER_DRONES {
+        return Err(ValidationError::new(
+            ErrorCode::ServerNeighborCount,
+            format!(
+                "Server [{}] has less than {} neighbors",
+                server.id, MIN_SERVER_DRONES
+            ),
+        ));
     }
     let mut set = FixedBitSet::with_capacity(MAX_NODES);
     for connected_id in &server.connected_drone_ids {
         if *connected_id == server.id {
-            return Err(format!("Server [{}] is connected to itself", server.id));
+            return Err(ValidationError::new(
+                ErrorCode::SelfConnection,
+                format!("Server [{}] is connected to itself", server.id),
+            ));
         }
-        if set.contains(*connected_id as usize) {
-            return Err(format!(
-                "Server [{}] has duplicate neighbor [{}]",
-                server.id, *connected_id
+        let index = NodeIndex::try_from(*connected_id)?.get();
+        if set.contains(index) {
+            return Err(ValidationError::new(
+                ErrorCode::DuplicateNeighbor,
+                format!(
+                    "Server [{}] has duplicate neighbor [{}]",
+                    server.id, *connected_id
+                ),
             ));
         }
-        set.insert(*connected_id as usize);
+        set.insert(index);
     }
     Ok(())
 }
 
 /// Validates that all neighbors specified for clients and servers are drones.
 ///
-/// Iterates over every client and server in the configuration and ensures that every
-/// neighbor ID appears in the provided set of drone IDs.
+/// Iterates over the edges starting from every client and server in the topology and ensures
+/// that every neighbor ID appears in the provided set of drone IDs.
 ///
 /// # Parameters
-/// - `config`: The network configuration.
+/// - `topology`: The network topology.
 /// - `drone_ids`: A FixedBitSet containing all drone IDs.
 ///
 /// Returns an error if the checks are not passed.
@@ -220,61 +1672,69 @@ fn validate_server(server: &Server) -> Result<(), String> {
 /// # Performance
 /// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
 fn validate_all_neighbors_are_drones(
-    config: &Config,
+    topology: &Topology,
     drone_ids: &FixedBitSet,
-) -> Result<(), String> {
-    for client in &config.client {
-        for id in &client.connected_drone_ids {
-            if !drone_ids.contains(*id as usize) {
-                return Err(format!(
-                    "Client [{}] is connected to [{}], which is not a drone",
-                    client.id, *id
-                ));
-            }
-        }
-    }
-    for server in &config.server {
-        for id in &server.connected_drone_ids {
-            if !drone_ids.contains(*id as usize) {
-                return Err(format!(
-                    "Server [{}] is connected to [{}], which is not a drone",
-                    server.id, *id
-                ));
-            }
+) -> Result<(), ValidationError> {
+    let mut errors = Vec::new();
+    collect_neighbors_not_drones(topology, drone_ids, &mut errors)?;
+    errors.into_iter().next().map_or(Ok(()), Err)
+}
+
+/// Collects an error for every edge connecting a client or a server to a node which is not a
+/// drone.
+///
+/// # Parameters
+/// - `topology`: The network topology.
+/// - `drone_ids`: A FixedBitSet containing the IDs of all drones.
+/// - `errors`: The errors found so far, extended with the violations.
+///
+/// Returns an error if an edge has an endpoint whose ID is out of range.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn collect_neighbors_not_drones(
+    topology: &Topology,
+    drone_ids: &FixedBitSet,
+    errors: &mut Vec<ValidationError>,
+) -> Result<(), ValidationError> {
+    let types = node_types(topology);
+    for (from, to) in &topology.edges {
+        let kind = match types[NodeIndex::try_from(*from)?.get()] {
+            Some(NodeType::Client) => "Client",
+            Some(NodeType::Server) => "Server",
+            _ => continue,
+        };
+        if !drone_ids.contains(NodeIndex::try_from(*to)?.get()) {
+            errors.push(ValidationError::new(
+                ErrorCode::NeighborNotDrone,
+                format!(
+                    "{} [{}] is connected to [{}], which is not a drone",
+                    kind, from, to
+                ),
+            ));
         }
     }
     Ok(())
 }
 
-/// Builds the initial network graph from the configuration.
+/// Builds the initial network graph from the topology.
 ///
 /// The graph is represented as an array of FixedBitSet (one per node), where each FixedBitSet
 /// contains the neighbor IDs for that node.
 ///
 /// # Parameters
 /// - `graph`: A mutable reference to the graph to be constructed.
-/// - `config`: The network configuration.
+/// - `topology`: The network topology.
 ///
-/// Returns an error if the checks are not passed.
+/// Returns an error if an edge has an endpoint whose ID is out of range.
 ///
 /// # Performance
-/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
-fn compute_init_graph(graph: &mut Graph, config: &Config) {
-    for drone in &config.drone {
-        for id in &drone.connected_node_ids {
-            graph[drone.id as usize].insert(*id as usize);
-        }
-    }
-    for client in &config.client {
-        for id in &client.connected_drone_ids {
-            graph[client.id as usize].insert(*id as usize);
-        }
-    }
-    for server in &config.server {
-        for id in &server.connected_drone_ids {
-            graph[server.id as usize].insert(*id as usize);
-        }
+/// `O(m)`, where `m` is the number of edges.
+fn compute_init_graph(graph: &mut Graph, topology: &Topology) -> Result<(), ValidationError> {
+    for (from, to) in &topology.edges {
+        graph[NodeIndex::try_from(*from)?.get()].insert(NodeIndex::try_from(*to)?.get());
     }
+    Ok(())
 }
 
 /// Validates that the network graph is bidirectional.
@@ -289,24 +1749,47 @@ fn compute_init_graph(graph: &mut Graph, config: &Config) {
 ///
 /// # Performance
 /// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
-fn validate_bidirectional_graph(graph: &Graph, node_ids: &FixedBitSet) -> Result<(), String> {
+fn validate_bidirectional_graph(
+    graph: &Graph,
+    node_ids: &FixedBitSet,
+) -> Result<(), ValidationError> {
+    let mut errors = Vec::new();
+    collect_one_way_edges(graph, node_ids, &mut errors);
+    errors.into_iter().next().map_or(Ok(()), Err)
+}
+
+/// Collects an error for every edge leading to an unknown node, and for every edge which is
+/// not declared by its other endpoint.
+///
+/// # Parameters
+/// - `graph`: The network graph.
+/// - `node_ids`: A FixedBitSet containing all valid node IDs.
+/// - `errors`: The errors found so far, extended with the violations.
+///
+/// # Performance
+/// `O(n + m)`, where `n` is the number of nodes and `m` is the number of edges.
+fn collect_one_way_edges(graph: &Graph, node_ids: &FixedBitSet, errors: &mut Vec<ValidationError>) {
     for node in node_ids.ones() {
         for id in graph[node].ones() {
             if !node_ids.contains(id) {
-                return Err(format!(
-                    "Node [{}] has [{}] as neighbor, which does not exist in the topology.",
-                    node, id
+                errors.push(ValidationError::new(
+                    ErrorCode::UnknownNeighbor,
+                    format!(
+                        "Node [{}] has [{}] as neighbor, which does not exist in the topology.",
+                        node, id
+                    ),
                 ));
-            }
-            if !graph[id].contains(node) {
-                return Err(format!(
-                    "The topology is not bidirectional: node [{}] is reachable from [{}], but not vice versa.",
-                    id, node
+            } else if !graph[id].contains(node) {
+                errors.push(ValidationError::new(
+                    ErrorCode::NotBidirectional,
+                    format!(
+                        "The topology is not bidirectional: node [{}] is reachable from [{}], but not vice versa.",
+                        id, node
+                    ),
                 ));
             }
         }
     }
-    Ok(())
 }
 
 /// Validates that the network graph is connected.
@@ -327,7 +1810,7 @@ fn validate_connected_graph(
     graph: &Graph,
     node_ids: &FixedBitSet,
     n_nodes: usize,
-) -> Result<(), String> {
+) -> Result<(), ValidationError> {
     if n_nodes == 0 {
         return Ok(());
     }
@@ -352,7 +1835,10 @@ fn validate_connected_graph(
     if n_visited == n_nodes {
         Ok(())
     } else {
-        Err("The network topology is not connected".to_string())
+        Err(ValidationError::new(
+            ErrorCode::NotConnected,
+            "The network topology is not connected".to_string(),
+        ))
     }
 }
 
@@ -377,7 +1863,8 @@ fn validate_edges_clients_servers(
     drone_ids: &FixedBitSet,
     n_nodes: usize,
     n_drones: usize,
-) -> Result<(), String> {
+) -> Result<(), ValidationError> {
+    // Networks without drones are rejected by `validate_has_drones`.
     if n_nodes == 0 || n_drones == 0 {
         return Ok(());
     }
@@ -406,18 +1893,32 @@ fn validate_edges_clients_servers(
     if n_visited == n_drones {
         Ok(())
     } else {
-        Err("Clients and servers are not all on the edge of the network".to_string())
+        Err(ValidationError::new(
+            ErrorCode::NotAtEdge,
+            "Clients and servers are not all on the edge of the network".to_string(),
+        ))
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::distribution::DistributionPlan;
+    use crate::examples;
+    use crate::generator::generate_small_world;
     use crate::network_init;
     use crate::network_validate;
-    use crate::validate::validate_config;
+    use crate::validate::{
+        check_config, check_config_all, check_config_with_plan, check_config_with_policy,
+        dedup_neighbors, network_validate_all, network_validate_many, network_validate_str,
+        out_of_range_ids, parse_and_validate, unused_impls, validate_config, validate_scope,
+        validation_report, DuplicateEdgePolicy, ErrorCode, Scope, Severity, ValidatedConfig,
+        ValidationPolicy,
+    };
+    use rust_roveri_api::{MAX_IMPL, MAX_NODES};
     use std::{env, fs};
     use wg_2024::config::{Client, Config, Drone, Server};
     use wg_2024::network::NodeId;
+    use wg_2024::packet::NodeType;
 
     #[test]
     fn test_validate() {
@@ -651,6 +2152,166 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_validate_no_drones() {
+        let empty = Config {
+            drone: vec![],
+            client: vec![],
+            server: vec![],
+        };
+        let error = check_config_with_policy(&empty, &ValidationPolicy::default()).unwrap_err();
+        assert_eq!(error.code, ErrorCode::NoDrones);
+
+        let config = Config {
+            drone: vec![],
+            client: vec![Client {
+                id: 1,
+                connected_drone_ids: vec![2],
+            }],
+            server: vec![Server {
+                id: 2,
+                connected_drone_ids: vec![1, 1],
+            }],
+        };
+        let error = check_config_with_policy(&config, &ValidationPolicy::default()).unwrap_err();
+        assert_eq!(error.code, ErrorCode::DuplicateNeighbor);
+
+        let config = Config {
+            server: vec![Server {
+                id: 2,
+                connected_drone_ids: vec![1, 3],
+            }],
+            ..config
+        };
+        assert_eq!(
+            validate_config(&config),
+            Err(
+                "The network has no drones, so its 1 clients and 1 servers cannot communicate"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_all() {
+        const CONFIG: &str = r#"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 3]
+            pdr = 1.5
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [1]
+            pdr = 0.1
+
+            [[drone]]
+            id = 5
+            connected_node_ids = []
+            pdr = 0.1
+
+            [[client]]
+            id = 3
+            connected_drone_ids = [1, 4]
+
+            [[server]]
+            id = 2
+            connected_drone_ids = [1, 5]
+        "#;
+        let path = env::temp_dir().join(format!("validate_all_{}.toml", std::process::id()));
+        fs::write(&path, CONFIG).unwrap();
+        let errors = network_validate_all(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        let codes: Vec<ErrorCode> = errors.unwrap_err().iter().map(|error| error.code).collect();
+        assert_eq!(
+            codes,
+            [
+                ErrorCode::InvalidPdr,
+                ErrorCode::DuplicateId,
+                ErrorCode::NeighborNotDrone,
+                ErrorCode::NotBidirectional,
+                ErrorCode::UnknownNeighbor,
+                ErrorCode::NotConnected,
+            ]
+        );
+        let error = network_validate(path.to_str().unwrap()).unwrap_err();
+        assert_eq!(error, "Unable to read configuration file");
+        assert_eq!(
+            network_validate_all(path.to_str().unwrap()).unwrap_err()[0].code,
+            ErrorCode::Malformed
+        );
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        assert!(check_config_all(&config).is_empty());
+    }
+
+    #[test]
+    fn test_validate_many() {
+        let dir = env::temp_dir();
+        let valid = dir.join(format!("validate_many_valid_{}.toml", std::process::id()));
+        let invalid = dir.join(format!("validate_many_invalid_{}.toml", std::process::id()));
+        let missing = dir.join(format!("validate_many_missing_{}.toml", std::process::id()));
+        fs::write(&valid, examples::get("star10").unwrap()).unwrap();
+        fs::write(
+            &invalid,
+            "[[drone]]\nid = 1\nconnected_node_ids = [1]\npdr = 2.0\n",
+        )
+        .unwrap();
+        let paths = [
+            valid.clone(),
+            invalid.clone(),
+            missing.clone(),
+            valid.clone(),
+        ];
+        let results = network_validate_many(&paths, 2);
+        fs::remove_file(&valid).unwrap();
+        fs::remove_file(&invalid).unwrap();
+
+        let outcomes: Vec<_> = results
+            .iter()
+            .map(|(path, result)| (path, result.is_ok()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                (&valid, true),
+                (&invalid, false),
+                (&missing, false),
+                (&valid, true)
+            ]
+        );
+        assert!(results[0].1.as_ref().unwrap().source_hash.is_some());
+        assert_eq!(
+            results[1].1.as_ref().unwrap_err().errors[0].code,
+            ErrorCode::Malformed
+        );
+        assert_eq!(
+            results[2].1.as_ref().unwrap_err().errors[0].code,
+            ErrorCode::Malformed
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_ids() {
+        let mut config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        assert!(out_of_range_ids(&config).is_empty());
+
+        // Every ID fits when `MAX_NODES` covers the whole range of `NodeId`.
+        if MAX_NODES > NodeId::MAX as usize {
+            return;
+        }
+        let high = NodeId::MAX;
+        config.drone[0].id = high;
+        config.client[0].connected_drone_ids.push(high - 1);
+        let ids: Vec<NodeId> = out_of_range_ids(&config).into_iter().collect();
+        assert_eq!(ids, [high - 1, high]);
+        let error = check_config(&config).unwrap_err();
+        assert_eq!(error.code, ErrorCode::IdOutOfRange);
+        assert!(error
+            .message
+            .contains(&format!("[{}], [{}]", high - 1, high)));
+    }
+
     #[test]
     fn test_validate_invalid_pdr_1() {
         const INVALID_PDR: f32 = -0.1;
@@ -935,7 +2596,11 @@ mod test {
         const CLIENT_1_ID: NodeId = 71;
         const CLIENT_2_ID: NodeId = 72;
         const DRONE_ID: NodeId = 73;
-        let drone = vec![];
+        let drone = vec![Drone {
+            id: DRONE_ID,
+            connected_node_ids: vec![CLIENT_2_ID],
+            pdr: 0.1,
+        }];
         let client = vec![
             Client {
                 id: CLIENT_1_ID,
@@ -970,7 +2635,11 @@ mod test {
         const SERVER_ID: NodeId = 72;
         const DRONE_1_ID: NodeId = 73;
         const DRONE_2_ID: NodeId = 74;
-        let drone = vec![];
+        let drone = vec![Drone {
+            id: DRONE_1_ID,
+            connected_node_ids: vec![SERVER_ID],
+            pdr: 0.1,
+        }];
         let client = vec![Client {
             id: CLIENT_ID,
             connected_drone_ids: vec![SERVER_ID],
@@ -1148,4 +2817,361 @@ mod test {
             Err("The network topology is not connected".to_string())
         );
     }
+
+    #[test]
+    fn test_validate_policy_node_counts() {
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2, 3, 4],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1, 4],
+                pdr: 0.0,
+            },
+        ];
+        let client = vec![Client {
+            id: 3,
+            connected_drone_ids: vec![1],
+        }];
+        let server = vec![Server {
+            id: 4,
+            connected_drone_ids: vec![1, 2],
+        }];
+        let config_before = Config {
+            drone,
+            client,
+            server,
+        };
+
+        let within_limits = ValidationPolicy {
+            min_drones: Some(2),
+            max_clients: Some(1),
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(
+            check_config_with_policy(&config_before, &within_limits).map(|_| ()),
+            Ok(())
+        );
+
+        let too_few_clients = ValidationPolicy {
+            min_clients: Some(2),
+            max_clients: Some(4),
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(
+            check_config_with_policy(&config_before, &too_few_clients).map_err(String::from),
+            Err("The network has 1 clients, but at least 2 are required".to_string())
+        );
+
+        let too_many_servers = ValidationPolicy {
+            max_servers: Some(0),
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(
+            check_config_with_policy(&config_before, &too_many_servers).map_err(String::from),
+            Err("The network has 1 servers, but at most 0 are allowed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_policy_duplicate_edge() {
+        const CONFIG: &str = r#"
+            [[drone]]
+            id = 1
+            connected_node_ids = [2, 3, 2, 4]
+            pdr = 0.1
+
+            [[drone]]
+            id = 2
+            connected_node_ids = [1, 3, 4]
+            pdr = 0.1
+
+            [[client]]
+            id = 3
+            connected_drone_ids = [1, 2, 1]
+
+            [[server]]
+            id = 4
+            connected_drone_ids = [1, 2]
+        "#;
+        let error = parse_and_validate(CONFIG, &ValidationPolicy::default()).unwrap_err();
+        assert_eq!(error, "Drone [1] has duplicate neighbor [2]");
+
+        let warn = ValidationPolicy {
+            on_duplicate_edge: DuplicateEdgePolicy::Warn,
+            ..ValidationPolicy::default()
+        };
+        let config = parse_and_validate(CONFIG, &warn).unwrap();
+        assert_eq!(config.drone[0].connected_node_ids, [2, 3, 4]);
+        assert_eq!(config.client[0].connected_drone_ids, [1, 2]);
+        let mut config = toml::from_str(CONFIG).unwrap();
+        let report = check_config_with_policy(&config, &warn).unwrap();
+        assert_eq!(report.warnings.len(), 2);
+        assert_eq!(report.warnings[0].code, ErrorCode::DuplicateNeighbor);
+        assert_eq!(dedup_neighbors(&mut config), [(1, 2), (3, 1)]);
+
+        let silent = ValidationPolicy {
+            on_duplicate_edge: DuplicateEdgePolicy::Silent,
+            ..ValidationPolicy::default()
+        };
+        let report = check_config_with_policy(&config, &silent).unwrap();
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_policy_unused_impls() {
+        let drone = vec![
+            Drone {
+                id: 1,
+                connected_node_ids: vec![2],
+                pdr: 0.0,
+            },
+            Drone {
+                id: 2,
+                connected_node_ids: vec![1],
+                pdr: 0.0,
+            },
+        ];
+        let config_before = Config {
+            drone,
+            client: vec![],
+            server: vec![],
+        };
+        let n_unused = MAX_IMPL.saturating_sub(2);
+        assert_eq!(unused_impls(&config_before).len(), n_unused);
+
+        let allow = ValidationPolicy::default();
+        let report = check_config_with_policy(&config_before, &allow).unwrap();
+        assert!(report.warnings.is_empty());
+
+        let warn = ValidationPolicy {
+            unused_impls: Severity::Warn,
+            ..ValidationPolicy::default()
+        };
+        let report = check_config_with_policy(&config_before, &warn).unwrap();
+        assert_eq!(report.warnings.len(), usize::from(n_unused > 0));
+
+        let deny = ValidationPolicy {
+            unused_impls: Severity::Deny,
+            ..ValidationPolicy::default()
+        };
+        let result = check_config_with_policy(&config_before, &deny).map_err(|err| err.code);
+        if n_unused > 0 {
+            assert_eq!(result.map(|_| ()), Err(ErrorCode::UnusedImpl));
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_validate_plan_coverage() {
+        let config = generate_small_world(3, 2, 0.0, 1, 1, 1).unwrap();
+        let warn = ValidationPolicy {
+            unused_impls: Severity::Warn,
+            ..ValidationPolicy::default()
+        };
+        let sequential = DistributionPlan::new(&config);
+        let report = check_config_with_plan(&config, &sequential, &warn).unwrap();
+        assert_eq!(
+            report.warnings,
+            check_config_with_policy(&config, &warn).unwrap().warnings
+        );
+
+        // A seeded plan uses other implementations, which the findings follow.
+        for seed in 0..10 {
+            let plan = DistributionPlan::try_seeded(&config, seed).unwrap();
+            let report = check_config_with_plan(&config, &plan, &warn).unwrap();
+            let unused = plan.unused_impls();
+            assert_eq!(report.warnings.len(), usize::from(!unused.is_empty()));
+            if let Some(warning) = report.warnings.first() {
+                assert!(warning.message.ends_with(&format!("{:?}", unused)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_policy_single_points_of_failure() {
+        // Drone 1 is the hub of the star, between every client and every server.
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let deny = ValidationPolicy {
+            single_points_of_failure: Severity::Deny,
+            ..ValidationPolicy::default()
+        };
+        let result = check_config_with_policy(&config, &deny).map_err(|err| err.code);
+        assert_eq!(result.map(|_| ()), Err(ErrorCode::SinglePointOfFailure));
+
+        let warn = ValidationPolicy {
+            single_points_of_failure: Severity::Warn,
+            ..ValidationPolicy::default()
+        };
+        let report = check_config_with_policy(&config, &warn).unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].code, ErrorCode::SinglePointOfFailure);
+
+        let report = check_config_with_policy(&config, &ValidationPolicy::default()).unwrap();
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_scope() {
+        // Drone 1 has an invalid pdr, and drone 3 is not linked back by drone 2.
+        let config_before = Config {
+            drone: vec![
+                Drone {
+                    id: 1,
+                    connected_node_ids: vec![2],
+                    pdr: 1.5,
+                },
+                Drone {
+                    id: 2,
+                    connected_node_ids: vec![1],
+                    pdr: 0.0,
+                },
+                Drone {
+                    id: 3,
+                    connected_node_ids: vec![2],
+                    pdr: 0.0,
+                },
+            ],
+            client: vec![],
+            server: vec![],
+        };
+        let code = |scope| validate_scope(&config_before, scope).map_err(|err| err.code);
+
+        assert_eq!(code(Scope::NodesOnly), Err(ErrorCode::InvalidPdr));
+        assert_eq!(code(Scope::GraphOnly), Err(ErrorCode::NotBidirectional));
+        assert_eq!(code(Scope::Node(1)), Err(ErrorCode::InvalidPdr));
+        assert_eq!(code(Scope::Node(2)), Err(ErrorCode::NotBidirectional));
+        assert_eq!(code(Scope::Node(3)), Err(ErrorCode::NotBidirectional));
+        assert_eq!(code(Scope::Node(4)), Err(ErrorCode::UnknownNode));
+
+        // A client linked to another client is reported by its own scope.
+        let mut config = config_before.clone();
+        for (id, neighbor) in [(5, 6), (6, 5)] {
+            config.client.push(Client {
+                id,
+                connected_drone_ids: vec![neighbor],
+            });
+        }
+        let error = validate_scope(&config, Scope::Node(5)).unwrap_err();
+        assert_eq!(error.code, ErrorCode::NeighborNotDrone);
+        assert_eq!(
+            error.message,
+            "Client [5] is connected to [6], which is not a drone"
+        );
+    }
+
+    #[test]
+    fn test_validate_node_summary() {
+        let config_before = Config {
+            drone: vec![
+                Drone {
+                    id: 1,
+                    connected_node_ids: vec![2, 3, 4],
+                    pdr: 0.0,
+                },
+                Drone {
+                    id: 2,
+                    connected_node_ids: vec![1, 4],
+                    pdr: 0.0,
+                },
+            ],
+            client: vec![Client {
+                id: 3,
+                connected_drone_ids: vec![1],
+            }],
+            server: vec![Server {
+                id: 4,
+                connected_drone_ids: vec![1, 2],
+            }],
+        };
+
+        let report =
+            check_config_with_policy(&config_before, &ValidationPolicy::default()).unwrap();
+        let ids: Vec<NodeId> = report.nodes.iter().map(|node| node.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+
+        let drone = &report.nodes[0];
+        assert_eq!(drone.node_type, NodeType::Drone);
+        assert_eq!(drone.degree, 3);
+        assert_eq!(
+            (
+                drone.drone_neighbors,
+                drone.client_neighbors,
+                drone.server_neighbors
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!((drone.can_add, drone.can_remove), (None, 3));
+
+        let client = &report.nodes[2];
+        assert_eq!(client.node_type, NodeType::Client);
+        assert_eq!((client.can_add, client.can_remove), (Some(1), 0));
+
+        let server = &report.nodes[3];
+        assert_eq!(server.node_type, NodeType::Server);
+        assert_eq!((server.can_add, server.can_remove), (None, 0));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let fingerprint = ValidatedConfig::new(config.clone()).unwrap().fingerprint();
+
+        // Reordering the nodes and the neighbors keeps the fingerprint.
+        let mut reordered = config.clone();
+        reordered.drone.reverse();
+        for drone in &mut reordered.drone {
+            drone.connected_node_ids.reverse();
+        }
+        let reordered = ValidatedConfig::new(reordered).unwrap();
+        assert_eq!(reordered.fingerprint(), fingerprint);
+
+        let mut changed = config;
+        changed.drone[0].pdr += 0.1;
+        let changed = ValidatedConfig::new(changed).unwrap();
+        assert_ne!(changed.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_validation_report() {
+        let config = network_validate_str(examples::get("star10").unwrap()).unwrap();
+        let policy = ValidationPolicy::default();
+        let report = validation_report(&config, &policy);
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.nodes.len(), 14);
+
+        let mut lossy = config.clone();
+        lossy.drone[1].pdr = 1.0;
+        let report = validation_report(&lossy, &policy);
+        assert!(report.is_valid());
+        let codes: Vec<ErrorCode> = report.warnings.iter().map(|w| w.code).collect();
+        assert_eq!(codes, [ErrorCode::DropsEverything]);
+        for drone in &mut lossy.drone {
+            drone.pdr = 0.9;
+        }
+        let report = validation_report(&lossy, &policy);
+        assert_eq!(
+            report.warnings.last().unwrap().code,
+            ErrorCode::HighAveragePdr
+        );
+
+        // Cutting drone 2 off the hub leaves it with its client only, which is an error too.
+        let mut cut = config;
+        cut.drone[0].connected_node_ids.retain(|id| *id != 2);
+        cut.drone[1].connected_node_ids.retain(|id| *id != 1);
+        let report = validation_report(&cut, &policy);
+        assert!(!report.is_valid());
+        assert!(report.nodes.is_empty());
+        assert!(report
+            .errors
+            .iter()
+            .any(|error| error.code == ErrorCode::NotConnected));
+        let codes: Vec<ErrorCode> = report.warnings.iter().map(|w| w.code).collect();
+        assert_eq!(codes, [ErrorCode::NoDroneNeighbors]);
+    }
 }